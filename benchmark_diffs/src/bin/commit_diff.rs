@@ -118,7 +118,7 @@ impl DiffProcessor {
         let user = repo_parts[0];
         let name = repo_parts[1];
         let repo = hyperast_vcs_git::git::Forge::Github.repo(user, name);
-        let repo = preprocessed.register_config(repo, RepoConfig::JavaMaven);
+        let repo = preprocessed.register_config(repo, RepoConfig::JavaMaven)?;
         let repo = repo.fetch();
 
         Ok(Self {
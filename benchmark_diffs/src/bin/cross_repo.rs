@@ -53,7 +53,7 @@ fn main() {
             let (user, name) = name.split_once("/").unwrap();
             let repo = Forge::Github.repo(user, name);
             let config = hyperast_vcs_git::processing::RepoConfig::JavaMaven;
-            let configured_repo = preprocessed.register_config(repo, config);
+            let configured_repo = preprocessed.register_config(repo, config).unwrap();
             Some(CommitCompareParameters {
                 configured_repo,
                 before: "",
@@ -37,7 +37,9 @@ fn main() {
     let user = repo.next().unwrap();
     let name = repo.next().unwrap();
     let repo = hyperast_vcs_git::git::Forge::Github.repo(user, name);
-    let repo = preprocessed.register_config(repo, RepoConfig::JavaMaven);
+    let repo = preprocessed
+        .register_config(repo, RepoConfig::JavaMaven)
+        .unwrap();
     let repo = repo.fetch();
     // let (before, after) = (
     //         // "e01840e86db739374c9c4eb84a948b24ca017d8e", // parent
@@ -1698,6 +1698,23 @@ fn find(buggy_path: std::path::PathBuf, root_buggy: &Path, root_fixed: &Path) ->
 }
 
 pub fn run(buggy_path: &Path, fixed_path: &Path, name: &Path) -> Option<String> {
+    run_config(buggy_path, fixed_path, name, "gumtree")
+}
+
+/// Same as [`run`], for a given GumTree configuration,
+/// ie. "gumtree", "gumtree-simple" or "gumtree-hybrid" as named by the Java tool,
+/// gives `None` for other configurations
+pub fn run_config(
+    buggy_path: &Path,
+    fixed_path: &Path,
+    name: &Path,
+    config: &str,
+) -> Option<String> {
+    if !["gumtree", "gumtree-simple", "gumtree-hybrid"].contains(&config) {
+        // checked before running the Java tool, which can take minutes
+        println!("gumtree configuration {} is not implemented", config);
+        return None;
+    }
     let buggy = std::fs::read_to_string(&buggy_path).expect("the buggy code");
     let fixed = std::fs::read_to_string(fixed_path).expect("the fixed code");
     let mut stores = SimpleStores {
@@ -1715,7 +1732,7 @@ pub fn run(buggy_path: &Path, fixed_path: &Path, name: &Path) -> Option<String>
         &stores,
         src_tr.local.compressed_node,
         dst_tr.local.compressed_node,
-        "gumtree",
+        config,
         "Chawathe",
         60 * 5,
         gt_out_format,
@@ -1728,11 +1745,24 @@ pub fn run(buggy_path: &Path, fixed_path: &Path, name: &Path) -> Option<String>
         actions,
         prepare_gen_t,
         gen_t,
-    } = algorithms::gumtree::diff(
-        &stores,
-        &src_tr.local.compressed_node,
-        &dst_tr.local.compressed_node,
-    );
+    } = match config {
+        "gumtree" => algorithms::gumtree::diff(
+            &stores,
+            &src_tr.local.compressed_node,
+            &dst_tr.local.compressed_node,
+        ),
+        "gumtree-simple" => algorithms::gumtree_simple::diff(
+            &stores,
+            &src_tr.local.compressed_node,
+            &dst_tr.local.compressed_node,
+        ),
+        "gumtree-hybrid" => algorithms::gumtree_hybrid::diff(
+            &stores,
+            &src_tr.local.compressed_node,
+            &dst_tr.local.compressed_node,
+        ),
+        _ => return None,
+    };
     let MappingDurations([subtree_matcher_t, bottomup_matcher_t]) = mapping_durations.into();
 
     let timings = vec![subtree_matcher_t, bottomup_matcher_t, gen_t + prepare_gen_t];
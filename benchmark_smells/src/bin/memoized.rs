@@ -0,0 +1,90 @@
+//! Compares the counting of matches over consecutive commits,
+//! without and with the memoization of counts by subtree of [`hyperast_tsquery::match_cache`].
+use std::time::Instant;
+
+use hyperast_benchmark_smells::simple::count_matches;
+use hyperast_tsquery::match_cache::{MatchCache, MemoizedQuery};
+use hyperast_vcs_git::preprocessed::PreProcessedRepository;
+
+#[cfg(not(target_env = "msvc"))]
+use jemallocator::Jemalloc;
+
+/// enables uses of [`hyperast::utils::memusage_linux()`]
+#[cfg(not(target_env = "msvc"))]
+#[global_allocator]
+static GLOBAL: Jemalloc = Jemalloc;
+
+fn main() {
+    use std::env;
+    let args: Vec<String> = env::args().collect();
+    log::warn!("args: {:?}", args);
+    let repo_name = args
+        .get(1)
+        .expect("give an argument like openjdk/jdk or INRIA/spoon");
+    let commit = args.get(2).map_or("", |x| x);
+    let limit = args.get(3).map_or(500, |x| x.parse().expect("a number"));
+    let query = args.get(4).map_or("(method_declaration)", |x| x);
+    memoized(repo_name, commit, limit, query);
+}
+
+fn memoized(repo_name: &str, commit: &str, limit: usize, query: &str) {
+    let query = hyperast_tsquery::Query::with_precomputed(
+        query,
+        hyperast_gen_ts_java::language(),
+        hyperast_vcs_git::java_processor::SUB_QUERIES,
+    )
+    .map_err(|x| x.to_string())
+    .unwrap()
+    .1;
+    assert!(query.enabled_pattern_count() > 0);
+    let memoized = MemoizedQuery::new(&query, 0);
+    let cache = MatchCache::default();
+
+    let mut preprocessed = PreProcessedRepository::new(repo_name);
+    let oids = preprocessed.pre_process_first_parents_with_limit(
+        &mut hyperast_vcs_git::git::fetch_github_repository(&preprocessed.name),
+        "",
+        commit,
+        "",
+        limit,
+    );
+    eprintln!("computing matches of {} commits", oids.len());
+
+    let stores = &preprocessed.processor.main_stores;
+
+    println!("commit_sha, plain_time, memoized_time, cached_counts, matches_count");
+    let mut plain_total = 0.0;
+    let mut memoized_total = 0.0;
+    for oid in oids {
+        let commit = preprocessed.commits.get(&oid).unwrap();
+        let tr = commit.ast_root;
+
+        let now = Instant::now();
+        let plain = count_matches(stores, tr, &query);
+        let plain_time = now.elapsed().as_secs_f64();
+
+        let now = Instant::now();
+        let counts = memoized.count(stores, tr, &cache, None, |_| false).unwrap();
+        let memoized_time = now.elapsed().as_secs_f64();
+
+        let plain: Vec<u64> = plain.into_iter().map(|x| x as u64).collect();
+        assert_eq!(plain, counts, "different counts on {oid}");
+        plain_total += plain_time;
+        memoized_total += memoized_time;
+
+        let counts = counts.iter().map(|x| format!(",{}", x)).collect::<String>();
+        println!(
+            "{oid},{},{},{}{}",
+            plain_time,
+            memoized_time,
+            cache.len(),
+            counts
+        );
+    }
+    eprintln!(
+        "plain: {}s, memoized: {}s, speedup: {:.2}",
+        plain_total,
+        memoized_total,
+        plain_total / memoized_total
+    );
+}
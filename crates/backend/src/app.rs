@@ -11,7 +11,7 @@ use tower::ServiceBuilder;
 use tower_http::trace::TraceLayer;
 
 use crate::{
-    commit, fetch, file, pull_requests, querying,
+    commit, dependencies, diff, fetch, file, metric_columns, pull_requests, querying,
     scriptingv1::{self, ScriptContent, ScriptContentDepth, ScriptingError, ScriptingParam},
     smells, track, view, SharedState,
 };
@@ -44,6 +44,7 @@ impl IntoResponse for crate::tsg::QueryingError {
 mod tsg {
     #[derive(serde::Deserialize)]
     pub struct Param {
+        forge: hyperast_vcs_git::git::Forge,
         user: String,
         name: String,
         commit: String,
@@ -86,11 +87,11 @@ pub fn scripting_app(_st: SharedState) -> Router<SharedState> {
         .layer(TraceLayer::new_for_http());
     Router::new()
         .route(
-            "/script/github/:user/:name/:commit",
+            "/script/:forge/:user/:name/:commit",
             post(scripting).layer(scripting_service_config.clone()),
         )
         .route(
-            "/script-depth/github/:user/:name/:commit",
+            "/script-depth/:forge/:user/:name/:commit",
             post(scripting_depth).layer(scripting_service_config.clone()),
         )
         .route("/sharing-scripts/shared-db", get(crate::ws::connect_db))
@@ -168,6 +169,15 @@ async fn querying_differential(
     Ok(r)
 }
 
+async fn querying_history(
+    axum::extract::Path(path): axum::extract::Path<querying::Param>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    axum::extract::Json(script): axum::extract::Json<querying::Content>,
+) -> axum::response::Result<Json<querying::history::ComputeResultsHistory>> {
+    let r = querying::history::history(script, state, path)?;
+    Ok(r)
+}
+
 pub fn querying_app(_st: SharedState) -> Router<SharedState> {
     let querying_service_config = ServiceBuilder::new()
         .layer(HandleErrorLayer::new(|e: BoxError| async move {
@@ -182,17 +192,21 @@ pub fn querying_app(_st: SharedState) -> Router<SharedState> {
         .layer(TraceLayer::new_for_http());
     Router::new()
         .route(
-            "/query/github/:user/:name/*commit",
+            "/query/:forge/:user/:name/*commit",
             post(querying).layer(querying_service_config.clone()), // .with_state(Arc::clone(&shared_state)),
         )
         .route(
-            "/query-st/github/:user/:name/*commit",
+            "/query-st/:forge/:user/:name/*commit",
             post(querying_streamed).layer(querying_service_config.clone()), // .with_state(Arc::clone(&shared_state)),
         )
         .route(
-            "/query-differential/github/:user/:name/:commit/:baseline",
+            "/query-differential/:forge/:user/:name/:commit/:baseline",
             post(querying_differential).layer(querying_service_config.clone()), // .with_state(Arc::clone(&shared_state)),
         )
+        .route(
+            "/query-history/:forge/:user/:name/*commit",
+            post(querying_history).layer(querying_service_config.clone()),
+        )
         .route(
             "/sharing-queries/shared-db",
             get(crate::ws::connect_db), // .with_state(Arc::clone(&shared_state)),
@@ -234,7 +248,7 @@ pub fn tsg_app(_st: SharedState) -> Router<SharedState> {
         .layer(TraceLayer::new_for_http());
     Router::new()
         .route(
-            "/tsg/github/:user/:name/:commit",
+            "/tsg/:forge/:user/:name/:commit",
             post(tsg).layer(tsg_service_config.clone()), // .with_state(Arc::clone(&shared_state)),
         )
         .route(
@@ -278,11 +292,11 @@ pub fn smells_app(_st: SharedState) -> Router<SharedState> {
         .layer(TraceLayer::new_for_http());
     Router::new()
         .route(
-            "/smells/github/:user/:name/:commit/:len",
+            "/smells/:forge/:user/:name/:commit/:len",
             post(smells).layer(smells_service_config.clone()),
         )
         .route(
-            "/smells_ex_from_diffs/github/:user/:name/:commit/:len",
+            "/smells_ex_from_diffs/:forge/:user/:name/:commit/:len",
             post(smells_ex_from_diffs).layer(smells_service_config.clone()),
         )
 }
@@ -300,7 +314,7 @@ pub fn fetch_git_file(_st: SharedState) -> Router<SharedState> {
         .timeout(Duration::from_secs(10))
         .layer(TraceLayer::new_for_http());
     Router::new().route(
-        "/file/github/:user/:name/:commit/*file",
+        "/file/:forge/:user/:name/:commit/*file",
         get(file).layer(service_config.clone()), // .with_state(Arc::clone(&shared_state)),
     )
 }
@@ -328,15 +342,15 @@ pub fn track_code_route(_st: SharedState) -> Router<SharedState> {
         .layer(TraceLayer::new_for_http());
     Router::new()
         .route(
-            "/track/github/:user/:name/:commit/*file",
+            "/track/:forge/:user/:name/:commit/*file",
             get(track_code).layer(service_config.clone()), // .with_state(Arc::clone(&shared_state)),
         )
         .route(
-            "/track_at_path/github/:user/:name/:commit/*path",
+            "/track_at_path/:forge/:user/:name/:commit/*path",
             get(track_code_at_path).layer(service_config.clone()),
         )
         .route(
-            "/track_at_path_with_changes/github/:user/:name/:commit/*path",
+            "/track_at_path_with_changes/:forge/:user/:name/:commit/*path",
             get(track_code_at_path_with_changes).layer(service_config.clone()),
         )
 }
@@ -384,11 +398,11 @@ pub fn view_code_route(_st: SharedState) -> Router<SharedState> {
         .layer(TraceLayer::new_for_http());
     Router::new()
         .route(
-            "/view/github/:user/:name/:commit/*path",
+            "/view/:forge/:user/:name/:commit/*path",
             get(view_code).layer(service_config.clone()), // .with_state(Arc::clone(&shared_state)),
         )
         .route(
-            "/view/github/:user/:name/:commit/",
+            "/view/:forge/:user/:name/:commit/",
             get(view_code).layer(service_config.clone()), // .with_state(Arc::clone(&shared_state)),
         )
         .route(
@@ -426,11 +440,11 @@ pub fn fetch_code_route(_st: SharedState) -> Router<SharedState> {
         .layer(TraceLayer::new_for_http());
     Router::new()
         .route(
-            "/fetch/github/:user/:name/:commit/*path",
+            "/fetch/:forge/:user/:name/:commit/*path",
             get(fetch_code).layer(service_config.clone()), // .with_state(Arc::clone(&shared_state)),
         )
         .route(
-            "/fetch/github/:user/:name/:commit/",
+            "/fetch/:forge/:user/:name/:commit/",
             get(fetch_code).layer(service_config.clone()), // .with_state(Arc::clone(&shared_state)),
         )
         .route(
@@ -500,15 +514,15 @@ pub fn commit_metadata_route(_st: SharedState) -> Router<SharedState> {
         .layer(TraceLayer::new_for_http());
     Router::new()
         .route(
-            "/commit/github/:user/:name/:version",
+            "/commit/:forge/:user/:name/:version",
             get(commit_metadata).layer(service_config.clone()), // .with_state(Arc::clone(&shared_state)),
         )
         .route(
-            "/pr/github/:user/:name/:version",
+            "/pr/:forge/:user/:name/:version",
             get(pull_requests::pr_commits).layer(service_config.clone()),
         )
         .route(
-            "/fork/github/:user/:name/:other_user/:other_name/:head",
+            "/fork/:forge/:user/:name/:other_user/:other_name/:head",
             post(add_remote).layer(service_config.clone()),
         )
 }
@@ -530,6 +544,43 @@ async fn add_remote(
     dbg!(&path);
     commit::add_remote(state, path).map_err(|err| err.into())
 }
+
+pub fn dependencies_route(_st: SharedState) -> Router<SharedState> {
+    let service_config = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(|e: BoxError| async move {
+            dbg!(e);
+        }))
+        .load_shed()
+        .concurrency_limit(8)
+        .buffer(20)
+        .rate_limit(5, Duration::from_secs(1))
+        // .request_body_limit(1024 * 5_000 /* ~5mb */)
+        .timeout(Duration::from_secs(60))
+        .layer(TraceLayer::new_for_http());
+    Router::new()
+        .route(
+            "/dependencies/:forge/:user/:name/:commit",
+            get(dependencies).layer(service_config.clone()),
+        )
+        .route(
+            "/dependencies/:forge/:user/:name/:before/:after",
+            get(dependency_changes).layer(service_config.clone()),
+        )
+}
+
+async fn dependencies(
+    axum::extract::Path(path): axum::extract::Path<dependencies::Param>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+) -> axum::response::Result<Json<dependencies::Graph>> {
+    dependencies::dependencies(state, path).map_err(|err| err.into())
+}
+
+async fn dependency_changes(
+    axum::extract::Path(path): axum::extract::Path<dependencies::DiffParam>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+) -> axum::response::Result<Json<dependencies::Changes>> {
+    dependencies::dependency_changes(state, path).map_err(|err| err.into())
+}
 pub struct Timed<T> {
     pub(crate) time: f64,
     pub(crate) content: T,
@@ -549,3 +600,215 @@ impl<T: IntoResponse> IntoResponse for Timed<T> {
         resp
     }
 }
+
+pub fn diff_route(_st: SharedState) -> Router<SharedState> {
+    let service_config = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(|e: BoxError| async move {
+            dbg!(e);
+        }))
+        .load_shed()
+        .concurrency_limit(8)
+        .buffer(20)
+        .rate_limit(2, Duration::from_secs(1))
+        // .request_body_limit(1024 * 5_000 /* ~5mb */)
+        .timeout(Duration::from_secs(60))
+        .layer(TraceLayer::new_for_http());
+    Router::new().route(
+        "/diff/:forge/:user/:name/:before/:after",
+        get(edit_script).layer(service_config.clone()),
+    )
+}
+
+async fn edit_script(
+    axum::extract::Path(path): axum::extract::Path<diff::Param>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    axum::extract::Query(query): axum::extract::Query<diff::DiffQuery>,
+) -> axum::response::Result<Json<diff::EditScript>> {
+    diff::diff(state, path, query).map_err(|err| err.into())
+}
+
+pub fn metric_columns_route(_st: SharedState) -> Router<SharedState> {
+    let service_config = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(|e: BoxError| async move {
+            dbg!(e);
+        }))
+        .load_shed()
+        .concurrency_limit(8)
+        .buffer(20)
+        .rate_limit(2, Duration::from_secs(1))
+        // .request_body_limit(1024 * 5_000 /* ~5mb */)
+        .timeout(Duration::from_secs(60))
+        .layer(TraceLayer::new_for_http());
+    Router::new()
+        .route(
+            "/metric-columns",
+            get(list_metric_columns).layer(service_config.clone()),
+        )
+        .route(
+            "/metric-columns/:column",
+            post(define_metric_column)
+                .delete(remove_metric_column)
+                .layer(service_config.clone())
+                // the columns are shared by all users
+                .route_layer(axum::middleware::from_fn(crate::auth::admin)),
+        )
+        .route(
+            "/metric/:forge/:user/:name/:commit/:column",
+            get(metric_value).layer(service_config.clone()),
+        )
+}
+
+async fn list_metric_columns(
+    axum::extract::State(state): axum::extract::State<SharedState>,
+) -> Json<Vec<metric_columns::Column>> {
+    metric_columns::list(state)
+}
+
+async fn define_metric_column(
+    axum::extract::Path(path): axum::extract::Path<metric_columns::ColumnParam>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    axum::extract::Json(definition): axum::extract::Json<metric_columns::Definition>,
+) -> axum::response::Result<Json<metric_columns::Column>> {
+    metric_columns::define(state, path, definition).map_err(|err| err.into())
+}
+
+async fn remove_metric_column(
+    axum::extract::Path(path): axum::extract::Path<metric_columns::ColumnParam>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+) -> axum::response::Result<Json<metric_columns::Column>> {
+    metric_columns::remove(state, path).map_err(|err| err.into())
+}
+
+async fn metric_value(
+    axum::extract::Path(path): axum::extract::Path<metric_columns::Param>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    axum::extract::Query(query): axum::extract::Query<metric_columns::ValueQuery>,
+) -> axum::response::Result<Json<metric_columns::Value>> {
+    metric_columns::value(state, path, query).map_err(|err| err.into())
+}
+
+pub fn jobs_route(_st: SharedState) -> Router<SharedState> {
+    let service_config = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(|e: BoxError| async move {
+            dbg!(e);
+        }))
+        .load_shed()
+        .concurrency_limit(16)
+        .buffer(50)
+        .rate_limit(10, Duration::from_secs(1))
+        // .request_body_limit(1024 * 5_000 /* ~5mb */)
+        .timeout(Duration::from_secs(10))
+        .layer(TraceLayer::new_for_http());
+    Router::new()
+        .route(
+            "/jobs/query/:forge/:user/:name/*commit",
+            post(query_job).layer(service_config.clone()),
+        )
+        .route(
+            "/jobs/script-depth/:forge/:user/:name/:commit",
+            post(script_job).layer(service_config.clone()),
+        )
+        .route(
+            "/jobs/track/:forge/:user/:name/:commit/*file",
+            post(track_job).layer(service_config.clone()),
+        )
+        .route(
+            "/jobs/:id",
+            get(job_status)
+                .delete(cancel_job)
+                .layer(service_config.clone()),
+        )
+        // not rate limited, the socket stays open until the end of the job
+        .route("/jobs/:id/ws", get(job_status_ws))
+        // jobs wait for their turn instead of being rejected
+        .route_layer(axum::middleware::from_fn(crate::auth::authorize))
+}
+
+type JobUser = Option<axum::Extension<std::sync::Arc<crate::auth::User>>>;
+
+#[derive(serde::Serialize)]
+struct Submitted {
+    id: crate::jobs::JobId,
+}
+
+async fn query_job(
+    axum::extract::Path(path): axum::extract::Path<querying::Param>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    user: JobUser,
+    axum::extract::Json(query): axum::extract::Json<querying::Content>,
+) -> Json<Submitted> {
+    let repo = path.forge.repo(&path.user, &path.name);
+    let key = crate::jobs::CacheKey::new("query", repo, &path.commit, &query);
+    let id = crate::jobs::submit(state, user.map(|x| x.0), key, move |state, progress| {
+        querying::simple_with_progress(query, state, path, progress)
+    });
+    Json(Submitted { id })
+}
+
+async fn script_job(
+    axum::extract::Path(path): axum::extract::Path<ScriptingParam>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    user: JobUser,
+    axum::extract::Json(script): axum::extract::Json<ScriptContentDepth>,
+) -> Json<Submitted> {
+    let repo = path.forge.repo(&path.user, &path.name);
+    let key = crate::jobs::CacheKey::new("script", repo, &path.commit, &script);
+    let id = crate::jobs::submit(state, user.map(|x| x.0), key, move |state, progress| {
+        scriptingv1::simple_depth_with_progress(script, state, path, progress).map(|x| x.0)
+    });
+    Json(Submitted { id })
+}
+
+async fn track_job(
+    axum::extract::Path(path): axum::extract::Path<track::TrackingParam>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    user: JobUser,
+    axum::extract::Query(query): axum::extract::Query<track::TrackingQuery>,
+) -> Json<Submitted> {
+    let repo = path.forge.repo(&path.user, &path.name);
+    let key = crate::jobs::CacheKey::new("track", repo, &path.commit, &(&path.file, &query));
+    let id = crate::jobs::submit(state, user.map(|x| x.0), key, move |state, progress| {
+        track::track_code_with_progress(state, path, query, progress)
+    });
+    Json(Submitted { id })
+}
+
+async fn job_status(
+    axum::extract::Path(id): axum::extract::Path<crate::jobs::JobId>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    user: JobUser,
+) -> axum::response::Result<Json<crate::jobs::JobStatus>> {
+    let user = user.as_ref().map(|x| x.0.as_ref());
+    let status = state.jobs.status(id, user).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(status))
+}
+
+async fn cancel_job(
+    axum::extract::Path(id): axum::extract::Path<crate::jobs::JobId>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    user: JobUser,
+) -> StatusCode {
+    let user = user.as_ref().map(|x| x.0.as_ref());
+    if state.jobs.cancel(id, user) {
+        StatusCode::ACCEPTED
+    } else if state.jobs.status(id, user).is_some() {
+        // already finished
+        StatusCode::CONFLICT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+async fn job_status_ws(
+    ws: axum::extract::ws::WebSocketUpgrade,
+    axum::extract::Path(id): axum::extract::Path<crate::jobs::JobId>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    user: JobUser,
+) -> axum::response::Result<Response> {
+    let user = user.as_ref().map(|x| x.0.as_ref());
+    let status = state
+        .jobs
+        .subscribe(id, user)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ws.on_upgrade(move |socket| crate::jobs::stream_status(status, socket)))
+}
@@ -0,0 +1,440 @@
+//! Authentication of the users of the server and limits on what they can request.
+//!
+//! Users are authenticated by a bearer token, either given on the command line or listed in a user file,
+//! through the [`AsyncRequireAuthorizationLayer`] of tower-http.
+//! Without any configured user, every request is anonymous and gets the default limits.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::Request;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use http::{StatusCode, header};
+use hyperast_vcs_git::git::Forge;
+use serde::Deserialize;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tower_http::auth::{AsyncAuthorizeRequest, AsyncRequireAuthorizationLayer};
+
+/// Limits of a user, the missing ones are taken from the defaults given on the command line
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct Limits {
+    /// number of requests of a user processed at the same time
+    pub max_jobs: Option<usize>,
+    /// ceiling of the `max_matches` of queries
+    pub max_matches: Option<u64>,
+    /// ceiling of the `timeout` of queries, in milliseconds
+    pub timeout: Option<u64>,
+    /// the repositories that can be requested, all of them if absent
+    pub repositories: Option<Vec<RepoPattern>>,
+}
+
+impl Limits {
+    fn or(self, defaults: &Limits) -> Limits {
+        Limits {
+            max_jobs: self.max_jobs.or(defaults.max_jobs),
+            max_matches: self.max_matches.or(defaults.max_matches),
+            timeout: self.timeout.or(defaults.timeout),
+            repositories: self.repositories.or_else(|| defaults.repositories.clone()),
+        }
+    }
+}
+
+/// `<forge>/<user>/<name>` where any part can be `*`, eg. `github.com/INRIA/*`
+#[derive(Deserialize, Clone, Debug)]
+#[serde(try_from = "String")]
+pub struct RepoPattern {
+    forge: Option<Forge>,
+    user: Option<String>,
+    name: Option<String>,
+}
+
+impl std::str::FromStr for RepoPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split("/");
+        let (Some(forge), Some(user), Some(name), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!("'{}' should look like <forge>/<user>/<name>", s));
+        };
+        let any = |x: &str| (x != "*").then(|| x.to_string());
+        let forge = match forge {
+            "*" => None,
+            forge => Some(forge.parse()?),
+        };
+        Ok(Self {
+            forge,
+            user: any(user),
+            name: any(name),
+        })
+    }
+}
+
+impl TryFrom<String> for RepoPattern {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl RepoPattern {
+    pub fn matches(&self, forge: Forge, user: &str, name: &str) -> bool {
+        self.forge.is_none_or(|x| x == forge)
+            && self.user.as_ref().is_none_or(|x| x == user)
+            && self.name.as_ref().is_none_or(|x| x == name)
+    }
+}
+
+/// An entry of the user file, a json array of such objects
+#[derive(Deserialize, Clone, Debug)]
+pub struct UserEntry {
+    pub name: String,
+    pub token: String,
+    /// can change what is shared by all users, eg. the metric columns
+    #[serde(default)]
+    pub admin: bool,
+    #[serde(flatten)]
+    pub limits: Limits,
+}
+
+/// `<user>:<token>` as given on the command line
+impl std::str::FromStr for UserEntry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, token) = s
+            .split_once(":")
+            .ok_or_else(|| format!("'{}' should look like <user>:<token>", s))?;
+        Ok(Self {
+            name: name.to_string(),
+            token: token.to_string(),
+            admin: false,
+            limits: Limits::default(),
+        })
+    }
+}
+
+pub fn read_users(path: &Path) -> Result<Vec<UserEntry>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub struct User {
+    pub name: String,
+    pub admin: bool,
+    pub limits: Limits,
+    /// shared by the requests of the user
+    jobs: Option<Arc<Semaphore>>,
+}
+
+impl User {
+    pub(crate) fn new(name: String, admin: bool, limits: Limits) -> Self {
+        let jobs = limits.max_jobs.map(|x| Arc::new(Semaphore::new(x)));
+        Self {
+            name,
+            admin,
+            limits,
+            jobs,
+        }
+    }
+
+    /// Waits for the end of other jobs of the user if it reached its limit
+    pub(crate) async fn acquire_job(&self) -> Option<OwnedSemaphorePermit> {
+        let jobs = self.jobs.clone()?;
+        jobs.acquire_owned().await.ok()
+    }
+
+    pub fn can_fetch(&self, forge: Forge, user: &str, name: &str) -> bool {
+        match &self.limits.repositories {
+            Some(patterns) => patterns.iter().any(|x| x.matches(forge, user, name)),
+            None => true,
+        }
+    }
+}
+
+pub struct Users {
+    /// searched exhaustively, to compare tokens in constant time
+    by_token: Vec<(String, Arc<User>)>,
+    /// only when no user is configured
+    anonymous: Option<Arc<User>>,
+}
+
+impl Default for Users {
+    fn default() -> Self {
+        Self::new(Limits::default(), vec![])
+    }
+}
+
+impl Users {
+    pub fn new(defaults: Limits, entries: Vec<UserEntry>) -> Self {
+        // without users the server is not shared, so the anonymous user administrates it
+        let anonymous = entries
+            .is_empty()
+            .then(|| Arc::new(User::new("anonymous".to_string(), true, defaults.clone())));
+        let by_token = entries
+            .into_iter()
+            .map(|x| {
+                let user = User::new(x.name, x.admin, x.limits.or(&defaults));
+                (x.token, Arc::new(user))
+            })
+            .collect();
+        Self {
+            by_token,
+            anonymous,
+        }
+    }
+
+    fn authenticate(&self, headers: &http::HeaderMap) -> Option<Arc<User>> {
+        if let Some(anonymous) = &self.anonymous {
+            return Some(anonymous.clone());
+        }
+        let token = headers
+            .get(header::AUTHORIZATION)?
+            .to_str()
+            .ok()?
+            .strip_prefix("Bearer ")?;
+        let token = token.trim().as_bytes();
+        let mut found = None;
+        for (t, user) in &self.by_token {
+            if constant_time_eq(t.as_bytes(), token) {
+                found = Some(user.clone());
+            }
+        }
+        found
+    }
+}
+
+/// Does not stop at the first difference, to not leak the length of the common prefix
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Authenticates requests and puts the [`User`] in their extensions, for [`limit`]
+#[derive(Clone)]
+pub struct Authenticator(Arc<Users>);
+
+impl<B: Send + 'static> AsyncAuthorizeRequest<B> for Authenticator {
+    type RequestBody = B;
+    type ResponseBody = Body;
+    type Future = std::future::Ready<Result<http::Request<B>, Response>>;
+
+    fn authorize(&mut self, mut request: http::Request<B>) -> Self::Future {
+        let Some(user) = self.0.authenticate(request.headers()) else {
+            let response = (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                "missing or unknown token",
+            );
+            return std::future::ready(Err(response.into_response()));
+        };
+        request.extensions_mut().insert(user);
+        std::future::ready(Ok(request))
+    }
+}
+
+pub fn authentication(users: Arc<Users>) -> AsyncRequireAuthorizationLayer<Authenticator> {
+    AsyncRequireAuthorizationLayer::new(Authenticator(users))
+}
+
+/// Checks that the requested repository can be fetched by the user,
+/// bounds the number of concurrent requests of the user,
+/// and caps the `max_matches` and `timeout` of queries.
+///
+/// Must be a route layer, to access the parameters of the route.
+pub async fn limit(
+    params: Option<axum::extract::Path<HashMap<String, String>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(user) = request.extensions().get::<Arc<User>>().cloned() else {
+        return next.run(request).await;
+    };
+    let _permit = match &user.jobs {
+        Some(jobs) => match jobs.clone().try_acquire_owned() {
+            Ok(permit) => Some(permit),
+            Err(_) => {
+                let msg = format!("too many requests of {} in progress", user.name);
+                return (StatusCode::TOO_MANY_REQUESTS, msg).into_response();
+            }
+        },
+        None => None,
+    };
+    match check(&user, params, request).await {
+        Ok(request) => next.run(request).await,
+        Err(response) => response,
+    }
+}
+
+/// Same as [`limit`] without bounding the number of concurrent requests,
+/// for the routes of background jobs that wait for their turn instead.
+pub async fn authorize(
+    params: Option<axum::extract::Path<HashMap<String, String>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(user) = request.extensions().get::<Arc<User>>().cloned() else {
+        return next.run(request).await;
+    };
+    match check(&user, params, request).await {
+        Ok(request) => next.run(request).await,
+        Err(response) => response,
+    }
+}
+
+/// The parameters of the routes naming a user and a repository of the forge,
+/// eg. the fork of `/fork/:forge/:user/:name/:other_user/:other_name/:head`
+const REPO_PARAMS: &[(&str, &str)] = &[("user", "name"), ("other_user", "other_name")];
+
+async fn check(
+    user: &User,
+    params: Option<axum::extract::Path<HashMap<String, String>>>,
+    request: Request,
+) -> Result<Request, Response> {
+    if let Some(axum::extract::Path(params)) = params {
+        // unknown forges are rejected by the routes themselves
+        if let Some(Ok(forge)) = params.get("forge").map(|x| x.parse()) {
+            for (owner, name) in REPO_PARAMS {
+                let (Some(owner), Some(name)) = (params.get(*owner), params.get(*name)) else {
+                    continue;
+                };
+                if !user.can_fetch(forge, owner, name) {
+                    let msg = format!("{} cannot access {}/{}", user.name, owner, name);
+                    return Err((StatusCode::FORBIDDEN, msg).into_response());
+                }
+            }
+        }
+    }
+    cap_query(&user.limits, request).await
+}
+
+/// Only lets admins through, for the routes changing what is shared by all users
+pub async fn admin(request: Request, next: Next) -> Response {
+    match request.extensions().get::<Arc<User>>() {
+        Some(user) if !user.admin => {
+            let msg = format!("{} is not an admin", user.name);
+            (StatusCode::FORBIDDEN, msg).into_response()
+        }
+        _ => next.run(request).await,
+    }
+}
+
+/// Bodies of queries are read to cap them, queries and their precomputed patterns are way smaller
+const MAX_QUERY_BODY: usize = 2 * 1024 * 1024;
+
+/// Caps the `max_matches` and `timeout` of bodies looking like [`crate::querying::Content`]
+async fn cap_query(limits: &Limits, request: Request) -> Result<Request, Response> {
+    if limits.max_matches.is_none() && limits.timeout.is_none() {
+        return Ok(request);
+    }
+    let is_json = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|x| x.to_str().ok())
+        .is_some_and(|x| x.starts_with("application/json"));
+    if !is_json {
+        return Ok(request);
+    }
+    let (mut parts, body) = request.into_parts();
+    let bytes = axum::body::to_bytes(body, MAX_QUERY_BODY)
+        .await
+        .map_err(|e| (StatusCode::PAYLOAD_TOO_LARGE, e.to_string()).into_response())?;
+    let mut content = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(content)) if content.contains_key("query") => content,
+        // let the route handle it
+        _ => return Ok(Request::from_parts(parts, Body::from(bytes))),
+    };
+    let mut cap = |key: &str, ceiling: Option<u64>, default: u64| {
+        let Some(ceiling) = ceiling else { return };
+        let value = content.get(key).and_then(|x| x.as_u64()).unwrap_or(default);
+        content.insert(key.to_string(), value.min(ceiling).into());
+    };
+    cap(
+        "max_matches",
+        limits.max_matches,
+        crate::querying::default_max_matches(),
+    );
+    cap(
+        "timeout",
+        limits.timeout,
+        crate::querying::default_timeout(),
+    );
+    let bytes = serde_json::to_vec(&content).unwrap();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Ok(Request::from_parts(parts, Body::from(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authenticate() {
+        let defaults = Limits {
+            repositories: Some(vec!["github.com/INRIA/*".parse().unwrap()]),
+            ..Default::default()
+        };
+        let users = Users::new(defaults, vec!["alice:secret".parse().unwrap()]);
+        let mut headers = http::HeaderMap::new();
+        assert!(users.authenticate(&headers).is_none());
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(users.authenticate(&headers).is_none());
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        let alice = users.authenticate(&headers).unwrap();
+        assert_eq!(alice.name, "alice");
+        assert!(alice.can_fetch(Forge::Github, "INRIA", "spoon"));
+        assert!(!alice.can_fetch(Forge::Github, "google", "gson"));
+        assert!(!alice.can_fetch(Forge::Gitlab, "INRIA", "spoon"));
+    }
+
+    #[test]
+    fn test_anonymous() {
+        let users = Users::default();
+        let anonymous = users.authenticate(&http::HeaderMap::new()).unwrap();
+        assert!(anonymous.can_fetch(Forge::Gitlab, "INRIA", "spoon"));
+        assert!(anonymous.jobs.is_none());
+        assert!(anonymous.admin);
+    }
+
+    #[test]
+    fn test_token_comparison() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secrets"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[tokio::test]
+    async fn test_check_fork() {
+        let defaults = Limits {
+            repositories: Some(vec!["github.com/INRIA/*".parse().unwrap()]),
+            ..Default::default()
+        };
+        let users = Users::new(defaults, vec!["alice:secret".parse().unwrap()]);
+        let alice = &users.by_token[0].1;
+        assert!(!alice.admin);
+        let params = |other_user: &str| {
+            let params = [
+                ("forge", "github.com"),
+                ("user", "INRIA"),
+                ("name", "spoon"),
+                ("other_user", other_user),
+                ("other_name", "spoon"),
+            ];
+            let params = params.map(|(k, v)| (k.to_string(), v.to_string()));
+            Some(axum::extract::Path(params.into_iter().collect()))
+        };
+        let request = || Request::new(Body::empty());
+        assert!(check(alice, params("INRIA"), request()).await.is_ok());
+        let response = check(alice, params("google"), request()).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,215 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use backend::offline;
+use clap::{Parser, Subcommand};
+use hyperast_vcs_git::processing::RepoConfig;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser)]
+/// Analyze the history of a local git repository without running the server
+///
+/// set the env variable RUST_LOG=debug to display logs during computation
+struct Cli {
+    /// The local repository, processed in place without fetching it
+    #[clap(short, long, default_value = ".")]
+    repository: PathBuf,
+    /// The configuration used to process the repository, eg. java, gradle, cpp, c, python, rust
+    #[clap(short, long, default_value = "java")]
+    config: RepoConfig,
+    /// Print the results as JSON instead of text
+    #[clap(long, global = true)]
+    json: bool,
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Count the matches of a tree-sitter query on commits
+    Query {
+        /// The start commit, or a range like A..B
+        commit: String,
+        /// File containing the query, - to read it from stdin
+        query: PathBuf,
+        /// The language of the queried files, defaults to the one of the configuration
+        #[clap(short, long)]
+        language: Option<String>,
+        /// File containing precomputed patterns
+        #[clap(long)]
+        precomp: Option<PathBuf>,
+        /// Number of commits to query, the whole range by default
+        #[clap(short = 'n', long, default_value_t = usize::MAX)]
+        commits: usize,
+        /// Maximum number of matches per pattern and commit
+        #[clap(long, default_value_t = 500)]
+        max_matches: u64,
+        /// Timeout per commit, in milliseconds
+        #[clap(long, default_value_t = 1000)]
+        timeout: u64,
+        /// Also give the location of the captured nodes, only with --json
+        #[clap(long)]
+        captures: bool,
+    },
+    /// List the nodes deleted and added between two commits
+    Diff { before: String, after: String },
+    /// Track a piece of code through the history
+    Track {
+        commit: String,
+        /// The file containing the tracked code
+        file: String,
+        /// Start offset of the tracked code in the file
+        #[clap(long)]
+        start: Option<usize>,
+        /// End offset of the tracked code in the file
+        #[clap(long)]
+        end: Option<usize>,
+        /// Stop tracking at this commit
+        #[clap(long)]
+        before: Option<String>,
+    },
+    /// Measure the size, height and number of lines of commits
+    Metrics {
+        /// The start commit, or a range like A..B
+        commit: String,
+        /// Number of commits to process
+        #[clap(short, long, default_value_t = 10)]
+        depth: usize,
+    },
+    /// Print the subtree of a commit, as stored in the HyperAST
+    View {
+        commit: String,
+        /// The offsets of the subtree from the root of the commit, like 0/2/1
+        path: Option<String>,
+    },
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Cli::parse();
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::builder()
+                .with_default_directive(tracing::level_filters::LevelFilter::OFF.into())
+                .from_env_lossy(),
+        )
+        .try_init()
+        .unwrap();
+
+    let state = Arc::new(backend::AppState::default());
+    let repo = offline::open(&state, &args.repository, args.config)?;
+    match args.command {
+        Commands::Query {
+            commit,
+            query,
+            language,
+            precomp,
+            commits,
+            max_matches,
+            timeout,
+            captures,
+        } => {
+            let language = language
+                .or_else(|| offline::language(args.config).map(|x| x.to_string()))
+                .ok_or_else(|| "missing language for this configuration, use --language")?;
+            let query = if query.as_os_str() == "-" {
+                std::io::read_to_string(std::io::stdin())?
+            } else {
+                std::fs::read_to_string(query)?
+            };
+            let precomp = precomp.map(std::fs::read_to_string).transpose()?;
+            let content = offline::Content {
+                language,
+                query,
+                precomp,
+                commits,
+                max_matches,
+                timeout,
+                captures,
+                depth: None,
+                since: None,
+                until: None,
+            };
+            let results = offline::query(state, &repo, &commit, content).map_err(failed)?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&results)?);
+                return Ok(());
+            }
+            for result in results.results {
+                match result {
+                    Ok(x) => println!("{}\t{}", x.commit, tabulate(&x.inner.result)),
+                    Err(offline::MatchingError::TimeOut(x)) => {
+                        println!("{}\ttimeout\t{}", x.commit, tabulate(&x.inner.result))
+                    }
+                    Err(offline::MatchingError::MaxMatches(x)) => {
+                        println!("{}\tmax matches\t{}", x.commit, tabulate(&x.inner.result))
+                    }
+                }
+            }
+        }
+        Commands::Diff { before, after } => {
+            let changes = offline::diff(state, &repo, &before, &after)?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&changes)?);
+            } else {
+                println!(
+                    "{}\t{}\t-{}\t+{}",
+                    changes.before,
+                    changes.after,
+                    changes.deletions.len(),
+                    changes.additions.len()
+                );
+            }
+        }
+        Commands::Track {
+            commit,
+            file,
+            start,
+            end,
+            before,
+        } => {
+            let query = offline::TrackingQuery {
+                start,
+                end,
+                before,
+                depth: None,
+                since: None,
+                until: None,
+                flags: Default::default(),
+            };
+            let result = offline::track(state, &repo, &commit, &file, query).map_err(failed)?;
+            // the tracked pieces of code are too structured to be printed as text
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        Commands::Metrics { commit, depth } => {
+            let metrics = offline::metrics(state, &repo, &commit, depth)?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&metrics)?);
+                return Ok(());
+            }
+            for x in metrics {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    x.commit, x.processing_time, x.size, x.height, x.line_count
+                );
+            }
+        }
+        Commands::View { commit, path } => {
+            let view = offline::view(state, &repo, &commit, path)?;
+            // the view is too structured to be printed as text
+            println!("{}", serde_json::to_string_pretty(&view)?);
+        }
+    }
+    Ok(())
+}
+
+fn tabulate(counts: &[u64]) -> String {
+    let counts: Vec<_> = counts.iter().map(|x| x.to_string()).collect();
+    counts.join("\t")
+}
+
+/// The errors of the routes are only serializable
+fn failed(err: impl serde::Serialize) -> Box<dyn std::error::Error> {
+    serde_json::to_string(&err)
+        .unwrap_or_else(|e| e.to_string())
+        .into()
+}
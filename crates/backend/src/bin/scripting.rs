@@ -68,7 +68,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     .repositories
                     .write()
                     .unwrap()
-                    .register_config(repo_spec.clone(), config);
+                    .register_config(repo_spec.clone(), config)?;
                 let repo = state
                     .repositories
                     .read()
@@ -115,7 +115,7 @@ fn scripting(
         .repositories
         .write()
         .unwrap()
-        .register_config_with_prepro(repo_spec.clone(), config, script.into());
+        .register_config_with_prepro(repo_spec.clone(), config, script.into())?;
     let repo = state
         .repositories
         .read()
@@ -18,17 +18,17 @@ use crate::{matching, no_space};
 pub struct SrcChanges {
     user: String,
     name: String,
-    commit: String,
+    pub(crate) commit: String,
     /// Global position of deleted elements
-    deletions: Vec<u32>, // TODO diff encode
+    pub(crate) deletions: Vec<u32>, // TODO diff encode
 }
 #[derive(Deserialize, Serialize, Debug)]
 pub struct DstChanges {
     user: String,
     name: String,
-    commit: String,
+    pub(crate) commit: String,
     /// Global position of added elements
-    additions: Vec<u32>, // TODO diff encode
+    pub(crate) additions: Vec<u32>, // TODO diff encode
 }
 
 pub(crate) fn added_deleted(
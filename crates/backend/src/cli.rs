@@ -1,4 +1,4 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::PathBuf};
 
 use clap::Parser;
 
@@ -22,8 +22,64 @@ pub struct Options {
     ///
     /// use the following syntax: <forge>/<user>/<name>:<config>
     /// example: github.com/INRIA/spoon:Java
-    #[clap(short, long)]
+    #[clap(short = 'r', long = "repository")]
+    repository_raw: Vec<String>,
+
+    /// repositories parsed after the registration of the forges
+    #[clap(skip)]
     pub repository: Vec<RepoConfig>,
+
+    /// allow a self-hosted forge, reached in https (multiple uses)
+    ///
+    /// example: git.example.org, then used in routes as /query/git.example.org/<user>/<name>/...
+    #[clap(long)]
+    pub forge: Vec<String>,
+
+    /// directory of local bare repositories, laid out as <dir>/<user>/<name>
+    ///
+    /// used in routes with the `file` forge, eg. /query/file/<user>/<name>/...
+    #[clap(long)]
+    pub local_forge: Option<PathBuf>,
+
+    /// directory where the stores are checkpointed
+    ///
+    /// the checkpoint is restored at startup, what each processing adds is appended to its log,
+    /// and a full checkpoint is written back on shutdown
+    #[clap(long)]
+    pub store_dir: Option<PathBuf>,
+
+    /// accept requests bearing this token, given as <user>:<token> (multiple uses)
+    ///
+    /// without any token nor user file, requests are anonymous
+    #[clap(long = "token")]
+    tokens: Vec<String>,
+
+    /// json file listing users, eg. [{"name": "alice", "token": "...", "max_jobs": 2}],
+    /// each user can also override the limits below
+    #[clap(long)]
+    users: Option<PathBuf>,
+
+    /// number of requests processed at the same time per user, unlimited by default
+    #[clap(long)]
+    max_jobs: Option<usize>,
+
+    /// ceiling of the max_matches of queries
+    #[clap(long)]
+    max_matches: Option<u64>,
+
+    /// ceiling of the timeout of queries, in milliseconds
+    #[clap(long)]
+    max_timeout: Option<u64>,
+
+    /// allow requests on repositories matching <forge>/<user>/<name>, where parts can be * (multiple uses)
+    ///
+    /// all repositories are allowed if none is given
+    #[clap(long = "allow")]
+    allowed: Vec<String>,
+
+    /// users and limits parsed after the registration of the forges
+    #[clap(skip)]
+    pub users_limits: std::sync::Arc<crate::auth::Users>,
 }
 
 pub struct RepoConfig {
@@ -44,7 +100,38 @@ impl std::str::FromStr for RepoConfig {
 }
 
 pub fn parse() -> Options {
-    let opts = Options::parse();
+    use clap::CommandFactory;
+    let mut opts = Options::parse();
+
+    for host in &opts.forge {
+        if let Err(e) = hyperast_vcs_git::git::Forge::register_self_hosted(host) {
+            Options::command()
+                .error(clap::ErrorKind::InvalidValue, e)
+                .exit()
+        }
+    }
+    if let Some(dir) = &opts.local_forge {
+        if let Err(e) = hyperast_vcs_git::git::Forge::set_local_root(dir) {
+            Options::command()
+                .error(clap::ErrorKind::InvalidValue, e)
+                .exit()
+        }
+    }
+    opts.repository = std::mem::take(&mut opts.repository_raw)
+        .iter()
+        .map(|x| x.parse())
+        .collect::<Result<_, String>>()
+        .unwrap_or_else(|e| {
+            Options::command()
+                .error(clap::ErrorKind::InvalidValue, e)
+                .exit()
+        });
+
+    opts.users_limits = std::sync::Arc::new(users(&opts).unwrap_or_else(|e| {
+        Options::command()
+            .error(clap::ErrorKind::InvalidValue, e)
+            .exit()
+    }));
 
     let debug_level = match opts.verbose {
         0 => log::Level::Info,
@@ -57,6 +144,30 @@ pub fn parse() -> Options {
     opts
 }
 
+fn users(opts: &Options) -> Result<crate::auth::Users, String> {
+    let repositories = if opts.allowed.is_empty() {
+        None
+    } else {
+        let patterns = opts.allowed.iter().map(|x| x.parse());
+        Some(patterns.collect::<Result<_, _>>()?)
+    };
+    let defaults = crate::auth::Limits {
+        max_jobs: opts.max_jobs,
+        max_matches: opts.max_matches,
+        timeout: opts.max_timeout,
+        repositories,
+    };
+    let mut entries = opts
+        .tokens
+        .iter()
+        .map(|x| x.parse())
+        .collect::<Result<Vec<crate::auth::UserEntry>, _>>()?;
+    if let Some(path) = &opts.users {
+        entries.extend(crate::auth::read_users(path)?);
+    }
+    Ok(crate::auth::Users::new(defaults, entries))
+}
+
 fn default_log_config(debug_level: log::Level) {
     // TODO should just leverage the env variable... I was just playing with clap :/
     if debug_level == log::Level::Trace {
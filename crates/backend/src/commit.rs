@@ -1,12 +1,13 @@
 use axum::Json;
 // use hyperast::types::LabelStore;
-use hyperast_vcs_git::git::{fetch_github_repository, retrieve_commit};
+use hyperast_vcs_git::git::retrieve_commit;
 use serde::{Deserialize, Serialize};
 
 use crate::SharedState;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Param {
+    pub forge: hyperast_vcs_git::git::Forge,
     pub user: String,
     pub name: String,
     /// either a commit id or a tag
@@ -35,11 +36,12 @@ pub struct Metadata {
 // TODO prefetch a list of parent ids in power of 2 [2,4,8,16,32]
 pub fn commit_metadata(_state: SharedState, path: Param) -> Result<Json<Metadata>, String> {
     let Param {
+        forge,
         user,
         name,
         version,
     } = path.clone();
-    let repo = fetch_github_repository(&format!("{}/{}", user, name));
+    let repo = forge.try_repo(&user, &name)?.fetch();
     log::debug!("done cloning {user}/{name}");
     let commit = retrieve_commit(&repo, &version);
     if let Err(err) = &commit {
@@ -111,6 +113,7 @@ impl From<BuffOut> for String {
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct ParamRemote {
+    pub forge: hyperast_vcs_git::git::Forge,
     pub user: String,
     pub name: String,
     pub other_user: String,
@@ -120,14 +123,16 @@ pub struct ParamRemote {
 
 pub fn add_remote(_state: SharedState, path: ParamRemote) -> Result<(), String> {
     let ParamRemote {
+        forge,
         user,
         name,
         other_user,
         other_name,
         head,
     } = path.clone();
-    let repo = fetch_github_repository(&format!("{}/{}", user, name));
-    let remote = format!("{}{}/{}", "https://github.com/", user, name);
+    let repo_spec = forge.try_repo(user, name)?;
+    let repo = repo_spec.fetch();
+    let remote = repo_spec.url();
     log::error!("{:?}", &remote);
     let other = format!("{}_{}", other_user, other_name);
     let r = repo.remote(&other, &remote);
@@ -0,0 +1,200 @@
+use axum::Json;
+use hyperast_vcs_git::maven::Coordinates;
+use hyperast_vcs_git::maven_dependencies::{self, DependencyChanges, DependencyGraph};
+use hyperast_vcs_git::processing::RepoConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::SharedState;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Param {
+    pub forge: hyperast_vcs_git::git::Forge,
+    pub user: String,
+    pub name: String,
+    /// either a commit id or a tag
+    pub commit: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct DiffParam {
+    pub forge: hyperast_vcs_git::git::Forge,
+    pub user: String,
+    pub name: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Module {
+    /// relative to the root of the repository
+    path: String,
+    /// groupId:artifactId:version
+    coordinates: String,
+    parent: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Edge {
+    from: String,
+    to: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    classifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    optional: bool,
+    /// the index of the depended upon module, absent for external dependencies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    module: Option<usize>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Graph {
+    commit: String,
+    modules: Vec<Module>,
+    edges: Vec<Edge>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Upgrade {
+    from: String,
+    before: String,
+    after: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Changes {
+    before: String,
+    after: String,
+    added: Vec<Edge>,
+    removed: Vec<Edge>,
+    upgraded: Vec<Upgrade>,
+}
+
+fn coordinates(x: &Coordinates) -> String {
+    x.to_string()
+}
+
+impl From<&maven_dependencies::Edge> for Edge {
+    fn from(x: &maven_dependencies::Edge) -> Self {
+        Self {
+            from: coordinates(&x.from),
+            to: coordinates(&x.to),
+            kind: x.kind.clone(),
+            classifier: x.classifier.clone(),
+            scope: x.scope.clone(),
+            optional: x.optional,
+            module: x.module,
+        }
+    }
+}
+
+/// Processes `commit` (with the maven configuration if the repository is not configured yet)
+/// and extracts the dependency graph from its maven modules,
+/// fails if the commit is not a maven project
+fn graph(
+    state: &SharedState,
+    repo: &mut hyperast_vcs_git::processing::ConfiguredRepo2,
+    commit: &str,
+) -> Result<(hyperast_vcs_git::git::Oid, DependencyGraph), String> {
+    let commits = crate::utils::handle_pre_processing(state, repo, "", commit, 1)
+        .map_err(|e| e.to_string())?;
+    let oid = *commits
+        .first()
+        .ok_or_else(|| format!("no commit found for {}", commit))?;
+    let repositories = state.repositories.read().unwrap();
+    let commit = repositories
+        .get_commit(&repo.config, &oid)
+        .ok_or_else(|| format!("missing processed commit {}", oid))?;
+    let stores = &repositories.processor.main_stores;
+    let graph = DependencyGraph::compute(stores, commit.ast_root).ok_or_else(|| {
+        format!(
+            "{} is not a maven project, there is no pom.xml at its root",
+            oid
+        )
+    })?;
+    Ok((oid, graph))
+}
+
+fn configured_repo(
+    state: &SharedState,
+    forge: hyperast_vcs_git::git::Forge,
+    user: String,
+    name: String,
+) -> Result<hyperast_vcs_git::processing::ConfiguredRepo2, String> {
+    let repo_spec = forge.repo(user, name);
+    let repo = state
+        .repositories
+        .read()
+        .unwrap()
+        .get_config(repo_spec.clone());
+    let repo = match repo {
+        Some(repo) => repo,
+        None => {
+            let configs = &mut state.repositories.write().unwrap();
+            log::warn!("missing config for {}, using maven", repo_spec);
+            configs
+                .register_config(repo_spec, RepoConfig::JavaMaven)
+                .map_err(|e| e.to_string())?
+        }
+    };
+    let repo = repo.fetch();
+    log::info!("done cloning {}", repo.spec);
+    Ok(repo)
+}
+
+pub fn dependencies(state: SharedState, path: Param) -> Result<Json<Graph>, String> {
+    let Param {
+        forge,
+        user,
+        name,
+        commit,
+    } = path;
+    let mut repo = configured_repo(&state, forge, user, name)?;
+    let (oid, graph) = graph(&state, &mut repo, &commit)?;
+    let modules = graph
+        .modules
+        .iter()
+        .map(|x| Module {
+            path: x.path.clone(),
+            coordinates: coordinates(&x.coordinates),
+            parent: x.parent.as_ref().map(coordinates),
+        })
+        .collect();
+    let edges = graph.edges.iter().map(Edge::from).collect();
+    Ok(Json(Graph {
+        commit: oid.to_string(),
+        modules,
+        edges,
+    }))
+}
+
+pub fn dependency_changes(state: SharedState, path: DiffParam) -> Result<Json<Changes>, String> {
+    let DiffParam {
+        forge,
+        user,
+        name,
+        before,
+        after,
+    } = path;
+    let mut repo = configured_repo(&state, forge, user, name)?;
+    let (before, graph_before) = graph(&state, &mut repo, &before)?;
+    let (after, graph_after) = graph(&state, &mut repo, &after)?;
+    let changes = DependencyChanges::compute(&graph_before, &graph_after);
+    Ok(Json(Changes {
+        before: before.to_string(),
+        after: after.to_string(),
+        added: changes.added.iter().map(Edge::from).collect(),
+        removed: changes.removed.iter().map(Edge::from).collect(),
+        upgraded: changes
+            .upgraded
+            .iter()
+            .map(|x| Upgrade {
+                from: coordinates(&x.from),
+                before: coordinates(&x.before),
+                after: coordinates(&x.after),
+            })
+            .collect(),
+    }))
+}
@@ -0,0 +1,369 @@
+//! Edit scripts between two commits, computed by one of the algorithms of hyper_diff.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use axum::Json;
+use hyper_diff::actions::action_vec::ActionsVec;
+use hyper_diff::actions::script_generator2::{Act, ScriptGenerator, SimpleAction};
+use hyper_diff::algorithms::{self, DiffResult};
+use hyper_diff::decompressed_tree_store::bfs_wrapper::SimpleBfsMapper;
+use hyper_diff::decompressed_tree_store::{ShallowDecompressedTreeStore, complete_post_order_ref};
+use hyper_diff::matchers::mapping_store::{MappingStore, VecStore};
+use hyper_diff::matchers::{Decompressible, Mapper, Mapping};
+use hyper_diff::tree::tree_path::CompressedTreePath;
+use hyperast::store::defaults::{LabelIdentifier, NodeIdentifier};
+use hyperast::types::{HyperType, LabelStore as _, WithChildren as _, WithStats};
+use hyperast_vcs_git::preprocessed::child_at_path;
+use serde::{Deserialize, Serialize};
+
+use crate::SharedState;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Param {
+    pub forge: hyperast_vcs_git::git::Forge,
+    pub user: String,
+    pub name: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct DiffQuery {
+    /// restricts the diff to a directory or a file, eg. `src/main/java/A.java`
+    pub path: Option<String>,
+    #[serde(default)]
+    pub algorithm: Algorithm,
+}
+
+/// The matching algorithms of [`hyper_diff::algorithms`]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Algorithm {
+    /// lazy gumtree, its mappings and decompressions are cached in the state of the server
+    #[default]
+    Lazy,
+    Gumtree,
+    GumtreeSimple,
+    GumtreeHybrid,
+    GumtreePartialLazy,
+    ChangeDistiller,
+    ChangeDistillerLazy,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Location {
+    /// relative to the root of the repository
+    file: String,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Action {
+    /// delete, insert, update, move or move_update
+    action: &'static str,
+    /// the type of the changed node
+    r#type: &'static str,
+    /// the node in `before`, absent for insertions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    src: Option<Location>,
+    /// the node in `after`, absent for deletions and for updates of nodes that are not moved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dst: Option<Location>,
+    /// the new label of updated nodes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EditScript {
+    before: String,
+    after: String,
+    algorithm: Algorithm,
+    /// number of mapped nodes
+    mappings: usize,
+    /// in seconds
+    compute_time: f64,
+    actions: Vec<Action>,
+}
+
+type A = SimpleAction<LabelIdentifier, CompressedTreePath<u16>, NodeIdentifier>;
+
+type Stores<'a> = hyperast::store::SimpleStores<
+    hyperast_vcs_git::TStore,
+    hyperast_vcs_git::no_space::NoSpaceNodeStoreWrapper<'a>,
+    &'a hyperast::store::labels::LabelStore,
+>;
+
+/// Processes `commit` and returns its id
+fn pre_process(
+    state: &SharedState,
+    repo: &mut hyperast_vcs_git::processing::ConfiguredRepo2,
+    commit: &str,
+) -> Result<hyperast_vcs_git::git::Oid, String> {
+    let commits = crate::utils::handle_pre_processing(state, repo, "", commit, 1)
+        .map_err(|e| e.to_string())?;
+    commits
+        .first()
+        .copied()
+        .ok_or_else(|| format!("no commit found for {}", commit))
+}
+
+/// The edit script between `before` and `after`, the whole commits unless restricted to a path.
+///
+/// Except for the lazy gumtree, the matching algorithms decompress the compared trees completely,
+/// so they should be restricted to a path in large repositories.
+pub fn diff(state: SharedState, path: Param, query: DiffQuery) -> Result<Json<EditScript>, String> {
+    let Param {
+        forge,
+        user,
+        name,
+        before,
+        after,
+    } = path;
+    let repo_spec = forge.repo(user, name);
+    let repo = state
+        .repositories
+        .read()
+        .unwrap()
+        .get_config(repo_spec)
+        .ok_or_else(|| "missing config for repository".to_string())?;
+    let mut repo = repo.fetch();
+    log::info!("done cloning {}", repo.spec);
+    let before = pre_process(&state, &mut repo, &before)?;
+    let after = pre_process(&state, &mut repo, &after)?;
+
+    let repositories = state.repositories.read().unwrap();
+    let root = |oid: &hyperast_vcs_git::git::Oid| {
+        repositories
+            .get_commit(&repo.config, oid)
+            .map(|x| x.ast_root)
+            .ok_or_else(|| format!("missing processed commit {}", oid))
+    };
+    let (mut src_tr, mut dst_tr) = (root(&before)?, root(&after)?);
+    let with_spaces_stores = &repositories.processor.main_stores;
+    let stores = &hyperast_vcs_git::no_space::as_nospaces2(with_spaces_stores);
+
+    // paths of the locations are computed from the compared subtrees, whose name is included
+    let mut prefix = PathBuf::new();
+    if let Some(path) = &query.path {
+        let at = |tr, oid| {
+            child_at_path(with_spaces_stores, tr, path.split("/"))
+                .ok_or_else(|| format!("{} not found in {}", path, oid))
+        };
+        src_tr = at(src_tr, before)?;
+        dst_tr = at(dst_tr, after)?;
+        prefix = Path::new(path.trim_matches('/'))
+            .parent()
+            .map_or_else(PathBuf::new, Path::to_path_buf);
+    }
+
+    let now = Instant::now();
+    let (mappings, actions) = if src_tr == dst_tr {
+        (stores.node_store.resolve(src_tr).size(), ActionsVec(vec![]))
+    } else {
+        match query.algorithm {
+            Algorithm::Lazy => lazy(&state, stores, src_tr, dst_tr)?,
+            Algorithm::Gumtree => script(algorithms::gumtree::diff(stores, &src_tr, &dst_tr))?,
+            Algorithm::GumtreeSimple => {
+                script(algorithms::gumtree_simple::diff(stores, &src_tr, &dst_tr))?
+            }
+            Algorithm::GumtreeHybrid => {
+                script(algorithms::gumtree_hybrid::diff(stores, &src_tr, &dst_tr))?
+            }
+            Algorithm::GumtreePartialLazy => script(algorithms::gumtree_partial_lazy::diff(
+                stores, &src_tr, &dst_tr,
+            ))?,
+            Algorithm::ChangeDistiller => {
+                script(algorithms::change_distiller::diff(stores, &src_tr, &dst_tr))?
+            }
+            Algorithm::ChangeDistillerLazy => script(algorithms::change_distiller_lazy::diff(
+                stores, &src_tr, &dst_tr,
+            ))?,
+        }
+    };
+    let compute_time = now.elapsed().as_secs_f64();
+    log::info!(
+        "{} actions between {} and {} in {}s",
+        actions.0.len(),
+        before,
+        after,
+        compute_time
+    );
+
+    let locate = |tr: NodeIdentifier, path: &CompressedTreePath<u16>| {
+        // converting a path that does not exist in `tr` would panic
+        let mut x = tr;
+        for i in path.iter() {
+            x = stores.node_store.resolve(x).child(&i).ok_or_else(|| {
+                format!(
+                    "action at {:?} out of the compared trees",
+                    path.iter().collect::<Vec<_>>()
+                )
+            })?;
+        }
+        let (path, _) =
+            hyperast::position::path_with_spaces(tr, &mut path.iter(), with_spaces_stores);
+        let (pos, id) =
+            hyperast::position::compute_position(tr, &mut path.iter().copied(), with_spaces_stores);
+        let t = hyperast::types::HyperAST::resolve_type(with_spaces_stores, &id);
+        let location = Location {
+            file: prefix.join(pos.file()).to_string_lossy().to_string(),
+            start: pos.range().start,
+            end: pos.range().end,
+        };
+        Ok::<_, String>((t.as_static_str(), location))
+    };
+    let label = |l: &LabelIdentifier| Some(stores.label_store.resolve(l).to_string());
+    let mut result = Vec::with_capacity(actions.0.len());
+    // the update of a moved node is located in `after`, it directly follows the move
+    let mut previous: Option<&A> = None;
+    for a in actions.0.iter() {
+        let action = match &a.action {
+            Act::Delete {} => {
+                let (t, src) = locate(src_tr, &a.path.ori)?;
+                Action::new("delete", t, Some(src), None, None)
+            }
+            Act::Insert { .. } => {
+                let (t, dst) = locate(dst_tr, &a.path.ori)?;
+                Action::new("insert", t, None, Some(dst), None)
+            }
+            Act::Update { new } => match previous.and_then(|x| match &x.action {
+                Act::Move { from } if x.path.ori == a.path.ori => Some(from),
+                _ => None,
+            }) {
+                Some(from) => {
+                    let (t, src) = locate(src_tr, &from.ori)?;
+                    let (_, dst) = locate(dst_tr, &a.path.ori)?;
+                    Action::new("update", t, Some(src), Some(dst), label(new))
+                }
+                None => {
+                    let (t, src) = locate(src_tr, &a.path.ori)?;
+                    Action::new("update", t, Some(src), None, label(new))
+                }
+            },
+            Act::Move { from } => {
+                let (t, src) = locate(src_tr, &from.ori)?;
+                let (_, dst) = locate(dst_tr, &a.path.ori)?;
+                Action::new("move", t, Some(src), Some(dst), None)
+            }
+            Act::MovUpd { from, new } => {
+                let (t, src) = locate(src_tr, &from.ori)?;
+                let (_, dst) = locate(dst_tr, &a.path.ori)?;
+                Action::new("move_update", t, Some(src), Some(dst), label(new))
+            }
+        };
+        previous = Some(a);
+        result.push(action);
+    }
+
+    Ok(Json(EditScript {
+        before: before.to_string(),
+        after: after.to_string(),
+        algorithm: query.algorithm,
+        mappings,
+        compute_time,
+        actions: result,
+    }))
+}
+
+impl Action {
+    fn new(
+        action: &'static str,
+        r#type: &'static str,
+        src: Option<Location>,
+        dst: Option<Location>,
+        label: Option<String>,
+    ) -> Self {
+        Self {
+            action,
+            r#type,
+            src,
+            dst,
+            label,
+        }
+    }
+}
+
+fn script<HAST, DS, DD, MD: Clone>(
+    result: DiffResult<A, Mapper<HAST, DS, DD, VecStore<u32>>, MD>,
+) -> Result<(usize, ActionsVec<A>), String> {
+    let mappings = result.mapper.mapping.mappings.len();
+    let actions = result
+        .actions
+        .ok_or_else(|| "failed to compute the edit script".to_string())?;
+    Ok((mappings, actions))
+}
+
+/// Same matching as the tracking, reusing the cached decompressions and mappings
+fn lazy(
+    state: &SharedState,
+    stores: &Stores,
+    src_tr: NodeIdentifier,
+    dst_tr: NodeIdentifier,
+) -> Result<(usize, ActionsVec<A>), String> {
+    let binding = crate::utils::bind_tree_pair(&state.partial_decomps, &src_tr, &dst_tr);
+    let mapped = match state.mappings_alone.entry((src_tr, dst_tr)) {
+        dashmap::mapref::entry::Entry::Occupied(entry) => entry.into_ref().downgrade(),
+        dashmap::mapref::entry::Entry::Vacant(entry) => {
+            let mut locked = binding.lock();
+            let (src_arena, dst_arena) = locked.as_mut(stores);
+            let mut mapper = Mapper {
+                hyperast: stores,
+                mapping: Mapping {
+                    src_arena: Decompressible {
+                        hyperast: stores,
+                        decomp: src_arena,
+                    },
+                    dst_arena: Decompressible {
+                        hyperast: stores,
+                        decomp: dst_arena,
+                    },
+                    mappings: VecStore::default(),
+                },
+            };
+            mapper.mapping.mappings.topit(
+                mapper.mapping.src_arena.len(),
+                mapper.mapping.dst_arena.len(),
+            );
+            crate::matching::full2(&mut mapper);
+            let mappings = mapper.mappings.clone();
+            entry
+                .insert((crate::MappingStage::Bottomup, mappings))
+                .downgrade()
+        }
+    };
+    let mut locked = binding.lock();
+    let (src_arena, dst_arena) = locked.as_mut(stores);
+    // the script generator needs complete trees
+    let mut src_arena = Decompressible {
+        hyperast: stores,
+        decomp: src_arena,
+    };
+    src_arena.complete_subtree(&src_arena.root());
+    let src_arena = complete_post_order_ref::CompletePostOrder::from(&*src_arena.decomp);
+    let mut dst_arena = Decompressible {
+        hyperast: stores,
+        decomp: dst_arena,
+    };
+    dst_arena.complete_subtree(&dst_arena.root());
+    let dst_arena = complete_post_order_ref::CompletePostOrder::from(&*dst_arena.decomp);
+    let mapping = Mapping {
+        src_arena: Decompressible {
+            hyperast: stores,
+            decomp: src_arena,
+        },
+        // the dst side has to be traversed in bfs
+        dst_arena: SimpleBfsMapper::with_store(
+            stores,
+            Decompressible {
+                hyperast: stores,
+                decomp: dst_arena,
+            },
+        ),
+        mappings: mapped.1.clone(),
+    };
+    let actions = ScriptGenerator::compute_actions(stores, &mapping)?;
+    Ok((mapping.mappings.len(), actions))
+}
@@ -6,21 +6,23 @@ use crate::{utils, SharedState};
 
 #[derive(Deserialize, Clone, Debug)]
 pub struct FetchFileParam {
-    user: String,
-    name: String,
-    commit: String,
-    file: String,
+    pub(crate) forge: hyperast_vcs_git::git::Forge,
+    pub(crate) user: String,
+    pub(crate) name: String,
+    pub(crate) commit: String,
+    pub(crate) file: String,
 }
 
 pub fn from_hyperast(state: SharedState, path: FetchFileParam) -> Result<String, String> {
     let now = Instant::now();
     let FetchFileParam {
+        forge,
         user,
         name,
         commit,
         file,
     } = path.clone();
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo = state
         .repositories
         .write()
@@ -0,0 +1,444 @@
+//! Long analyses run in the background.
+//!
+//! Submitting an analysis gives a job id,
+//! then its progress can be polled or followed with a websocket, and the job can be cancelled.
+//! Successful results are cached by repository, commits and content of the request,
+//! so submitting the same analysis again on the same commits directly gives a finished job.
+//!
+//! Jobs are only visible to the user that submitted them.
+
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::extract::ws::{Message, WebSocket};
+use dashmap::DashMap;
+use hyperast_vcs_git::git::{Oid, RevRange};
+use serde::Serialize;
+use tokio::sync::watch;
+
+use crate::SharedState;
+
+pub type JobId = u64;
+
+/// finished jobs are forgotten once there are more jobs than that
+const MAX_JOBS: u64 = 1024;
+
+/// cached results are forgotten after that time
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// the oldest cached result is forgotten when there are more than that
+const MAX_CACHED: usize = 256;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum State {
+    /// waiting for other jobs of the same user to finish
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl State {
+    fn is_finished(&self) -> bool {
+        matches!(self, State::Done | State::Failed | State::Cancelled)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct JobStatus {
+    pub id: JobId,
+    /// query, script or track
+    pub kind: &'static str,
+    pub state: State,
+    pub commits_processed: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_commit: Option<String>,
+    /// only counted by queries
+    pub matches: u64,
+    /// the result comes from a previous job
+    pub cached: bool,
+    /// the response of the corresponding route, once finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Arc<serde_json::Value>>,
+}
+
+pub(crate) struct Job {
+    status: watch::Sender<JobStatus>,
+    cancelled: AtomicBool,
+    /// name of the user that submitted the job, if authentication is enabled
+    owner: Option<String>,
+}
+
+impl Job {
+    fn new(id: JobId, kind: &'static str, owner: Option<String>) -> Self {
+        let status = JobStatus {
+            id,
+            kind,
+            state: State::Queued,
+            commits_processed: 0,
+            current_commit: None,
+            matches: 0,
+            cached: false,
+            result: None,
+        };
+        Self {
+            status: watch::channel(status).0,
+            cancelled: AtomicBool::new(false),
+            owner,
+        }
+    }
+
+    fn finish(&self, state: State, result: Option<Arc<serde_json::Value>>) {
+        self.status.send_modify(|x| {
+            x.state = state;
+            if x.current_commit.take().is_some() {
+                x.commits_processed += 1;
+            }
+            x.result = result;
+        });
+    }
+
+    fn is_finished(&self) -> bool {
+        self.status.borrow().state.is_finished()
+    }
+}
+
+/// Given to analyses to report their progress and to check if they were cancelled,
+/// the default one is for analyses that are not run as jobs.
+#[derive(Clone, Default)]
+pub struct Progress(Option<Arc<Job>>);
+
+impl Progress {
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.0
+            .as_ref()
+            .is_some_and(|x| x.cancelled.load(Ordering::Relaxed))
+    }
+
+    /// Starts the processing of `commit`, also counting the previous one as processed
+    pub(crate) fn commit(&self, commit: &Oid) {
+        let Some(job) = &self.0 else { return };
+        job.status.send_modify(|x| {
+            if x.current_commit.is_some() {
+                x.commits_processed += 1;
+            }
+            x.current_commit = Some(commit.to_string());
+        });
+    }
+
+    pub(crate) fn matches(&self, count: u64) {
+        let Some(job) = &self.0 else { return };
+        job.status.send_modify(|x| x.matches += count);
+    }
+}
+
+/// Identifies the results of an analysis
+#[derive(Hash, PartialEq, Eq, Clone, Debug)]
+pub(crate) struct CacheKey {
+    kind: &'static str,
+    repo: hyperast_vcs_git::git::Repo,
+    commits: String,
+    /// hash of the content of the request, eg. the query and its limits
+    content: u64,
+}
+
+impl CacheKey {
+    pub(crate) fn new(
+        kind: &'static str,
+        repo: hyperast_vcs_git::git::Repo,
+        commits: &str,
+        content: &impl Serialize,
+    ) -> Self {
+        let mut hasher = std::hash::DefaultHasher::new();
+        serde_json::to_string(content).unwrap().hash(&mut hasher);
+        Self {
+            kind,
+            repo,
+            commits: commits.to_string(),
+            content: hasher.finish(),
+        }
+    }
+
+    /// The same key with the revisions of `commits`, eg. `v1..v2/v3`, resolved to the commits they designate,
+    /// so that the results of a tag or of HEAD are not reused once it moved
+    fn resolve(&self, state: &SharedState) -> Result<Self, String> {
+        let repo = state
+            .repositories
+            .read()
+            .unwrap()
+            .get_config(self.repo.clone())
+            .ok_or_else(|| "missing config for repository".to_string())?;
+        let repo = repo.fetch();
+        let resolve = |rev: &str| {
+            let commit = if rev.is_empty() {
+                repo.repo.head().and_then(|x| x.peel_to_commit())
+            } else {
+                hyperast_vcs_git::git::retrieve_commit(&repo.repo, rev)
+            };
+            commit
+                .map(|x| x.id().to_string())
+                .map_err(|e| e.to_string())
+        };
+        let commits = self
+            .commits
+            .split('/')
+            .map(|x| {
+                if !RevRange::is_range(x) {
+                    return resolve(x);
+                }
+                let range: RevRange = x.parse()?;
+                let excluded = match range.excluded.as_str() {
+                    "" => String::new(),
+                    excluded => resolve(excluded)?,
+                };
+                Ok(format!("{}..{}", excluded, resolve(&range.included)?))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self {
+            commits: commits.join("/"),
+            ..self.clone()
+        })
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Jobs {
+    next_id: AtomicU64,
+    jobs: DashMap<JobId, Arc<Job>>,
+    cache: DashMap<CacheKey, (Instant, Arc<serde_json::Value>)>,
+}
+
+impl Jobs {
+    fn create(&self, kind: &'static str, owner: Option<&crate::auth::User>) -> Arc<Job> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if id > MAX_JOBS {
+            let oldest = id - MAX_JOBS;
+            self.jobs.retain(|k, x| *k > oldest || !x.is_finished());
+        }
+        let job = Arc::new(Job::new(id, kind, owner.map(|x| x.name.clone())));
+        self.jobs.insert(id, job.clone());
+        job
+    }
+
+    /// The job, unless it was submitted by another user than `user`
+    fn get(&self, id: JobId, user: Option<&crate::auth::User>) -> Option<Arc<Job>> {
+        let job = self.jobs.get(&id)?.clone();
+        (job.owner.as_deref() == user.map(|x| x.name.as_str())).then_some(job)
+    }
+
+    pub(crate) fn status(&self, id: JobId, user: Option<&crate::auth::User>) -> Option<JobStatus> {
+        self.get(id, user).map(|x| x.status.borrow().clone())
+    }
+
+    /// Returns false if the job does not exist, is not visible to `user` or is already finished
+    pub(crate) fn cancel(&self, id: JobId, user: Option<&crate::auth::User>) -> bool {
+        let Some(job) = self.get(id, user) else {
+            return false;
+        };
+        if job.is_finished() {
+            return false;
+        }
+        job.cancelled.store(true, Ordering::Relaxed);
+        // queued jobs are cancelled right away, running ones once they check it
+        job.status.send_if_modified(|x| {
+            let queued = x.state == State::Queued;
+            if queued {
+                x.state = State::Cancelled;
+            }
+            queued
+        });
+        true
+    }
+
+    pub(crate) fn subscribe(
+        &self,
+        id: JobId,
+        user: Option<&crate::auth::User>,
+    ) -> Option<watch::Receiver<JobStatus>> {
+        self.get(id, user).map(|x| x.status.subscribe())
+    }
+
+    fn cached(&self, key: &CacheKey) -> Option<Arc<serde_json::Value>> {
+        let entry = self.cache.get(key)?;
+        (entry.0.elapsed() < CACHE_TTL).then(|| entry.1.clone())
+    }
+
+    fn cache(&self, key: CacheKey, result: Arc<serde_json::Value>) {
+        self.cache.retain(|_, x| x.0.elapsed() < CACHE_TTL);
+        if self.cache.len() >= MAX_CACHED {
+            let oldest = self
+                .cache
+                .iter()
+                .min_by_key(|x| x.0)
+                .map(|x| x.key().clone());
+            if let Some(oldest) = oldest {
+                self.cache.remove(&oldest);
+            }
+        }
+        self.cache.insert(key, (Instant::now(), result));
+    }
+}
+
+/// Runs `analysis` on the blocking pool, after the other jobs of `user` if it reached its limit,
+/// and returns the id of the job.
+///
+/// The revisions of `key` are resolved once the repository is fetched,
+/// the results of repositories that are not registered yet are not cached.
+///
+/// The result of `analysis` is the response of the corresponding route,
+/// the error being kept as the result of the failed job.
+pub(crate) fn submit<T, E>(
+    state: SharedState,
+    user: Option<Arc<crate::auth::User>>,
+    key: CacheKey,
+    analysis: impl FnOnce(SharedState, Progress) -> Result<T, E> + Send + 'static,
+) -> JobId
+where
+    T: Serialize,
+    E: Serialize,
+{
+    let job = state.jobs.create(key.kind, user.as_deref());
+    let id = job.status.borrow().id;
+    tokio::spawn(async move {
+        let _permit = match &user {
+            Some(user) => user.acquire_job().await,
+            None => None,
+        };
+        if job.cancelled.load(Ordering::Relaxed) {
+            return;
+        }
+        job.status.send_modify(|x| x.state = State::Running);
+        let key = tokio::task::spawn_blocking({
+            let state = state.clone();
+            move || key.resolve(&state)
+        })
+        .await;
+        let key = match key {
+            Ok(Ok(key)) => Some(key),
+            Ok(Err(err)) => {
+                log::warn!("results of job {} are not cached: {}", id, err);
+                None
+            }
+            Err(err) => {
+                log::error!("job {} panicked: {}", id, err);
+                return job.finish(State::Failed, None);
+            }
+        };
+        if let Some(result) = key.as_ref().and_then(|key| state.jobs.cached(key)) {
+            job.status.send_modify(|x| x.cached = true);
+            return job.finish(State::Done, Some(result));
+        }
+        let progress = Progress(Some(job.clone()));
+        let result = tokio::task::spawn_blocking({
+            let state = state.clone();
+            let progress = progress.clone();
+            move || match analysis(state, progress) {
+                Ok(x) => Ok(serde_json::to_value(x).unwrap()),
+                Err(x) => Err(serde_json::to_value(x).unwrap()),
+            }
+        })
+        .await;
+        match result {
+            // the partial result of a cancelled analysis is not cached
+            Ok(result) if progress.is_cancelled() => job.finish(
+                State::Cancelled,
+                Some(Arc::new(result.unwrap_or_else(|x| x))),
+            ),
+            Ok(Ok(result)) => {
+                let result = Arc::new(result);
+                if let Some(key) = key {
+                    state.jobs.cache(key, result.clone());
+                }
+                job.finish(State::Done, Some(result));
+            }
+            Ok(Err(err)) => job.finish(State::Failed, Some(Arc::new(err))),
+            Err(err) => {
+                log::error!("job {} panicked: {}", id, err);
+                job.finish(State::Failed, None)
+            }
+        }
+    });
+    id
+}
+
+/// Sends the status of the job each time it changes, until it is finished
+pub(crate) async fn stream_status(mut status: watch::Receiver<JobStatus>, mut socket: WebSocket) {
+    loop {
+        let current = status.borrow_and_update().clone();
+        let msg = serde_json::to_string(&current).unwrap();
+        if socket.send(Message::Text(msg)).await.is_err() {
+            return;
+        }
+        if current.state.is_finished() || status.changed().await.is_err() {
+            break;
+        }
+    }
+    let _ = socket.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_and_cancel() {
+        let jobs = Jobs::default();
+        let job = jobs.create("query", None);
+        let id = job.status.borrow().id;
+        let progress = Progress(Some(job));
+        progress.commit(&Oid::zero());
+        progress.commit(&Oid::zero());
+        progress.matches(3);
+        let status = jobs.status(id, None).unwrap();
+        assert_eq!(status.state, State::Queued);
+        assert_eq!(status.commits_processed, 1);
+        assert_eq!(status.matches, 3);
+        assert!(jobs.cancel(id, None));
+        assert!(progress.is_cancelled());
+        assert_eq!(jobs.status(id, None).unwrap().state, State::Cancelled);
+        assert!(!jobs.cancel(id, None));
+        assert!(!jobs.cancel(id + 1, None));
+        assert!(!Progress::default().is_cancelled());
+    }
+
+    #[test]
+    fn test_owner() {
+        let user = |name: &str| crate::auth::User::new(name.to_string(), false, Default::default());
+        let (alice, bob) = (user("alice"), user("bob"));
+        let jobs = Jobs::default();
+        let id = jobs.create("query", Some(&alice)).status.borrow().id;
+        assert!(jobs.status(id, Some(&alice)).is_some());
+        assert!(jobs.status(id, Some(&bob)).is_none());
+        assert!(jobs.status(id, None).is_none());
+        assert!(jobs.subscribe(id, Some(&bob)).is_none());
+        assert!(!jobs.cancel(id, Some(&bob)));
+        assert!(jobs.cancel(id, Some(&alice)));
+    }
+
+    #[test]
+    fn test_cache_bounded() {
+        let jobs = Jobs::default();
+        let repo = hyperast_vcs_git::git::Forge::Github.repo("user", "name");
+        let key = |i: usize| CacheKey::new("query", repo.clone(), &i.to_string(), &());
+        let result = Arc::new(serde_json::Value::Null);
+        for i in 0..MAX_CACHED {
+            jobs.cache(key(i), result.clone());
+        }
+        // older than the first one
+        let older = jobs.cache.get(&key(0)).unwrap().0 - Duration::from_millis(1);
+        jobs.cache.alter(&key(1), |_, (_, x)| (older, x));
+        jobs.cache(key(MAX_CACHED), result.clone());
+        assert_eq!(jobs.cache.len(), MAX_CACHED);
+        assert!(jobs.cached(&key(1)).is_none());
+        assert!(jobs.cached(&key(0)).is_some());
+        assert!(jobs.cached(&key(MAX_CACHED)).is_some());
+        if let Some(expired) = Instant::now().checked_sub(CACHE_TTL) {
+            jobs.cache.alter(&key(0), |_, (_, x)| (expired, x));
+            assert!(jobs.cached(&key(0)).is_none());
+        }
+    }
+}
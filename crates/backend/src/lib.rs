@@ -15,13 +15,19 @@ use axum::body::Bytes;
 use hyperast::store::nodes::legion::NodeIdentifier;
 
 pub mod app;
+pub mod auth;
 mod changes;
 pub mod cli;
 mod commit;
+mod dependencies;
+mod diff;
 pub mod examples;
 mod fetch;
 mod file;
+mod jobs;
 mod matching;
+pub mod metric_columns;
+pub mod offline;
 mod pull_requests;
 mod querying;
 mod scriptingv1;
@@ -54,6 +60,11 @@ pub struct AppState {
     // Multiple shared docs
     doc2: ws::SharedDocs,
     pr_cache: RwLock<std::collections::HashMap<commit::Param, pull_requests::RawPrData>>,
+    jobs: jobs::Jobs,
+    /// counts of matches by file and directory, reused across the commits queried by [`querying::simple`]
+    match_cache: hyperast_tsquery::match_cache::MatchCache<NodeIdentifier>,
+    /// metrics defined by scripts after the processing of repositories, see [`metric_columns`]
+    metric_columns: hyperast::scripting::columns::MetricColumns,
 }
 
 impl Default for AppState {
@@ -71,6 +82,9 @@ impl Default for AppState {
             )),
             doc2: Default::default(),
             pr_cache: Default::default(),
+            jobs: Default::default(),
+            match_cache: Default::default(),
+            metric_columns: Default::default(),
         }
     }
 }
@@ -395,7 +409,7 @@ fn run_scripting(
         .repositories
         .write()
         .unwrap()
-        .register_config_with_prepro(repo_spec.clone(), config, prepro.into());
+        .register_config_with_prepro(repo_spec.clone(), config, prepro.into())?;
     // state
     //     .repositories
     //     .write()
@@ -482,6 +496,66 @@ fn run_scripting(
     Ok(())
 }
 
+#[ignore] // ignore (from normal cargo test) for now, later make a feature
+#[test]
+// slow test, more of an integration test, try using release
+fn test_metric_column_mcc() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    use hyperast::scripting::columns::MetricKind;
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter("backend=debug,hyperast_vcs_git=info,hyperast=error")
+        .try_init()
+        .unwrap();
+
+    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo("graphhopper", "graphhopper");
+    let config = hyperast_vcs_git::processing::RepoConfig::JavaMaven;
+    let commit = "f5f2b7765e6b392c5e8c7855986153af82cc1abe";
+    let prepro = hyperast::scripting::lua_scripting::PREPRO_MCC_WITH_FINISH;
+    let state = crate::AppState::default();
+    state
+        .repositories
+        .write()
+        .unwrap()
+        .register_config_with_prepro(repo_spec.clone(), config, prepro.into())?;
+    let repo = state
+        .repositories
+        .read()
+        .unwrap()
+        .get_config(repo_spec)
+        .ok_or_else(|| "missing config for repository".to_string())?;
+    let mut repository = repo.fetch();
+    let commits = state.repositories.write().unwrap().pre_process_with_limit(
+        &mut repository,
+        "",
+        &commit,
+        2,
+    )?;
+    // computed after the processing, the column must match the preprocessing with the same script
+    let column = state.metric_columns.add("mcc", MetricKind::Int, prepro)?;
+    let repositories = state.repositories.read().unwrap();
+    let stores = &repositories.processor.main_stores;
+    let mut computed = 0;
+    for oid in &commits {
+        let root = repositories
+            .get_commit(&repository.config, oid)
+            .unwrap()
+            .ast_root;
+        let value = column.compute(stores, root)?;
+        let n = stores.node_store.resolve(root);
+        let dd = n
+            .get_component::<hyperast::scripting::DerivedData>()
+            .unwrap();
+        let expected = dd.0.get("mcc").unwrap();
+        let expected = expected
+            .as_int()
+            .map(|x| x as f64)
+            .or_else(|_| expected.as_float())?;
+        assert_eq!(value.as_f64(), expected);
+        log::debug!("{} new values for {oid}", column.len() - computed);
+        computed = column.len();
+    }
+    Ok(())
+}
+
 #[ignore] // ignore (from normal cargo test) for now, later make a feature
 #[test]
 // slow test, more of an integration test, try using release
@@ -517,7 +591,7 @@ fn run_tsg(
         .repositories
         .write()
         .unwrap()
-        .register_config_with_tsg(repo_spec.clone(), config, tsg.into());
+        .register_config_with_tsg(repo_spec.clone(), config, tsg.into())?;
     let repo = state
         .repositories
         .read()
@@ -1,15 +1,16 @@
 // #![feature(array_chunks)]
 // #![feature(map_many_mut)]
 // #![feature(iter_collect_into)]
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, process::ExitCode, sync::Arc};
 
 use backend::*;
 
 use axum::Router;
 use backend::{
     app::{
-        commit_metadata_route, fetch_code_route, fetch_git_file, querying_app, scripting_app,
-        smells_app, track_code_route, tsg_app, view_code_route,
+        commit_metadata_route, dependencies_route, diff_route, fetch_code_route, fetch_git_file,
+        jobs_route, metric_columns_route, querying_app, scripting_app, smells_app,
+        track_code_route, tsg_app, view_code_route,
     },
     examples::{example_app, kv_store_app},
 };
@@ -29,7 +30,7 @@ pub type PersistableMappings<I> = hyper_diff::matchers::Mapping<DS<I>, DS<I>, Ve
 type SharedState = Arc<AppState>;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
     let opts = backend::cli::parse();
     #[cfg(feature = "rerun")]
     {
@@ -41,24 +42,58 @@ async fn main() {
     {
         use hyperast_vcs_git::processing::RepoConfig;
         let mut repos = shared_state.repositories.write().unwrap();
-        repos.register_config(Forge::Github.repo("INRIA", "spoon"), RepoConfig::JavaMaven);
-        repos.register_config(Forge::Github.repo("google", "gson"), RepoConfig::JavaMaven);
-        repos.register_config(
-            Forge::Github.repo("Marcono1234", "gson"),
-            RepoConfig::JavaMaven,
-        );
-        repos.register_config(
-            Forge::Github.repo("official-stockfish", "Stockfish"),
-            RepoConfig::CppMake,
-        );
-        repos.register_config(Forge::Github.repo("torvalds", "linux"), RepoConfig::CppMake);
-        repos.register_config(
-            Forge::Github.repo("systemd", "systemd"),
-            RepoConfig::CppMake,
-        );
-        opts.repository.iter().for_each(|x| {
-            repos.register_config(x.repo.clone(), x.config);
-        })
+        let defaults = [
+            (Forge::Github.repo("INRIA", "spoon"), RepoConfig::JavaMaven),
+            (Forge::Github.repo("google", "gson"), RepoConfig::JavaMaven),
+            (
+                Forge::Github.repo("Marcono1234", "gson"),
+                RepoConfig::JavaMaven,
+            ),
+            (
+                Forge::Github.repo("official-stockfish", "Stockfish"),
+                RepoConfig::CppMake,
+            ),
+            (Forge::Github.repo("torvalds", "linux"), RepoConfig::CppMake),
+            (
+                Forge::Github.repo("systemd", "systemd"),
+                RepoConfig::CppMake,
+            ),
+        ];
+        let given = opts.repository.iter().map(|x| (x.repo.clone(), x.config));
+        for (repo, config) in defaults.into_iter().chain(given) {
+            if let Err(e) = repos.register_config(repo.clone(), config) {
+                log::error!("cannot register {}: {}", repo, e);
+            }
+        }
+        if let Some(dir) = &opts.store_dir {
+            // a checkpoint that cannot be read is left as is rather than overwritten by an empty one
+            match repos.restore(dir) {
+                Ok(true) => log::info!("restored stores from {}", dir.display()),
+                Ok(false) => log::info!("no checkpoint in {}", dir.display()),
+                Err(e) => {
+                    log::error!("failed to restore stores from {}: {}", dir.display(), e);
+                    return ExitCode::FAILURE;
+                }
+            }
+            if let Err(e) = repos.persist_in(dir.clone()) {
+                log::error!("failed to checkpoint stores in {}: {}", dir.display(), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if let Some(dir) = &opts.store_dir {
+        match backend::metric_columns::restore(&shared_state, dir) {
+            Ok(true) => log::info!("restored metric columns from {}", dir.display()),
+            Ok(false) => (),
+            Err(e) => {
+                log::error!(
+                    "failed to restore metric columns from {}: {}",
+                    dir.display(),
+                    e
+                );
+                return ExitCode::FAILURE;
+            }
+        }
     }
     let app = Router::new()
         .fallback(fallback)
@@ -73,15 +108,22 @@ async fn main() {
         .merge(view_code_route(Arc::clone(&shared_state)))
         .merge(fetch_code_route(Arc::clone(&shared_state)))
         .merge(commit_metadata_route(Arc::clone(&shared_state)))
+        .merge(dependencies_route(Arc::clone(&shared_state)))
+        .merge(diff_route(Arc::clone(&shared_state)))
+        .merge(metric_columns_route(Arc::clone(&shared_state)))
         .merge(example_app())
+        .route_layer(axum::middleware::from_fn(backend::auth::limit))
+        // after the limit, jobs are queued per user instead
+        .merge(jobs_route(Arc::clone(&shared_state)))
+        .layer(backend::auth::authentication(Arc::clone(
+            &opts.users_limits,
+        )))
         .layer(CorsLayer::permissive()) // WARN unwanted for deployment
         .layer(TraceLayer::new_for_http())
         .with_state(Arc::clone(&shared_state));
     // TODOs auth admin to list pending constructions,
-    // all repositories are blacklised by default
     // give provider per forge
     // to whitelist repositories either for all past commits or also all future commits
-    // manage users and quota
     tracing::debug!("listening on {}", opts.address);
     let listener = tokio::net::TcpListener::bind(&opts.address).await.unwrap();
     axum::serve(
@@ -91,6 +133,22 @@ async fn main() {
     .with_graceful_shutdown(shutdown_signal())
     .await
     .unwrap();
+    if let Some(dir) = &opts.store_dir {
+        let mut repos = shared_state.repositories.write().unwrap();
+        match repos.checkpoint(dir) {
+            Ok(()) => log::info!("checkpointed stores in {}", dir.display()),
+            Err(e) => log::error!("failed to checkpoint stores in {}: {}", dir.display(), e),
+        }
+        match backend::metric_columns::checkpoint(&shared_state, dir) {
+            Ok(()) => log::info!("checkpointed metric columns in {}", dir.display()),
+            Err(e) => log::error!(
+                "failed to checkpoint metric columns in {}: {}",
+                dir.display(),
+                e
+            ),
+        }
+    }
+    ExitCode::SUCCESS
 }
 /// axum handler for any request that fails to match the router routes.
 /// This implementation returns HTTP status code Not Found (404).
@@ -0,0 +1,193 @@
+//! Metric columns defined by scripts, added to already processed repositories,
+//! see [`hyperast::scripting::columns`].
+
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use axum::Json;
+use hyperast::scripting::columns::{MetricColumn, MetricColumns, MetricKind, MetricValue};
+use hyperast::store::defaults::NodeIdentifier;
+use hyperast::store::persist::Persist;
+use hyperast_vcs_git::preprocessed::child_at_path;
+use serde::{Deserialize, Serialize};
+
+use crate::SharedState;
+
+/// Next to the checkpoint of the stores, the values refer to their nodes
+pub const COLUMNS_FILE: &str = "metric_columns.bin";
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ColumnParam {
+    pub column: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Param {
+    pub forge: hyperast_vcs_git::git::Forge,
+    pub user: String,
+    pub name: String,
+    pub commit: String,
+    pub column: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Definition {
+    /// int, float or bool
+    pub kind: String,
+    /// a lua script with an `acc(c)` and a `finish()` function,
+    /// `finish()` returning the value of the column under its name
+    pub script: String,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ValueQuery {
+    /// the value of a directory or a file instead of the whole commit, eg. `src/main/java/A.java`
+    pub path: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Column {
+    name: String,
+    #[serde(flatten)]
+    definition: Definition,
+    /// number of memoized values
+    computed: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Value {
+    commit: String,
+    column: String,
+    value: serde_json::Value,
+    /// in seconds
+    compute_time: f64,
+}
+
+impl From<&MetricColumn> for Column {
+    fn from(column: &MetricColumn) -> Self {
+        Self {
+            name: column.name().to_string(),
+            definition: Definition {
+                kind: column.kind().to_string(),
+                script: column.script().to_string(),
+            },
+            computed: column.len(),
+        }
+    }
+}
+
+fn to_json(value: MetricValue) -> serde_json::Value {
+    match value {
+        MetricValue::Int(x) => x.into(),
+        MetricValue::Float(x) => x.into(),
+        MetricValue::Bool(x) => x.into(),
+    }
+}
+
+pub fn list(state: SharedState) -> Json<Vec<Column>> {
+    let columns = &state.metric_columns;
+    let columns = columns.names().into_iter().filter_map(|x| columns.get(&x));
+    Json(columns.map(|x| x.as_ref().into()).collect())
+}
+
+/// Adds a column, nothing is computed until its values are requested
+pub fn define(
+    state: SharedState,
+    path: ColumnParam,
+    definition: Definition,
+) -> Result<Json<Column>, String> {
+    let kind: MetricKind = definition.kind.parse()?;
+    let column = state
+        .metric_columns
+        .add(&path.column, kind, &definition.script)
+        .map_err(|e| e.to_string())?;
+    Ok(Json(column.as_ref().into()))
+}
+
+pub fn remove(state: SharedState, path: ColumnParam) -> Result<Json<Column>, String> {
+    let column = state
+        .metric_columns
+        .remove(&path.column)
+        .ok_or_else(|| format!("unknown metric column {}", path.column))?;
+    Ok(Json(column.as_ref().into()))
+}
+
+/// The value of a column on a commit, computed on the subtrees not already computed
+pub fn value(state: SharedState, path: Param, query: ValueQuery) -> Result<Json<Value>, String> {
+    let Param {
+        forge,
+        user,
+        name,
+        commit,
+        column,
+    } = path;
+    let repo_spec = forge.repo(user, name);
+    let repo = state
+        .repositories
+        .read()
+        .unwrap()
+        .get_config(repo_spec)
+        .ok_or_else(|| "missing config for repository".to_string())?;
+    let mut repo = repo.fetch();
+    log::info!("done cloning {}", repo.spec);
+    let commits = crate::utils::handle_pre_processing(&state, &mut repo, "", &commit, 1)
+        .map_err(|e| e.to_string())?;
+    let oid = commits
+        .first()
+        .copied()
+        .ok_or_else(|| format!("no commit found for {}", commit))?;
+
+    let repositories = state.repositories.read().unwrap();
+    let mut root = repositories
+        .get_commit(&repo.config, &oid)
+        .map(|x| x.ast_root)
+        .ok_or_else(|| format!("missing processed commit {}", oid))?;
+    let stores = &repositories.processor.main_stores;
+    if let Some(path) = &query.path {
+        root = child_at_path(stores, root, path.split("/"))
+            .ok_or_else(|| format!("{} not found in {}", path, oid))?;
+    }
+
+    let now = Instant::now();
+    let value = state
+        .metric_columns
+        .value(stores, &column, root)
+        .map_err(|e| e.to_string())?;
+    let compute_time = now.elapsed().as_secs_f64();
+    log::info!("{} of {} in {}s", column, oid, compute_time);
+    Ok(Json(Value {
+        commit: oid.to_string(),
+        column,
+        value: to_json(value),
+        compute_time,
+    }))
+}
+
+/// The values of the columns, as the named metrics of queries missing from nodes,
+/// see [`hyperast_tsquery::hyperast_cursor::Node::with_metrics`]
+pub(crate) fn lookup<'a>(
+    columns: &'a MetricColumns,
+    stores: &'a hyperast::store::SimpleStores<hyperast_vcs_git::TStore>,
+) -> impl Fn(&NodeIdentifier, &str) -> Option<f64> + 'a {
+    move |id, name| columns.value(stores, name, *id).ok().map(|x| x.as_f64())
+}
+
+/// Writes the columns and their values in `dir`, along with the checkpoint of the stores
+pub fn checkpoint(state: &SharedState, dir: &Path) -> io::Result<()> {
+    let mut w = io::BufWriter::new(File::create(dir.join(COLUMNS_FILE))?);
+    state.metric_columns.persist(&mut w)?;
+    w.flush()
+}
+
+/// Restores the columns of `dir`, once the stores they were computed on are restored
+pub fn restore(state: &SharedState, dir: &Path) -> io::Result<bool> {
+    let path = dir.join(COLUMNS_FILE);
+    if !path.exists() {
+        return Ok(false);
+    }
+    let columns = MetricColumns::restore(&mut BufReader::new(File::open(path)?))?;
+    state.metric_columns.replace(columns);
+    Ok(true)
+}
@@ -0,0 +1,198 @@
+//! Analyses of a local git clone without running the server, used by the `hyperast` binary.
+//!
+//! Each analysis goes through the same code path as the corresponding route,
+//! the repository being processed in place, see [`Repo::on_disk`].
+
+use std::path::Path;
+
+use hyperast::store::defaults::NodeIdentifier;
+use hyperast::types::WithStats;
+use hyperast_vcs_git::git::Repo;
+use hyperast_vcs_git::processing::RepoConfig;
+use serde::Serialize;
+
+use crate::SharedState;
+
+pub use crate::querying::{ComputeResults, Content, MatchingError, QueryingError};
+pub use crate::track::{Flags, TrackingError, TrackingQuery, TrackingResult};
+pub use crate::view::ViewRes;
+
+/// The tree-sitter language used to query the files of repositories configured with `config`
+pub fn language(config: RepoConfig) -> Option<&'static str> {
+    match config {
+        RepoConfig::CppMake => Some("Cpp"),
+        RepoConfig::CMake => Some("C"),
+        RepoConfig::JavaMaven | RepoConfig::JavaGradle => Some("Java"),
+        RepoConfig::TsNpm => Some("TypeScript"),
+        RepoConfig::PythonPip => Some("Python"),
+        RepoConfig::RustCargo => Some("Rust"),
+        RepoConfig::Any => None,
+    }
+}
+
+/// Registers the repository at `path` with `config`, it is then processed in place, see [`Repo::on_disk`]
+pub fn open(state: &SharedState, path: &Path, config: RepoConfig) -> Result<Repo, String> {
+    let repo = Repo::on_disk(path, None)?;
+    state
+        .repositories
+        .write()
+        .unwrap()
+        .register_config(repo.clone(), config)
+        .map_err(|e| e.to_string())?;
+    Ok(repo)
+}
+
+fn configured_repo(
+    state: &SharedState,
+    repo: &Repo,
+) -> Result<hyperast_vcs_git::processing::ConfiguredRepo2, String> {
+    let repo = state
+        .repositories
+        .read()
+        .unwrap()
+        .get_config(repo.clone())
+        .ok_or_else(|| "missing config for repository".to_string())?;
+    // the repository on disk could have been moved since it was registered
+    let repository = repo.spec.open_on_disk().map_err(|e| e.to_string())?;
+    let repo = match repository {
+        Some(repository) => hyperast_vcs_git::processing::ConfiguredRepo2 {
+            spec: repo.spec,
+            config: repo.config,
+            repo: repository,
+        },
+        None => repo.fetch(),
+    };
+    log::info!("done cloning {}", repo.spec);
+    Ok(repo)
+}
+
+/// Processes `commit` and returns its id
+fn pre_process(
+    state: &SharedState,
+    repo: &mut hyperast_vcs_git::processing::ConfiguredRepo2,
+    commit: &str,
+) -> Result<hyperast_vcs_git::git::Oid, String> {
+    let commits = crate::utils::handle_pre_processing(state, repo, "", commit, 1)
+        .map_err(|e| e.to_string())?;
+    commits
+        .first()
+        .copied()
+        .ok_or_else(|| format!("no commit found for {}", commit))
+}
+
+/// Same as the `/query` route
+pub fn query(
+    state: SharedState,
+    repo: &Repo,
+    commit: &str,
+    content: Content,
+) -> Result<ComputeResults, QueryingError> {
+    let path = crate::querying::Param {
+        forge: repo.forge(),
+        user: repo.user().to_string(),
+        name: repo.name().to_string(),
+        commit: commit.to_string(),
+    };
+    crate::querying::simple(content, state, path)
+}
+
+/// Same as the `/track` route
+pub fn track(
+    state: SharedState,
+    repo: &Repo,
+    commit: &str,
+    file: &str,
+    query: TrackingQuery,
+) -> Result<TrackingResult<NodeIdentifier, u16>, TrackingError> {
+    let path = crate::track::TrackingParam {
+        forge: repo.forge(),
+        user: repo.user().to_string(),
+        name: repo.name().to_string(),
+        commit: commit.to_string(),
+        file: file.to_string(),
+    };
+    crate::track::track_code(state, path, query)
+}
+
+/// The subtree at `path` in `commit`, same as the `/view` route
+pub fn view(
+    state: SharedState,
+    repo: &Repo,
+    commit: &str,
+    path: Option<String>,
+) -> Result<ViewRes, String> {
+    let path = crate::view::Parameters {
+        forge: repo.forge(),
+        user: repo.user().to_string(),
+        name: repo.name().to_string(),
+        commit: commit.to_string(),
+        path,
+    };
+    crate::view::view(state, path).map(|x| x.0)
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Changes {
+    pub before: String,
+    pub after: String,
+    /// global positions of the nodes deleted from `before`
+    pub deletions: Vec<u32>,
+    /// global positions of the nodes added in `after`
+    pub additions: Vec<u32>,
+}
+
+/// The nodes added and deleted between two commits, as computed for the tracking
+pub fn diff(state: SharedState, repo: &Repo, before: &str, after: &str) -> Result<Changes, String> {
+    let mut repo = configured_repo(&state, repo)?;
+    let before = pre_process(&state, &mut repo, before)?;
+    let after = pre_process(&state, &mut repo, after)?;
+    let (src, dst) = crate::changes::added_deleted(state, &repo, before, after)?;
+    Ok(Changes {
+        before: src.commit,
+        after: dst.commit,
+        deletions: src.deletions,
+        additions: dst.additions,
+    })
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Metrics {
+    pub commit: String,
+    /// in nanoseconds
+    pub processing_time: u128,
+    /// number of nodes in the syntax tree of the commit
+    pub size: usize,
+    pub height: usize,
+    pub line_count: usize,
+}
+
+/// Basic metrics of the commits walked from `commit`, at most `depth` commits,
+/// `commit` can also be a range like `A..B`
+pub fn metrics(
+    state: SharedState,
+    repo: &Repo,
+    commit: &str,
+    depth: usize,
+) -> Result<Vec<Metrics>, String> {
+    let mut repo = configured_repo(&state, repo)?;
+    let commits = crate::utils::handle_pre_processing(&state, &mut repo, "", commit, depth)
+        .map_err(|e| e.to_string())?;
+    let repositories = state.repositories.read().unwrap();
+    let node_store = &repositories.processor.main_stores.node_store;
+    commits
+        .iter()
+        .map(|oid| {
+            let commit = repositories
+                .get_commit(&repo.config, oid)
+                .ok_or_else(|| format!("missing processed commit {}", oid))?;
+            let n = node_store.resolve(commit.ast_root);
+            Ok(Metrics {
+                commit: oid.to_string(),
+                processing_time: commit.processing_time(),
+                size: n.size(),
+                height: n.height(),
+                line_count: n.line_count(),
+            })
+        })
+        .collect()
+}
@@ -53,6 +53,13 @@ pub(super) async fn pr_commits(
     axum::extract::Path(path): axum::extract::Path<commit::Param>,
     axum::extract::State(state): axum::extract::State<SharedState>,
 ) -> Result<PrData, String> {
+    if path.forge != hyperast_vcs_git::git::Forge::Github {
+        // pull requests are retrieved through the graphql api of github
+        return Err(format!(
+            "pull requests are not available on {}",
+            path.forge.host()
+        ));
+    }
     if let Some(x) = state.pr_cache.read().unwrap().get(&path) {
         let data = serde_json::from_str(&x.0).map_err(|e| e.to_string())?;
         return format_result(&data);
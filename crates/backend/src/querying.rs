@@ -10,15 +10,18 @@ use hyperast::{
     store::defaults::NodeIdentifier,
     types::{Children, Childrn, HyperAST, Typed, WithChildren, WithStats},
 };
-use hyperast_vcs_git::git::Oid;
+use hyperast_vcs_git::git::{DagBounds, Oid};
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
+pub(crate) mod history;
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Param {
-    user: String,
-    name: String,
-    commit: String,
+    pub(crate) forge: hyperast_vcs_git::git::Forge,
+    pub(crate) user: String,
+    pub(crate) name: String,
+    pub(crate) commit: String,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -26,6 +29,9 @@ pub struct Content {
     pub language: String,
     pub query: String,
     pub precomp: Option<String>,
+    /// maximum number of commits to query,
+    /// can be omitted when `commit` is a range like `A..B` to query the whole range
+    #[serde(default = "default_commits")]
     pub commits: usize,
     // TODO disable the incriminated pattern for subsequent matches
     /// checked per individual match
@@ -36,13 +42,36 @@ pub struct Content {
     /// checked each match (in milli seconds)
     #[serde(default = "default_timeout")]
     pub timeout: u64,
+    /// also return, for each match, the location of its captured nodes (see [`MatchCaptures`])
+    #[serde(default)]
+    pub captures: bool,
+    /// when `commit` is a range like `A..B`, the maximum number of parent links from `B`
+    pub depth: Option<usize>,
+    /// when `commit` is a range, in seconds since the epoch, older commits are not queried
+    pub since: Option<i64>,
+    /// when `commit` is a range, in seconds since the epoch, newer commits are not queried
+    pub until: Option<i64>,
+}
+
+impl Content {
+    pub(crate) fn bounds(&self) -> DagBounds {
+        DagBounds {
+            depth: self.depth,
+            since: self.since,
+            until: self.until,
+        }
+    }
+}
+
+fn default_commits() -> usize {
+    usize::MAX
 }
 
-fn default_max_matches() -> u64 {
+pub(crate) fn default_max_matches() -> u64 {
     500
 }
 
-fn default_timeout() -> u64 {
+pub(crate) fn default_timeout() -> u64 {
     1000
 }
 
@@ -87,6 +116,25 @@ pub struct ComputeResultIdentified {
 pub struct ComputeResult {
     pub compute_time: f64,
     pub result: Vec<u64>,
+    /// only provided when [`Content::captures`] is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matches: Option<Vec<MatchCaptures>>,
+}
+
+/// The nodes captured by a single match
+#[derive(Debug, Serialize, Clone)]
+pub struct MatchCaptures {
+    /// index of the enabled pattern, ie. the column in [`ComputeResult::result`]
+    pub pattern: u16,
+    pub captures: Vec<Capture>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Capture {
+    /// the name of the capture in the query, without the `@`
+    pub name: String,
+    #[serde(flatten)]
+    pub(crate) range: crate::smells::CodeRange,
 }
 impl ComputeResult {
     fn with(self, commit_oid: &Oid) -> ComputeResultIdentified {
@@ -103,11 +151,22 @@ pub fn simple(
     query: Content,
     state: SharedState,
     path: Param,
+) -> Result<ComputeResults, QueryingError> {
+    simple_with_progress(query, state, path, Default::default())
+}
+
+/// When cancelled, only gives the results of the commits already queried
+pub(crate) fn simple_with_progress(
+    query: Content,
+    state: SharedState,
+    path: Param,
+    progress: crate::jobs::Progress,
 ) -> Result<ComputeResults, QueryingError> {
     let now = Instant::now();
-    let Param { user, name, commit } = path.clone();
+    let Param { forge, user, name, commit } = path.clone();
     let mut additional = commit.split("/");
     let commit = additional.next().unwrap();
+    let bounds = query.bounds();
     let Content {
         language,
         query,
@@ -115,6 +174,8 @@ pub fn simple(
         commits,
         max_matches,
         timeout,
+        captures,
+        ..
     } = query;
     let timeout = std::time::Duration::from_millis(timeout);
     let mut proc_commit_limit = commits;
@@ -122,13 +183,19 @@ pub fn simple(
         hyperast_vcs_git::processing::RepoConfig::JavaMaven
     } else if language == "Cpp" {
         hyperast_vcs_git::processing::RepoConfig::CppMake
+    } else if language == "C" {
+        hyperast_vcs_git::processing::RepoConfig::CMake
+    } else if language == "Python" {
+        hyperast_vcs_git::processing::RepoConfig::PythonPip
+    } else if language == "Rust" {
+        hyperast_vcs_git::processing::RepoConfig::RustCargo
     } else {
         hyperast_vcs_git::processing::RepoConfig::Any
     };
     let lang = &language;
     let language = hyperast_vcs_git::resolve_language(&language)
         .ok_or_else(|| QueryingError::MissingLanguage(language.to_string()))?;
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo = state
         .repositories
         .read()
@@ -138,21 +205,23 @@ pub fn simple(
         Some(repo) => repo,
         None => {
             let configs = &mut state.repositories.write().unwrap();
+            log::error!("missing config for {}", repo_spec);
             if let Some(precomp) = precomp {
-                configs.register_config_with_prequeries(repo_spec.clone(), config, &[&precomp]);
+                configs.register_config_with_prequeries(repo_spec.clone(), config, &[&precomp])
             } else {
                 // configs.register_config_alt_lang(repo_spec.clone(), config, "C");
-                configs.register_config(repo_spec.clone(), config);
+                configs.register_config(repo_spec.clone(), config)
             }
-            log::error!("missing config for {}", repo_spec);
-            configs.get_config(repo_spec.clone()).unwrap()
+            .map_err(|e| QueryingError::ProcessingError(e.to_string()))?
         }
     };
 
     let mut repo = repo.fetch();
     log::warn!("done cloning {}", &repo.spec);
-    let commits = crate::utils::handle_pre_processing(&state, &mut repo, "", &commit, commits)
-        .map_err(|x| QueryingError::ProcessingError(x.to_string()))?;
+    let commits = crate::utils::handle_pre_processing_bounded(
+        &state, &mut repo, "", &commit, commits, bounds,
+    )
+    .map_err(|x| QueryingError::ProcessingError(x.to_string()))?;
     log::info!("done construction of {commits:?} in  {}", repo.spec);
     let language: tree_sitter::Language = language.clone();
 
@@ -166,6 +235,22 @@ pub fn simple(
         })
         .flatten();
 
+    // identifies the query in the match cache,
+    // the precomputed patterns coming first in the pattern ids
+    let query_hash = {
+        use hyperast_tsquery::ArrayStr;
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::hash::DefaultHasher::new();
+        (lang, &query, precomputeds.as_ref().map(|x| x.len())).hash(&mut hasher);
+        // named metrics can come from metric columns, which can be redefined
+        for name in state.metric_columns.names() {
+            if let Some(column) = state.metric_columns.get(&name) {
+                (name, column.kind(), column.script()).hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    };
+
     let query = if let Some(precomputeds) = precomputeds {
         hyperast_tsquery::Query::with_precomputed(&query, language, precomputeds).map(|x| x.1)
     } else {
@@ -174,17 +259,21 @@ pub fn simple(
     .map_err(|e| QueryingError::ParsingError(e.to_string()))?;
 
     log::info!("done query construction");
+    // captures are located from the root of each commit, so they cannot be memoized
+    let memoized =
+        (!captures).then(|| hyperast_tsquery::match_cache::MemoizedQuery::new(&query, query_hash));
     let prepare_time = now.elapsed().as_secs_f64();
     let mut results = vec![];
     let mut matching_error_count = 0;
     for commit_oid in &commits {
-        if results.len() > proc_commit_limit {
+        if results.len() > proc_commit_limit || progress.is_cancelled() {
             return Ok(ComputeResults {
                 prepare_time,
                 matching_error_count,
                 results,
             });
         }
+        progress.commit(commit_oid);
         let mut oid = commit_oid.to_string();
         oid.truncate(6);
         log::info!("start querying {}", oid);
@@ -192,9 +281,23 @@ pub fn simple(
         let commit = repositories.get_commit(&repo.config, commit_oid).unwrap();
         let code = commit.ast_root;
         let stores = &repositories.processor.main_stores;
-        let result = simple_aux(stores, code, &query, timeout, max_matches);
+        let result = match &memoized {
+            Some(memoized) => {
+                let cache = &state.match_cache;
+                let columns = &state.metric_columns;
+                memoized_aux(stores, code, memoized, cache, columns, timeout, max_matches)
+            }
+            None => {
+                let captures = captures.then_some((&repo, *commit_oid));
+                let columns = &state.metric_columns;
+                simple_aux(stores, code, &query, columns, timeout, max_matches, captures)
+            }
+        };
         let result = match result {
-            Ok(inner) => Ok(inner.with(commit_oid)),
+            Ok(inner) => {
+                progress.matches(inner.result.iter().sum());
+                Ok(inner.with(commit_oid))
+            }
             Err(err) if results.is_empty() => {
                 return Err(QueryingError::MatchingErrOnFirst(
                     err.map(|inner| inner.with(commit_oid)),
@@ -252,6 +355,7 @@ pub fn streamed(mut state: SharedState, path: Param, content: Content) -> axum::
         commits: mut proc_commit_limit,
         max_matches,
         timeout,
+        captures,
         ..
     } = content.clone();
     let timeout = std::time::Duration::from_millis(timeout);
@@ -298,7 +402,9 @@ pub fn streamed(mut state: SharedState, path: Param, content: Content) -> axum::
             let commit = repositories.get_commit(&repo.config, &commit_oid).unwrap();
             let code = commit.ast_root;
             let stores = &repositories.processor.main_stores;
-            let result = simple_aux(stores, code, &query, timeout, max_matches);
+            let captures = captures.then_some((&repo, commit_oid));
+            let columns = &state.metric_columns;
+            let result = simple_aux(stores, code, &query, columns, timeout, max_matches, captures);
             let result = match result {
                 Ok(inner) => Ok(inner.with(&commit_oid)),
                 Err(err) => {
@@ -344,7 +450,7 @@ fn pre_repo(
     path: &Param,
     content: &Content,
 ) -> Result<(hyperast_vcs_git::processing::ConfiguredRepo2, Vec<Oid>), Box<dyn std::error::Error>> {
-    let Param { user, name, commit } = path.clone();
+    let Param { forge, user, name, commit } = path.clone();
     let mut additional = commit.split("/");
     let commit = additional.next().unwrap();
     let Content {
@@ -354,37 +460,45 @@ fn pre_repo(
         commits,
         max_matches: _,
         timeout: _,
+        captures: _,
+        ..
     } = content.clone();
     let config = if language == "Java" {
         hyperast_vcs_git::processing::RepoConfig::JavaMaven
     } else if language == "Cpp" {
         hyperast_vcs_git::processing::RepoConfig::CppMake
+    } else if language == "C" {
+        hyperast_vcs_git::processing::RepoConfig::CMake
+    } else if language == "Python" {
+        hyperast_vcs_git::processing::RepoConfig::PythonPip
+    } else if language == "Rust" {
+        hyperast_vcs_git::processing::RepoConfig::RustCargo
     } else {
         hyperast_vcs_git::processing::RepoConfig::Any
     };
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo = state
         .repositories
         .read()
         .unwrap()
         .get_config(repo_spec.clone());
     let repo = match repo {
-        Some(_) | None => {
+        Some(repo) => repo,
+        None => {
             let configs = &mut state.repositories.write().unwrap();
+            log::error!("missing config for {}", repo_spec);
             if let Some(precomp) = precomp {
                 let precomp = precomp.split("\n\n").filter(|x|!x.is_empty()).collect::<Vec<_>>();
-                configs.register_config_with_prequeries(repo_spec.clone(), config, precomp.as_slice());
+                configs.register_config_with_prequeries(repo_spec.clone(), config, precomp.as_slice())
             } else {
-                configs.register_config(repo_spec.clone(), config);
-            }
-            log::error!("missing config for {}", repo_spec);
-            configs.get_config(repo_spec.clone()).unwrap()
+                configs.register_config(repo_spec.clone(), config)
+            }?
         }
     };
     let repo = repo.fetch();
     log::warn!("done cloning {}", &repo.spec);
     let afters = [commit].into_iter().chain(additional.into_iter());
-    let rw = crate::utils::walk_commits_multi(&repo, afters)?.take(commits);
+    let rw = crate::utils::walk_commits_multi(&repo, afters, content.bounds())?.take(commits);
     assert!(state.repositories.try_write().is_ok());
     let commits = crate::utils::handle_pre_processing_aux(state, &repo, rw);
     log::info!("done construction of {commits:?} in  {}", repo.spec);
@@ -398,7 +512,7 @@ fn pre_query(
     content: &Content,
     repo_config: hyperast_vcs_git::processing::ParametrizedCommitProcessorHandle,
 ) -> Result<hyperast_tsquery::Query, QueryingError> {
-    let Param { user, name, commit } = path.clone();
+    let Param { forge, user, name, commit } = path.clone();
     let mut additional = commit.split("/");
     let commit = additional.next().unwrap();
     let Content {
@@ -408,11 +522,19 @@ fn pre_query(
         commits: _,
         max_matches: _,
         timeout: _,
+        captures: _,
+        ..
     } = &content;
     let config = if language == "Java" {
         hyperast_vcs_git::processing::RepoConfig::JavaMaven
     } else if language == "Cpp" {
         hyperast_vcs_git::processing::RepoConfig::CppMake
+    } else if language == "C" {
+        hyperast_vcs_git::processing::RepoConfig::CMake
+    } else if language == "Python" {
+        hyperast_vcs_git::processing::RepoConfig::PythonPip
+    } else if language == "Rust" {
+        hyperast_vcs_git::processing::RepoConfig::RustCargo
     } else {
         hyperast_vcs_git::processing::RepoConfig::Any
     };
@@ -441,24 +563,48 @@ fn simple_aux(
     stores: &hyperast::store::SimpleStores<hyperast_vcs_git::TStore>,
     code: NodeIdentifier,
     query: &hyperast_tsquery::Query,
+    columns: &hyperast::scripting::columns::MetricColumns,
     timeout: std::time::Duration,
     max_matches: u64,
+    captures: Option<(&hyperast_vcs_git::processing::ConfiguredRepo2, Oid)>,
 ) -> Result<ComputeResult, MatchingError<ComputeResult>> {
     let pos = hyperast::position::StructuralPosition::new(code);
-    let cursor = hyperast_tsquery::hyperast_cursor::TreeCursor::new(stores, pos);
+    let metrics = crate::metric_columns::lookup(columns, stores);
+    let cursor =
+        hyperast_tsquery::hyperast_cursor::TreeCursor::new(stores, pos).with_metrics(&metrics);
     let qcursor = query.matches(cursor);
     let now = Instant::now();
     let mut result = vec![0; query.enabled_pattern_count()];
+    let mut matches = captures.map(|_| vec![]);
     for m in qcursor {
         let i = m.pattern_index;
         let i = query.enabled_pattern_index(i).unwrap();
         result[i as usize] += 1;
+        if let (Some((repo, commit_oid)), Some(matches)) = (captures, &mut matches) {
+            let mut captures = vec![];
+            for c in &m.captures {
+                let p = &c.node.pos;
+                captures.push(Capture {
+                    name: query.capture_name(c.index).to_string(),
+                    range: globalize(
+                        repo,
+                        commit_oid,
+                        (p.make_position(stores), p.iter_offsets().collect()),
+                    ),
+                });
+            }
+            matches.push(MatchCaptures {
+                pattern: i,
+                captures,
+            });
+        }
         let compute_time = now.elapsed();
         if compute_time >= timeout {
             let compute_time = now.elapsed().as_secs_f64();
             return Err(MatchingError::TimeOut(ComputeResult {
                 result,
                 compute_time,
+                matches,
             }));
         } else if result[i as usize] > max_matches {
             // TODO disable the pattern, return the new query
@@ -466,29 +612,65 @@ fn simple_aux(
             return Err(MatchingError::MaxMatches(ComputeResult {
                 result,
                 compute_time,
+                matches,
             }));
         }
-
-        // dbg!(m.pattern_index);
-        // dbg!(m.captures.len());
-        // for c in &m.captures {
-        //     let i = c.index;
-        //     dbg!(i);
-        //     let name = query.capture_name(i);
-        //     dbg!(name);
-        //     use hyperast::position::TreePath;
-        //     let n = c.node.pos.node().unwrap();
-        //     let n = hyperast::nodes::SyntaxSerializer::new(c.node.stores, *n);
-        //     dbg!(n.to_string());
-        // }
     }
     let compute_time = now.elapsed().as_secs_f64();
     Ok(ComputeResult {
         result,
         compute_time,
+        matches,
     })
 }
 
+/// Same as [`simple_aux`] without captures,
+/// reusing the counts of the files and directories already queried in other commits
+fn memoized_aux(
+    stores: &hyperast::store::SimpleStores<hyperast_vcs_git::TStore>,
+    code: NodeIdentifier,
+    query: &hyperast_tsquery::match_cache::MemoizedQuery,
+    cache: &hyperast_tsquery::match_cache::MatchCache<NodeIdentifier>,
+    columns: &hyperast::scripting::columns::MetricColumns,
+    timeout: std::time::Duration,
+    max_matches: u64,
+) -> Result<ComputeResult, MatchingError<ComputeResult>> {
+    let now = Instant::now();
+    let mut timed_out = false;
+    let stop = |result: &[u64]| {
+        timed_out = now.elapsed() >= timeout;
+        timed_out || result.iter().any(|x| *x > max_matches)
+    };
+    let metrics = crate::metric_columns::lookup(columns, stores);
+    let result = query.count(stores, code, cache, Some(&metrics), stop);
+    let compute_time = now.elapsed().as_secs_f64();
+    match result {
+        // cached counts are not checked while counting
+        Ok(result) if result.iter().any(|x| *x > max_matches) => {
+            Err(MatchingError::MaxMatches(ComputeResult {
+                result,
+                compute_time,
+                matches: None,
+            }))
+        }
+        Ok(result) => Ok(ComputeResult {
+            result,
+            compute_time,
+            matches: None,
+        }),
+        Err(result) if timed_out => Err(MatchingError::TimeOut(ComputeResult {
+            result,
+            compute_time,
+            matches: None,
+        })),
+        Err(result) => Err(MatchingError::MaxMatches(ComputeResult {
+            result,
+            compute_time,
+            matches: None,
+        })),
+    }
+}
+
 #[derive(Serialize)]
 pub struct ComputeResultsDifferential {
     pub prepare_time: f64,
@@ -496,6 +678,7 @@ pub struct ComputeResultsDifferential {
 }
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ParamDifferential {
+    forge: hyperast_vcs_git::git::Forge,
     user: String,
     name: String,
     commit: String,
@@ -509,6 +692,7 @@ pub fn differential(
 ) -> Result<Json<ComputeResultsDifferential>, QueryingError> {
     let now = Instant::now();
     let ParamDifferential {
+        forge,
         user,
         name,
         commit,
@@ -527,13 +711,19 @@ pub fn differential(
         hyperast_vcs_git::processing::RepoConfig::JavaMaven
     } else if language == "Cpp" {
         hyperast_vcs_git::processing::RepoConfig::CppMake
+    } else if language == "C" {
+        hyperast_vcs_git::processing::RepoConfig::CMake
+    } else if language == "Python" {
+        hyperast_vcs_git::processing::RepoConfig::PythonPip
+    } else if language == "Rust" {
+        hyperast_vcs_git::processing::RepoConfig::RustCargo
     } else {
         hyperast_vcs_git::processing::RepoConfig::Any
     };
     let lang = &language;
     let language: tree_sitter::Language = hyperast_vcs_git::resolve_language(&language)
         .ok_or_else(|| QueryingError::MissingLanguage(language.to_string()))?;
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo = state
         .repositories
         .read()
@@ -543,13 +733,13 @@ pub fn differential(
         Some(repo) => repo,
         None => {
             let configs = &mut state.repositories.write().unwrap();
+            log::error!("missing config for {}", repo_spec);
             if let Some(precomp) = precomp {
-                configs.register_config_with_prequeries(repo_spec.clone(), config, &[&precomp]);
+                configs.register_config_with_prequeries(repo_spec.clone(), config, &[&precomp])
             } else {
-                configs.register_config(repo_spec.clone(), config);
+                configs.register_config(repo_spec.clone(), config)
             }
-            log::error!("missing config for {}", repo_spec);
-            configs.get_config(repo_spec.clone()).unwrap()
+            .map_err(|e| QueryingError::ProcessingError(e.to_string()))?
         }
     };
     let mut repo = repo.fetch();
@@ -0,0 +1,423 @@
+//! The evolution of the matches of a query along the history of a repository.
+//!
+//! The matches of each commit are paired with the ones of its parents through the mappings computed by [`hyper_diff`],
+//! so that one can see in which commit a match (eg. a code smell) appeared and in which one it disappeared.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::path::PathBuf;
+
+use axum::Json;
+use hyper_diff::decompressed_tree_store::{DecompressedWithParent, ShallowDecompressedTreeStore};
+use hyper_diff::matchers::mapping_store::{MappingStore, MonoMappingStore, VecStore};
+use hyper_diff::matchers::{Decompressible, Mapper, Mapping};
+use hyperast::position::StructuralPosition;
+use hyperast::position::position_accessors::WithPreOrderOffsets;
+use hyperast::store::defaults::NodeIdentifier;
+use hyperast_vcs_git::git::Oid;
+use hyperast_vcs_git::processing::ConfiguredRepo2;
+use serde::Serialize;
+use std::time::Instant;
+
+use super::{ComputeResult, Content, MatchingError, Param, QueryingError};
+use crate::SharedState;
+use crate::smells::{CodeRange, globalize};
+
+type Idx = u16;
+
+#[derive(Serialize)]
+pub struct ComputeResultsHistory {
+    pub prepare_time: f64,
+    pub compute_time: f64,
+    /// one per commit of the range and parent also in the range
+    pub evolutions: Vec<MatchesEvolution>,
+}
+
+/// How the matches of each pattern evolved from a parent commit to its child
+#[derive(Serialize)]
+pub struct MatchesEvolution {
+    pub before: String,
+    pub after: String,
+    /// indexed by enabled pattern, like [`ComputeResult::result`]
+    pub patterns: Vec<PatternEvolution>,
+}
+
+#[derive(Serialize, Default)]
+pub struct PatternEvolution {
+    /// matches of `after` that are not mapped from a match of `before`
+    pub appeared: Vec<CodeRange>,
+    /// matches of `before` that are not mapped to a match of `after`
+    pub disappeared: Vec<CodeRange>,
+    /// matches mapped to a match in another file or under a node that is not mapped to their parent
+    pub moved: Vec<(CodeRange, CodeRange)>,
+    /// number of matches mapped to a match under the node mapped to their parent
+    pub unchanged: usize,
+}
+
+/// A match, located with its `@root` capture or its first capture
+struct Located {
+    pattern: u16,
+    pos: StructuralPosition<NodeIdentifier, Idx>,
+}
+
+pub fn history(
+    content: Content,
+    mut state: SharedState,
+    path: Param,
+) -> Result<Json<ComputeResultsHistory>, QueryingError> {
+    let now = Instant::now();
+    let (repo, commits) = super::pre_repo(&mut state, &path, &content)
+        .map_err(|e| QueryingError::ProcessingError(e.to_string()))?;
+    let query = super::pre_query(&mut state, &path, &content, repo.config)?;
+    let timeout = std::time::Duration::from_millis(content.timeout);
+    let pattern_count = query.enabled_pattern_count();
+    let prepare_time = now.elapsed().as_secs_f64();
+    let now = Instant::now();
+
+    let mut matches = HashMap::new();
+    let mut roots = HashMap::new();
+    let mut parents = HashMap::new();
+    for commit_oid in &commits {
+        let repositories = state.repositories.read().unwrap();
+        let commit = repositories.get_commit(&repo.config, commit_oid).unwrap();
+        let stores = &repositories.processor.main_stores;
+        let located = locate_matches(
+            stores,
+            commit.ast_root,
+            &query,
+            timeout,
+            content.max_matches,
+        )
+        .map_err(QueryingError::MatchingError)?;
+        matches.insert(*commit_oid, located);
+        roots.insert(*commit_oid, commit.ast_root);
+        parents.insert(*commit_oid, commit.parents.clone());
+    }
+    log::info!(
+        "done querying of {} commits in {}",
+        commits.len(),
+        repo.spec
+    );
+
+    let mut evolutions = vec![];
+    for after in &commits {
+        for before in &parents[after] {
+            let Some(before_matches) = matches.get(before) else {
+                // the parent is outside of the range
+                continue;
+            };
+            let patterns = pair_matches(
+                &state,
+                &repo,
+                (*before, roots[before], before_matches),
+                (*after, roots[after], &matches[after]),
+                pattern_count,
+            );
+            evolutions.push(MatchesEvolution {
+                before: before.to_string(),
+                after: after.to_string(),
+                patterns,
+            });
+        }
+    }
+    log::info!("done pairing matches in {}", repo.spec);
+    Ok(Json(ComputeResultsHistory {
+        prepare_time,
+        compute_time: now.elapsed().as_secs_f64(),
+        evolutions,
+    }))
+}
+
+fn locate_matches(
+    stores: &hyperast::store::SimpleStores<hyperast_vcs_git::TStore>,
+    code: NodeIdentifier,
+    query: &hyperast_tsquery::Query,
+    timeout: std::time::Duration,
+    max_matches: u64,
+) -> Result<Vec<Located>, MatchingError<ComputeResult>> {
+    let pos = StructuralPosition::new(code);
+    let cursor = hyperast_tsquery::hyperast_cursor::TreeCursor::new(stores, pos);
+    let qcursor = query.matches(cursor);
+    let root = query.capture_index_for_name("root");
+    let now = Instant::now();
+    let mut result = vec![0; query.enabled_pattern_count()];
+    let mut located = vec![];
+    for m in qcursor {
+        let i = query.enabled_pattern_index(m.pattern_index).unwrap();
+        result[i as usize] += 1;
+        let node = root
+            .and_then(|root| m.nodes_for_capture_index(root).next())
+            .or_else(|| (&m.captures).into_iter().next().map(|c| &c.node));
+        if let Some(node) = node {
+            located.push(Located {
+                pattern: i,
+                pos: node.pos.clone(),
+            });
+        }
+        if now.elapsed() >= timeout {
+            return Err(MatchingError::TimeOut(ComputeResult {
+                compute_time: now.elapsed().as_secs_f64(),
+                result,
+                matches: None,
+            }));
+        } else if result[i as usize] > max_matches {
+            return Err(MatchingError::MaxMatches(ComputeResult {
+                compute_time: now.elapsed().as_secs_f64(),
+                result,
+                matches: None,
+            }));
+        }
+    }
+    Ok(located)
+}
+
+fn pair_matches(
+    state: &SharedState,
+    repo: &ConfiguredRepo2,
+    (src_oid, src_tr, src_matches): (Oid, NodeIdentifier, &[Located]),
+    (dst_oid, dst_tr, dst_matches): (Oid, NodeIdentifier, &[Located]),
+    pattern_count: usize,
+) -> Vec<PatternEvolution> {
+    let mut patterns: Vec<PatternEvolution> =
+        (0..pattern_count).map(|_| Default::default()).collect();
+    if src_tr == dst_tr {
+        // identical trees, thus identical matches
+        for m in src_matches {
+            patterns[m.pattern as usize].unchanged += 1;
+        }
+        return patterns;
+    }
+    let repositories = state.repositories.read().unwrap();
+    let with_spaces_stores = &repositories.processor.main_stores;
+    let stores = &hyperast_vcs_git::no_space::as_nospaces2(with_spaces_stores);
+    let binding = crate::utils::bind_tree_pair(&state.partial_decomps, &src_tr, &dst_tr);
+    let mut locked = binding.lock();
+    let (src_tree, dst_tree) = locked.as_mut(stores);
+
+    let mapped = match state.mappings_alone.entry((src_tr, dst_tr)) {
+        dashmap::mapref::entry::Entry::Occupied(entry) => entry.into_ref().downgrade(),
+        dashmap::mapref::entry::Entry::Vacant(entry) => {
+            let hyperast = stores;
+            let mut mapper = Mapper {
+                hyperast,
+                mapping: Mapping {
+                    src_arena: Decompressible {
+                        hyperast,
+                        decomp: &mut *src_tree,
+                    },
+                    dst_arena: Decompressible {
+                        hyperast,
+                        decomp: &mut *dst_tree,
+                    },
+                    mappings: VecStore::default(),
+                },
+            };
+            mapper.mapping.mappings.topit(
+                mapper.mapping.src_arena.len(),
+                mapper.mapping.dst_arena.len(),
+            );
+            crate::matching::full2(&mut mapper);
+            let mappings = mapper.mapping.mappings;
+            entry
+                .insert((crate::MappingStage::Bottomup, mappings))
+                .downgrade()
+        }
+    };
+    let mappings = &mapped.1;
+
+    let mut src_arena = Decompressible {
+        hyperast: stores,
+        decomp: src_tree,
+    };
+    let mut dst_arena = Decompressible {
+        hyperast: stores,
+        decomp: dst_tree,
+    };
+    // the mappings are on the trees without spaces
+    let no_spaces_path = |tr: NodeIdentifier, m: &Located| {
+        hyperast::position::compute_position_with_no_spaces(
+            tr,
+            &mut m.pos.iter_offsets(),
+            with_spaces_stores,
+        )
+        .2
+    };
+    let code_range = |oid: Oid, m: &Located| {
+        let pos = m.pos.make_position(with_spaces_stores);
+        globalize(repo, oid, (pos, m.pos.iter_offsets().collect()))
+    };
+
+    let file = |m: &Located| m.pos.make_position(with_spaces_stores).file().to_owned();
+    let mut src_anchors = vec![];
+    for m in src_matches {
+        let path = no_spaces_path(src_tr, m);
+        let root = src_arena.root();
+        let node = src_arena.child_decompressed(&root, path.iter().copied());
+        src_anchors.push(Anchor {
+            pattern: m.pattern,
+            node,
+            parent: src_arena.parent(&node),
+            file: file(m),
+        });
+    }
+    let mut dst_anchors = vec![];
+    for m in dst_matches {
+        let path = no_spaces_path(dst_tr, m);
+        let root = dst_arena.root();
+        let node = dst_arena.child_decompressed(&root, path.iter().copied());
+        dst_anchors.push(Anchor {
+            pattern: m.pattern,
+            node,
+            parent: dst_arena.parent(&node),
+            file: file(m),
+        });
+    }
+    let pairing = pair_anchors(&src_anchors, &dst_anchors, |id| mappings.get_dst(id));
+    let mut paired = HashSet::new();
+    for (m, pairing) in src_matches.iter().zip(pairing) {
+        let evolution = &mut patterns[m.pattern as usize];
+        match pairing {
+            Paired::Unchanged(j) => {
+                paired.insert(j);
+                evolution.unchanged += 1;
+            }
+            Paired::Moved(j) => {
+                paired.insert(j);
+                let dst_m = &dst_matches[j];
+                evolution
+                    .moved
+                    .push((code_range(src_oid, m), code_range(dst_oid, dst_m)));
+            }
+            Paired::Disappeared => evolution.disappeared.push(code_range(src_oid, m)),
+        }
+    }
+    for (j, m) in dst_matches.iter().enumerate() {
+        if !paired.contains(&j) {
+            patterns[m.pattern as usize]
+                .appeared
+                .push(code_range(dst_oid, m));
+        }
+    }
+    patterns
+}
+
+/// A match in the decompressed trees without spaces
+struct Anchor<IdD> {
+    pattern: u16,
+    node: IdD,
+    parent: Option<IdD>,
+    file: PathBuf,
+}
+
+/// How a match of the parent commit is found in the child commit
+#[derive(Debug, PartialEq, Eq)]
+enum Paired {
+    Unchanged(usize),
+    Moved(usize),
+    Disappeared,
+}
+
+/// Pairs each match of `src` with a match of `dst` of the same pattern, on the node it is `mapped` to.
+///
+/// A paired match moved when it is in another file or when its parent is not mapped to its new parent,
+/// thus inserting or removing siblings before a match does not move it.
+/// Several matches on the same node are paired one to one.
+fn pair_anchors<IdD: Copy + Eq + Hash>(
+    src: &[Anchor<IdD>],
+    dst: &[Anchor<IdD>],
+    mapped: impl Fn(&IdD) -> Option<IdD>,
+) -> Vec<Paired> {
+    let mut dsts: HashMap<_, VecDeque<usize>> = HashMap::new();
+    for (j, m) in dst.iter().enumerate() {
+        dsts.entry((m.pattern, m.node)).or_default().push_back(j);
+    }
+    let mut pairing = vec![];
+    for m in src {
+        let j = mapped(&m.node)
+            .and_then(|node| dsts.get_mut(&(m.pattern, node)))
+            .and_then(|js| js.pop_front());
+        let Some(j) = j else {
+            pairing.push(Paired::Disappeared);
+            continue;
+        };
+        let dst_m = &dst[j];
+        let same_parent = match (m.parent, dst_m.parent) {
+            (Some(p), Some(dst_p)) => mapped(&p) == Some(dst_p),
+            (None, None) => true,
+            _ => false,
+        };
+        if same_parent && m.file == dst_m.file {
+            pairing.push(Paired::Unchanged(j));
+        } else {
+            pairing.push(Paired::Moved(j));
+        }
+    }
+    pairing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anchor(pattern: u16, node: u32, parent: u32, file: &str) -> Anchor<u32> {
+        Anchor {
+            pattern,
+            node,
+            parent: Some(parent),
+            file: file.into(),
+        }
+    }
+
+    fn mappings(pairs: &[(u32, u32)]) -> impl Fn(&u32) -> Option<u32> {
+        let pairs: HashMap<_, _> = pairs.iter().copied().collect();
+        move |id| pairs.get(id).copied()
+    }
+
+    #[test]
+    fn test_insert_before() {
+        // a statement is inserted before the matched ones, changing their index in their parent
+        let src = [anchor(0, 1, 10, "A.java"), anchor(0, 2, 10, "A.java")];
+        let dst = [
+            anchor(0, 5, 20, "A.java"),
+            anchor(0, 6, 20, "A.java"),
+            anchor(0, 7, 20, "A.java"),
+        ];
+        let mapped = mappings(&[(1, 6), (2, 7), (10, 20)]);
+        let pairing = pair_anchors(&src, &dst, mapped);
+        assert_eq!(pairing, [Paired::Unchanged(1), Paired::Unchanged(2)]);
+    }
+
+    #[test]
+    fn test_rename() {
+        // the matched method is renamed, but mapped, and its file is renamed
+        let src = [anchor(0, 1, 10, "A.java"), anchor(1, 2, 11, "B.java")];
+        let dst = [anchor(0, 3, 20, "A.java"), anchor(1, 4, 21, "C.java")];
+        let mapped = mappings(&[(1, 3), (10, 20), (2, 4), (11, 21)]);
+        let pairing = pair_anchors(&src, &dst, mapped);
+        assert_eq!(pairing, [Paired::Unchanged(0), Paired::Moved(1)]);
+    }
+
+    #[test]
+    fn test_move() {
+        // the first match is moved to another parent, the second one is removed
+        let src = [anchor(0, 1, 10, "A.java"), anchor(0, 2, 10, "A.java")];
+        let dst = [anchor(0, 3, 21, "A.java"), anchor(1, 4, 20, "A.java")];
+        let mapped = mappings(&[(1, 3), (10, 20), (2, 4)]);
+        let pairing = pair_anchors(&src, &dst, mapped);
+        assert_eq!(pairing, [Paired::Moved(0), Paired::Disappeared]);
+    }
+
+    #[test]
+    fn test_same_node() {
+        // two matches of a pattern on the same node
+        let src = [anchor(0, 1, 10, "A.java"), anchor(0, 1, 10, "A.java")];
+        let dst = [
+            anchor(0, 3, 20, "A.java"),
+            anchor(0, 3, 20, "A.java"),
+            anchor(0, 3, 20, "A.java"),
+        ];
+        let mapped = mappings(&[(1, 3), (10, 20)]);
+        let pairing = pair_anchors(&src, &dst, mapped);
+        assert_eq!(pairing, [Paired::Unchanged(0), Paired::Unchanged(1)]);
+    }
+}
@@ -28,12 +28,13 @@ use std::fmt::Display;
 
 #[derive(Deserialize, Clone)]
 pub struct ScriptingParam {
-    user: String,
-    name: String,
-    commit: String,
+    pub(crate) forge: hyperast_vcs_git::git::Forge,
+    pub(crate) user: String,
+    pub(crate) name: String,
+    pub(crate) commit: String,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct ScriptContentDepth {
     #[serde(flatten)]
     inner: ScriptContent,
@@ -119,13 +120,23 @@ pub fn simple_depth(
     script: ScriptContentDepth,
     state: SharedState,
     path: ScriptingParam,
+) -> Result<Json<ComputeResults>, ScriptingError> {
+    simple_depth_with_progress(script, state, path, Default::default())
+}
+
+/// When cancelled, only gives the results of the commits already evaluated
+pub(crate) fn simple_depth_with_progress(
+    script: ScriptContentDepth,
+    state: SharedState,
+    path: ScriptingParam,
+    progress: crate::jobs::Progress,
 ) -> Result<Json<ComputeResults>, ScriptingError> {
     let ScriptContentDepth {
         inner: script,
         commits,
     } = script;
     let now = Instant::now();
-    let ScriptingParam { user, name, commit } = path.clone();
+    let ScriptingParam { forge, user, name, commit } = path.clone();
     let mut engine = Engine::new();
     engine.disable_symbol("/");
     add_utils(&mut engine);
@@ -138,7 +149,7 @@ pub fn simple_depth(
     let accumulate_script = engine.compile(script.accumulate.clone()).map_err(|x| {
         ScriptingError::AtCompilation(format!("Acc: {}, {}", x, script.accumulate.clone()))
     })?;
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo = state
         .repositories
         .write()
@@ -148,12 +159,13 @@ pub fn simple_depth(
         Some(repo) => repo,
         None => {
             let configs = &mut state.repositories.write().unwrap();
-            configs.register_config(
-                repo_spec.clone(),
-                hyperast_vcs_git::processing::RepoConfig::JavaMaven,
-            );
             log::error!("missing config for {}", repo_spec);
-            configs.get_config(repo_spec.clone()).unwrap()
+            configs
+                .register_config(
+                    repo_spec.clone(),
+                    hyperast_vcs_git::processing::RepoConfig::JavaMaven,
+                )
+                .map_err(|e| ScriptingError::Other(e.to_string()))?
         }
     };
     // .ok_or_else(|| ScriptingError::Other("missing config for repository".to_string()))?;
@@ -168,6 +180,10 @@ pub fn simple_depth(
     let prepare_time = now.elapsed().as_secs_f64();
     let mut results = vec![];
     for commit_oid in &commits {
+        if progress.is_cancelled() {
+            break;
+        }
+        progress.commit(commit_oid);
         let now = Instant::now();
         let r = simple_aux(
             state.clone(),
@@ -210,7 +226,7 @@ fn simple_prepare(
     ),
     ScriptingError,
 > {
-    let ScriptingParam { user, name, commit } = path.clone();
+    let ScriptingParam { forge, user, name, commit } = path.clone();
     let mut engine = Engine::new();
     engine.disable_symbol("/");
     add_utils(&mut engine);
@@ -223,7 +239,7 @@ fn simple_prepare(
     let accumulate_script = engine.compile(script.accumulate.clone()).map_err(|x| {
         ScriptingError::AtCompilation(format!("Acc: {}, {}", x, script.accumulate.clone()))
     })?;
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo = state
         .repositories
         .write()
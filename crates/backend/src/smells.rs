@@ -25,6 +25,7 @@ type Idx = u16;
 
 #[derive(Deserialize, Clone)]
 pub struct Param {
+    forge: hyperast_vcs_git::git::Forge,
     user: String,
     name: String,
     commit: String,
@@ -33,6 +34,7 @@ pub struct Param {
 
 #[derive(Deserialize, Clone)]
 pub struct Diffs {
+    forge: hyperast_vcs_git::git::Forge,
     user: String,
     name: String,
     commit: String,
@@ -116,6 +118,7 @@ pub(crate) fn smells(
 ) -> Result<Json<SearchResults>, String> {
     let now = Instant::now();
     let Param {
+        forge,
         user,
         name,
         commit,
@@ -137,7 +140,7 @@ pub(crate) fn smells(
         true
     };
 
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo_handle = state
         .repositories
         .write()
@@ -282,13 +285,14 @@ pub(crate) fn smells_ex_from_diffs(
 ) -> Result<Json<ExamplesResults>, String> {
     let now = Instant::now();
     let Diffs {
+        forge,
         user,
         name,
         commit,
         len,
     } = path;
     log::warn!("use len value={len}");
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo_handle = state
         .repositories
         .write()
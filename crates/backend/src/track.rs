@@ -23,11 +23,14 @@ use hyperast::{
     types::{self, Childrn, HyperAST, NodeStore, WithChildren, WithHashs, WithStats},
 };
 use hyperast_vcs_git::{
-    TStore, git::Repo, multi_preprocessed, preprocessed::child_at_path_tracked,
+    TStore,
+    git::{DagBounds, Repo, RevRange},
+    multi_preprocessed,
+    preprocessed::child_at_path_tracked,
     processing::ConfiguredRepoTrait,
 };
 use serde::{Deserialize, Serialize};
-use serde_aux::prelude::deserialize_bool_from_anything;
+use serde_aux::prelude::{deserialize_bool_from_anything, deserialize_option_number_from_string};
 use tokio::time::Instant;
 
 use crate::{
@@ -38,6 +41,7 @@ use crate::{
 
 #[derive(Deserialize, Clone, Debug)]
 pub struct TrackingParam {
+    pub forge: hyperast_vcs_git::git::Forge,
     pub user: String,
     pub name: String,
     pub commit: String,
@@ -46,17 +50,27 @@ pub struct TrackingParam {
 
 #[derive(Deserialize, Clone, Debug)]
 pub struct TrackingAtPathParam {
+    forge: hyperast_vcs_git::git::Forge,
     user: String,
     name: String,
     commit: String,
     path: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct TrackingQuery {
     pub start: Option<usize>,
     pub end: Option<usize>,
     pub before: Option<String>,
+    /// tracking stops at the commit that is `depth` commits away from the tracked one
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub depth: Option<usize>,
+    /// in seconds since the epoch, tracking stops at the first commit older than `since`
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub since: Option<i64>,
+    /// in seconds since the epoch, tracking starts from the first ancestor not newer than `until`
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub until: Option<i64>,
     #[serde(flatten)]
     pub flags: Flags,
 }
@@ -255,9 +269,19 @@ pub fn track_code(
     state: SharedState,
     path: TrackingParam,
     query: TrackingQuery,
+) -> Result<TrackingResult<IdN, Idx>, TrackingError> {
+    track_code_with_progress(state, path, query, Default::default())
+}
+
+pub(crate) fn track_code_with_progress(
+    state: SharedState,
+    path: TrackingParam,
+    query: TrackingQuery,
+    progress: crate::jobs::Progress,
 ) -> Result<TrackingResult<IdN, Idx>, TrackingError> {
     let now = Instant::now();
     let TrackingParam {
+        forge,
         user,
         name,
         commit,
@@ -267,9 +291,17 @@ pub fn track_code(
         start,
         end,
         before,
+        depth,
+        since,
+        until,
         flags,
     } = query;
-    let repo_specifier = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let bounds = DagBounds {
+        depth,
+        since,
+        until,
+    };
+    let repo_specifier = forge.repo(user, name);
     let repo_handle = state
         .repositories
         .write()
@@ -283,7 +315,7 @@ pub fn track_code(
         })?;
     let mut repository = repo_handle.fetch();
     log::debug!("done cloning {}", repository.spec);
-    let mut commit = commit.clone();
+    let (mut commit, before) = split_range(&repository, commit, before, &bounds)?;
     let mut node_processed = 0;
     let mut commits_processed = 1;
     let mut file = file;
@@ -291,6 +323,14 @@ pub fn track_code(
     let mut end = end;
     let mut source = None;
     while node_processed < MAX_NODES {
+        if progress.is_cancelled() {
+            return Err(TrackingError {
+                compute_time: now.elapsed().as_secs_f64(),
+                commits_processed,
+                node_processed,
+                message: "cancelled".to_string(),
+            });
+        }
         commits_processed += 1;
         let commits = state
             .repositories
@@ -306,6 +346,7 @@ pub fn track_code(
         log::debug!("done construction of {commits:?} in {}", repository.spec);
         let src_oid = commits[0];
         let dst_oid = commits[1];
+        progress.commit(&src_oid);
         match track_aux(
             state.clone(),
             &repository,
@@ -361,7 +402,9 @@ pub fn track_code(
             MappingResult::Skipped { nodes, src, next } => {
                 node_processed += nodes;
                 dbg!(src_oid, dst_oid);
-                if before.as_ref() == Some(&dst_oid.to_string()) {
+                if before.as_ref() == Some(&dst_oid.to_string())
+                    || out_of_bounds(&repository, &bounds, commits_processed - 1, dst_oid)
+                {
                     let aaa = src.globalize(repository.spec, commit);
                     let (src, intermediary) = if let Some(src) = source {
                         (src, Some(aaa))
@@ -416,15 +459,24 @@ pub(crate) fn track_code_at_path(
         start,
         end,
         before,
+        depth,
+        since,
+        until,
         flags,
     } = query;
+    let bounds = DagBounds {
+        depth,
+        since,
+        until,
+    };
     let TrackingAtPathParam {
+        forge,
         user,
         name,
         commit,
         path,
     } = path;
-    let repo_specifier = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_specifier = forge.repo(user, name);
     let repository = state
         .repositories
         .write()
@@ -440,7 +492,7 @@ pub(crate) fn track_code_at_path(
     log::debug!("done cloning {}", repository.spec);
     // let mut get_mut = state.write().unwrap();
     // let state = get_mut.deref_mut();
-    let mut commit = commit.clone();
+    let (mut commit, before) = split_range(&repository, commit, before, &bounds)?;
     let mut node_processed = 0;
     let mut commits_processed = 1;
     let mut path: Vec<Idx> = path.split("/").filter_map(|x| x.parse().ok()).collect();
@@ -517,7 +569,9 @@ pub(crate) fn track_code_at_path(
             })?,
             MappingResult::Skipped { nodes, src, next } => {
                 // TODO handle cases where there is no more commits
-                if before.is_some() {
+                if before.is_some()
+                    || out_of_bounds(&repository, &bounds, commits_processed - 1, dst_oid)
+                {
                     let aaa = src.globalize(repository.spec, commit);
                     let (src, intermediary) = if let Some(src) = source {
                         (src, Some(aaa))
@@ -573,15 +627,24 @@ pub(crate) fn track_code_at_path_with_changes(
         start: _,
         end: _,
         before,
+        depth,
+        since,
+        until,
         flags,
     } = query;
+    let bounds = DagBounds {
+        depth,
+        since,
+        until,
+    };
     let TrackingAtPathParam {
+        forge,
         user,
         name,
         commit,
         path,
     } = path;
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo_handle = state
         .repositories
         .write()
@@ -596,7 +659,7 @@ pub(crate) fn track_code_at_path_with_changes(
     let mut repository = repo_handle.fetch();
     log::debug!("done cloning {}", repository.spec);
     let mut ori_oid = None;
-    let mut commit = commit.clone();
+    let (mut commit, before) = split_range(&repository, commit, before, &bounds)?;
     let mut node_processed = 0;
     let mut commits_processed = 1;
     let mut path: Vec<_> = path.split("/").filter_map(|x| x.parse().ok()).collect();
@@ -692,9 +755,14 @@ pub(crate) fn track_code_at_path_with_changes(
                 dbg!(src_oid, dst_oid);
                 // TODO fix issue of not stoping when failling to match accurately,
                 // most likely related to miss use of fallback value ?
-                if commits.len() < 3 || !(node_processed < MAX_NODES) {
+                if commits.len() < 3
+                    || !(node_processed < MAX_NODES)
+                    || before.as_ref() == Some(&dst_oid.to_string())
+                    || out_of_bounds(&repository, &bounds, commits_processed - 1, dst_oid)
+                {
                     // no commit remaining (first + second < 3)
                     // NOTE there is no parent commit to dst_commit, thus we should stop now
+                    // or the start of the tracked range was reached
                     let changes =
                         changes::added_deleted(state, &repository, dst_oid, ori_oid.unwrap())
                             .map_err(|err| TrackingError {
@@ -747,6 +815,72 @@ pub(crate) fn track_code_at_path_with_changes(
     })
 }
 
+/// A `commit` like `A..B` is tracked from `B` and stops at `A`, like with `before`,
+/// with `bounds.until` it is tracked from its first (first parent) ancestor that is not newer
+fn split_range(
+    repository: &hyperast_vcs_git::processing::ConfiguredRepo2,
+    commit: String,
+    before: Option<String>,
+    bounds: &DagBounds,
+) -> Result<(String, Option<String>), TrackingError> {
+    let error = |message: String| TrackingError {
+        compute_time: 0.0,
+        commits_processed: 0,
+        node_processed: 0,
+        message,
+    };
+    let resolve = |rev: &str| {
+        hyperast_vcs_git::git::retrieve_commit(&repository.repo, rev)
+            .map_err(|e| error(e.to_string()))
+    };
+    let (commit, before) = if !RevRange::is_range(&commit) {
+        if bounds.until.is_none() {
+            return Ok((commit, before));
+        }
+        (resolve(&commit)?, before)
+    } else {
+        let range: RevRange = commit.parse().map_err(error)?;
+        let commit = if range.included.is_empty() {
+            let head = repository.repo.head().and_then(|x| x.peel_to_commit());
+            head.map_err(|e| error(e.to_string()))?
+        } else {
+            resolve(&range.included)?
+        };
+        if range.excluded.is_empty() {
+            (commit, before)
+        } else {
+            (commit, Some(resolve(&range.excluded)?.id().to_string()))
+        }
+    };
+    let mut commit = commit;
+    while let Some(until) = bounds
+        .until
+        .filter(|until| commit.time().seconds() > *until)
+    {
+        commit = commit
+            .parent(0)
+            .map_err(|_| error(format!("no commit before {} in {}", until, repository.spec)))?;
+    }
+    Ok((commit.id().to_string(), before))
+}
+
+/// Whether the tracking should stop at `dst`, reached after `steps` commits
+fn out_of_bounds(
+    repository: &hyperast_vcs_git::processing::ConfiguredRepo2,
+    bounds: &DagBounds,
+    steps: usize,
+    dst: hyperast_vcs_git::git::Oid,
+) -> bool {
+    if bounds.depth.is_some_and(|depth| steps >= depth) {
+        return true;
+    }
+    let Some(since) = bounds.since else {
+        return false;
+    };
+    let time = repository.repo.find_commit(dst).map(|c| c.time().seconds());
+    time.is_ok_and(|time| time < since)
+}
+
 enum MappingResult<IdN, Idx, T = PieceOfCode<IdN, Idx>> {
     Direct {
         src: LocalPieceOfCode<IdN, Idx>,
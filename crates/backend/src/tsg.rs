@@ -10,6 +10,7 @@ use std::time::Instant;
 
 #[derive(Deserialize, Clone)]
 pub struct Param {
+    forge: hyperast_vcs_git::git::Forge,
     user: String,
     name: String,
     commit: String,
@@ -27,6 +28,7 @@ pub struct Content {
 pub enum QueryingError {
     MissingLanguage(String),
     TsgParsing(String),
+    ProcessingError(String),
 }
 
 #[derive(Serialize)]
@@ -54,7 +56,7 @@ pub fn simple(
     path: Param,
 ) -> Result<Json<ComputeResults>, QueryingError> {
     let now = Instant::now();
-    let Param { user, name, commit } = path.clone();
+    let Param { forge, user, name, commit } = path.clone();
     let Content {
         language: lang_name,
         query,
@@ -63,7 +65,7 @@ pub fn simple(
     } = query;
     let language: tree_sitter::Language = hyperast_vcs_git::resolve_language(&lang_name)
         .ok_or_else(|| QueryingError::MissingLanguage(lang_name.clone()))?;
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo = state
         .repositories
         .write()
@@ -73,12 +75,13 @@ pub fn simple(
         Some(repo) => repo,
         None => {
             let configs = &mut state.repositories.write().unwrap();
-            configs.register_config(
-                repo_spec.clone(),
-                hyperast_vcs_git::processing::RepoConfig::JavaMaven,
-            );
             log::error!("missing config for {}", repo_spec);
-            configs.get_config(repo_spec.clone()).unwrap()
+            configs
+                .register_config(
+                    repo_spec.clone(),
+                    hyperast_vcs_git::processing::RepoConfig::JavaMaven,
+                )
+                .map_err(|e| QueryingError::ProcessingError(e.to_string()))?
         }
     };
     // .ok_or_else(|| ScriptingError::Other("missing config for repository".to_string()))?;
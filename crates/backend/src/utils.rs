@@ -4,6 +4,7 @@ use hyperast::{
     store::defaults::NodeIdentifier,
     types::{HyperAST, WithStats},
 };
+use hyperast_vcs_git::git::{DagBounds, RevRange};
 
 pub type LPO<T> = SharedValue<lazy_post_order::LazyPostOrder<T, u32>>;
 type IdN = NodeIdentifier;
@@ -149,8 +150,30 @@ pub(crate) fn handle_pre_processing(
     after: &str,
     limit: usize,
 ) -> Result<Vec<hyperast_vcs_git::git::Oid>, Box<dyn std::error::Error>> {
-    let rw = hyperast_vcs_git::git::Builder::new(&repo.repo)?
-        .before(before)?
+    handle_pre_processing_bounded(state, repo, before, after, limit, DagBounds::default())
+}
+
+/// Like [`handle_pre_processing`], with a range like `A..B` the walk is bounded by `bounds`
+pub(crate) fn handle_pre_processing_bounded(
+    state: &std::sync::Arc<crate::AppState>,
+    repo: &mut hyperast_vcs_git::processing::ConfiguredRepo2,
+    before: &str,
+    after: &str,
+    limit: usize,
+    bounds: DagBounds,
+) -> Result<Vec<hyperast_vcs_git::git::Oid>, Box<dyn std::error::Error>> {
+    let builder = hyperast_vcs_git::git::Builder::new(&repo.repo)?.before(before)?;
+    if RevRange::is_range(after) {
+        // a range like `A..B`, the merged branches are also walked
+        let range: RevRange = after.parse()?;
+        let rw = builder
+            .range(&range)?
+            .dag(bounds)?
+            .take(limit)
+            .map(|x| x.unwrap());
+        return Ok(handle_pre_processing_aux(state, repo, rw));
+    }
+    let rw = builder
         .after(after)?
         .walk()?
         .take(limit)
@@ -162,16 +185,29 @@ pub(crate) fn handle_pre_processing(
     Ok(handle_pre_processing_aux(state, repo, rw))
 }
 
+/// Each element of `after` is either a commit or a range like `A..B`,
+/// with ranges the merged branches are also walked, within `bounds`.
 pub(crate) fn walk_commits_multi<'a, R: AsRef<str>>(
     repo: &'a hyperast_vcs_git::processing::ConfiguredRepo2,
     after: impl Iterator<Item = R>,
-) -> Result<impl Iterator<Item = hyperast_vcs_git::git::Oid> + 'a, Box<dyn std::error::Error>> {
+    bounds: DagBounds,
+) -> Result<Box<dyn Iterator<Item = hyperast_vcs_git::git::Oid> + 'a>, Box<dyn std::error::Error>> {
     let mut rw = hyperast_vcs_git::git::Builder::new(&repo.repo)?;
+    let mut ranges = false;
     for after in after {
-        rw = rw.after(after.as_ref())?;
+        let after = after.as_ref();
+        if RevRange::is_range(after) {
+            ranges = true;
+            rw = rw.range(&after.parse()?)?;
+        } else {
+            rw = rw.after(after)?;
+        }
+    }
+    if ranges {
+        Ok(Box::new(rw.dag(bounds)?.map(|x| x.unwrap())))
+    } else {
+        Ok(Box::new(rw.walk()?.map(|x| x.unwrap())))
     }
-    let rw = rw.walk()?.map(|x| x.unwrap());
-    Ok(rw)
 }
 
 /// Ensures the range is preprocessed --doing it if needed-- while avoiding to lock global state
@@ -192,8 +228,8 @@ pub(crate) fn handle_pre_processing_aux(
     match commits {
         Ok(commits) => commits,
         Err(mut commits) => {
-            let repository_processor = &mut state.repositories.write().unwrap().processor;
-            commits.extend(repository_processor.pre_pro(&mut rw, repo, usize::MAX));
+            let mut repositories = state.repositories.write().unwrap();
+            commits.extend(repositories.pre_process_chunk(&mut rw, repo, usize::MAX));
             commits
         }
     }
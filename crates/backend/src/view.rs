@@ -5,7 +5,9 @@ use const_chunks::IteratorConstChunks;
 use hyperast::{
     compat::HashMap,
     store::defaults::{LabelIdentifier, NodeIdentifier},
-    types::{self, Children, Childrn, HyperAST, LabelStore, Labeled, NodeStore, WithChildren},
+    types::{
+        self, Children, Childrn, HyperAST, HyperType, LabelStore, Labeled, NodeStore, WithChildren,
+    },
 };
 use serde::{Deserialize, Serialize};
 use tokio::time::Instant;
@@ -14,15 +16,18 @@ use crate::SharedState;
 
 #[derive(Deserialize, Clone, Debug)]
 pub struct Parameters {
-    user: String,
-    name: String,
-    commit: String,
-    path: Option<String>,
+    pub(crate) forge: hyperast_vcs_git::git::Forge,
+    pub(crate) user: String,
+    pub(crate) name: String,
+    pub(crate) commit: String,
+    /// the offsets of the viewed node from the root of the commit, separated by `/`
+    pub(crate) path: Option<String>,
 }
 
 type NodeId = u64;
 
-#[derive(Serialize, Clone, Debug)]
+/// The names of the types, indexed by the kinds of the view
+#[derive(Serialize, Clone, Debug, Default)]
 pub struct TypeSys(Vec<String>);
 
 #[derive(Serialize, Clone, Debug)]
@@ -77,13 +82,14 @@ pub struct ViewBoth {
 pub fn view(state: SharedState, path: Parameters) -> Result<Json<ViewRes>, String> {
     let now = Instant::now();
     let Parameters {
+        forge,
         user,
         name,
         commit,
         path,
     } = path;
     dbg!(&path);
-    let repo_spec = hyperast_vcs_git::git::Forge::Github.repo(user, name);
+    let repo_spec = forge.repo(user, name);
     let repo = state
         .repositories
         .write()
@@ -104,18 +110,12 @@ pub fn view(state: SharedState, path: Parameters) -> Result<Json<ViewRes>, Strin
     let src_tr = commit_src.ast_root;
     dbg!(src_tr);
     let node_store = &repositories.processor.main_stores.node_store;
-    let label_store = &repositories.processor.main_stores.label_store;
 
     log::info!("searching for {path:?}");
     let curr = resolve_path(src_tr, path, node_store);
-    todo!(
-        "should deprecate or accomodate changes in type repr ie. lang + type btw. could allow paking as u16 like before"
-    );
-    // let type_sys = TypeSys(types::Type::it().map(|x| x.to_string()).collect());
-
-    // let view = make_view(vec![(curr, 20)], &repositories.processor.main_stores);
-    // let view_res = ViewRes { type_sys, view };
-    // Ok(view_res.into())
+    let view_res = make_view(vec![(curr, 20)], &repositories.processor.main_stores);
+    log::info!("done viewing {} in {}", commit, now.elapsed().as_secs_f64());
+    Ok(view_res.into())
 }
 
 pub fn view_with_node_id(state: SharedState, id: u64) -> Result<Json<ViewRes>, String> {
@@ -126,22 +126,15 @@ pub fn view_with_node_id(state: SharedState, id: u64) -> Result<Json<ViewRes>, S
     dbg!(&id);
     let id: NodeIdentifier = unsafe { std::mem::transmute(id) };
     dbg!(&id);
-    let mut get_mut = state;
-    let repositories = get_mut.repositories.read().unwrap();
+    let repositories = state.repositories.read().unwrap();
     let node_store = &repositories.processor.main_stores.node_store;
-    let label_store = &repositories.processor.main_stores.label_store;
-
-    todo!(
-        "should deprecate or accomodate changes in type repr ie. lang + type btw. could allow paking as u16 like before"
-    );
-    // let type_sys = TypeSys(types::Type::it().map(|x| x.to_string()).collect());
 
-    // if node_store.try_resolve(id).is_none() {
-    //     return Err(format!("{id:?} is absent from the HyperAST"));
-    // }
-    // let view = make_view(vec![(id, 8)], &repositories.processor.main_stores);
-    // let view_res = ViewRes { type_sys, view };
-    // Ok(view_res.into())
+    if node_store.try_resolve(id).is_none() {
+        return Err(format!("{id:?} is absent from the HyperAST"));
+    }
+    let view_res = make_view(vec![(id, 8)], &repositories.processor.main_stores);
+    log::info!("done viewing {id:?} in {}", now.elapsed().as_secs_f64());
+    Ok(view_res.into())
 }
 
 fn resolve_path(
@@ -162,15 +155,15 @@ fn resolve_path(
     curr
 }
 
+/// The kinds of the view index the types that are in it, as types are no longer packed as u16s
 fn make_view<'a, HAST>(
     mut queue: Vec<(HAST::IdN, usize)>,
     stores: &'a HAST,
     // node_store: &hyperast::store::nodes::legion::NodeStore,
     // label_store: &hyperast::store::labels::LabelStore,
-) -> View
+) -> ViewRes
 where
     HAST::IdN: Hash,
-    <HAST::TS as types::TypeStore>::Ty: Into<u16>,
     // HAST: NodeStore<HAST::IdN, R<'a> = HAST::T> + LabelStore<str, I = HAST::Label>,
     HAST: HyperAST<Label = LabelIdentifier>,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
@@ -188,6 +181,8 @@ where
     // let mut children = vec![];
     // let mut labels = vec![];
     let mut label_map = HashMap::<LabelIdentifier, u32>::default();
+    let mut type_sys = TypeSys::default();
+    let mut type_map = HashMap::<&'static str, u16>::default();
 
     #[derive(Default)]
     pub struct EntityHasher(u64);
@@ -220,7 +215,11 @@ where
         curr.hash(&mut id);
         let nid = id.finish();
         let n = stores.node_store().resolve(&curr); //hyperast::types::NodeStore::resolve(stores, &curr);
-        let k = stores.resolve_type(&curr);
+        let k = stores.resolve_type(&curr).as_static_str();
+        let k = *type_map.entry(k).or_insert_with(|| {
+            type_sys.0.push(k.to_string());
+            (type_sys.0.len() - 1) as u16
+        });
         if let Some(l) = n.try_get_label() {
             let l = label_map.entry(*l).or_insert_with(|| {
                 let i = label_list.len() as u32;
@@ -229,7 +228,7 @@ where
             });
             if let Some(cs) = n.children() {
                 with_both.ids.push(nid);
-                with_both.kinds.push(k.into());
+                with_both.kinds.push(k);
                 with_both.cs_ofs.push(with_both.children.len() as u32);
                 with_both.cs_lens.push(cs.child_count().to_u32().unwrap());
                 with_both.children.extend(cs.iter_children().map(|curr| {
@@ -244,12 +243,12 @@ where
                 with_both.labels.push(*l);
             } else {
                 labeled.ids.push(nid);
-                labeled.kinds.push(k.into());
+                labeled.kinds.push(k);
                 labeled.labels.push(*l);
             }
         } else if let Some(cs) = n.children() {
             with_children.ids.push(nid);
-            with_children.kinds.push(k.into());
+            with_children.kinds.push(k);
             with_children
                 .cs_ofs
                 .push(with_children.children.len() as u32);
@@ -269,7 +268,7 @@ where
                 }));
         } else {
             only_typed.ids.push(nid);
-            only_typed.kinds.push(k.into());
+            only_typed.kinds.push(k);
         }
     }
     dbg!(&labeled.ids.len());
@@ -288,7 +287,7 @@ where
         both: with_both,
         typed: only_typed,
     };
-    view
+    ViewRes { type_sys, view }
 }
 
 #[derive(Default)]
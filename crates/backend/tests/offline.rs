@@ -0,0 +1,180 @@
+//! Analyses of a fixture repository processed in place, ie. without any remote operation.
+use std::path::PathBuf;
+
+use backend::{AppState, metric_columns, offline};
+use hyperast_vcs_git::git::Forge;
+use hyperast_vcs_git::processing::RepoConfig;
+
+static POM: &str = r#"<project>
+    <modelVersion>4.0.0</modelVersion>
+    <groupId>org.example</groupId>
+    <artifactId>fixture</artifactId>
+    <version>1.0</version>
+</project>
+"#;
+
+static CLASS: &str = r#"package org.example;
+
+public class A {
+    int f() {
+        return 42;
+    }
+}
+"#;
+
+/// Initializes a repository without remote in the temporary directory
+fn fixture(name: &str) -> (PathBuf, git2::Oid) {
+    let path = std::env::temp_dir().join("hyperast_fixtures").join(format!(
+        "{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&path);
+    let repository = git2::Repository::init(&path).unwrap();
+    let java = "src/main/java/org/example/A.java";
+    for (file, text) in [("pom.xml", POM), (java, CLASS)] {
+        let file = path.join(file);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, text).unwrap();
+    }
+    let mut index = repository.index().unwrap();
+    index
+        .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    let tree = repository.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("fixture", "fixture@example.org").unwrap();
+    let oid = repository
+        .commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+        .unwrap();
+    (path, oid)
+}
+
+fn content(query: &str) -> offline::Content {
+    offline::Content {
+        language: "Java".to_string(),
+        query: query.to_string(),
+        precomp: None,
+        commits: 1,
+        max_matches: u64::MAX,
+        timeout: u64::MAX,
+        captures: false,
+        depth: None,
+        since: None,
+        until: None,
+    }
+}
+
+#[test]
+fn test_query_on_disk() {
+    let (path, oid) = fixture("offline_query");
+    let state: std::sync::Arc<AppState> = AppState::default().into();
+    let repo = offline::open(&state, &path, RepoConfig::JavaMaven).unwrap();
+    let content = content("(class_declaration) @root");
+    let query = || {
+        let Ok(results) = offline::query(state.clone(), &repo, &oid.to_string(), content.clone())
+        else {
+            panic!("failed to query {}", path.display())
+        };
+        let [Ok(result)] = results.results.as_slice() else {
+            panic!("expected a single commit, got {:?}", results.results)
+        };
+        assert_eq!(result.commit, oid.to_string());
+        assert_eq!(result.inner.result, [1]);
+    };
+    query();
+    // as done by routes, registering without the path must keep the one of the repository on disk
+    let spec = Forge::Disk.repo(repo.user(), repo.name());
+    state
+        .repositories
+        .write()
+        .unwrap()
+        .register_config(spec, RepoConfig::JavaMaven)
+        .unwrap();
+    query();
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+/// Commits processed with a store directory are appended to its logs,
+/// then restored in a new state without processing them again.
+#[test]
+fn test_persist_restore() {
+    let (path, oid) = fixture("offline_persist");
+    let dir = path.with_extension("store");
+    let _ = std::fs::remove_dir_all(&dir);
+    let count = |state: &std::sync::Arc<AppState>, repo: &hyperast_vcs_git::git::Repo| {
+        let content = content("(class_declaration) @root");
+        let Ok(results) = offline::query(state.clone(), repo, &oid.to_string(), content) else {
+            panic!("failed to query {}", path.display())
+        };
+        let [Ok(result)] = results.results.as_slice() else {
+            panic!("expected a single commit, got {:?}", results.results)
+        };
+        result.inner.result.clone()
+    };
+    let state: std::sync::Arc<AppState> = AppState::default().into();
+    let repo = offline::open(&state, &path, RepoConfig::JavaMaven).unwrap();
+    // nothing is processed yet, so the checkpoint is empty and the commit can only be in the logs
+    let mut repositories = state.repositories.write().unwrap();
+    repositories.persist_in(dir.clone()).unwrap();
+    drop(repositories);
+    assert_eq!(count(&state, &repo), [1]);
+    state.repositories.read().unwrap().flush_log();
+
+    let restored: std::sync::Arc<AppState> = AppState::default().into();
+    let repo = offline::open(&restored, &path, RepoConfig::JavaMaven).unwrap();
+    let mut repositories = restored.repositories.write().unwrap();
+    assert!(repositories.restore(&dir).unwrap());
+    let config = repositories.get_config(repo.clone()).unwrap().config;
+    assert!(repositories.get_commit(&config, &oid).is_some());
+    drop(repositories);
+    assert_eq!(count(&restored, &repo), [1]);
+    let _ = std::fs::remove_dir_all(&path);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+static BRANCHES: &str = r#"
+local branches = 0
+
+function acc(c)
+  branches += c.branches
+end
+
+function finish()
+  if is_branch() then
+    branches += 1
+  end
+  return { branches = branches }
+end
+"#;
+
+#[test]
+fn test_query_metric_column() {
+    let (path, oid) = fixture("offline_metric_column");
+    let state: std::sync::Arc<AppState> = AppState::default().into();
+    let repo = offline::open(&state, &path, RepoConfig::JavaMaven).unwrap();
+    let count = |query: &str| {
+        let results = offline::query(state.clone(), &repo, &oid.to_string(), content(query));
+        let Ok(results) = results else {
+            panic!("failed to query {}", path.display())
+        };
+        let [Ok(result)] = results.results.as_slice() else {
+            panic!("expected a single commit, got {:?}", results.results)
+        };
+        result.inner.result.clone()
+    };
+    let without = r#"((method_declaration) @m (#metric<? @m "branches" 1))"#;
+    let with = r#"((method_declaration) @m (#metric>? @m "branches" 0))"#;
+    // the metric is neither derived while processing nor defined yet
+    assert_eq!(count(without), [0]);
+    let definition = metric_columns::Definition {
+        kind: "int".to_string(),
+        script: BRANCHES.to_string(),
+    };
+    let column = metric_columns::ColumnParam {
+        column: "branches".to_string(),
+    };
+    let _column = metric_columns::define(state.clone(), column, definition).unwrap();
+    assert_eq!(count(without), [1]);
+    assert_eq!(count(with), [0]);
+    let _ = std::fs::remove_dir_all(&path);
+}
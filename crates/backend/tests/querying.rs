@@ -36,7 +36,7 @@ fn compare_querying_with_and_without_skipping(
         .repositories
         .write()
         .unwrap()
-        .register_config_with_prequeries(repo_spec.clone(), config, &["(try_statement)"]);
+        .register_config_with_prequeries(repo_spec.clone(), config, &["(try_statement)"])?;
     let repo = state
         .repositories
         .read()
@@ -156,7 +156,11 @@ fn compare_querying_cpp_with_and_without_skipping(
         .repositories
         .write()
         .unwrap()
-        .register_config_with_prequeries(repo_spec.clone(), config, &["(conditional_expression)"]);
+        .register_config_with_prequeries(
+            repo_spec.clone(),
+            config,
+            &["(conditional_expression)"],
+        )?;
     // .register_config(repo_spec.clone(), config);
     let repo = state
         .repositories
@@ -15,8 +15,9 @@ fn test_track_at_file_pos() -> Result<(), Box<dyn std::error::Error>> {
         .repositories
         .write()
         .unwrap()
-        .register_config(Forge::Github.repo("INRIA", "spoon"), RepoConfig::JavaMaven);
+        .register_config(Forge::Github.repo("INRIA", "spoon"), RepoConfig::JavaMaven)?;
     let path = TrackingParam {
+        forge: Forge::Github,
         user: "INRIA".to_string(),
         name: "spoon".to_string(),
         commit: "5f250ead2df52d7fe26a3ed2bdd7a38355f764b1".to_string(),
@@ -28,6 +29,9 @@ fn test_track_at_file_pos() -> Result<(), Box<dyn std::error::Error>> {
         start: Some(10),
         end: Some(200),
         before: Some("8cafc796a3afdda4d52e90f3d17f12c09735be02".to_string()),
+        depth: None,
+        since: None,
+        until: None,
         flags,
     };
     match track_code(state, path, query) {
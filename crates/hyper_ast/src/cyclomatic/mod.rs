@@ -1,3 +1,4 @@
+use crate::store::persist::Persist;
 use crate::types::{TypeTrait, Typed, WithMetaData};
 
 pub fn is_cyclomatic_persisted<K: TypeTrait>(t: &K) -> bool {
@@ -45,6 +46,11 @@ impl Mcc {
         acc.value += self.value
     }
 
+    /// The branching statements + 1, as retrieved by [`MetaData`]
+    pub fn complexity(&self) -> u32 {
+        self.value + 1
+    }
+
     pub fn persist<K: TypeTrait>(kind: &K) -> bool {
         is_cyclomatic_persisted(kind)
     }
@@ -60,6 +66,17 @@ impl Mcc {
     // }
 }
 
+impl Persist for Mcc {
+    fn persist(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.value.persist(w)
+    }
+    fn restore(r: &mut dyn std::io::Read) -> std::io::Result<Self> {
+        Ok(Self {
+            value: u32::restore(r)?,
+        })
+    }
+}
+
 impl<T: Typed + WithMetaData<Mcc>> MetaData<T> for Mcc
 where
     T::Type: TypeTrait,
@@ -50,6 +50,22 @@ impl<T, V: BitViewSized> Default for Bloom<T, V> {
         }
     }
 }
+#[cfg(feature = "legion")]
+impl<T, V> crate::store::persist::Persist for Bloom<T, V>
+where
+    V: BitViewSized + crate::store::persist::Persist,
+{
+    fn persist(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        V::persist(&self.bits.data, w)
+    }
+    fn restore(r: &mut dyn std::io::Read) -> std::io::Result<Self> {
+        Ok(Self {
+            bits: bitvec::array::BitArray::new(V::restore(r)?),
+            _phantom: PhantomData,
+        })
+    }
+}
+
 impl<T, V: BitViewSized> Debug for Bloom<T, V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Bloom").field("bits", &self.bits).finish()
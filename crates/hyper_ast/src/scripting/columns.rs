@@ -0,0 +1,663 @@
+//! Named metric columns, computed by scripts on already constructed HyperASTs.
+//!
+//! Contrary to a [`Prepro`] given when registering a repository,
+//! a [`MetricColumn`] can be added at any time.
+//! Its values are computed lazily, bottom-up from the requested subtree,
+//! and memoized by [`NodeIdentifier`], so the subtrees shared between commits are only computed once.
+//!
+//! Scripts follow the same protocol as preprocessing scripts:
+//! they are executed on each node, `acc(c)` is called on each child,
+//! and `finish()` returns a table containing the value of the column under its name.
+//! The child given to `acc` only exposes the value of the column and `is_comment()`.
+//! For example, [`PREPRO_MCC_WITH_FINISH`] defines a column named `mcc`.
+//!
+//! [`PREPRO_MCC_WITH_FINISH`]: super::lua_scripting::PREPRO_MCC_WITH_FINISH
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, RwLock};
+
+use crate::store::defaults::NodeIdentifier;
+use crate::store::persist::{Persist, invalid_data};
+use crate::types::{AnyType, HyperAST, HyperType, LabelStore, Labeled, WithChildren};
+
+use super::{Acc, Prepro};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MetricKind {
+    Int,
+    Float,
+    Bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MetricValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+#[derive(Debug)]
+pub enum ColumnError {
+    /// no column with this name
+    Unknown(String),
+    /// a column with this name but another kind or script already exists
+    Conflict(String),
+    /// `finish()` did not return a value for the column
+    Missing {
+        column: String,
+    },
+    /// `finish()` did not return a value of the kind of the column
+    Kind {
+        column: String,
+        kind: MetricKind,
+        found: String,
+    },
+    Script(mlua::Error),
+}
+
+impl Display for ColumnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnError::Unknown(name) => write!(f, "unknown metric column {}", name),
+            ColumnError::Conflict(name) => {
+                write!(f, "metric column {} is already defined differently", name)
+            }
+            ColumnError::Missing { column } => {
+                write!(f, "metric column {} was not given a value", column)
+            }
+            ColumnError::Kind {
+                column,
+                kind,
+                found,
+            } => write!(
+                f,
+                "metric column {} expects {}, found {}",
+                column, kind, found
+            ),
+            ColumnError::Script(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ColumnError {}
+
+impl From<mlua::Error> for ColumnError {
+    fn from(value: mlua::Error) -> Self {
+        ColumnError::Script(value)
+    }
+}
+
+impl Display for MetricKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricKind::Int => write!(f, "int"),
+            MetricKind::Float => write!(f, "float"),
+            MetricKind::Bool => write!(f, "bool"),
+        }
+    }
+}
+
+impl std::str::FromStr for MetricKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "int" => Ok(MetricKind::Int),
+            "float" => Ok(MetricKind::Float),
+            "bool" => Ok(MetricKind::Bool),
+            x => Err(format!(
+                "{} is not a metric kind, ie. int, float or bool",
+                x
+            )),
+        }
+    }
+}
+
+impl Display for MetricValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricValue::Int(x) => write!(f, "{}", x),
+            MetricValue::Float(x) => write!(f, "{}", x),
+            MetricValue::Bool(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+impl MetricValue {
+    pub fn kind(&self) -> MetricKind {
+        match self {
+            MetricValue::Int(_) => MetricKind::Int,
+            MetricValue::Float(_) => MetricKind::Float,
+            MetricValue::Bool(_) => MetricKind::Bool,
+        }
+    }
+
+    /// Numerical value, booleans being 0 or 1
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            MetricValue::Int(x) => *x as f64,
+            MetricValue::Float(x) => *x,
+            MetricValue::Bool(x) => *x as u8 as f64,
+        }
+    }
+
+    fn from_dynamic(kind: MetricKind, d: &rhai::Dynamic) -> Option<Self> {
+        match kind {
+            MetricKind::Int => d
+                .as_int()
+                .ok()
+                // luau only has floating point numbers
+                .or_else(|| {
+                    d.as_float()
+                        .ok()
+                        .filter(|x| x.fract() == 0.0)
+                        .map(|x| x as i64)
+                })
+                .map(MetricValue::Int),
+            MetricKind::Float => d
+                .as_float()
+                .ok()
+                .or_else(|| d.as_int().ok().map(|x| x as f64))
+                .map(MetricValue::Float),
+            MetricKind::Bool => d.as_bool().ok().map(MetricValue::Bool),
+        }
+    }
+
+    fn to_dynamic(self) -> rhai::Dynamic {
+        match self {
+            MetricValue::Int(x) => rhai::Dynamic::from_int(x),
+            MetricValue::Float(x) => rhai::Dynamic::from_float(x),
+            MetricValue::Bool(x) => rhai::Dynamic::from_bool(x),
+        }
+    }
+}
+
+pub struct MetricColumn {
+    name: String,
+    kind: MetricKind,
+    script: Arc<str>,
+    values: RwLock<HashMap<NodeIdentifier, MetricValue>>,
+}
+
+impl MetricColumn {
+    pub fn new(name: impl Into<String>, kind: MetricKind, script: impl Into<Arc<str>>) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            script: script.into(),
+            values: Default::default(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> MetricKind {
+        self.kind
+    }
+
+    pub fn script(&self) -> &str {
+        &self.script
+    }
+
+    /// Number of memoized values
+    pub fn len(&self) -> usize {
+        self.values.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The memoized value of `id`, without computing it
+    pub fn get(&self, id: NodeIdentifier) -> Option<MetricValue> {
+        self.values.read().unwrap().get(&id).copied()
+    }
+
+    /// The value of `id`, computing and memoizing the missing values of its subtree
+    ///
+    /// The subtree is walked in post-order with an explicit stack, whatever its depth,
+    /// each node being finished before the next one is initialized,
+    /// so a single lua instance is used for the whole computation.
+    pub fn compute<HAST>(
+        &self,
+        stores: &HAST,
+        id: NodeIdentifier,
+    ) -> Result<MetricValue, ColumnError>
+    where
+        HAST: HyperAST<IdN = NodeIdentifier>,
+    {
+        if let Some(value) = self.get(id) {
+            return Ok(value);
+        }
+        let prepro: Prepro<(), &Acc> = Prepro::from_arc(self.script.clone());
+        // children are given once the node is visited, and its value is computed when popped again
+        let mut stack: Vec<(NodeIdentifier, Option<Vec<NodeIdentifier>>)> = vec![(id, None)];
+        let mut value = None;
+        while let Some((id, children)) = stack.pop() {
+            let Some(children) = children else {
+                if self.get(id).is_some() {
+                    // shared by an already computed subtree
+                    continue;
+                }
+                let children: Vec<NodeIdentifier> = stores
+                    .resolve(&id)
+                    .children()
+                    .map_or(vec![], |cs| cs.collect());
+                let missing = children.iter().rev().filter(|x| self.get(**x).is_none());
+                let missing: Vec<_> = missing.map(|x| (*x, None)).collect();
+                stack.push((id, Some(children)));
+                stack.extend(missing);
+                continue;
+            };
+            let v = self.compute_node(&prepro, stores, id, children)?;
+            self.values.write().unwrap().insert(id, v);
+            value = Some(v);
+        }
+        Ok(value.expect("the root is computed last"))
+    }
+
+    /// The value of `id`, the values of its `children` being already memoized
+    fn compute_node<HAST>(
+        &self,
+        prepro: &Prepro<(), &Acc>,
+        stores: &HAST,
+        id: NodeIdentifier,
+        children: Vec<NodeIdentifier>,
+    ) -> Result<MetricValue, ColumnError>
+    where
+        HAST: HyperAST<IdN = NodeIdentifier>,
+    {
+        let label = {
+            let n = stores.resolve(&id);
+            let label = n.try_get_label();
+            label.map(|l| stores.label_store().resolve(l).to_string())
+        };
+        let ty = stores.resolve_type(&id).as_static();
+        let mut acc = prepro.clone().init(AnyType::from(ty))?;
+        for child in children {
+            let child_ty = stores.resolve_type(&child).as_static();
+            let value = self.get(child).expect("children are computed first");
+            acc.acc_value(ty, child_ty, &self.name, &value.to_dynamic())?;
+        }
+        let derived = acc.finish_ty(ty, label.as_deref())?;
+        let value = derived
+            .0
+            .get(self.name.as_str())
+            .ok_or_else(|| ColumnError::Missing {
+                column: self.name.clone(),
+            })?;
+        MetricValue::from_dynamic(self.kind, value).ok_or_else(|| ColumnError::Kind {
+            column: self.name.clone(),
+            kind: self.kind,
+            found: value.type_name().to_string(),
+        })
+    }
+}
+
+/// The metric columns of a set of stores, by name
+#[derive(Default)]
+pub struct MetricColumns {
+    columns: RwLock<HashMap<String, Arc<MetricColumn>>>,
+}
+
+impl MetricColumns {
+    /// Adds a column, or returns the existing one if it has the same kind and script
+    pub fn add(
+        &self,
+        name: &str,
+        kind: MetricKind,
+        script: &str,
+    ) -> Result<Arc<MetricColumn>, ColumnError> {
+        let mut columns = self.columns.write().unwrap();
+        if let Some(column) = columns.get(name) {
+            if column.kind != kind || column.script() != script {
+                return Err(ColumnError::Conflict(name.to_string()));
+            }
+            return Ok(column.clone());
+        }
+        let column = Arc::new(MetricColumn::new(name, kind, script));
+        columns.insert(name.to_string(), column.clone());
+        Ok(column)
+    }
+
+    /// Replaces all the columns, e.g. by restored ones
+    pub fn replace(&self, columns: MetricColumns) {
+        let columns = columns.columns.into_inner().unwrap();
+        *self.columns.write().unwrap() = columns;
+    }
+
+    pub fn remove(&self, name: &str) -> Option<Arc<MetricColumn>> {
+        self.columns.write().unwrap().remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<MetricColumn>> {
+        self.columns.read().unwrap().get(name).cloned()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.columns.read().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// The value of the column `name` on `id`, see [`MetricColumn::compute`]
+    pub fn value<HAST>(
+        &self,
+        stores: &HAST,
+        name: &str,
+        id: NodeIdentifier,
+    ) -> Result<MetricValue, ColumnError>
+    where
+        HAST: HyperAST<IdN = NodeIdentifier>,
+    {
+        let column = self
+            .get(name)
+            .ok_or_else(|| ColumnError::Unknown(name.to_string()))?;
+        column.compute(stores, id)
+    }
+}
+
+impl Persist for MetricKind {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        let tag: u8 = match self {
+            MetricKind::Int => 0,
+            MetricKind::Float => 1,
+            MetricKind::Bool => 2,
+        };
+        tag.persist(w)
+    }
+
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        match u8::restore(r)? {
+            0 => Ok(MetricKind::Int),
+            1 => Ok(MetricKind::Float),
+            2 => Ok(MetricKind::Bool),
+            x => Err(invalid_data(format!("{} is not a metric kind tag", x))),
+        }
+    }
+}
+
+/// Values are written without tag, as all values of a column have its kind.
+impl Persist for MetricColumn {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.name.persist(w)?;
+        self.kind.persist(w)?;
+        self.script.to_string().persist(w)?;
+        let values = self.values.read().unwrap();
+        values.len().persist(w)?;
+        for (id, value) in values.iter() {
+            id.persist(w)?;
+            match value {
+                MetricValue::Int(x) => x.persist(w)?,
+                MetricValue::Float(x) => x.persist(w)?,
+                MetricValue::Bool(x) => x.persist(w)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let name = String::restore(r)?;
+        let kind = MetricKind::restore(r)?;
+        let script = String::restore(r)?;
+        let mut values = HashMap::new();
+        for _ in 0..usize::restore(r)? {
+            let id = NodeIdentifier::restore(r)?;
+            let value = match kind {
+                MetricKind::Int => MetricValue::Int(i64::restore(r)?),
+                MetricKind::Float => MetricValue::Float(f64::restore(r)?),
+                MetricKind::Bool => MetricValue::Bool(bool::restore(r)?),
+            };
+            values.insert(id, value);
+        }
+        Ok(Self {
+            values: RwLock::new(values),
+            ..Self::new(name, kind, script)
+        })
+    }
+}
+
+/// Must be restored along with the stores it was computed on,
+/// see [`crate::store::persist`].
+impl Persist for MetricColumns {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        let columns = self.columns.read().unwrap();
+        columns.len().persist(w)?;
+        for column in columns.values() {
+            column.persist(w)?;
+        }
+        Ok(())
+    }
+
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let mut columns = HashMap::new();
+        for _ in 0..usize::restore(r)? {
+            let column = MetricColumn::restore(r)?;
+            columns.insert(column.name.clone(), Arc::new(column));
+        }
+        Ok(Self {
+            columns: RwLock::new(columns),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashed::SyntaxNodeHashs;
+    use crate::scripting::lua_scripting::PREPRO_MCC_WITH_FINISH;
+    use crate::store::SimpleStores;
+    use crate::store::nodes::EntityBuilder as _;
+    use crate::store::nodes::compo;
+    use crate::store::nodes::legion::{NodeStore, dyn_builder};
+    use crate::types::{Abstracts, LangWrapper, Shared};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum Ty {
+        Block,
+        If,
+        Leaf,
+    }
+
+    impl Display for Ty {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.as_static_str())
+        }
+    }
+
+    impl HyperType for Ty {
+        fn as_shared(&self) -> Shared {
+            match self {
+                Ty::If => Shared::Branch,
+                _ => Shared::Other,
+            }
+        }
+
+        fn as_abstract(&self) -> Abstracts {
+            todo!()
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn as_static(&self) -> &'static dyn HyperType {
+            match self {
+                Ty::Block => &Ty::Block,
+                Ty::If => &Ty::If,
+                Ty::Leaf => &Ty::Leaf,
+            }
+        }
+
+        fn as_static_str(&self) -> &'static str {
+            match self {
+                Ty::Block => "block",
+                Ty::If => "if",
+                Ty::Leaf => "leaf",
+            }
+        }
+
+        fn generic_eq(&self, other: &dyn HyperType) -> bool
+        where
+            Self: 'static + Sized,
+        {
+            other.as_any().downcast_ref::<Self>() == Some(self)
+        }
+
+        fn is_file(&self) -> bool {
+            false
+        }
+
+        fn is_directory(&self) -> bool {
+            false
+        }
+
+        fn is_spaces(&self) -> bool {
+            false
+        }
+
+        fn is_syntax(&self) -> bool {
+            false
+        }
+
+        fn is_statement(&self) -> bool {
+            false
+        }
+
+        fn is_hidden(&self) -> bool {
+            false
+        }
+
+        fn is_named(&self) -> bool {
+            true
+        }
+
+        fn is_supertype(&self) -> bool {
+            false
+        }
+
+        fn get_lang(&self) -> LangWrapper<Self> {
+            todo!()
+        }
+
+        fn lang_ref(&self) -> LangWrapper<AnyType> {
+            todo!()
+        }
+    }
+
+    #[derive(Default)]
+    struct TStore;
+
+    impl crate::types::TypeStore for TStore {
+        type Ty = Ty;
+    }
+
+    type Stores = SimpleStores<TStore>;
+
+    fn insert(stores: &mut Stores, ty: Ty, h: u32, cs: Vec<NodeIdentifier>) -> NodeIdentifier {
+        let hashs = SyntaxNodeHashs {
+            structt: h,
+            label: h,
+            syntax: h,
+        };
+        let insertion = stores
+            .node_store
+            .prepare_insertion(&hashs.syntax, |_| false);
+        let mut builder = dyn_builder::EntityBuilder::new();
+        builder.add(ty);
+        builder.add(hashs);
+        if !cs.is_empty() {
+            builder.add(compo::CS(cs.into_boxed_slice()));
+        }
+        NodeStore::insert_built_after_prepare(insertion.vacant(), builder.build())
+    }
+
+    /// a block with two ifs, each containing a leaf
+    fn two_ifs(stores: &mut Stores) -> (NodeIdentifier, NodeIdentifier) {
+        let leaf = insert(stores, Ty::Leaf, 1, vec![]);
+        let if0 = insert(stores, Ty::If, 2, vec![leaf]);
+        let leaf = insert(stores, Ty::Leaf, 3, vec![]);
+        let if1 = insert(stores, Ty::If, 4, vec![leaf]);
+        let root = insert(stores, Ty::Block, 5, vec![if0, if1]);
+        (root, if0)
+    }
+
+    #[test]
+    fn test_compute_memoized() {
+        let mut stores = Stores::default();
+        let (root, if0) = two_ifs(&mut stores);
+        let column = MetricColumn::new("mcc", MetricKind::Int, PREPRO_MCC_WITH_FINISH);
+        assert_eq!(column.compute(&stores, if0).unwrap(), MetricValue::Int(1));
+        // the if and its leaf
+        assert_eq!(column.len(), 2);
+        assert_eq!(column.get(root), None);
+        assert_eq!(column.compute(&stores, root).unwrap(), MetricValue::Int(2));
+        assert_eq!(column.len(), 5);
+        assert_eq!(column.get(root), Some(MetricValue::Int(2)));
+        assert_eq!(column.compute(&stores, root).unwrap(), MetricValue::Int(2));
+        assert_eq!(column.len(), 5);
+    }
+
+    #[test]
+    fn test_compute_deep() {
+        let mut stores = Stores::default();
+        let depth = 20_000;
+        let mut id = insert(&mut stores, Ty::Leaf, 0, vec![]);
+        for h in 1..=depth {
+            id = insert(&mut stores, Ty::If, h, vec![id]);
+        }
+        let column = MetricColumn::new("mcc", MetricKind::Int, PREPRO_MCC_WITH_FINISH);
+        let value = column.compute(&stores, id).unwrap();
+        assert_eq!(value, MetricValue::Int(depth as i64));
+        assert_eq!(column.len(), depth as usize + 1);
+    }
+
+    #[test]
+    fn test_compute_errors() {
+        let mut stores = Stores::default();
+        let (root, _) = two_ifs(&mut stores);
+        let column = MetricColumn::new("mcc", MetricKind::Bool, PREPRO_MCC_WITH_FINISH);
+        let err = column.compute(&stores, root).unwrap_err();
+        assert!(matches!(err, ColumnError::Kind { .. }), "{}", err);
+        assert!(column.is_empty());
+        let column = MetricColumn::new("loc", MetricKind::Int, PREPRO_MCC_WITH_FINISH);
+        let err = column.compute(&stores, root).unwrap_err();
+        assert!(matches!(err, ColumnError::Missing { .. }), "{}", err);
+        assert!(column.is_empty());
+    }
+
+    #[test]
+    fn test_persist_restore() {
+        let mut stores = Stores::default();
+        let (root, if0) = two_ifs(&mut stores);
+        let columns = MetricColumns::default();
+        let script = PREPRO_MCC_WITH_FINISH;
+        columns.add("mcc", MetricKind::Int, script).unwrap();
+        assert_eq!(
+            columns.value(&stores, "mcc", root).unwrap(),
+            MetricValue::Int(2)
+        );
+        let mut buf = vec![];
+        columns.persist(&mut buf).unwrap();
+
+        let restored = MetricColumns::restore(&mut buf.as_slice()).unwrap();
+        assert_eq!(restored.names(), vec!["mcc".to_string()]);
+        let column = restored.get("mcc").unwrap();
+        assert_eq!(column.kind(), MetricKind::Int);
+        assert_eq!(column.script(), script);
+        assert_eq!(column.len(), 5);
+        assert_eq!(column.get(if0), Some(MetricValue::Int(1)));
+        // the same definition keeps the restored values
+        assert_eq!(
+            restored.add("mcc", MetricKind::Int, script).unwrap().len(),
+            5
+        );
+        assert!(restored.add("mcc", MetricKind::Float, script).is_err());
+        let err = restored.value(&stores, "loc", root).unwrap_err();
+        assert!(matches!(err, ColumnError::Unknown(_)), "{}", err);
+    }
+}
@@ -90,7 +90,7 @@ impl Drop for Acc {
 
         LUA_POOL.with_borrow_mut(|pool| {
             let lua = &pool[self.id as usize];
-            log::info!("{} drop {count} {:p}", lua.used_memory(), &self);
+            log::trace!("{} drop {count} {:p}", lua.used_memory(), &self);
             if count < 2 {
                 // log::info!(
                 //     "timings {} {} {} {} {}",
@@ -184,7 +184,7 @@ impl<HAST, Acc> Prepro<HAST, &Acc> {
         }
     }
 
-    fn init<T: HyperType + 'static>(self, ty: T) -> Result<self::Acc> {
+    pub(crate) fn init<T: HyperType + 'static>(self, ty: T) -> Result<self::Acc> {
         let now = Instant::now();
         let mut count = LUA_INSTANCES.get();
         LUA_POOL.with_borrow_mut(|pool| {
@@ -208,7 +208,7 @@ impl<HAST, Acc> Prepro<HAST, &Acc> {
             log::debug!("gen {} {prepare_time}", &lua.used_memory());
 
             lua.scope(|scope| {
-                let ty = scope.create_any_userdata(Ty(ty))?;
+                let ty = scope.create_any_userdata(Ty(ty.as_static()))?;
                 lua.globals().set("TY", ty)?;
                 // log::warn!("{} init {count}  {:p}", &lua.used_memory(), &self);
                 lua.load(self.txt.as_ref()).exec()?;
@@ -335,6 +335,65 @@ impl Acc {
             Ok(())
         })
     }
+    /// Accumulates a child given by its `value` under `name`, instead of its [`DerivedData`],
+    /// e.g. for values computed after the construction of the child.
+    pub(crate) fn acc_value(
+        &mut self,
+        ty: &'static dyn HyperType,
+        child_ty: &'static dyn HyperType,
+        name: &str,
+        value: &Dynamic,
+    ) -> Result<()> {
+        LUA_POOL.with_borrow_mut(|pool| {
+            let lua = &mut pool[self.id as usize];
+            let acc = lua.globals().get::<_, mlua::Function>("acc")?;
+            lua.scope(|scope| {
+                let ty = scope.create_any_userdata(Ty(ty))?;
+                lua.globals().set("TY", ty)?;
+                let child = lua.create_table()?;
+                child.set(name, d_to_lua(lua, value)?)?;
+                let is_comment = child_ty.as_shared() == Shared::Comment;
+                let is_comment = lua.create_function(move |_, _: ()| Ok(is_comment))?;
+                child.set("is_comment", is_comment)?;
+                let m: mlua::Value = acc.call((child,))?;
+                debug_assert!(m.is_nil());
+                Ok(())
+            })
+        })
+    }
+
+    /// Same as [`Acc::finish_with_label`], but only needs the type of the subtree
+    pub(crate) fn finish_ty(
+        self,
+        ty: &'static dyn HyperType,
+        label: Option<&str>,
+    ) -> Result<DerivedData> {
+        LUA_POOL.with_borrow_mut(|pool| {
+            let lua = &mut pool[self.id as usize];
+            let finish = lua.globals().get::<_, mlua::Function>("finish")?;
+            let m = lua.scope(|scope| {
+                let ty = scope.create_any_userdata(Ty(ty))?;
+                lua.globals().set("TY", ty)?;
+                lua.globals().set("L", label)?;
+                let m: mlua::Value = finish.call(())?;
+                Ok(m)
+            })?;
+            lua.gc_collect()?;
+            let map = match m.as_table() {
+                Some(m) => DerivedData::try_from(m)?,
+                None => {
+                    return Err(mlua::Error::FromLuaConversionError {
+                        from: m.type_name(),
+                        to: "table",
+                        message: Some("finish() must return a table".to_string()),
+                    });
+                }
+            };
+            lua.sandbox(false)?;
+            Ok(map)
+        })
+    }
+
     pub fn finish<T: HyperType>(self, subtree: &Subtr<T>) -> Result<DerivedData> {
         let now = Instant::now();
         let ptr = format!("{:p}", &self);
@@ -5,6 +5,8 @@ mod querying;
 pub mod lua_scripting;
 #[cfg(feature = "scripting")]
 mod rhai_scripting;
+#[cfg(all(feature = "scripting", feature = "legion"))]
+pub mod columns;
 // mod native_impl {}
 
 #[cfg(feature = "scripting")]
@@ -92,6 +94,58 @@ pub struct DerivedData(
     pub rhai::Map,
 );
 
+/// Only scalar values and strings can be persisted.
+#[cfg(all(feature = "scripting", feature = "legion"))]
+impl crate::store::persist::Persist for DerivedData {
+    fn persist(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        use crate::store::persist::invalid_data;
+        self.0.len().persist(w)?;
+        for (k, v) in &self.0 {
+            k.to_string().persist(w)?;
+            if let Ok(x) = v.as_int() {
+                0u8.persist(w)?;
+                x.persist(w)?;
+            } else if let Ok(x) = v.as_float() {
+                1u8.persist(w)?;
+                x.persist(w)?;
+            } else if let Ok(x) = v.as_bool() {
+                2u8.persist(w)?;
+                x.persist(w)?;
+            } else if v.is_string() {
+                3u8.persist(w)?;
+                v.clone().into_string().unwrap().persist(w)?;
+            } else if v.is_unit() {
+                4u8.persist(w)?;
+            } else {
+                return Err(invalid_data(format!(
+                    "cannot persist derived value {} of type {}",
+                    k,
+                    v.type_name()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn restore(r: &mut dyn std::io::Read) -> std::io::Result<Self> {
+        use crate::store::persist::invalid_data;
+        let mut map = rhai::Map::new();
+        for _ in 0..usize::restore(r)? {
+            let k = String::restore(r)?;
+            let v = match u8::restore(r)? {
+                0 => rhai::Dynamic::from_int(i64::restore(r)?),
+                1 => rhai::Dynamic::from_float(f64::restore(r)?),
+                2 => rhai::Dynamic::from_bool(bool::restore(r)?),
+                3 => rhai::Dynamic::from(String::restore(r)?),
+                4 => rhai::Dynamic::UNIT,
+                x => return Err(invalid_data(format!("{} is not a derived value tag", x))),
+            };
+            map.insert(k.into(), v);
+        }
+        Ok(Self(map))
+    }
+}
+
 #[derive(PartialEq, Eq)]
 pub struct Prepro<HAST, Acc> {
     txt: std::sync::Arc<str>,
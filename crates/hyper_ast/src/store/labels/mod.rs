@@ -89,3 +89,84 @@ impl LabelStore {
         r
     }
 }
+
+/// Labels are written in interning order,
+/// thus restoring them in an empty store gives back the same identifiers.
+#[cfg(feature = "legion")]
+impl crate::store::persist::Persist for LabelStore {
+    fn persist(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.count.persist(w)?;
+        self.internal.len().persist(w)?;
+        for (_, s) in &self.internal {
+            s.to_string().persist(w)?;
+        }
+        Ok(())
+    }
+
+    fn restore(r: &mut dyn std::io::Read) -> std::io::Result<Self> {
+        let count = usize::restore(r)?;
+        let mut internal = StringInterner::<string_interner::DefaultBackend>::default();
+        for i in 0..usize::restore(r)? {
+            let s = String::restore(r)?;
+            let sym = internal.get_or_intern(s);
+            if sym.to_usize() != i {
+                return Err(crate::store::persist::invalid_data(
+                    "labels are not in interning order",
+                ));
+            }
+        }
+        Ok(Self { count, internal })
+    }
+}
+
+#[cfg(feature = "legion")]
+impl LabelStore {
+    pub(crate) fn interned_len(&self) -> usize {
+        self.internal.len()
+    }
+
+    /// Writes the labels interned after the first `from` ones,
+    /// see [`crate::store::SimpleStores::write_log_segment`].
+    pub(crate) fn persist_since(
+        &self,
+        from: usize,
+        w: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        use crate::store::persist::Persist as _;
+        self.count.persist(w)?;
+        from.persist(w)?;
+        (self.internal.len() - from).persist(w)?;
+        for (_, s) in (&self.internal).into_iter().skip(from) {
+            s.to_string().persist(w)?;
+        }
+        Ok(())
+    }
+
+    /// Interns the labels written by [`LabelStore::persist_since`],
+    /// the ones already present are skipped.
+    pub(crate) fn restore_since(&mut self, r: &mut dyn std::io::Read) -> std::io::Result<()> {
+        use crate::store::persist::Persist as _;
+        let count = usize::restore(r)?;
+        let from = usize::restore(r)?;
+        let len = usize::restore(r)?;
+        if from > self.internal.len() {
+            return Err(crate::store::persist::invalid_data(
+                "labels are missing before the log segment",
+            ));
+        }
+        for i in from..from + len {
+            let s = String::restore(r)?;
+            if i < self.internal.len() {
+                continue;
+            }
+            let sym = self.internal.get_or_intern(s);
+            if sym.to_usize() != i {
+                return Err(crate::store::persist::invalid_data(
+                    "labels are not in interning order",
+                ));
+            }
+        }
+        self.count = self.count.max(count);
+        Ok(())
+    }
+}
@@ -6,6 +6,8 @@ pub mod handle;
 pub mod labels;
 // pub mod mapped_world;
 pub mod nodes;
+#[cfg(feature = "legion")]
+pub mod persist;
 // pub mod ecs; // TODO try a custom ecs ?
 // pub mod radix_hash_store; // TODO yet another WIP store
 // pub mod vec_map_store; // TODO yet another WIP store
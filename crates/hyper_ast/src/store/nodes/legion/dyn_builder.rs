@@ -107,6 +107,16 @@ use super::*;
 /// A builder of entities for a archetypal store, here legion.
 pub struct BuiltEntity {
     inner: Common<fn() -> Box<dyn UnknownComponentStorage>>,
+    id: Option<Entity>,
+}
+
+impl BuiltEntity {
+    /// Inserts the entity with `id` instead of allocating a new identifier,
+    /// used to restore snapshots.
+    pub(crate) fn with_id(mut self, id: Entity) -> Self {
+        self.id = Some(id);
+        self
+    }
 }
 
 impl Debug for BuiltEntity {
@@ -125,7 +135,10 @@ impl EntityBuilder {
         Self::default()
     }
     pub fn build(self) -> BuiltEntity {
-        BuiltEntity { inner: self.inner }
+        BuiltEntity {
+            inner: self.inner,
+            id: None,
+        }
     }
     /// Add `component` to the entity.
     ///
@@ -218,6 +231,7 @@ impl ComponentSource for BuiltEntity {
         mut entities: impl Iterator<Item = Entity>,
     ) {
         let entity = entities.next().unwrap();
+        let entity = self.id.unwrap_or(entity);
         writer.push(entity);
 
         // let v = unsafe { Vec::from_raw_parts(self.inner.storage.as_ptr(), self.inner.cursor, 4) };
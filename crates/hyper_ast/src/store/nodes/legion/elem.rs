@@ -467,6 +467,25 @@ impl<'a, T> crate::types::WithStats for HashedNodeRef<'a, T> {
     }
 }
 
+/// Metrics derived by preprocessing scripts, then McCabe's complexity as `mcc`
+impl<'a, T> crate::types::WithMetrics for HashedNodeRef<'a, T> {
+    fn get_metric(&self, name: &str) -> Option<f64> {
+        #[cfg(feature = "scripting")]
+        {
+            let dd = self.0.get_component::<crate::scripting::DerivedData>();
+            if let Some(d) = dd.ok().and_then(|dd| dd.0.get(name)) {
+                let v = d.as_int().map(|x| x as f64).or_else(|_| d.as_float());
+                return v.or_else(|_| d.as_bool().map(|x| x as u8 as f64)).ok();
+            }
+        }
+        if name == "mcc" {
+            let mcc = self.0.get_component::<crate::cyclomatic::Mcc>().ok()?;
+            return Some(mcc.complexity() as f64);
+        }
+        None
+    }
+}
+
 impl<'a, T> HashedNodeRef<'a, T> {
     pub fn size_no_spaces(&self) -> usize {
         self.0
@@ -10,6 +10,7 @@ use std::{fmt::Debug, hash::Hash, ops::Deref};
 
 pub mod dyn_builder;
 mod elem;
+mod persist;
 pub use elem::{EntryRef, HashedNode, HashedNodeRef, NodeIdentifier};
 
 pub struct NodeStore<I = NodeStoreInner, D = hashbrown::HashMap<NodeIdentifier, (), ()>> {
@@ -27,6 +28,8 @@ pub struct NodeStoreInner {
     // roots: HashMap<(u8, u8, u8), NodeIdentifier>,
     // dedup: hashbrown::HashMap<NodeIdentifier, (), ()>,
     internal: legion::World,
+    /// greatest identifier in `internal`, maintained on insertion for checkpoints
+    max_id: u64,
     // TODO intern lists of [`NodeIdentifier`]s, e.g. children, no space children, ...
     // hasher: DefaultHashBuilder,
     //fasthash::city::Hash64,//fasthash::RandomState<fasthash::>,
@@ -161,6 +164,7 @@ impl NodeStore {
     {
         let (&mut symbol, _) = {
             let symbol = inner.internal.push(components);
+            inner.track_id(symbol);
             vacant.insert_with_hasher(hash, symbol, (), |id| {
                 let node: HashedNodeRef<'_, NodeIdentifier> = inner
                     .internal
@@ -185,6 +189,7 @@ impl NodeStore {
     ) -> legion::Entity {
         let (&mut symbol, _) = {
             let symbol = inner.internal.extend(components)[0];
+            inner.track_id(symbol);
             vacant.insert_with_hasher(hash, symbol, (), |id| {
                 let node: HashedNodeRef<'_, NodeIdentifier> = inner
                     .internal
@@ -472,6 +477,7 @@ impl Default for NodeStoreInner {
             stats: Default::default(),
             // roots: Default::default(),
             internal: Default::default(),
+            max_id: 0,
             hasher: Default::default(),
         }
     }
@@ -0,0 +1,294 @@
+//! Snapshots of the legion [`NodeStore`], see [`crate::store::persist`].
+//!
+//! Entities are written with their identifier followed by their components,
+//! then the identifiers registered in the dedup map.
+//!
+//! Nodes are never removed and legion hands out increasing identifiers,
+//! so the nodes added after a checkpoint are the ones with a greater identifier.
+use std::io::{self, Read, Write};
+
+use legion::{EntityStore, IntoQuery};
+
+use super::{HashedNodeRef, NodeIdentifier, NodeStore, NodeStoreInner, dyn_builder};
+use crate::store::persist::{Codecs, Persist, id_to_u64, invalid_data};
+use crate::utils::make_hash;
+
+impl NodeStore {
+    pub fn write_snapshot(&self, codecs: &Codecs, w: &mut dyn Write) -> io::Result<()> {
+        self.write_since(codecs, 0, w)
+    }
+
+    /// see [`crate::store::SimpleStores::read_snapshot`]
+    pub fn read_snapshot(codecs: &Codecs, r: &mut dyn Read) -> io::Result<Self> {
+        Self::read_snapshot_reserving(codecs, r, 0)
+    }
+
+    /// Greatest identifier of the store, nodes added afterward will have greater ones.
+    pub(crate) fn max_id(&self) -> u64 {
+        self.inner.max_id
+    }
+
+    /// Writes the nodes with an identifier greater than `since`,
+    /// everything when `since` is 0.
+    pub(crate) fn write_since(
+        &self,
+        codecs: &Codecs,
+        since: u64,
+        w: &mut dyn Write,
+    ) -> io::Result<()> {
+        let inner = &self.inner;
+        inner.count.persist(w)?;
+        inner.errors.persist(w)?;
+        codecs.persist_names(w)?;
+
+        let mut query = <NodeIdentifier>::query();
+        let ids: Vec<NodeIdentifier> = query
+            .iter(&inner.internal)
+            .copied()
+            .filter(|x| id_to_u64(*x) > since)
+            .collect();
+        let max = ids.iter().map(|x| id_to_u64(*x)).max().unwrap_or(since);
+        max.persist(w)?;
+        ids.len().persist(w)?;
+        for id in ids {
+            id.persist(w)?;
+            let e = inner.internal.entry_ref(id).unwrap();
+            let compos = e
+                .archetype()
+                .layout()
+                .component_types()
+                .iter()
+                .map(|t| codecs.index_of(t))
+                .collect::<io::Result<Vec<_>>>()?;
+            (compos.len() as u16).persist(w)?;
+            for i in compos {
+                i.persist(w)?;
+                codecs.persist_compo(i, &e, w)?;
+            }
+        }
+
+        let dedup: Vec<_> = self
+            .dedup
+            .keys()
+            .filter(|x| id_to_u64(**x) > since)
+            .collect();
+        dedup.len().persist(w)?;
+        dedup.into_iter().try_for_each(|id| id.persist(w))
+    }
+
+    /// Reads a snapshot while making sure that identifiers up to `reserved` are never allocated,
+    /// as they will be replayed from a log afterward.
+    pub(crate) fn read_snapshot_reserving(
+        codecs: &Codecs,
+        r: &mut dyn Read,
+        reserved: u64,
+    ) -> io::Result<Self> {
+        let count = usize::restore(r)?;
+        let errors = usize::restore(r)?;
+        let compos = codecs.restore_names(r)?;
+
+        let max = u64::restore(r)?;
+        reserve_identifiers(max.max(reserved));
+        let mut store = Self {
+            dedup: hashbrown::HashMap::with_capacity_and_hasher(1 << 21, Default::default()),
+            inner: NodeStoreInner {
+                count,
+                errors,
+                ..Default::default()
+            },
+        };
+        store.read_nodes(codecs, &compos, r)?;
+        Ok(store)
+    }
+
+    /// Adds the nodes written by [`NodeStore::write_since`],
+    /// the ones already present are skipped.
+    ///
+    /// Their identifiers must have been reserved, see [`NodeStore::read_snapshot_reserving`].
+    pub(crate) fn replay_since(&mut self, codecs: &Codecs, r: &mut dyn Read) -> io::Result<()> {
+        let count = usize::restore(r)?;
+        let errors = usize::restore(r)?;
+        let compos = codecs.restore_names(r)?;
+        let _max = u64::restore(r)?;
+        self.inner.count = self.inner.count.max(count);
+        self.inner.errors = self.inner.errors.max(errors);
+        self.read_nodes(codecs, &compos, r)
+    }
+
+    fn read_nodes(&mut self, codecs: &Codecs, compos: &[u16], r: &mut dyn Read) -> io::Result<()> {
+        let Self { dedup, inner } = self;
+        let len = usize::restore(r)?;
+        for _ in 0..len {
+            let id = NodeIdentifier::restore(r)?;
+            let mut builder = dyn_builder::EntityBuilder::new();
+            for _ in 0..u16::restore(r)? {
+                let i = u16::restore(r)?;
+                let i = *compos
+                    .get(i as usize)
+                    .ok_or_else(|| invalid_data(format!("unknown component index {}", i)))?;
+                codecs.restore_compo(i, r, &mut builder)?;
+            }
+            if !inner.internal.contains(id) {
+                inner.internal.extend(builder.build().with_id(id));
+                inner.track_id(id);
+            }
+        }
+
+        for _ in 0..usize::restore(r)? {
+            let id = NodeIdentifier::restore(r)?;
+            let node: HashedNodeRef<'_, NodeIdentifier> = inner
+                .internal
+                .entry_ref(id)
+                .map(|x| HashedNodeRef::new(x))
+                .map_err(|_| invalid_data("deduplicated node is missing"))?;
+            let hash = make_hash(&inner.hasher, &node);
+            match dedup.raw_entry_mut().from_hash(hash, |x| *x == id) {
+                hashbrown::hash_map::RawEntryMut::Occupied(_) => (),
+                hashbrown::hash_map::RawEntryMut::Vacant(vacant) => {
+                    vacant.insert_with_hasher(hash, id, (), |id| {
+                        let node: HashedNodeRef<'_, NodeIdentifier> = inner
+                            .internal
+                            .entry_ref(*id)
+                            .map(|x| HashedNodeRef::new(x))
+                            .unwrap();
+                        make_hash(&inner.hasher, &node)
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl NodeStoreInner {
+    pub(super) fn track_id(&mut self, id: NodeIdentifier) {
+        self.max_id = self.max_id.max(id_to_u64(id));
+    }
+}
+
+/// legion allocates identifiers by blocks taken from a process-wide counter,
+/// so the ones being restored must be skipped before the restored world allocates any.
+fn reserve_identifiers(max: u64) {
+    loop {
+        let Some(e) = legion::world::Allocate::new().next() else {
+            break;
+        };
+        if id_to_u64(e) > max {
+            break;
+        }
+    }
+}
+
+#[test]
+fn round_trip() {
+    use crate::hashed::SyntaxNodeHashs;
+    use crate::store::{SimpleStores, nodes::EntityBuilder as _, nodes::compo, persist::Codecs};
+    use crate::types::LabelStore as _;
+
+    let mut stores: SimpleStores<()> = SimpleStores::default();
+    let insert = |stores: &mut SimpleStores<()>, label: &str, h: u32, cs: Vec<NodeIdentifier>| {
+        let label = stores.label_store.get_or_insert(label);
+        let hashs = SyntaxNodeHashs {
+            structt: h,
+            label: h,
+            syntax: h,
+        };
+        let insertion = stores
+            .node_store
+            .prepare_insertion(&hashs.syntax, |_| false);
+        let mut builder = dyn_builder::EntityBuilder::new();
+        builder.add(label);
+        builder.add(hashs);
+        if !cs.is_empty() {
+            builder.add(compo::CS(cs.into_boxed_slice()));
+        }
+        NodeStore::insert_built_after_prepare(insertion.vacant(), builder.build())
+    };
+    let a = insert(&mut stores, "a", 1, vec![]);
+    let b = insert(&mut stores, "b", 2, vec![]);
+    let root = insert(&mut stores, "root", 3, vec![a, b]);
+
+    let codecs = Codecs::default();
+    let mut buf = vec![];
+    stores.write_snapshot(&codecs, &mut buf).unwrap();
+    let restored: SimpleStores<()> =
+        SimpleStores::read_snapshot(&codecs, &mut buf.as_slice()).unwrap();
+
+    assert_eq!(restored.node_store.dedup.len(), 3);
+    let root = restored.node_store.inner.internal.entry_ref(root).unwrap();
+    let cs = root.get_component::<compo::CS<NodeIdentifier>>().unwrap();
+    assert_eq!(&*cs.0, &[a, b]);
+    let label = root
+        .get_component::<crate::store::defaults::LabelIdentifier>()
+        .unwrap();
+    assert_eq!(restored.label_store.resolve(label), "root");
+}
+
+#[test]
+fn replay_log() {
+    use crate::hashed::SyntaxNodeHashs;
+    use crate::store::{SimpleStores, nodes::EntityBuilder as _, nodes::compo, persist::Codecs};
+    use crate::types::LabelStore as _;
+
+    let insert = |stores: &mut SimpleStores<()>, label: &str, h: u32, cs: Vec<NodeIdentifier>| {
+        let label = stores.label_store.get_or_insert(label);
+        let hashs = SyntaxNodeHashs {
+            structt: h,
+            label: h,
+            syntax: h,
+        };
+        let insertion = stores
+            .node_store
+            .prepare_insertion(&hashs.syntax, |_| false);
+        let mut builder = dyn_builder::EntityBuilder::new();
+        builder.add(label);
+        builder.add(hashs);
+        if !cs.is_empty() {
+            builder.add(compo::CS(cs.into_boxed_slice()));
+        }
+        NodeStore::insert_built_after_prepare(insertion.vacant(), builder.build())
+    };
+    let codecs = Codecs::default();
+    let mut stores: SimpleStores<()> = SimpleStores::default();
+    let a = insert(&mut stores, "a", 1, vec![]);
+    let mut snapshot = vec![];
+    stores.write_snapshot(&codecs, &mut snapshot).unwrap();
+    let checkpoint = stores.checkpoint();
+
+    let mut log = vec![];
+    let b = insert(&mut stores, "b", 2, vec![]);
+    let checkpoint = stores
+        .write_log_segment(&codecs, &checkpoint, &mut log)
+        .unwrap();
+    let root = insert(&mut stores, "root", 3, vec![a, b]);
+    stores
+        .write_log_segment(&codecs, &checkpoint, &mut log)
+        .unwrap();
+    // a partially appended segment
+    log.extend_from_slice(&42u64.to_le_bytes());
+
+    let restored: SimpleStores<()> =
+        SimpleStores::read_snapshot_with_log(&codecs, &mut snapshot.as_slice(), &log).unwrap();
+
+    assert_eq!(restored.node_store.dedup.len(), 3);
+    assert_eq!(restored.checkpoint(), stores.checkpoint());
+    let root = restored.node_store.inner.internal.entry_ref(root).unwrap();
+    let cs = root.get_component::<compo::CS<NodeIdentifier>>().unwrap();
+    assert_eq!(&*cs.0, &[a, b]);
+    let label = root
+        .get_component::<crate::store::defaults::LabelIdentifier>()
+        .unwrap();
+    assert_eq!(restored.label_store.resolve(label), "root");
+}
+
+#[test]
+fn corrupted_log_len() {
+    use crate::store::persist::log_len;
+    let mut log = 4u64.to_le_bytes().to_vec();
+    log.extend_from_slice(&[1, 2, 3, 4]);
+    let complete = log.len();
+    // would overflow when added to the current length
+    log.extend_from_slice(&u64::MAX.to_le_bytes());
+    log.extend_from_slice(&[0; 16]);
+    assert_eq!(log_len(&log), complete);
+}
@@ -0,0 +1,626 @@
+//! Versioned binary snapshots of [`SimpleStores`].
+//!
+//! Labels are written in interning order and nodes are written along with their identifiers,
+//! so reloading a snapshot gives back the exact same [`LabelIdentifier`]s and [`NodeIdentifier`]s.
+//! It matters because identifiers are kept outside of the stores, e.g. the roots of processed commits.
+//!
+//! Node components are type-erased in the node store,
+//! thus each persisted component must be registered in a [`Codecs`] under a stable name.
+//! Common components are registered by default,
+//! language specific ones (e.g. their [`TypeU16`]) must be registered by their users.
+//!
+//! The stores are append-only, thus between full snapshots,
+//! only what was added since the last [`Checkpoint`] needs to be appended to a log.
+//!
+//! [`LabelIdentifier`]: crate::store::defaults::LabelIdentifier
+//! [`NodeIdentifier`]: crate::store::defaults::NodeIdentifier
+//! [`TypeU16`]: crate::types::TypeU16
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+use legion::storage::{Component, ComponentTypeId};
+
+use super::SimpleStores;
+use super::labels::LabelStore;
+use super::nodes::compo;
+use super::nodes::legion::{EntryRef, NodeIdentifier, NodeStore, dyn_builder};
+use crate::store::defaults::LabelIdentifier;
+
+/// Identifies HyperAST snapshot files
+pub const MAGIC: &[u8; 8] = b"HYPERAST";
+/// Bumped on any change to the layout of snapshots
+pub const VERSION: u32 = 1;
+
+/// Binary (de)serialization of values stored in snapshots.
+///
+/// Values are written in little endian, lengths as u64.
+pub trait Persist: Sized {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()>;
+    fn restore(r: &mut dyn Read) -> io::Result<Self>;
+}
+
+pub(crate) fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+macro_rules! persist_prim {
+    ($($t:ty),*) => {$(
+        impl Persist for $t {
+            fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+                w.write_all(&self.to_le_bytes())
+            }
+            fn restore(r: &mut dyn Read) -> io::Result<Self> {
+                let mut b = [0; std::mem::size_of::<$t>()];
+                r.read_exact(&mut b)?;
+                Ok(<$t>::from_le_bytes(b))
+            }
+        }
+    )*};
+}
+
+persist_prim!(u8, u16, u32, u64, u128, i64, f64);
+
+impl Persist for bool {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        (*self as u8).persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        match u8::restore(r)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            x => Err(invalid_data(format!("{} is not a bool", x))),
+        }
+    }
+}
+
+impl Persist for usize {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        (*self as u64).persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let x = u64::restore(r)?;
+        x.try_into()
+            .map_err(|_| invalid_data(format!("{} does not fit in usize", x)))
+    }
+}
+
+impl Persist for String {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.len().persist(w)?;
+        w.write_all(self.as_bytes())
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let len = usize::restore(r)?;
+        let mut b = vec![0; len];
+        r.read_exact(&mut b)?;
+        String::from_utf8(b).map_err(|e| invalid_data(e.to_string()))
+    }
+}
+
+impl<T: Persist> Persist for Vec<T> {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.len().persist(w)?;
+        self.iter().try_for_each(|x| x.persist(w))
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let len = usize::restore(r)?;
+        (0..len).map(|_| T::restore(r)).collect()
+    }
+}
+
+impl<T: Persist> Persist for Box<[T]> {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.len().persist(w)?;
+        self.iter().try_for_each(|x| x.persist(w))
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        Vec::restore(r).map(Vec::into_boxed_slice)
+    }
+}
+
+impl<T: Persist, const N: usize> Persist for [T; N] {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.iter().try_for_each(|x| x.persist(w))
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let v: Vec<T> = (0..N).map(|_| T::restore(r)).collect::<io::Result<_>>()?;
+        v.try_into().map_err(|_| invalid_data("wrong array length"))
+    }
+}
+
+impl<T: Persist> Persist for Option<T> {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.is_some().persist(w)?;
+        self.as_ref().map_or(Ok(()), |x| x.persist(w))
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        Ok(if bool::restore(r)? {
+            Some(T::restore(r)?)
+        } else {
+            None
+        })
+    }
+}
+
+impl<A: Persist, B: Persist> Persist for (A, B) {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.0.persist(w)?;
+        self.1.persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        Ok((A::restore(r)?, B::restore(r)?))
+    }
+}
+
+impl<T: enumset::EnumSetType> Persist for enumset::EnumSet<T> {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.as_u64().persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let x = u64::restore(r)?;
+        enumset::EnumSet::try_from_u64(x).ok_or_else(|| invalid_data("invalid enum set"))
+    }
+}
+
+impl Persist for NodeIdentifier {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        id_to_u64(*self).persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        u64_to_id(u64::restore(r)?)
+    }
+}
+
+pub(crate) fn id_to_u64(id: NodeIdentifier) -> u64 {
+    // NOTE legion entities are a transparent NonZeroU64
+    unsafe { std::mem::transmute(id) }
+}
+
+pub(crate) fn u64_to_id(id: u64) -> io::Result<NodeIdentifier> {
+    let id = std::num::NonZeroU64::new(id).ok_or_else(|| invalid_data("null node identifier"))?;
+    Ok(unsafe { std::mem::transmute(id) })
+}
+
+impl Persist for LabelIdentifier {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        use string_interner::Symbol;
+        (self.0.to_usize() as u32).persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let x = u32::restore(r)?;
+        super::labels::label_id_from_usize(x as usize)
+            .ok_or_else(|| invalid_data(format!("{} is not a label identifier", x)))
+    }
+}
+
+impl<T: Persist + crate::PrimInt> Persist for crate::hashed::SyntaxNodeHashs<T> {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.structt.persist(w)?;
+        self.label.persist(w)?;
+        self.syntax.persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        Ok(Self {
+            structt: T::restore(r)?,
+            label: T::restore(r)?,
+            syntax: T::restore(r)?,
+        })
+    }
+}
+
+impl<L: crate::types::LLang<Self, I = u16>> Persist for crate::types::TypeU16<L> {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        <L as crate::types::Lang<L::E>>::to_u16(self.e()).persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let e = <L as crate::types::Lang<L::E>>::make(u16::restore(r)?);
+        Ok(Self::new(*e))
+    }
+}
+
+impl Persist for crate::types::Role {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.to_string().persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let s = String::restore(r)?;
+        crate::types::Role::try_from(s.as_str())
+            .map_err(|_| invalid_data(format!("{} is not a role", s)))
+    }
+}
+
+impl Persist for crate::filter::BloomSize {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        use crate::filter::BloomSize;
+        let x: u8 = match self {
+            BloomSize::None => 0,
+            BloomSize::B16 => 1,
+            BloomSize::B32 => 2,
+            BloomSize::B64 => 3,
+            BloomSize::B128 => 4,
+            BloomSize::B256 => 5,
+            BloomSize::B512 => 6,
+            BloomSize::B1024 => 7,
+            BloomSize::B2048 => 8,
+            BloomSize::B4096 => 9,
+            BloomSize::Much => 10,
+        };
+        x.persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        use crate::filter::BloomSize;
+        Ok(match u8::restore(r)? {
+            0 => BloomSize::None,
+            1 => BloomSize::B16,
+            2 => BloomSize::B32,
+            3 => BloomSize::B64,
+            4 => BloomSize::B128,
+            5 => BloomSize::B256,
+            6 => BloomSize::B512,
+            7 => BloomSize::B1024,
+            8 => BloomSize::B2048,
+            9 => BloomSize::B4096,
+            10 => BloomSize::Much,
+            x => return Err(invalid_data(format!("{} is not a bloom size", x))),
+        })
+    }
+}
+
+macro_rules! persist_newtype {
+    ($($t:ident),*) => {$(
+        impl Persist for compo::$t {
+            fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+                self.0.persist(w)
+            }
+            fn restore(r: &mut dyn Read) -> io::Result<Self> {
+                Persist::restore(r).map(compo::$t)
+            }
+        }
+    )*};
+    ($($t:ident<$p:ident>),*) => {$(
+        impl<$p: Persist> Persist for compo::$t<$p> {
+            fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+                self.0.persist(w)
+            }
+            fn restore(r: &mut dyn Read) -> io::Result<Self> {
+                Persist::restore(r).map(compo::$t)
+            }
+        }
+    )*};
+}
+
+persist_newtype!(
+    Size,
+    SizeNoSpaces,
+    Height,
+    BytesLen,
+    LineCount,
+    VizCsCount,
+    HStruct,
+    HLabel,
+    RoleOffsets
+);
+persist_newtype!(
+    CS<T>,
+    NoSpacesCS<T>,
+    Roles<T>,
+    Precomp<T>,
+    Flags<T>,
+    More<T>
+);
+
+impl<T: Persist, const N: usize> Persist for compo::CS0<T, N> {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.0.persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        Persist::restore(r).map(compo::CS0)
+    }
+}
+
+impl<T: Persist, const N: usize> Persist for compo::NoSpacesCS0<T, N> {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.0.persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        Persist::restore(r).map(compo::NoSpacesCS0)
+    }
+}
+
+impl Persist for compo::PrecompFlag {
+    fn persist(&self, _w: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+    fn restore(_r: &mut dyn Read) -> io::Result<Self> {
+        Ok(compo::PrecompFlag)
+    }
+}
+
+struct Codec {
+    name: &'static str,
+    persist: fn(&EntryRef, &mut dyn Write) -> io::Result<()>,
+    restore: fn(&mut dyn Read, &mut dyn_builder::EntityBuilder) -> io::Result<()>,
+}
+
+fn persist_compo<T: Component + Persist>(e: &EntryRef, w: &mut dyn Write) -> io::Result<()> {
+    let c = e
+        .get_component::<T>()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{:?}", e)))?;
+    c.persist(w)
+}
+
+fn restore_compo<T: Component + Persist>(
+    r: &mut dyn Read,
+    builder: &mut dyn_builder::EntityBuilder,
+) -> io::Result<()> {
+    builder._add(T::restore(r)?);
+    Ok(())
+}
+
+/// Registry of the node components that can be written to snapshots.
+///
+/// Names are written in snapshots thus they should not change between versions,
+/// while registration order does not matter.
+pub struct Codecs {
+    codecs: Vec<Codec>,
+    by_type: HashMap<ComponentTypeId, u16>,
+    by_name: HashMap<&'static str, u16>,
+}
+
+impl Codecs {
+    /// Without any registered component, see [`Codecs::default`] to get common components.
+    pub fn empty() -> Self {
+        Self {
+            codecs: vec![],
+            by_type: Default::default(),
+            by_name: Default::default(),
+        }
+    }
+
+    /// Registers `T` under `name`.
+    ///
+    /// Panics if `name` is already used by another component.
+    pub fn register<T: Component + Persist>(&mut self, name: &'static str) -> &mut Self {
+        let tid = ComponentTypeId::of::<T>();
+        if let Some(&i) = self.by_name.get(name) {
+            assert_eq!(
+                self.by_type.get(&tid),
+                Some(&i),
+                "{} is already registered for another component",
+                name
+            );
+            return self;
+        }
+        let i = self.codecs.len() as u16;
+        self.codecs.push(Codec {
+            name,
+            persist: persist_compo::<T>,
+            restore: restore_compo::<T>,
+        });
+        self.by_type.insert(tid, i);
+        self.by_name.insert(name, i);
+        self
+    }
+
+    pub(crate) fn index_of(&self, tid: &ComponentTypeId) -> io::Result<u16> {
+        self.by_type.get(tid).copied().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("no codec registered for component {:?}", tid),
+            )
+        })
+    }
+
+    pub(crate) fn persist_compo(&self, i: u16, e: &EntryRef, w: &mut dyn Write) -> io::Result<()> {
+        (self.codecs[i as usize].persist)(e, w)
+    }
+
+    /// Writes the names of registered codecs,
+    /// their position is then used to identify components in the snapshot.
+    pub(crate) fn persist_names(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.codecs.len().persist(w)?;
+        self.codecs
+            .iter()
+            .try_for_each(|c| c.name.to_string().persist(w))
+    }
+
+    /// Reads the names written by [`Codecs::persist_names`],
+    /// and maps them to the local codecs.
+    pub(crate) fn restore_names(&self, r: &mut dyn Read) -> io::Result<Vec<u16>> {
+        let names: Vec<String> = Persist::restore(r)?;
+        names
+            .into_iter()
+            .map(|name| {
+                self.by_name.get(name.as_str()).copied().ok_or_else(|| {
+                    invalid_data(format!("no codec registered for component {}", name))
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn restore_compo(
+        &self,
+        i: u16,
+        r: &mut dyn Read,
+        builder: &mut dyn_builder::EntityBuilder,
+    ) -> io::Result<()> {
+        (self.codecs[i as usize].restore)(r, builder)
+    }
+}
+
+impl Default for Codecs {
+    /// Components defined in this crate and used by most generators.
+    fn default() -> Self {
+        type Bloom<V> = crate::filter::Bloom<&'static [u8], V>;
+        let mut r = Self::empty();
+        r.register::<LabelIdentifier>("label")
+            .register::<compo::CS<NodeIdentifier>>("cs")
+            .register::<compo::CS0<NodeIdentifier, 1>>("cs0_1")
+            .register::<compo::CS0<NodeIdentifier, 2>>("cs0_2")
+            .register::<compo::NoSpacesCS<NodeIdentifier>>("no_spaces_cs")
+            .register::<compo::NoSpacesCS0<NodeIdentifier, 1>>("no_spaces_cs0_1")
+            .register::<compo::NoSpacesCS0<NodeIdentifier, 2>>("no_spaces_cs0_2")
+            .register::<compo::CS<LabelIdentifier>>("cs_names")
+            .register::<compo::Size>("size")
+            .register::<compo::SizeNoSpaces>("size_no_spaces")
+            .register::<compo::Height>("height")
+            .register::<compo::BytesLen>("bytes_len")
+            .register::<compo::LineCount>("line_count")
+            .register::<compo::VizCsCount>("viz_cs_count")
+            .register::<compo::HStruct>("h_struct")
+            .register::<compo::HLabel>("h_label")
+            .register::<compo::Roles<crate::types::Role>>("roles")
+            .register::<compo::RoleOffsets>("role_offsets")
+            .register::<compo::Precomp<crate::tree_gen::PrecompQueries>>("precomp")
+            .register::<compo::PrecompFlag>("precomp_flag")
+            .register::<crate::hashed::SyntaxNodeHashs<u32>>("hashs")
+            .register::<crate::filter::BloomSize>("bloom_size")
+            .register::<Bloom<u16>>("bloom_u16")
+            .register::<Bloom<u32>>("bloom_u32")
+            .register::<Bloom<u64>>("bloom_u64")
+            .register::<Bloom<[u64; 2]>>("bloom_u64x2")
+            .register::<Bloom<[u64; 4]>>("bloom_u64x4")
+            .register::<Bloom<[u64; 8]>>("bloom_u64x8")
+            .register::<Bloom<[u64; 16]>>("bloom_u64x16")
+            .register::<Bloom<[u64; 32]>>("bloom_u64x32")
+            .register::<Bloom<[u64; 64]>>("bloom_u64x64")
+            .register::<crate::cyclomatic::Mcc>("mcc");
+        #[cfg(feature = "scripting")]
+        r.register::<crate::scripting::DerivedData>("derived_data");
+        r
+    }
+}
+
+/// Position in the stores up to which they were written,
+/// either in a full snapshot or in a log segment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    labels: usize,
+    nodes: u64,
+}
+
+impl<TS> SimpleStores<TS, NodeStore, LabelStore> {
+    /// Writes a full snapshot of the label and node stores.
+    pub fn write_snapshot(&self, codecs: &Codecs, w: &mut dyn Write) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        VERSION.persist(w)?;
+        self.label_store.persist(w)?;
+        self.node_store.write_snapshot(codecs, w)
+    }
+
+    /// Reads a snapshot written by [`SimpleStores::write_snapshot`].
+    ///
+    /// Must be done before any other node store is created in the process,
+    /// as identifiers allocated before could collide with the restored ones.
+    pub fn read_snapshot(codecs: &Codecs, r: &mut dyn Read) -> io::Result<Self> {
+        Self::read_snapshot_with_log(codecs, r, &[])
+    }
+
+    /// Current position in the stores, e.g. right after writing a snapshot.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            labels: self.label_store.interned_len(),
+            nodes: self.node_store.max_id(),
+        }
+    }
+
+    /// Appends to `w` a log segment holding the labels and nodes added since `since`.
+    ///
+    /// The stores are append-only, so replaying the segments written since a snapshot
+    /// gives back the current state, see [`SimpleStores::read_snapshot_with_log`].
+    /// Returns the position to start the next segment from.
+    pub fn write_log_segment(
+        &self,
+        codecs: &Codecs,
+        since: &Checkpoint,
+        w: &mut dyn Write,
+    ) -> io::Result<Checkpoint> {
+        let next = self.checkpoint();
+        let mut payload = vec![];
+        next.nodes.persist(&mut payload)?;
+        self.label_store.persist_since(since.labels, &mut payload)?;
+        self.node_store
+            .write_since(codecs, since.nodes, &mut payload)?;
+        // the length comes first so that a partially appended segment can be detected
+        payload.len().persist(w)?;
+        w.write_all(&payload)?;
+        Ok(next)
+    }
+
+    /// Reads a snapshot then replays the segments of `log`, see [`SimpleStores::write_log_segment`].
+    ///
+    /// A truncated segment at the end of `log` is ignored, see [`log_len`].
+    pub fn read_snapshot_with_log(
+        codecs: &Codecs,
+        r: &mut dyn Read,
+        log: &[u8],
+    ) -> io::Result<Self> {
+        check_header(r)?;
+        let mut label_store = LabelStore::restore(r)?;
+        let segments = log_segments(log)?;
+        let reserved = segments.iter().map(|x| x.0).max().unwrap_or_default();
+        let mut node_store = NodeStore::read_snapshot_reserving(codecs, r, reserved)?;
+        for (_, mut payload) in segments {
+            label_store.restore_since(&mut payload)?;
+            node_store.replay_since(codecs, &mut payload)?;
+        }
+        Ok(Self {
+            label_store,
+            node_store,
+            type_store: std::marker::PhantomData,
+        })
+    }
+}
+
+/// Length of the complete segments at the start of `log`,
+/// the rest should be truncated before appending new segments.
+pub fn log_len(log: &[u8]) -> usize {
+    let mut len = 0;
+    while let Some(b) = log.get(len..len + 8) {
+        let l = u64::from_le_bytes(b.try_into().unwrap());
+        // a corrupted length is handled like a truncated segment
+        let end = usize::try_from(l)
+            .ok()
+            .and_then(|l| (len + 8).checked_add(l));
+        match end {
+            Some(end) if end <= log.len() => len = end,
+            _ => break,
+        }
+    }
+    len
+}
+
+/// Splits a log in segments, giving the greatest node identifier of each.
+fn log_segments(log: &[u8]) -> io::Result<Vec<(u64, &[u8])>> {
+    let len = log_len(log);
+    if len < log.len() {
+        log::warn!("ignoring a truncated segment at the end of the log");
+    }
+    let mut log = &log[..len];
+    let mut r = vec![];
+    while !log.is_empty() {
+        let l = usize::restore(&mut log)?;
+        let (mut payload, rest) = log.split_at(l);
+        let max = u64::restore(&mut payload)?;
+        r.push((max, payload));
+        log = rest;
+    }
+    Ok(r)
+}
+
+pub(crate) fn check_header(r: &mut dyn Read) -> io::Result<()> {
+    let mut magic = [0; 8];
+    r.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid_data("not a HyperAST snapshot"));
+    }
+    let version = u32::restore(r)?;
+    if version != VERSION {
+        return Err(invalid_data(format!(
+            "unsupported snapshot version {}, expected {}",
+            version, VERSION
+        )));
+    }
+    Ok(())
+}
@@ -218,15 +218,23 @@ impl crate::types::NodeStoreExt<Tree> for NS<Tree> {
         &mut self,
         _i: <Tree as crate::types::Stored>::TreeId,
         t: <Tree as crate::types::Typed>::Type,
-        _l: Option<<Tree as crate::types::Labeled>::Label>,
+        l: Option<<Tree as crate::types::Labeled>::Label>,
         cs: Vec<<Tree as Stored>::TreeId>,
     ) -> <Tree as Stored>::TreeId {
+        // same metrics as in `store`, so that rebuilt nodes dedup with the original ones
+        let mut size = 1;
+        let mut height = 0;
+        for c in &cs {
+            let c = &self.v[c.to_usize().unwrap()];
+            size += c.size;
+            height = height.max(c.height);
+        }
         let node = Tree {
             t,
-            label: 0,
+            label: l.unwrap_or(0),
             children: cs,
-            size: 0,
-            height: 0,
+            size,
+            height: height + 1,
         };
         self.get_or_insert(node)
     }
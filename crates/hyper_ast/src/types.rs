@@ -1008,6 +1008,12 @@ pub trait WithStats {
     fn height(&self) -> usize;
     fn line_count(&self) -> usize;
 }
+
+/// Numerical metrics of a node, by name, in addition to its [`WithStats`]
+pub trait WithMetrics: WithStats {
+    /// e.g. a metric derived by a preprocessing script, None if the node does not have it
+    fn get_metric(&self, name: &str) -> Option<f64>;
+}
 pub trait WithMetaData<C> {
     fn get_metadata(&self) -> Option<&C>;
 }
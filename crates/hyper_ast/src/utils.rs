@@ -200,6 +200,12 @@ impl fmt::Display for Bytes {
     }
 }
 
+impl From<isize> for Bytes {
+    fn from(value: isize) -> Self {
+        Bytes(value)
+    }
+}
+
 impl Into<isize> for &Bytes {
     fn into(self) -> isize {
         self.0
@@ -2,8 +2,12 @@ use std::fmt::Debug;
 
 use hyperast::{
     position::compute_range,
-    types::{HyperAST, LabelStore, Labeled, NodeId, NodeStore, NodeStoreExt, WithChildren},
+    types::{
+        HyperAST, LabelStore, Labeled, LendN, NodeId, NodeStore, NodeStoreExt, Typed, TypedTree,
+        WithChildren,
+    },
 };
+use num_traits::ToPrimitive;
 
 use crate::tree::tree_path::TreePath;
 
@@ -239,11 +243,18 @@ impl<L: Debug, P: TreePath, I: Debug> ActionsVec<SimpleAction<L, P, I>> {
 // //     fn build(t: T::Type, l: T::Label, cs: Vec<T::TreeId>) -> T;
 // // }
 
-/// WARN for now it is very inneficient because it completly apply actions every times,
+/// Applies a sequence of actions, in order, on the trees in `root`.
+///
+/// `root` holds the roots of the middle tree, its first element should be the source root,
+/// new roots (e.g. when the destination root is not mapped) are pushed at the end,
+/// thus once all actions of a script are applied the last root is the destination tree.
+///
+/// Nodes are never mutated in place, each action rebuilds the modified ancestors
+/// through [`NodeStoreExt::build_then_insert`], thus unchanged subtrees are shared
+/// and identical subtrees are deduplicated by the node store.
+///
+/// WARN for now it is quite inneficient because every action rebuilds all its ancestors,
 /// most likely it would need a temporary structure.
-/// Also actions are applied in order, thus there is a single way of applying actions.
-/// It might not have enough info to it flexibly, action_tree could definetly be more flexible.
-// pub fn apply_actions<S: for<'b> NodeStoreMut<'b, <T as Stored>::TreeId, &'b T>>(
 pub fn apply_actions<T, S, P>(
     actions: ActionsVec<SimpleAction<T::Label, P, T::TreeId>>,
     root: &mut Vec<T::TreeId>,
@@ -255,27 +266,45 @@ pub fn apply_actions<T, S, P>(
     T::Label: Debug + Copy,
     T::TreeId: Debug + Copy + NodeId<IdN = T::TreeId>,
     T::ChildIdx: Debug + Copy,
-    S: NodeStoreExt<T> + NodeStore<T::TreeId>, //NodeStoreExt<'a, T, R>,
-                                               // for<'d> S::R<'d>: hyperast::types::TypedTree<
-                                               //     TreeId = T::TreeId,
-                                               //     Type = T::Type,
-                                               //     Label = T::Label,
-                                               //     ChildIdx = T::ChildIdx,
-                                               // >,
+    S: NodeStoreExt<T> + NodeStore<T::TreeId>,
+    for<'t> LendN<'t, S, T::TreeId>: TypedTree<Type = T::Type, Label = T::Label>,
 {
     for a in actions.iter() {
-        // *log::debug!(
-        //     "mid tree:\n{:?}",
-        //     DisplayTree::new(&label_store, &node_store, *root.last().unwrap())
-        // );
         apply_action(a, root, node_store);
     }
 }
 
+/// Applies `actions` on the tree rooted at `src` and returns the root of the resulting tree.
+///
+/// When `actions` is an edit script computed from `src` to some `dst`,
+/// the returned identifier is the one of `dst` (provided the store deduplicates nodes).
+pub fn apply<T, S, P>(
+    actions: ActionsVec<SimpleAction<T::Label, P, T::TreeId>>,
+    src: T::TreeId,
+    node_store: &mut S,
+) -> T::TreeId
+where
+    P: TreePath<Item = T::ChildIdx> + Debug,
+    T: hyperast::types::TypedTree,
+    T::Type: Debug + Copy + Send + Sync,
+    T::Label: Debug + Copy,
+    T::TreeId: Debug + Copy + NodeId<IdN = T::TreeId>,
+    T::ChildIdx: Debug + Copy,
+    S: NodeStoreExt<T> + NodeStore<T::TreeId>,
+    for<'t> LendN<'t, S, T::TreeId>: TypedTree<Type = T::Type, Label = T::Label>,
+{
+    let mut root = vec![src];
+    apply_actions(actions, &mut root, node_store);
+    *root.last().unwrap()
+}
+
+/// Applies a single action on the trees in `root`, see [`apply_actions`].
+///
+/// Paths used are the `mid` ones, the first offset selecting the root.
 pub fn apply_action<T, S, P>(
     a: &SimpleAction<T::Label, P, T::TreeId>,
     root: &'_ mut Vec<T::TreeId>,
-    _s: &'_ mut S,
+    s: &'_ mut S,
 ) where
     S: NodeStoreExt<T> + NodeStore<T::TreeId>,
     T: hyperast::types::TypedTree,
@@ -284,246 +313,163 @@ pub fn apply_action<T, S, P>(
     T::TreeId: Debug + Copy + NodeId<IdN = T::TreeId>,
     T::ChildIdx: Debug + Copy,
     P: TreePath<Item = T::ChildIdx> + Debug,
+    for<'t> LendN<'t, S, T::TreeId>: TypedTree<Type = T::Type, Label = T::Label>,
 {
-    todo!("{:?}, {:?}", a, root)
-    // use hyperast::store::{
-    //     defaults::{LabelIdentifier, NodeIdentifier},
-    //     nodes::HashedNodeRef,
-    // };
-    // use hyperast::types::{AnyType, Children, TypeStore, Typed};
-    // let fun_name = |s: &mut S, x: &T::TreeId| -> (T::Type, Option<T::Label>) {
-    //     let node = s.resolve(x);
-    //     let t = node.get_type().to_owned();
-    //     let l = node.try_get_label().cloned();
-    //     (t, l)
-    // };
-    // let roots: &mut Vec<_> = root;
-    // log::trace!("{:?}", a);
-    // let SimpleAction { path, action } = a;
-
-    // let from = match action {
-    //     Act::Move { from } => Some(from),
-    //     Act::MovUpd { from, .. } => Some(from),
-    //     _ => None,
-    // };
+    log::trace!("{:?}", a);
+    let roots: &mut Vec<_> = root;
+    let SimpleAction { path, action } = a;
+    match action {
+        Act::Delete {} => {
+            with_children_of_parent(&path.mid, roots, s, |_, cs, i| {
+                cs.remove(i);
+            });
+        }
+        Act::Update { new } => {
+            let new = *new;
+            with_node(&path.mid, roots, s, |s, x| {
+                let (t, _, cs) = describe::<T, S>(s, &x);
+                s.build_then_insert(x, t, Some(new), cs)
+            });
+        }
+        Act::Insert { sub } => {
+            let (t, l, _) = describe::<T, S>(s, sub);
+            let sub = s.build_then_insert(*sub, t, l, vec![]);
+            insert_at(&path.mid, roots, s, sub);
+        }
+        Act::Move { from } => {
+            let sub = detach(&from.mid, roots, s);
+            insert_at(&path.mid, roots, s, sub);
+        }
+        Act::MovUpd { from, new } => {
+            let sub = detach(&from.mid, roots, s);
+            let (t, _, cs) = describe::<T, S>(s, &sub);
+            let sub = s.build_then_insert(sub, t, Some(*new), cs);
+            insert_at(&path.mid, roots, s, sub);
+        }
+    }
+}
 
-    // let sub = if let Some(from) = from {
-    //     // apply remove
-    //     log::trace!("sub path {:?}", from.mid.iter().collect::<Vec<_>>());
-    //     let mut path = from.mid.iter();
-    //     let fp = path.next().unwrap().to_usize().unwrap();
-    //     // dbg!(&fp);
-    //     let r = &mut roots[fp];
-    //     let mut x = *r;
-    //     let mut parents: Vec<(T::TreeId, T::ChildIdx, Vec<T::TreeId>)> = vec![];
-    //     while let Some(p) = path.next() {
-    //         // dbg!(&p);
-    //         let node = s.resolve(&x);
-    //         let cs = node.children().unwrap();
-    //         parents.push((x, p, cs.iter_children().cloned().collect()));
-    //         let i = p;
-    //         // dbg!(cs.len());
-    //         if i < cs.child_count() {
-    //             x = cs[i].clone();
-    //         } else {
-    //             assert!(path.next().is_none());
-    //             break;
-    //         }
-    //     }
-    //     log::trace!("parents {:?}", parents);
-    //     let (node, sub) = if let Some((x, i, cs)) = parents.pop() {
-    //         let mut children = Vec::with_capacity(cs.len() - 1);
-    //         children.extend_from_slice(&cs[..i.to_usize().unwrap()]);
-    //         children.extend_from_slice(&cs[i.to_usize().unwrap() + 1..]);
-    //         let (t, l) = fun_name(s, &x);
-    //         let node = s.build_then_insert(x, t, l, children);
-    //         (node, cs[i.to_usize().unwrap()].clone())
-    //     } else {
-    //         // let mut children = Vec::with_capacity(cs.len() - 1);
-    //         // children.extend_from_slice(&cs[..i.to_usize().unwrap()]);
-    //         // children.extend_from_slice(&cs[i.to_usize().unwrap() + 1..]);
-    //         // let node = s.resolve(&x);
-    //         // let node = Self::build(node.get_type(), node.get_label().clone(), children);
-    //         // s.get_or_insert(node)
-    //         (r.clone(), r.clone())
-    //     };
-    //     let mut node: T::TreeId = node;
-    //     for (x, i, cs) in parents.into_iter().rev() {
-    //         let mut children = Vec::with_capacity(cs.len() - 1);
-    //         children.extend_from_slice(&cs[..i.to_usize().unwrap()]);
-    //         children.push(node.clone());
-    //         children.extend_from_slice(&cs[i.to_usize().unwrap() + 1..]);
-    //         let (t, l) = fun_name(s, &x);
-    //         node = s.build_then_insert(x, t, l, children);
-    //         // let n: T = BuildableTree::build(n.get_type().to_owned(), n.get_label().clone(), children);
-    //         // node = s.get_or_insert(n);
-    //     }
-    //     *r = node;
-    //     Some(sub)
-    // } else {
-    //     None
-    // };
+fn describe<T, S>(s: &S, x: &T::TreeId) -> (T::Type, Option<T::Label>, Vec<T::TreeId>)
+where
+    T: hyperast::types::TypedTree,
+    T::Label: Copy,
+    T::TreeId: NodeId<IdN = T::TreeId>,
+    S: NodeStore<T::TreeId>,
+    for<'t> LendN<'t, S, T::TreeId>: TypedTree<Type = T::Type, Label = T::Label>,
+{
+    let node = s.resolve(x);
+    let t = node.get_type();
+    let l = node.try_get_label().copied();
+    let cs = node.children().map_or(vec![], |cs| cs.collect());
+    (t, l, cs)
+}
 
-    // let mut parents: Vec<(T::TreeId, T::ChildIdx, Vec<T::TreeId>)> = vec![];
-    // log::trace!("{:?}", path.mid.iter().collect::<Vec<_>>());
-    // // dbg!(path.mid.iter().collect::<Vec<_>>());
-    // let mut path = path.mid.iter();
-    // let fp = path.next().unwrap().to_usize().unwrap();
-    // // dbg!(&fp);
-    // let r = if roots.len() > fp {
-    //     &mut roots[fp]
-    // } else if roots.len() == fp {
-    //     roots.push(roots[fp - 1].clone());
-    //     &mut roots[fp]
-    // } else {
-    //     panic!()
-    // };
-    // let mut x: T::TreeId = *r;
-    // while let Some(p) = path.next() {
-    //     // dbg!(&p);
-    //     let node = s.resolve(&x);
-    //     let cs = node.children();
-    //     let i = p;
-    //     // TODO use pattern match
-    //     if cs.is_some() && i < cs.unwrap().child_count() {
-    //         let tmp = cs.unwrap()[i].clone();
-    //         parents.push((x, p, cs.unwrap().iter_children().cloned().collect()));
-    //         x = tmp;
-    //     } else {
-    //         if cs.is_some() && !cs.unwrap().is_empty() {
-    //             parents.push((x, p, cs.unwrap().iter_children().cloned().collect()));
-    //             log::error!("{:?} > {:?}", i, cs.unwrap().child_count());
-    //         } else {
-    //             parents.push((x, p, Default::default()));
-    //             log::error!("{:?} > {:?}", i, 0);
-    //         }
-    //         assert_eq!(path.next(), None);
-    //         break;
-    //     }
-    // }
+/// Removes the node at `path` from its parent and returns it.
+/// Roots are left in place, so that following paths stay valid.
+fn detach<T, S, P>(path: &P, roots: &mut Vec<T::TreeId>, s: &mut S) -> T::TreeId
+where
+    S: NodeStoreExt<T> + NodeStore<T::TreeId>,
+    T: hyperast::types::TypedTree,
+    T::Label: Copy,
+    T::TreeId: Copy + NodeId<IdN = T::TreeId>,
+    P: TreePath<Item = T::ChildIdx>,
+    for<'t> LendN<'t, S, T::TreeId>: TypedTree<Type = T::Type, Label = T::Label>,
+{
+    if path.iter().nth(1).is_none() {
+        let r = path.iter().next().expect("empty path");
+        return roots[r.to_usize().unwrap()];
+    }
+    with_children_of_parent(path, roots, s, |_, cs, i| cs.remove(i))
+}
 
-    // let node = match action {
-    //     Act::Delete {} => {
-    //         let (x, i, cs) = parents.pop().unwrap();
-    //         let mut children = Vec::with_capacity(cs.len() - 1);
-    //         children.extend_from_slice(&cs[..i.to_usize().unwrap()]);
-    //         children.extend_from_slice(&cs[i.to_usize().unwrap() + 1..]);
-    //         let (t, l) = fun_name(s, &x);
-    //         s.build_then_insert(x, t, l, children)
-    //     }
-    //     Act::Insert { sub } => {
-    //         if let Some((x, i, cs)) = parents.pop() {
-    //             let mut children = Vec::with_capacity(cs.len());
-    //             children.extend_from_slice(&cs[..i.to_usize().unwrap()]);
-    //             let sub = {
-    //                 let (t, l) = fun_name(s, sub);
-    //                 s.build_then_insert(*sub, t, l, vec![])
-    //             };
-    //             children.push(sub);
-    //             if i.to_usize().unwrap() < cs.len() {
-    //                 children.extend_from_slice(&cs[i.to_usize().unwrap()..]);
-    //             }
-    //             let (t, l) = fun_name(s, &x);
-    //             s.build_then_insert(x, t, l, children)
-    //         } else {
-    //             let sub = {
-    //                 let (t, l) = fun_name(s, sub);
-    //                 s.build_then_insert(*sub, t, l, vec![])
-    //             };
-    //             // *r = sub.clone();
-    //             sub
-    //         }
-    //     }
-    //     Act::Update { new } => {
-    //         if let Some((x, i, cs)) = parents.pop() {
-    //             let mut children = Vec::with_capacity(cs.len());
-    //             children.extend_from_slice(&cs[..i.to_usize().unwrap()]);
-    //             let sub = {
-    //                 let x = cs[i.to_usize().unwrap()].clone();
-    //                 // let node = s.resolve(&x);
-    //                 // s.build_then_insert(
-    //                 //     node.get_type(),
-    //                 //     new.clone(),
-    //                 //     node.get_children().to_vec(),
-    //                 // )
+/// Inserts `sub` at `path`, pushing it as a new root if `path` is a single offset.
+fn insert_at<T, S, P>(path: &P, roots: &mut Vec<T::TreeId>, s: &mut S, sub: T::TreeId)
+where
+    S: NodeStoreExt<T> + NodeStore<T::TreeId>,
+    T: hyperast::types::TypedTree,
+    T::Label: Copy,
+    T::TreeId: Copy + NodeId<IdN = T::TreeId>,
+    P: TreePath<Item = T::ChildIdx>,
+    for<'t> LendN<'t, S, T::TreeId>: TypedTree<Type = T::Type, Label = T::Label>,
+{
+    if path.iter().nth(1).is_none() {
+        let r = path.iter().next().expect("empty path").to_usize().unwrap();
+        if r < roots.len() {
+            roots[r] = sub;
+        } else {
+            roots.push(sub);
+        }
+        return;
+    }
+    with_children_of_parent(path, roots, s, |_, cs, i| {
+        if i < cs.len() {
+            cs.insert(i, sub)
+        } else {
+            cs.push(sub)
+        }
+    })
+}
 
-    //                 let (t, cs) = {
-    //                     let node = s.resolve(&x);
-    //                     let t = node.get_type().to_owned();
-    //                     let cs = node.children();
-    //                     let cs = cs.map(|cs| cs.iter_children().cloned().collect());
-    //                     (t, cs)
-    //                 };
-    //                 s.build_then_insert(x, t, Some(new.clone()), cs.unwrap_or_default())
-    //             };
-    //             children.push(sub);
-    //             children.extend_from_slice(&cs[i.to_usize().unwrap() + 1..]);
-    //             let (t, l) = fun_name(s, &x);
-    //             s.build_then_insert(x, t, l, children)
-    //         } else {
-    //             let (t, cs) = {
-    //                 let node = s.resolve(&x);
-    //                 let t = node.get_type().to_owned();
-    //                 let cs = node.children();
-    //                 let cs = cs.map(|cs| cs.iter_children().cloned().collect());
-    //                 (t, cs)
-    //             };
-    //             s.build_then_insert(x, t, Some(new.clone()), cs.unwrap_or_default())
-    //         }
-    //     }
+/// Replaces the node at `path` by the result of `f`.
+fn with_node<T, S, P>(
+    path: &P,
+    roots: &mut Vec<T::TreeId>,
+    s: &mut S,
+    f: impl FnOnce(&mut S, T::TreeId) -> T::TreeId,
+) where
+    S: NodeStoreExt<T> + NodeStore<T::TreeId>,
+    T: hyperast::types::TypedTree,
+    T::Label: Copy,
+    T::TreeId: Copy + NodeId<IdN = T::TreeId>,
+    P: TreePath<Item = T::ChildIdx>,
+    for<'t> LendN<'t, S, T::TreeId>: TypedTree<Type = T::Type, Label = T::Label>,
+{
+    if path.iter().nth(1).is_none() {
+        let r = path.iter().next().expect("empty path").to_usize().unwrap();
+        roots[r] = f(s, roots[r]);
+        return;
+    }
+    with_children_of_parent(path, roots, s, |s, cs, i| cs[i] = f(s, cs[i]))
+}
 
-    //     Act::Move { .. } => {
-    //         // apply insert
-    //         let (x, i, cs) = parents.pop().unwrap();
-    //         let mut children = Vec::with_capacity(cs.len());
-    //         children.extend_from_slice(&cs[..i.to_usize().unwrap()]);
-    //         let sub = {
-    //             // let node = s.resolve(&sub.unwrap());
-    //             // let node = BuildableTree::build(node.get_type(), node.get_label().clone(), vec![]);
-    //             // s.get_or_insert(node)
-    //             sub.unwrap()
-    //         };
-    //         children.push(sub);
-    //         if i.to_usize().unwrap() < cs.len() {
-    //             children.extend_from_slice(&cs[i.to_usize().unwrap()..]);
-    //         }
-    //         // dbg!(children.len());
-    //         let (t, l) = fun_name(s, &x);
-    //         s.build_then_insert(x, t, l, children)
-    //     }
-    //     Act::MovUpd { new, .. } => {
-    //         // apply insert
-    //         let (x, i, cs) = parents.pop().unwrap();
-    //         let mut children = Vec::with_capacity(cs.len());
-    //         children.extend_from_slice(&cs[..i.to_usize().unwrap()]);
-    //         let sub = {
-    //             // let node = s.resolve(&sub.unwrap());
-    //             // let node = BuildableTree::build(node.get_type(), node.get_label().clone(), vec![]);
-    //             // s.get_or_insert(node)
-    //             sub.unwrap()
-    //         };
-    //         children.push(sub);
-    //         if i.to_usize().unwrap() < cs.len() {
-    //             children.extend_from_slice(&cs[i.to_usize().unwrap()..]);
-    //         }
-    //         let t = {
-    //             let node = s.resolve(&x);
-    //             let t = node.get_type().to_owned();
-    //             t
-    //         };
-    //         s.build_then_insert(x, t, Some(new.clone()), children)
-    //     }
-    // };
-    // let mut node = node;
-    // for (x, i, cs) in parents.into_iter().rev() {
-    //     let mut children = Vec::with_capacity(cs.len() - 1);
-    //     children.extend_from_slice(&cs[..i.to_usize().unwrap()]);
-    //     children.push(node.clone());
-    //     children.extend_from_slice(&cs[i.to_usize().unwrap() + 1..]);
-    //     let (t, l) = fun_name(s, &x);
-    //     node = s.build_then_insert(x, t, l, children);
-    // }
-    // *r = node;
+/// Calls `f` on the children of the parent of the node at `path` (along with its offset),
+/// then rebuilds all the ancestors up to the root selected by the first offset of `path`.
+fn with_children_of_parent<T, S, P, R>(
+    path: &P,
+    roots: &mut [T::TreeId],
+    s: &mut S,
+    f: impl FnOnce(&mut S, &mut Vec<T::TreeId>, usize) -> R,
+) -> R
+where
+    S: NodeStoreExt<T> + NodeStore<T::TreeId>,
+    T: hyperast::types::TypedTree,
+    T::Label: Copy,
+    T::TreeId: Copy + NodeId<IdN = T::TreeId>,
+    P: TreePath<Item = T::ChildIdx>,
+    for<'t> LendN<'t, S, T::TreeId>: TypedTree<Type = T::Type, Label = T::Label>,
+{
+    let mut path = path.iter().map(|i| i.to_usize().unwrap());
+    let r = path.next().expect("empty path");
+    let offsets: Vec<usize> = path.collect();
+    let (&last, offsets) = offsets.split_last().expect("path to a root");
+    let mut parents: Vec<(T::TreeId, usize, Vec<T::TreeId>)> = vec![];
+    let mut x = roots[r];
+    for &o in offsets {
+        let (_, _, cs) = describe::<T, S>(s, &x);
+        let c = cs[o];
+        parents.push((x, o, cs));
+        x = c;
+    }
+    let (t, l, mut cs) = describe::<T, S>(s, &x);
+    let res = f(s, &mut cs, last);
+    let mut node = s.build_then_insert(x, t, l, cs);
+    for (x, o, mut cs) in parents.into_iter().rev() {
+        cs[o] = node;
+        let (t, l, _) = describe::<T, S>(s, &x);
+        node = s.build_then_insert(x, t, l, cs);
+    }
+    roots[r] = node;
+    res
 }
 
 // pub trait ActionApplier<T>
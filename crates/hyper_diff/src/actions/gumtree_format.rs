@@ -0,0 +1,246 @@
+//! Edit scripts in the textual formats of GumTree, ie. the JSON and XML outputs of `gumtree textdiff`,
+//! so that tools reading the diffs of GumTree can read the ones of HyperAST.
+//!
+//! Nodes are written like GumTree does, `type: label [start,end]` or `type [start,end]` without label,
+//! the offsets being relative to the file containing the node.
+
+use std::fmt::Write;
+
+use hyperast::position::compute_position;
+use hyperast::types::{HyperAST, LabelStore, Labeled, NodeId, WithSerialization};
+use num_traits::ToPrimitive;
+use serde::Serialize;
+
+use super::action_vec::ActionsVec;
+use super::script_generator2::{Act, SimpleAction};
+use crate::decompressed_tree_store::{DecompressedWithParent, ShallowDecompressedTreeStore};
+use crate::matchers::Mapping;
+use crate::matchers::mapping_store::MonoMappingStore;
+use crate::tree::tree_path::TreePath;
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub src: String,
+    pub dest: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Action {
+    /// update-node, move-tree, insert-node or delete-node
+    pub action: &'static str,
+    /// in the source tree, except for insertions
+    pub tree: String,
+    /// the parent in the destination tree, for moves and insertions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// the index in `parent`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<usize>,
+    /// the new label, for updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GumtreeDiff {
+    pub matches: Vec<Match>,
+    pub actions: Vec<Action>,
+}
+
+impl GumtreeDiff {
+    /// Formats the `mappings` and the `actions` generated from them,
+    /// the paths of the actions being relative to the roots of the arenas of `mapping`
+    pub fn new<HAST, SS, SD, M, P>(
+        stores: HAST,
+        mapping: &Mapping<SS, SD, M>,
+        actions: &ActionsVec<SimpleAction<HAST::Label, P, HAST::IdN>>,
+    ) -> Self
+    where
+        HAST: HyperAST + Copy,
+        HAST::IdN: Clone + NodeId<IdN = HAST::IdN>,
+        for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithSerialization,
+        M: MonoMappingStore,
+        SS: ShallowDecompressedTreeStore<HAST, M::Src> + DecompressedWithParent<HAST, M::Src>,
+        SD: ShallowDecompressedTreeStore<HAST, M::Dst> + DecompressedWithParent<HAST, M::Dst>,
+        P: TreePath<Item = HAST::Idx>,
+    {
+        let src = mapping.src_arena.original(&mapping.src_arena.root());
+        let dst = mapping.dst_arena.original(&mapping.dst_arena.root());
+        let matches = mapping
+            .mappings
+            .iter()
+            .map(|(a, b)| Match {
+                src: format_node(stores, src.clone(), mapping.src_arena.path_rooted(&a)),
+                dest: format_node(stores, dst.clone(), mapping.dst_arena.path_rooted(&b)),
+            })
+            .collect();
+        Self {
+            matches,
+            ..Self::from_actions(stores, src, dst, actions)
+        }
+    }
+
+    /// Formats the `actions` alone, their paths being relative to `src` and `dst`
+    pub fn from_actions<HAST, P>(
+        stores: HAST,
+        src: HAST::IdN,
+        dst: HAST::IdN,
+        actions: &ActionsVec<SimpleAction<HAST::Label, P, HAST::IdN>>,
+    ) -> Self
+    where
+        HAST: HyperAST + Copy,
+        HAST::IdN: Clone + NodeId<IdN = HAST::IdN>,
+        for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithSerialization,
+        P: TreePath<Item = HAST::Idx>,
+    {
+        let node = |root: &HAST::IdN, path: &P| format_node(stores, root.clone(), path.iter());
+        // moves and insertions are relative to the parent in the destination tree
+        let parent_at = |path: &P| {
+            let mut path: Vec<_> = path.iter().collect();
+            let at = path.pop()?;
+            Some((format_node(stores, dst.clone(), path), at.to_usize()?))
+        };
+        let label = |l: &HAST::Label| Some(stores.label_store().resolve(l).to_string());
+        let mut result = vec![];
+        // the update of a moved node directly follows the move, with the path in the destination tree
+        let mut moved: Option<&P> = None;
+        for a in actions.iter() {
+            let previous = moved.take();
+            match &a.action {
+                Act::Delete {} => result.push(Action::new("delete-node", node(&src, &a.path.ori))),
+                Act::Insert { .. } => {
+                    let (parent, at) = parent_at(&a.path.ori).unzip();
+                    result.push(Action {
+                        parent,
+                        at,
+                        ..Action::new("insert-node", node(&dst, &a.path.ori))
+                    })
+                }
+                Act::Update { new } => {
+                    let tree = match previous {
+                        Some(from) => node(&src, from),
+                        None => node(&src, &a.path.ori),
+                    };
+                    result.push(Action {
+                        label: label(new),
+                        ..Action::new("update-node", tree)
+                    })
+                }
+                Act::Move { from } => {
+                    let (parent, at) = parent_at(&a.path.ori).unzip();
+                    result.push(Action {
+                        parent,
+                        at,
+                        ..Action::new("move-tree", node(&src, &from.ori))
+                    });
+                    moved = Some(&from.ori);
+                }
+                Act::MovUpd { from, new } => {
+                    let (parent, at) = parent_at(&a.path.ori).unzip();
+                    let tree = node(&src, &from.ori);
+                    result.push(Action {
+                        parent,
+                        at,
+                        ..Action::new("move-tree", tree.clone())
+                    });
+                    result.push(Action {
+                        label: label(new),
+                        ..Action::new("update-node", tree)
+                    });
+                }
+            }
+        }
+        Self {
+            matches: vec![],
+            actions: result,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+        writeln!(out, "<diff>").unwrap();
+        writeln!(out, "  <matches>").unwrap();
+        for m in &self.matches {
+            writeln!(
+                out,
+                r#"    <match src="{}" dest="{}"/>"#,
+                escape(&m.src),
+                escape(&m.dest)
+            )
+            .unwrap();
+        }
+        writeln!(out, "  </matches>").unwrap();
+        writeln!(out, "  <actions>").unwrap();
+        for a in &self.actions {
+            write!(
+                out,
+                r#"    <action type="{}" tree="{}""#,
+                a.action,
+                escape(&a.tree)
+            )
+            .unwrap();
+            if let Some(parent) = &a.parent {
+                write!(out, r#" parent="{}""#, escape(parent)).unwrap();
+            }
+            if let Some(at) = a.at {
+                write!(out, r#" at="{}""#, at).unwrap();
+            }
+            if let Some(label) = &a.label {
+                write!(out, r#" label="{}""#, escape(label)).unwrap();
+            }
+            writeln!(out, "/>").unwrap();
+        }
+        writeln!(out, "  </actions>").unwrap();
+        writeln!(out, "</diff>").unwrap();
+        out
+    }
+}
+
+impl Action {
+    fn new(action: &'static str, tree: String) -> Self {
+        Self {
+            action,
+            tree,
+            parent: None,
+            at: None,
+            label: None,
+        }
+    }
+}
+
+/// `type: label [start,end]`, or `type [start,end]` without label
+fn format_node<HAST>(
+    stores: HAST,
+    root: HAST::IdN,
+    path: impl IntoIterator<Item = HAST::Idx>,
+) -> String
+where
+    HAST: HyperAST + Copy,
+    HAST::IdN: Clone + NodeId<IdN = HAST::IdN>,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithSerialization,
+{
+    let (pos, id) = compute_position(root, &mut path.into_iter(), stores);
+    let range = pos.range();
+    let t = stores.resolve_type(&id);
+    let n = stores.resolve(&id);
+    let label = n
+        .try_get_label()
+        .map(|l| stores.label_store().resolve(l))
+        .filter(|l| !l.is_empty());
+    match label {
+        Some(label) => format!("{}: {} [{},{}]", t, label, range.start, range.end),
+        None => format!("{} [{},{}]", t, range.start, range.end),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
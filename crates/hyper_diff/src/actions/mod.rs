@@ -1,6 +1,7 @@
 #[allow(unused)] // still very experimental
 pub mod action_tree;
 pub mod action_vec;
+pub mod gumtree_format;
 pub mod script_generator;
 pub mod script_generator2;
 
@@ -579,7 +579,9 @@ where
                     //     CompressedTreePath::from(vec![k])
                     // };
 
-                    let act = if w_l != x_l {
+                    // the rename is pushed after the move,
+                    // so that its mid path designates the moved node
+                    let rename = if w_l != x_l {
                         // and also rename
                         // Act::MovUpd {
                         //     from,
@@ -599,12 +601,11 @@ where
                         // dbg!(&action);
                         self.mid_arena[w.to_usize().unwrap()].compressed =
                             self.dst_arena.original(&x);
-                        self.mid_arena[w.to_usize().unwrap()].action = Some(self.actions.len());
-                        self.actions.push(action);
-                        Act::Move { from }
+                        Some(action)
                     } else {
-                        Act::Move { from }
+                        None
                     };
+                    let act = Act::Move { from };
                     {
                         // TODO do not mutate existing node
                         if let Some(z) = z {
@@ -631,6 +632,9 @@ where
                     let path = ApplicablePath { ori, mid };
                     let action = SimpleAction { path, action: act };
                     self.actions.push(action);
+                    if let Some(action) = rename {
+                        self.actions.push(action);
+                    }
                 } else if w_l != x_l {
                     // rename
                     let path = ApplicablePath {
@@ -748,6 +752,11 @@ where
                     if self.dirty[w.to_usize().unwrap()] {
                         // non uniform del.
                         // dbg!(waiting.len());
+                        // deletions waiting in ancestors were removed from the mid tree before,
+                        // thus they go first for the mid paths to stay valid
+                        for ele in parent.iter_mut() {
+                            self.actions.extend(ele.w.drain(..));
+                        }
                         self.actions.extend(waiting);
                         log::trace!("{:?}", action);
                         self.actions.push(action);
@@ -768,6 +777,10 @@ where
                 }
             } else {
                 if SUBTREE_DEL {
+                    // same as for non uniform deletions
+                    for ele in parent.iter_mut() {
+                        self.actions.extend(ele.w.drain(..));
+                    }
                     self.actions.extend(waiting);
                 }
                 // not modified
@@ -823,18 +836,9 @@ where
             for b in &s2 {
                 if self.ori_mappings.unwrap().has(&a, &b) && !lcs.contains(&(*a, *b)) {
                     let k = self.find_pos(&b, x);
-                    let path = ApplicablePath {
-                        ori: self.orig_src(*w).extend(&[k]),
-                        mid: self.path(*w),
-                    };
-                    let action = SimpleAction {
-                        path,
-                        action: Act::Move {
-                            from: ApplicablePath {
-                                ori: self.orig_src(*a),
-                                mid: self.path(*a),
-                            },
-                        },
+                    let from = ApplicablePath {
+                        ori: self.orig_src(*a),
+                        mid: self.path(*a),
                     };
                     // let action = SimpleAction::Move {
                     //     sub: self.ori_to_copy(*a),
@@ -861,10 +865,15 @@ where
                     };
                     self.mid_arena[a.to_usize().unwrap()].parent = cast(z).unwrap();
                     self.mid_arena[a.to_usize().unwrap()].action = Some(self.actions.len());
-                    assert!({
-                        self.path(*a);
-                        true
-                    });
+                    // the mid path is taken after the move, i.e. where a actually is now
+                    let path = ApplicablePath {
+                        ori: self.path_dst(&self.dst_arena.root(), b),
+                        mid: self.path(*a),
+                    };
+                    let action = SimpleAction {
+                        path,
+                        action: Act::Move { from },
+                    };
                     // self.apply_move(&action, &Some(*w), &self.ori_to_copy(*a), b);
                     self.actions.push(action);
                     self.src_in_order.push(*a);
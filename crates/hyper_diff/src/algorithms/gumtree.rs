@@ -11,6 +11,7 @@ use crate::{
             greedy_subtree_matcher::GreedySubtreeMatcher,
         },
         mapping_store::{DefaultMultiMappingStore, MappingStore, VecStore},
+        optimal::{OptimalMatcher, Zs},
     },
     tree::tree_path::CompressedTreePath,
 };
@@ -29,6 +30,27 @@ pub fn diff<HAST: HyperAST + Copy>(
     Mapper<HAST, CDS<HAST>, CDS<HAST>, VecStore<u32>>,
     PreparedMappingDurations<2>,
 >
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::IdN: NodeId<IdN = HAST::IdN>,
+    HAST::Idx: hyperast::PrimInt,
+    HAST::Label: Debug + Clone + Copy + Eq,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: types::WithHashs + types::WithStats,
+{
+    diff_with::<Zs, HAST>(hyperast, src, dst)
+}
+
+/// Same as [`diff`], using `LC` as the last chance matcher of the bottom-up phase,
+/// eg. `diff_with::<Apted, _>(hyperast, src, dst)`
+pub fn diff_with<LC: OptimalMatcher, HAST: HyperAST + Copy>(
+    hyperast: HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+) -> DiffResult<
+    SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>,
+    Mapper<HAST, CDS<HAST>, CDS<HAST>, VecStore<u32>>,
+    PreparedMappingDurations<2>,
+>
 where
     HAST::IdN: Clone + Debug + Eq,
     HAST::IdN: NodeId<IdN = HAST::IdN>,
@@ -55,7 +77,7 @@ where
     let bottomup_prepare_t = 0.; // nothing to prepare
 
     let now = Instant::now();
-    let mapper = GreedyBottomUpMatcher::<_, _, _, _>::match_it(mapper);
+    let mapper = GreedyBottomUpMatcher::<_, _, _, _>::match_it_with::<LC>(mapper);
     let bottomup_matcher_t = now.elapsed().as_secs_f64();
     let bottomup_mappings_s = mapper.mappings().len();
     tr!(bottomup_matcher_t, bottomup_mappings_s);
@@ -0,0 +1,108 @@
+use super::MappingDurations;
+use super::tr;
+use super::{DiffResult, PreparedMappingDurations};
+use crate::{
+    actions::script_generator2::{ScriptGenerator, SimpleAction},
+    decompressed_tree_store::{CompletePostOrder, bfs_wrapper::SimpleBfsMapper},
+    matchers::{
+        Decompressible, Mapper,
+        heuristic::gt::{
+            greedy_subtree_matcher::GreedySubtreeMatcher,
+            hybrid_bottom_up_matcher::HybridBottomUpMatcher,
+        },
+        mapping_store::{DefaultMultiMappingStore, MappingStore, VecStore},
+        optimal::{OptimalMatcher, Zs},
+    },
+    tree::tree_path::CompressedTreePath,
+};
+use hyperast::types::{self, HyperAST, HyperASTShared, NodeId};
+use std::{fmt::Debug, time::Instant};
+
+#[allow(type_alias_bounds)]
+type CDS<HAST: HyperASTShared> = Decompressible<HAST, CompletePostOrder<HAST::IdN, u32>>;
+
+/// GumTree in its "hybrid" configuration (`gumtree-hybrid` in the reference implementation),
+/// ie. a greedy top-down phase followed by the hybrid bottom-up phase
+pub fn diff<HAST: HyperAST + Copy>(
+    hyperast: HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+) -> DiffResult<
+    SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>,
+    Mapper<HAST, CDS<HAST>, CDS<HAST>, VecStore<u32>>,
+    PreparedMappingDurations<2>,
+>
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::IdN: NodeId<IdN = HAST::IdN>,
+    HAST::Idx: hyperast::PrimInt,
+    HAST::Label: Debug + Clone + Copy + Eq,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: types::WithHashs + types::WithStats,
+{
+    diff_with::<Zs, HAST>(hyperast, src, dst)
+}
+
+/// Same as [`diff`], using `LC` as the optimal matcher of the bottom-up phase,
+/// eg. `diff_with::<Apted, _>(hyperast, src, dst)`
+pub fn diff_with<LC: OptimalMatcher, HAST: HyperAST + Copy>(
+    hyperast: HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+) -> DiffResult<
+    SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>,
+    Mapper<HAST, CDS<HAST>, CDS<HAST>, VecStore<u32>>,
+    PreparedMappingDurations<2>,
+>
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::IdN: NodeId<IdN = HAST::IdN>,
+    HAST::Idx: hyperast::PrimInt,
+    HAST::Label: Debug + Clone + Copy + Eq,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: types::WithHashs + types::WithStats,
+{
+    let now = Instant::now();
+    let mapper: Mapper<_, CDS<HAST>, CDS<HAST>, VecStore<_>> =
+        hyperast.decompress_pair(src, dst).into();
+    let subtree_prepare_t = now.elapsed().as_secs_f64();
+    tr!(subtree_prepare_t);
+
+    let now = Instant::now();
+    let mapper =
+        GreedySubtreeMatcher::<_, _, _, _>::match_it::<DefaultMultiMappingStore<_>>(mapper);
+    let subtree_matcher_t = now.elapsed().as_secs_f64();
+    let subtree_mappings_s = mapper.mappings().len();
+    tr!(subtree_matcher_t, subtree_mappings_s);
+
+    let bottomup_prepare_t = 0.; // nothing to prepare
+
+    let now = Instant::now();
+    let mapper = HybridBottomUpMatcher::<_, _, _, _>::match_it_with::<LC>(mapper);
+    let bottomup_matcher_t = now.elapsed().as_secs_f64();
+    let bottomup_mappings_s = mapper.mappings().len();
+    tr!(bottomup_matcher_t, bottomup_mappings_s);
+    let mapping_durations = PreparedMappingDurations {
+        mappings: MappingDurations([subtree_matcher_t, bottomup_matcher_t]),
+        preparation: [subtree_prepare_t, bottomup_prepare_t],
+    };
+
+    let now = Instant::now();
+    let mapper = mapper.map(
+        |x| x,
+        // the dst side has to be traversed in bfs for chawathe
+        |dst_arena| SimpleBfsMapper::with_store(hyperast, dst_arena),
+    );
+    let prepare_gen_t = now.elapsed().as_secs_f64();
+    tr!(prepare_gen_t);
+    let now = Instant::now();
+    let actions = ScriptGenerator::compute_actions(hyperast, &mapper.mapping).ok();
+    let gen_t = now.elapsed().as_secs_f64();
+    tr!(gen_t);
+    let mapper = mapper.map(|x| x, |dst_arena| dst_arena.back);
+    DiffResult {
+        mapping_durations,
+        mapper,
+        actions,
+        prepare_gen_t,
+        gen_t,
+    }
+}
@@ -9,8 +9,10 @@ pub mod change_distiller_lazy;
 pub mod change_distiller_lazy_2;
 pub mod change_distiller_optimized;
 pub mod gumtree;
+pub mod gumtree_hybrid;
 pub mod gumtree_lazy;
 pub mod gumtree_partial_lazy;
+pub mod gumtree_simple;
 
 #[derive(Debug, Clone)]
 pub struct MappingDurations<const N: usize>(pub [f64; N]);
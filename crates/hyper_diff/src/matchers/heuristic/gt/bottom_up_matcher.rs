@@ -247,24 +247,13 @@ where
     Dsrc: DecompressedTreeStore<HAST, M::Src> + DecompressedWithParent<HAST, M::Src>,
     Ddst: DecompressedTreeStore<HAST, M::Dst> + DecompressedWithParent<HAST, M::Dst>,
 {
+    /// The simple recovery of GumTree, matches unmapped children of `src` and `dst`
+    /// that are isomorphic, then the ones that are structurally isomorphic,
+    /// and finally the ones that are the only ones of their type on both sides.
     pub fn last_chance_match_histogram(&mut self, src: &M::Src, dst: &M::Dst) {
         self.lcs_equal_matching(src, dst);
         self.lcs_structure_matching(src, dst);
-        if !src.is_zero() && !dst.is_zero() {
-            self.histogram_matching(src, dst); //self.histogramMaking(src, dst),
-        } else if !(src.is_zero() || dst.is_zero()) {
-            if self.hyperast.resolve_type(
-                &self
-                    .src_arena
-                    .original(&self.src_arena.parent(src).unwrap()),
-            ) == self.hyperast.resolve_type(
-                &self
-                    .dst_arena
-                    .original(&self.dst_arena.parent(dst).unwrap()),
-            ) {
-                self.histogram_matching(src, dst) //self.histogramMaking(src, dst),
-            }
-        }
+        self.histogram_matching(src, dst);
     }
 
     pub(super) fn are_srcs_unmapped(&self, src: &M::Src) -> bool {
@@ -289,11 +278,19 @@ where
         dst: &M::Dst,
         cmp: F,
     ) {
-        let src_children = &self.src_arena.children(src);
-        let dst_children = &self.dst_arena.children(dst);
+        let src_children = &self
+            .src_arena
+            .children(src)
+            .into_iter()
+            .filter(|x| !self.mappings.is_src(x))
+            .collect::<Vec<_>>();
+        let dst_children = &self
+            .dst_arena
+            .children(dst)
+            .into_iter()
+            .filter(|x| !self.mappings.is_dst(x))
+            .collect::<Vec<_>>();
 
-        // self.compressed_node_store
-        // .get_node_at_id(&self.src_arena.original(src));
         let lcs =
             longest_common_subsequence::<_, _, usize, _>(src_children, dst_children, |src, dst| {
                 cmp(self, src, dst)
@@ -360,6 +357,5 @@ where
                 }
             }
         }
-        todo!()
     }
 }
@@ -4,8 +4,9 @@ use crate::decompressed_tree_store::{
     PostOrderIterable, PostOrderKeyRoots,
 };
 use crate::matchers::mapping_store::MonoMappingStore;
+use crate::matchers::optimal::{OptimalMatcher, Zs};
+use crate::matchers::similarity_metrics;
 use crate::matchers::{Decompressible, Mapper, Mapping};
-use crate::matchers::{optimal::zs::ZsMatcher, similarity_metrics};
 use hyperast::PrimInt;
 use hyperast::types::{DecompressedFrom, HyperAST, NodeId, NodeStore, Tree, WithHashs};
 use num_traits::{cast, one};
@@ -27,7 +28,7 @@ pub struct GreedyBottomUpMatcher<
     pub(crate) internal: Mapper<HAST, Dsrc, Ddst, M>,
 }
 
-/// Enable using a slice instead of recreating a ZsTree for each call to ZsMatch, see last_chance_match
+/// Enable using a slice instead of recreating a ZsTree for each call to the last chance matcher, see last_chance_match
 const SLICE: bool = true;
 
 impl<
@@ -100,6 +101,13 @@ where
 
     pub fn match_it(
         mapping: crate::matchers::Mapper<HAST, Dsrc, Ddst, M>,
+    ) -> crate::matchers::Mapper<HAST, Dsrc, Ddst, M> {
+        Self::match_it_with::<Zs>(mapping)
+    }
+
+    /// Same as [`Self::match_it`], using `LC` as the last chance matcher
+    pub fn match_it_with<LC: OptimalMatcher>(
+        mapping: crate::matchers::Mapper<HAST, Dsrc, Ddst, M>,
     ) -> crate::matchers::Mapper<HAST, Dsrc, Ddst, M> {
         let mut matcher = mapping;
         matcher.mapping.mappings.topit(
@@ -107,7 +115,7 @@ where
             matcher.mapping.dst_arena.len(),
         );
         let mut matcher = Self { internal: matcher };
-        Self::execute(&mut matcher.internal);
+        Self::execute_with::<LC>(&mut matcher.internal);
         matcher.internal
     }
 
@@ -127,6 +135,12 @@ where
     }
 
     pub fn execute<'b>(mapper: &mut Mapper<HAST, Dsrc, Ddst, M>) {
+        Self::execute_with::<Zs>(mapper)
+    }
+
+    /// Same as [`Self::execute`], using `LC` as the last chance matcher,
+    /// eg. [`crate::matchers::optimal::Apted`] instead of Zhang and Shasha
+    pub fn execute_with<LC: OptimalMatcher>(mapper: &mut Mapper<HAST, Dsrc, Ddst, M>) {
         assert_eq!(
             // TODO move it inside the arena ...
             mapper.src_arena.root(),
@@ -158,7 +172,7 @@ where
                 }
 
                 if let Some(best) = best {
-                    Self::last_chance_match_zs(mapper, a, best);
+                    Self::last_chance_match::<LC>(mapper, a, best);
                     mapper.mappings.link(a, best);
                 }
             }
@@ -168,7 +182,7 @@ where
             mapper.mapping.src_arena.root(),
             mapper.mapping.dst_arena.root(),
         );
-        Self::last_chance_match_zs(mapper, mapper.src_arena.root(), mapper.dst_arena.root());
+        Self::last_chance_match::<LC>(mapper, mapper.src_arena.root(), mapper.dst_arena.root());
     }
 
     fn src_has_children(mapper: &mut Mapper<HAST, Dsrc, Ddst, M>, src: M::Src) -> bool {
@@ -188,7 +202,7 @@ where
         r
     }
 
-    pub(crate) fn last_chance_match_zs(
+    pub(crate) fn last_chance_match<LC: OptimalMatcher>(
         mapper: &mut Mapper<HAST, Dsrc, Ddst, M>,
         src: M::Src,
         dst: M::Dst,
@@ -206,7 +220,7 @@ where
             let src_arena = mapper.mapping.src_arena.slice_po(&src);
             src_offset = src - src_arena.root();
             let dst_arena = mapper.mapping.dst_arena.slice_po(&dst);
-            ZsMatcher::match_with(mapper.hyperast, src_arena, dst_arena)
+            LC::match_with(mapper.hyperast, src_arena, dst_arena)
         } else {
             let o_src = mapper.mapping.src_arena.original(&src);
             let o_dst = mapper.mapping.dst_arena.original(&dst);
@@ -251,7 +265,7 @@ where
                 assert!(dst_arena.kr[dst_arena.kr.len() - 1]);
                 dbg!(last == dst_arena_z.root());
             }
-            ZsMatcher::match_with(mapper.hyperast, src_arena, dst_arena)
+            LC::match_with(mapper.hyperast, src_arena, dst_arena)
         };
         let dst_offset = mapper.dst_arena.first_descendant(&dst);
         assert_eq!(mapper.src_arena.first_descendant(&src), src_offset);
@@ -0,0 +1,102 @@
+use super::greedy_bottom_up_matcher::GreedyBottomUpMatcher;
+use super::simple_bottom_up_matcher::SimpleBottomUpMatcher;
+use crate::decompressed_tree_store::{
+    ContiguousDescendants, DecompressedTreeStore, DecompressedWithParent, POBorrowSlice, PostOrder,
+    PostOrderIterable, Shallow,
+};
+use crate::matchers::Mapper;
+use crate::matchers::mapping_store::MonoMappingStore;
+use crate::matchers::optimal::{OptimalMatcher, Zs};
+use hyperast::PrimInt;
+use hyperast::types::{DecompressedFrom, HyperAST, NodeId, WithHashs};
+use std::fmt::Debug;
+
+/// The bottom-up matcher of the "hybrid" configuration of GumTree.
+///
+/// Selects candidates like the [`SimpleBottomUpMatcher`],
+/// but the last chance match uses an optimal matcher when one of the subtrees
+/// is smaller than `SIZE_THRESHOLD`, and the simple recovery otherwise.
+pub struct HybridBottomUpMatcher<
+    Dsrc,
+    Ddst,
+    HAST,
+    M: MonoMappingStore,
+    const SIZE_THRESHOLD: usize = 20,
+> {
+    pub(crate) internal: Mapper<HAST, Dsrc, Ddst, M>,
+}
+
+impl<
+    Dsrc: DecompressedTreeStore<HAST, M::Src>
+        + DecompressedWithParent<HAST, M::Src>
+        + PostOrder<HAST, M::Src>
+        + PostOrderIterable<HAST, M::Src>
+        + DecompressedFrom<HAST, Out = Dsrc>
+        + ContiguousDescendants<HAST, M::Src>
+        + POBorrowSlice<HAST, M::Src>,
+    Ddst: DecompressedTreeStore<HAST, M::Dst>
+        + DecompressedWithParent<HAST, M::Dst>
+        + PostOrder<HAST, M::Dst>
+        + PostOrderIterable<HAST, M::Dst>
+        + DecompressedFrom<HAST, Out = Ddst>
+        + ContiguousDescendants<HAST, M::Dst>
+        + POBorrowSlice<HAST, M::Dst>,
+    HAST: HyperAST + Copy,
+    M: MonoMappingStore + Default,
+    const SIZE_THRESHOLD: usize,
+> HybridBottomUpMatcher<Dsrc, Ddst, HAST, M, SIZE_THRESHOLD>
+where
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithHashs,
+    M::Src: PrimInt + Shallow<M::Src>,
+    M::Dst: PrimInt + Shallow<M::Dst>,
+    HAST::Label: Eq,
+    HAST::IdN: Debug,
+    HAST::IdN: NodeId<IdN = HAST::IdN>,
+{
+    pub fn match_it(
+        mapping: crate::matchers::Mapper<HAST, Dsrc, Ddst, M>,
+    ) -> crate::matchers::Mapper<HAST, Dsrc, Ddst, M> {
+        Self::match_it_with::<Zs>(mapping)
+    }
+
+    /// Same as [`Self::match_it`], using `LC` as the optimal matcher
+    pub fn match_it_with<LC: OptimalMatcher>(
+        mapping: crate::matchers::Mapper<HAST, Dsrc, Ddst, M>,
+    ) -> crate::matchers::Mapper<HAST, Dsrc, Ddst, M> {
+        let mut matcher = mapping;
+        matcher.mapping.mappings.topit(
+            matcher.mapping.src_arena.len(),
+            matcher.mapping.dst_arena.len(),
+        );
+        Self::execute_with::<LC>(&mut matcher);
+        matcher
+    }
+
+    pub fn execute(mapper: &mut Mapper<HAST, Dsrc, Ddst, M>) {
+        Self::execute_with::<Zs>(mapper)
+    }
+
+    /// Same as [`Self::execute`], using `LC` as the optimal matcher
+    pub fn execute_with<LC: OptimalMatcher>(mapper: &mut Mapper<HAST, Dsrc, Ddst, M>) {
+        SimpleBottomUpMatcher::<Dsrc, Ddst, HAST, M>::execute_with(mapper, |mapper, src, dst| {
+            Self::last_chance_match::<LC>(mapper, src, dst)
+        })
+    }
+
+    fn last_chance_match<LC: OptimalMatcher>(
+        mapper: &mut Mapper<HAST, Dsrc, Ddst, M>,
+        src: M::Src,
+        dst: M::Dst,
+    ) {
+        let src_s = mapper.src_arena.descendants_count(&src);
+        let dst_s = mapper.dst_arena.descendants_count(&dst);
+        if src_s < SIZE_THRESHOLD || dst_s < SIZE_THRESHOLD {
+            // the greedy matcher applies the same size condition
+            GreedyBottomUpMatcher::<Dsrc, Ddst, HAST, M, SIZE_THRESHOLD>::last_chance_match::<LC>(
+                mapper, src, dst,
+            )
+        } else {
+            mapper.last_chance_match_histogram(&src, &dst)
+        }
+    }
+}
@@ -3,7 +3,7 @@ use hyperast::types::{NodeId, NodeStore, WithChildren, Childrn};
 pub mod bottom_up_matcher;
 pub mod greedy_bottom_up_matcher;
 pub mod greedy_subtree_matcher;
-#[allow(unused)] // TODO finish simple bottom up matcher
+pub mod hybrid_bottom_up_matcher;
 pub mod simple_bottom_up_matcher;
 
 // lazy versions, that do not decompress directly subtrees
@@ -0,0 +1,976 @@
+//! APTED, the tree edit distance algorithm of Pawlik and Augsten,
+//! which is also the one of the GumTree Java reference.
+//!
+//! Computes the same optimal edit distance and mappings as [`super::zs`],
+//! but instead of always decomposing trees along their left paths,
+//! it first computes, for each pair of subtrees, the left, right or heavy path of either subtree
+//! minimizing the number of subproblems.
+//! Pairs decomposed along a left or right path are computed à la Zhang and Shasha,
+//! the ones decomposed along a heavy path with the general single path function.
+//!
+//! The cost model is the same as the one of [`super::zs`].
+
+use crate::decompressed_tree_store::PostOrder;
+use crate::matchers::mapping_store::MonoMappingStore;
+use hyperast::PrimInt;
+use hyperast::types::{DecompressedFrom, HyperAST};
+use num_traits::{ToPrimitive, cast};
+
+use super::zs::update_cost;
+
+pub struct AptedMatcher<M, SD, DD = SD> {
+    pub mappings: M,
+    pub src_arena: SD,
+    pub dst_arena: DD,
+}
+
+impl<SD, DD, M: MonoMappingStore + Default> AptedMatcher<M, SD, DD> {
+    pub fn matchh<HAST>(stores: HAST, src: HAST::IdN, dst: HAST::IdN) -> Self
+    where
+        M::Src: PrimInt,
+        M::Dst: PrimInt,
+        SD: PostOrder<HAST, M::Src> + DecompressedFrom<HAST, Out = SD>,
+        DD: PostOrder<HAST, M::Dst> + DecompressedFrom<HAST, Out = DD>,
+        HAST: HyperAST + Copy,
+        HAST::Label: Eq,
+    {
+        let src_arena = SD::decompress(stores, &src);
+        let dst_arena = DD::decompress(stores, &dst);
+        let mappings = Self::compute_mappings(stores, &src_arena, &dst_arena);
+        Self {
+            src_arena,
+            dst_arena,
+            mappings,
+        }
+    }
+
+    pub fn match_with<HAST>(stores: HAST, src_arena: SD, dst_arena: DD) -> M
+    where
+        M::Src: PrimInt,
+        M::Dst: PrimInt,
+        SD: PostOrder<HAST, M::Src>,
+        DD: PostOrder<HAST, M::Dst>,
+        HAST: HyperAST + Copy,
+        HAST::Label: Eq,
+    {
+        Self::compute_mappings(stores, &src_arena, &dst_arena)
+    }
+
+    fn compute_mappings<HAST>(stores: HAST, src_arena: &SD, dst_arena: &DD) -> M
+    where
+        M::Src: PrimInt,
+        M::Dst: PrimInt,
+        SD: PostOrder<HAST, M::Src>,
+        DD: PostOrder<HAST, M::Dst>,
+        HAST: HyperAST + Copy,
+        HAST::Label: Eq,
+    {
+        let mut mappings = M::default();
+        mappings.topit(src_arena.len(), dst_arena.len());
+        let mut apted = Apted::new(stores, src_arena, dst_arena);
+        apted.compute_dist();
+        for (x, y) in apted.compute_mappings() {
+            let src: M::Src = cast(x).unwrap();
+            let dst: M::Dst = cast(y).unwrap();
+            let t_src = stores.resolve_type(&src_arena.tree(&src));
+            let t_dst = stores.resolve_type(&dst_arena.tree(&dst));
+            if t_src == t_dst {
+                mappings.link(src, dst);
+            }
+        }
+        mappings
+    }
+}
+
+const DEL: f64 = 1.0;
+const INS: f64 = 1.0;
+
+/// Post-order layout of a tree,
+/// either the one of the decompressed tree (left to right) or its mirror (right to left).
+struct Layout {
+    /// leftmost leaf descendant of each node
+    lld: Vec<usize>,
+    /// children of each node, ordered according to the layout
+    children: Vec<Vec<usize>>,
+    /// true if the node is not the first child of its parent, ie. it roots a path of the layout
+    keyroot: Vec<bool>,
+    /// pre-order id of each node
+    pre: Vec<usize>,
+    /// nodes in pre-order
+    by_pre: Vec<usize>,
+    /// from layout ids to the post-order ids of the decompressed tree
+    ori: Vec<usize>,
+    /// from post-order ids of the decompressed tree to layout ids
+    lid: Vec<usize>,
+}
+
+impl Layout {
+    fn new(lld: Vec<usize>) -> Self {
+        let n = lld.len();
+        let mut children = vec![vec![]; n];
+        for x in 0..n {
+            let mut c = x;
+            while c > lld[x] {
+                let child = c - 1;
+                children[x].push(child);
+                c = lld[child];
+            }
+            children[x].reverse();
+        }
+        Self::with_children(lld, children, (0..n).collect(), (0..n).collect())
+    }
+
+    fn with_children(
+        lld: Vec<usize>,
+        children: Vec<Vec<usize>>,
+        ori: Vec<usize>,
+        lid: Vec<usize>,
+    ) -> Self {
+        let n = lld.len();
+        let mut keyroot = vec![true; n];
+        for cs in &children {
+            if let Some(&c) = cs.first() {
+                keyroot[c] = false;
+            }
+        }
+        let mut by_pre = Vec::with_capacity(n);
+        let mut stack = vec![n - 1];
+        while let Some(x) = stack.pop() {
+            by_pre.push(x);
+            stack.extend(children[x].iter().rev());
+        }
+        let mut pre = vec![0; n];
+        for (k, &x) in by_pre.iter().enumerate() {
+            pre[x] = k;
+        }
+        Self {
+            lld,
+            children,
+            keyroot,
+            pre,
+            by_pre,
+            ori,
+            lid,
+        }
+    }
+
+    /// the same tree with the order of siblings reversed
+    fn mirror(&self) -> Self {
+        let n = self.lld.len();
+        let root = n - 1;
+        // ids of self in the post-order of the mirrored tree
+        let mut order = Vec::with_capacity(n);
+        let mut stack = vec![(root, self.children[root].len())];
+        while let Some(top) = stack.last_mut() {
+            let x = top.0;
+            if top.1 == 0 {
+                order.push(x);
+                stack.pop();
+            } else {
+                top.1 -= 1;
+                let c = self.children[x][top.1];
+                stack.push((c, self.children[c].len()));
+            }
+        }
+        let mut mirrored = vec![0; n];
+        for (k, &x) in order.iter().enumerate() {
+            mirrored[x] = k;
+        }
+        let mut lld = vec![0; n];
+        let mut children = vec![vec![]; n];
+        for (k, &x) in order.iter().enumerate() {
+            children[k] = self.children[x]
+                .iter()
+                .rev()
+                .map(|c| mirrored[*c])
+                .collect();
+            lld[k] = children[k].first().map_or(k, |c| lld[*c]);
+        }
+        let ori: Vec<usize> = order.iter().map(|x| self.ori[*x]).collect();
+        let mut lid = vec![0; n];
+        for (k, &o) in ori.iter().enumerate() {
+            lid[o] = k;
+        }
+        Self::with_children(lld, children, ori, lid)
+    }
+
+    fn size(&self, x: usize) -> usize {
+        x - self.lld[x] + 1
+    }
+
+    /// the child with the largest subtree
+    fn heavy(&self, x: usize) -> Option<usize> {
+        self.children[x]
+            .iter()
+            .copied()
+            .max_by_key(|c| self.size(*c))
+    }
+
+    /// the child following `x` on its heavy path or on the path of first children of the layout
+    fn next_on_path(&self, x: usize, heavy: bool) -> Option<usize> {
+        if heavy {
+            self.heavy(x)
+        } else {
+            self.children[x].first().copied()
+        }
+    }
+
+    /// roots of the subtrees hanging off the path starting at `x`
+    fn hanging(&self, x: usize, heavy: bool) -> Vec<usize> {
+        let mut r = vec![];
+        let mut x = x;
+        while let Some(c) = self.next_on_path(x, heavy) {
+            r.extend(self.children[x].iter().filter(|y| **y != c));
+            x = c;
+        }
+        r
+    }
+}
+
+/// Which path is used to decompose a pair of subtrees.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Path {
+    LeftSrc,
+    RightSrc,
+    HeavySrc,
+    LeftDst,
+    RightDst,
+    HeavyDst,
+}
+
+impl Path {
+    /// the layout in which the path is the one of first children,
+    /// heavy paths are followed in the left to right layout
+    fn layout(self) -> usize {
+        match self {
+            Path::LeftSrc | Path::LeftDst | Path::HeavySrc | Path::HeavyDst => 0,
+            Path::RightSrc | Path::RightDst => 1,
+        }
+    }
+
+    fn heavy(self) -> bool {
+        matches!(self, Path::HeavySrc | Path::HeavyDst)
+    }
+}
+
+/// Uses three n1 x n2 matrices, as subtree distances are needed for all pairs of subtrees,
+/// the rename costs are cached as they are read multiple times, and the strategy only takes a byte per pair.
+struct Apted {
+    /// layouts of the src tree, left then right
+    src: [Layout; 2],
+    /// layouts of the dst tree, left then right
+    dst: [Layout; 2],
+    /// number of dst nodes, ie. the stride of the following matrices
+    n2: usize,
+    /// rename costs
+    ren: Vec<f64>,
+    /// subtree distances
+    delta: Vec<f64>,
+    strategy: Vec<Path>,
+    /// forest distances, reused between single path functions
+    fd: Vec<f64>,
+}
+
+impl Apted {
+    fn new<HAST, SD, DD, IdS, IdD>(stores: HAST, src_arena: &SD, dst_arena: &DD) -> Self
+    where
+        HAST: HyperAST + Copy,
+        HAST::Label: Eq,
+        SD: PostOrder<HAST, IdS>,
+        DD: PostOrder<HAST, IdD>,
+        IdS: PrimInt,
+        IdD: PrimInt,
+    {
+        let n1 = src_arena.len();
+        let n2 = dst_arena.len();
+        let src = Layout::new(
+            (0..n1)
+                .map(|i| src_arena.lld(&cast(i).unwrap()).to_usize().unwrap())
+                .collect(),
+        );
+        let dst = Layout::new(
+            (0..n2)
+                .map(|j| dst_arena.lld(&cast(j).unwrap()).to_usize().unwrap())
+                .collect(),
+        );
+        let src_trees: Vec<_> = (0..n1).map(|i| src_arena.tree(&cast(i).unwrap())).collect();
+        let dst_trees: Vec<_> = (0..n2).map(|j| dst_arena.tree(&cast(j).unwrap())).collect();
+        let mut ren = Vec::with_capacity(n1 * n2);
+        for x in &src_trees {
+            for y in &dst_trees {
+                ren.push(update_cost(stores, x, y));
+            }
+        }
+        let strategy = Self::compute_strategy(&src, &dst);
+        let src_r = src.mirror();
+        let dst_r = dst.mirror();
+        Self {
+            src: [src, src_r],
+            dst: [dst, dst_r],
+            n2,
+            ren,
+            delta: vec![0.0; n1 * n2],
+            strategy,
+            fd: vec![],
+        }
+    }
+
+    /// For each pair of subtrees, choose the path minimizing the number of subproblems,
+    /// following the cost formula of RTED and APTED.
+    fn compute_strategy(src: &Layout, dst: &Layout) -> Vec<Path> {
+        let n1 = src.lld.len();
+        let n2 = dst.lld.len();
+        // number of relevant subforests of each subtree for the left and right decompositions,
+        // and number of subforests obtained by removing leftmost and rightmost roots
+        let relevant = |t: &Layout| -> (Vec<u64>, Vec<u64>, Vec<u64>) {
+            let n = t.lld.len();
+            let mut left = vec![0u64; n];
+            let mut right = vec![0u64; n];
+            let mut all = vec![0u64; n];
+            // sum of the sizes of the subtrees of each subtree
+            let mut sizes = vec![0u64; n];
+            for x in 0..n {
+                let size = t.size(x) as u64;
+                let cs = &t.children[x];
+                left[x] = size;
+                right[x] = size;
+                if let Some((c, rest)) = cs.split_first() {
+                    left[x] += left[*c] - t.size(*c) as u64;
+                    left[x] += rest.iter().map(|c| left[*c]).sum::<u64>();
+                }
+                if let Some((c, rest)) = cs.split_last() {
+                    right[x] += right[*c] - t.size(*c) as u64;
+                    right[x] += rest.iter().map(|c| right[*c]).sum::<u64>();
+                }
+                sizes[x] = size + cs.iter().map(|c| sizes[*c]).sum::<u64>();
+                all[x] = size * (size + 3) / 2 - sizes[x];
+            }
+            (left, right, all)
+        };
+        let (src_left, src_right, src_all) = relevant(src);
+        let (dst_left, dst_right, dst_all) = relevant(dst);
+        let src_heavy: Vec<_> = (0..n1).map(|v| src.heavy(v)).collect();
+        let dst_heavy: Vec<_> = (0..n2).map(|w| dst.heavy(w)).collect();
+
+        // the costs and sums of src nodes are only read by their parent,
+        // so rows are dropped once read instead of keeping n1 x n2 matrices
+        let mut cost: Vec<Vec<u64>> = vec![vec![]; n1];
+        // sums of the costs of subtrees hanging off each kind of path
+        let mut hang_left_src: Vec<Vec<u64>> = vec![vec![]; n1];
+        let mut hang_right_src: Vec<Vec<u64>> = vec![vec![]; n1];
+        let mut hang_heavy_src: Vec<Vec<u64>> = vec![vec![]; n1];
+        // the ones of dst nodes are read in the same row
+        let mut hang_left_dst = vec![0u64; n2];
+        let mut hang_right_dst = vec![0u64; n2];
+        let mut hang_heavy_dst = vec![0u64; n2];
+        let mut strategy = vec![Path::LeftSrc; n1 * n2];
+        for v in 0..n1 {
+            let cv = &src.children[v];
+            let mut cost_v = vec![0u64; n2];
+            let mut hang_left_v = vec![0u64; n2];
+            let mut hang_right_v = vec![0u64; n2];
+            let mut hang_heavy_v = vec![0u64; n2];
+            for w in 0..n2 {
+                let cw = &dst.children[w];
+                if let Some((c, rest)) = cv.split_first() {
+                    hang_left_v[w] =
+                        hang_left_src[*c][w] + rest.iter().map(|c| cost[*c][w]).sum::<u64>();
+                }
+                if let Some((c, rest)) = cv.split_last() {
+                    hang_right_v[w] =
+                        hang_right_src[*c][w] + rest.iter().map(|c| cost[*c][w]).sum::<u64>();
+                }
+                if let Some(h) = src_heavy[v] {
+                    hang_heavy_v[w] = hang_heavy_src[h][w]
+                        + cv.iter()
+                            .filter(|c| **c != h)
+                            .map(|c| cost[*c][w])
+                            .sum::<u64>();
+                }
+                hang_left_dst[w] = match cw.split_first() {
+                    Some((d, rest)) => {
+                        hang_left_dst[*d] + rest.iter().map(|d| cost_v[*d]).sum::<u64>()
+                    }
+                    None => 0,
+                };
+                hang_right_dst[w] = match cw.split_last() {
+                    Some((d, rest)) => {
+                        hang_right_dst[*d] + rest.iter().map(|d| cost_v[*d]).sum::<u64>()
+                    }
+                    None => 0,
+                };
+                hang_heavy_dst[w] = match dst_heavy[w] {
+                    Some(h) => {
+                        hang_heavy_dst[h]
+                            + cw.iter()
+                                .filter(|d| **d != h)
+                                .map(|d| cost_v[*d])
+                                .sum::<u64>()
+                    }
+                    None => 0,
+                };
+                let size_v = src.size(v) as u64;
+                let size_w = dst.size(w) as u64;
+                let candidates = [
+                    (Path::LeftSrc, size_v * dst_left[w] + hang_left_v[w]),
+                    (Path::RightSrc, size_v * dst_right[w] + hang_right_v[w]),
+                    (Path::HeavySrc, size_v * dst_all[w] + hang_heavy_v[w]),
+                    (Path::LeftDst, size_w * src_left[v] + hang_left_dst[w]),
+                    (Path::RightDst, size_w * src_right[v] + hang_right_dst[w]),
+                    (Path::HeavyDst, size_w * src_all[v] + hang_heavy_dst[w]),
+                ];
+                let (p, c) = candidates.into_iter().min_by_key(|x| x.1).unwrap();
+                strategy[v * n2 + w] = p;
+                cost_v[w] = c;
+            }
+            for c in cv {
+                cost[*c] = vec![];
+                hang_left_src[*c] = vec![];
+                hang_right_src[*c] = vec![];
+                hang_heavy_src[*c] = vec![];
+            }
+            cost[v] = cost_v;
+            hang_left_src[v] = hang_left_v;
+            hang_right_src[v] = hang_right_v;
+            hang_heavy_src[v] = hang_heavy_v;
+        }
+        strategy
+    }
+
+    fn compute_dist(&mut self) -> f64 {
+        let n1 = self.src[0].lld.len();
+        let n2 = self.n2;
+        self.gted(n1 - 1, n2 - 1);
+        self.delta[(n1 - 1) * n2 + n2 - 1]
+    }
+
+    /// Computes the distances between all subtrees of `v` and all subtrees of `w`.
+    fn gted(&mut self, v: usize, w: usize) {
+        let path = self.strategy[v * self.n2 + w];
+        let d = path.layout();
+        let heavy = path.heavy();
+        match path {
+            Path::LeftSrc | Path::RightSrc | Path::HeavySrc => {
+                let t = &self.src[d];
+                let hanging = t.hanging(t.lid[v], heavy);
+                let hanging: Vec<_> = hanging.into_iter().map(|x| t.ori[x]).collect();
+                for x in hanging {
+                    self.gted(x, w);
+                }
+                if heavy {
+                    self.spf_a(false, v, w);
+                } else {
+                    self.spf(d, false, v, w);
+                }
+            }
+            Path::LeftDst | Path::RightDst | Path::HeavyDst => {
+                let t = &self.dst[d];
+                let hanging = t.hanging(t.lid[w], heavy);
+                let hanging: Vec<_> = hanging.into_iter().map(|x| t.ori[x]).collect();
+                for y in hanging {
+                    self.gted(v, y);
+                }
+                if heavy {
+                    self.spf_a(true, w, v);
+                } else {
+                    self.spf(d, true, w, v);
+                }
+            }
+        }
+    }
+
+    /// Single path function, computes the distances between the subtrees on the path of `v`
+    /// and all the subtrees of `w`.
+    ///
+    /// When `transposed`, `v` is in the dst tree and `w` is in the src tree.
+    fn spf(&mut self, d: usize, transposed: bool, v: usize, w: usize) {
+        let Self {
+            src,
+            dst,
+            n2,
+            ren,
+            delta,
+            fd,
+            ..
+        } = self;
+        let (f, g) = if transposed {
+            (&dst[d], &src[d])
+        } else {
+            (&src[d], &dst[d])
+        };
+        let mut ctx = ForestDist {
+            f,
+            g,
+            n2: *n2,
+            transposed,
+            ren,
+            delta,
+            fd,
+        };
+        let v = f.lid[v];
+        let w = g.lid[w];
+        for j in g.lld[w]..=w {
+            if j == w || g.keyroot[j] {
+                ctx.compute(v, j);
+            }
+        }
+    }
+
+    /// General single path function, computes the distances between the subtrees on the heavy path of `v`
+    /// and all the subtrees of `w`.
+    ///
+    /// When `transposed`, `v` is in the dst tree and `w` is in the src tree.
+    ///
+    /// Goes up the path, computing the distances between each relevant subforest of `v`
+    /// and all the subforests of `w` obtained by removing leftmost and rightmost roots.
+    /// Such a subforest is made of the nodes of `w` that are not before the `p`th in pre-order
+    /// and that are before the `q`th in post-order, so it is identified by `(p, q)`.
+    /// Above each node of the path, the relevant subforest first grows with the nodes on the right of the path,
+    /// in post-order, then with the nodes on its left, in reverse pre-order, and finally with the parent node.
+    fn spf_a(&mut self, transposed: bool, v: usize, w: usize) {
+        let Self {
+            src,
+            dst,
+            n2,
+            ren,
+            delta,
+            ..
+        } = self;
+        let (f, g) = if transposed {
+            (&dst[0], &src[0])
+        } else {
+            (&src[0], &dst[0])
+        };
+        let stride = *n2;
+        // index in the src x dst matrices, ids of the left to right layouts are the post-order ids
+        let at = |x: usize, y: usize| {
+            if transposed {
+                y * stride + x
+            } else {
+                x * stride + y
+            }
+        };
+        let n = g.size(w);
+        // first ids of the subtree of w in post-order and pre-order
+        let (lw, pw) = (g.lld[w], g.pre[w]);
+        let m = n + 1;
+        // number of nodes in each subforest of w
+        let mut count = vec![0usize; m * m];
+        for p in (0..n).rev() {
+            let y = g.by_pre[pw + p];
+            for q in 0..m {
+                count[p * m + q] = count[(p + 1) * m + q] + usize::from(y - lw < q);
+            }
+        }
+        // distances between the current relevant subforest of v and the subforests of w,
+        // starting with the empty subforest below the path
+        let mut row: Vec<f64> = count.iter().map(|c| *c as f64 * INS).collect();
+        let mut tab = vec![];
+        let mut path = vec![v];
+        while let Some(c) = f.heavy(*path.last().unwrap()) {
+            path.push(c);
+        }
+        let mut below = None;
+        for &x in path.iter().rev() {
+            if let Some(c) = below {
+                // nodes on the right of the path, removed as rightmost roots
+                let right = x - 1 - c;
+                let size = f.size(c);
+                for p in 0..m {
+                    tab.clear();
+                    tab.extend_from_slice(&row[p * m..(p + 1) * m]);
+                    tab.resize((right + 1) * m, 0.0);
+                    for b in 1..=right {
+                        let z = c + b;
+                        tab[b * m] = (size + b) as f64 * DEL;
+                        for q in 1..m {
+                            let y = lw + q - 1;
+                            tab[b * m + q] = if g.pre[y] - pw < p {
+                                // y is not in the subforest
+                                tab[b * m + q - 1]
+                            } else {
+                                let del = tab[(b - 1) * m + q] + DEL;
+                                let ins = tab[b * m + q - 1] + INS;
+                                let upd =
+                                    tab[(b - f.size(z)) * m + q - g.size(y)] + delta[at(z, y)];
+                                f64::min(f64::min(del, ins), upd)
+                            };
+                        }
+                    }
+                    row[p * m..(p + 1) * m].copy_from_slice(&tab[right * m..]);
+                }
+                // nodes on the left of the path, removed as leftmost roots
+                let left = f.lld[c] - f.lld[x];
+                let size = size + right;
+                for q in 0..m {
+                    tab.clear();
+                    tab.extend((0..m).map(|p| row[p * m + q]));
+                    tab.resize((left + 1) * m, 0.0);
+                    for a in 1..=left {
+                        let z = f.by_pre[f.pre[x] + 1 + left - a];
+                        tab[a * m + n] = (size + a) as f64 * DEL;
+                        for p in (0..n).rev() {
+                            let y = g.by_pre[pw + p];
+                            tab[a * m + p] = if y - lw >= q {
+                                // y is not in the subforest
+                                tab[a * m + p + 1]
+                            } else {
+                                let del = tab[(a - 1) * m + p] + DEL;
+                                let ins = tab[a * m + p + 1] + INS;
+                                let upd =
+                                    tab[(a - f.size(z)) * m + p + g.size(y)] + delta[at(z, y)];
+                                f64::min(f64::min(del, ins), upd)
+                            };
+                        }
+                    }
+                    for p in 0..m {
+                        row[p * m + q] = tab[left * m + p];
+                    }
+                }
+            }
+            // x itself, the relevant subforest becomes the subtree of x,
+            // rows are updated in increasing order of p as the match reads the subforests of children of x
+            for p in 0..m {
+                row[p * m] = f.size(x) as f64 * DEL;
+                for q in 1..m {
+                    let y = lw + q - 1;
+                    let py = g.pre[y] - pw;
+                    row[p * m + q] = if py < p {
+                        // y is not in the subforest
+                        row[p * m + q - 1]
+                    } else {
+                        let del = row[p * m + q] + DEL;
+                        let ins = row[p * m + q - 1] + INS;
+                        let upd = row[(py + 1) * m + q - 1]
+                            + ren[at(x, y)]
+                            + count[p * m + q - g.size(y)] as f64 * INS;
+                        let d = f64::min(f64::min(del, ins), upd);
+                        if py == p {
+                            // the subforest is the subtree of y
+                            delta[at(x, y)] = d;
+                        }
+                        d
+                    };
+                }
+            }
+            below = Some(x);
+        }
+    }
+
+    /// Extracts an optimal mapping, in post-order ids of the decompressed trees,
+    /// once subtree distances are computed.
+    fn compute_mappings(&mut self) -> Vec<(usize, usize)> {
+        let Self {
+            src,
+            dst,
+            n2,
+            ren,
+            delta,
+            fd,
+            ..
+        } = self;
+        let (f, g) = (&src[0], &dst[0]);
+        let mut ctx = ForestDist {
+            f,
+            g,
+            n2: *n2,
+            transposed: false,
+            ren,
+            delta,
+            fd,
+        };
+        let mut mappings = vec![];
+        let mut tree_pairs = vec![(f.lld.len() - 1, *n2 - 1)];
+        while let Some((i, j)) = tree_pairs.pop() {
+            ctx.compute(i, j);
+            let (li, lj) = (f.lld[i], g.lld[j]);
+            let stride = g.size(j) + 1;
+            let fd = &ctx.fd;
+            let mut a = f.size(i);
+            let mut b = g.size(j);
+            while a > 0 || b > 0 {
+                let curr = fd[a * stride + b];
+                if a > 0 && fd[(a - 1) * stride + b] + DEL == curr {
+                    a -= 1;
+                } else if b > 0 && fd[a * stride + b - 1] + INS == curr {
+                    b -= 1;
+                } else {
+                    let x = li + a - 1;
+                    let y = lj + b - 1;
+                    if f.lld[x] == li && g.lld[y] == lj {
+                        // both subforests are trees, map their roots
+                        mappings.push((x, y));
+                        a -= 1;
+                        b -= 1;
+                    } else {
+                        tree_pairs.push((x, y));
+                        // continue with the forests on the left of the popped subtree pair
+                        a = f.lld[x] - li;
+                        b = g.lld[y] - lj;
+                    }
+                }
+            }
+        }
+        mappings
+    }
+}
+
+/// Computes forest distances between the prefixes of two subtrees, à la Zhang and Shasha.
+struct ForestDist<'a> {
+    f: &'a Layout,
+    g: &'a Layout,
+    n2: usize,
+    transposed: bool,
+    ren: &'a [f64],
+    delta: &'a mut [f64],
+    fd: &'a mut Vec<f64>,
+}
+
+impl ForestDist<'_> {
+    /// index in the src x dst matrices
+    fn at(&self, x: usize, y: usize) -> usize {
+        let (x, y) = (self.f.ori[x], self.g.ori[y]);
+        if self.transposed {
+            y * self.n2 + x
+        } else {
+            x * self.n2 + y
+        }
+    }
+
+    /// Computes the forest distances between the prefixes of the subtrees `i` and `j`,
+    /// and the subtree distances between the nodes on their first paths.
+    ///
+    /// The distances of the other subtree pairs must already be known.
+    fn compute(&mut self, i: usize, j: usize) {
+        let (f, g) = (self.f, self.g);
+        let (li, lj) = (f.lld[i], g.lld[j]);
+        let (si, sj) = (f.size(i), g.size(j));
+        let stride = sj + 1;
+        self.fd.clear();
+        self.fd.resize((si + 1) * stride, 0.0);
+        for a in 1..=si {
+            self.fd[a * stride] = self.fd[(a - 1) * stride] + DEL;
+        }
+        for b in 1..=sj {
+            self.fd[b] = self.fd[b - 1] + INS;
+        }
+        for a in 1..=si {
+            let x = li + a - 1;
+            let lx = f.lld[x];
+            for b in 1..=sj {
+                let y = lj + b - 1;
+                let ly = g.lld[y];
+                let del = self.fd[(a - 1) * stride + b] + DEL;
+                let ins = self.fd[a * stride + b - 1] + INS;
+                let at = self.at(x, y);
+                let d = if lx == li && ly == lj {
+                    let d = f64::min(
+                        f64::min(del, ins),
+                        self.fd[(a - 1) * stride + b - 1] + self.ren[at],
+                    );
+                    self.delta[at] = d;
+                    d
+                } else {
+                    let (a0, b0) = (lx - li, ly - lj);
+                    f64::min(
+                        f64::min(del, ins),
+                        self.fd[a0 * stride + b0] + self.delta[at],
+                    )
+                };
+                self.fd[a * stride + b] = d;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::decompressed_tree_store::{CompletePostOrder, ShallowDecompressedTreeStore};
+    use crate::matchers::Decompressible;
+    use crate::matchers::mapping_store::{DefaultMappingStore, MappingStore};
+    use crate::tests::examples::*;
+    use hyperast::test_utils::simple_tree::{SimpleTree, vpair_to_stores};
+    use hyperast::types::HyperASTShared;
+
+    /// Naive edit distance, following the recursive definition on forests.
+    fn reference(ren: &[f64], n2: usize, src: &Layout, dst: &Layout) -> f64 {
+        type Forest = Vec<usize>;
+        fn rec(
+            ren: &[f64],
+            n2: usize,
+            src: &Layout,
+            dst: &Layout,
+            memo: &mut HashMap<(Forest, Forest), f64>,
+            f: Forest,
+            g: Forest,
+        ) -> f64 {
+            if let Some(d) = memo.get(&(f.clone(), g.clone())) {
+                return *d;
+            }
+            let d = match (f.last(), g.last()) {
+                (None, None) => 0.0,
+                (Some(&v), None) => {
+                    let mut f2 = f.clone();
+                    f2.pop();
+                    f2.extend(&src.children[v]);
+                    rec(ren, n2, src, dst, memo, f2, g.clone()) + DEL
+                }
+                (None, Some(&w)) => {
+                    let mut g2 = g.clone();
+                    g2.pop();
+                    g2.extend(&dst.children[w]);
+                    rec(ren, n2, src, dst, memo, f.clone(), g2) + INS
+                }
+                (Some(&v), Some(&w)) => {
+                    let mut f_del = f.clone();
+                    f_del.pop();
+                    let f_rest = f_del.clone();
+                    f_del.extend(&src.children[v]);
+                    let mut g_ins = g.clone();
+                    g_ins.pop();
+                    let g_rest = g_ins.clone();
+                    g_ins.extend(&dst.children[w]);
+                    let del = rec(ren, n2, src, dst, memo, f_del, g.clone()) + DEL;
+                    let ins = rec(ren, n2, src, dst, memo, f.clone(), g_ins) + INS;
+                    let f_v = src.children[v].clone();
+                    let g_w = dst.children[w].clone();
+                    let upd = rec(ren, n2, src, dst, memo, f_v, g_w)
+                        + ren[v * n2 + w]
+                        + rec(ren, n2, src, dst, memo, f_rest, g_rest);
+                    f64::min(f64::min(del, ins), upd)
+                }
+            };
+            memo.insert((f, g), d);
+            d
+        }
+        let mut memo = HashMap::new();
+        let f = vec![src.lld.len() - 1];
+        let g = vec![n2 - 1];
+        rec(ren, n2, src, dst, &mut memo, f, g)
+    }
+
+    fn check(pair: (SimpleTree<u8>, SimpleTree<u8>)) {
+        check_with(pair, None)
+    }
+
+    /// `path`, if any, is used to decompose all pairs of subtrees instead of the computed strategy
+    fn check_with(pair: (SimpleTree<u8>, SimpleTree<u8>), path: Option<Path>) {
+        let (stores, src, dst) = vpair_to_stores(pair);
+        let src_arena = Decompressible::<_, CompletePostOrder<_, u16>>::decompress(&stores, &src);
+        let dst_arena = Decompressible::<_, CompletePostOrder<_, u16>>::decompress(&stores, &dst);
+        let mut apted = Apted::new::<_, _, _, u16, u16>(&stores, &src_arena, &dst_arena);
+        if let Some(path) = path {
+            apted.strategy.fill(path);
+        }
+        let dist = apted.compute_dist();
+        let expected = reference(&apted.ren, apted.n2, &apted.src[0], &apted.dst[0]);
+        assert!((dist - expected).abs() < 1e-9, "{} != {}", dist, expected);
+
+        // the cost of the mapping should be the distance
+        let mappings = apted.compute_mappings();
+        let n1 = src_arena.len();
+        let n2 = dst_arena.len();
+        let mut cost = (n1 + n2 - 2 * mappings.len()) as f64;
+        for (x, y) in &mappings {
+            cost += apted.ren[x * n2 + y];
+        }
+        assert!((cost - dist).abs() < 1e-9, "{} != {}", cost, dist);
+    }
+
+    #[test]
+    fn test_zs_paper() {
+        check(example_zs_paper());
+    }
+
+    #[test]
+    fn test_gt_java_code() {
+        check(example_gt_java_code());
+    }
+
+    #[test]
+    fn test_gt_slides() {
+        check(example_gt_slides());
+    }
+
+    #[test]
+    fn test_other_examples() {
+        check(example_simple());
+        check(example_leaf_swap());
+        check(example_leaf_label_swap());
+        check(example_move());
+        check(example_move1());
+        check(example_move2());
+        check(example_move3());
+        check(example_action());
+        check(example_action2());
+        check(example_bottom_up());
+    }
+
+    /// Each single path function alone, in particular the general one,
+    /// which the strategy does not pick on small examples.
+    #[test]
+    fn test_each_path() {
+        for path in [
+            Path::LeftSrc,
+            Path::RightSrc,
+            Path::HeavySrc,
+            Path::LeftDst,
+            Path::RightDst,
+            Path::HeavyDst,
+        ] {
+            check_with(example_zs_paper(), Some(path));
+            check_with(example_gt_java_code(), Some(path));
+            check_with(example_gt_slides(), Some(path));
+            check_with(example_move2(), Some(path));
+            check_with(example_action(), Some(path));
+            check_with(example_action2(), Some(path));
+            check_with(example_bottom_up(), Some(path));
+        }
+    }
+
+    /// Both matchers are optimal, mappings can differ on ties but their costs cannot.
+    #[test]
+    fn test_same_cost_as_zs() {
+        use crate::matchers::optimal::zs::ZsMatcher;
+        type Arena<HAST> =
+            Decompressible<HAST, CompletePostOrder<<HAST as HyperASTShared>::IdN, u16>>;
+        fn cost<HAST: HyperAST + Copy>(
+            stores: HAST,
+            src_arena: &Arena<HAST>,
+            dst_arena: &Arena<HAST>,
+            mappings: &DefaultMappingStore<u16>,
+        ) -> f64
+        where
+            HAST::Label: Eq,
+            Arena<HAST>: PostOrder<HAST, u16>,
+        {
+            let mut cost = (src_arena.len() + dst_arena.len() - 2 * mappings.len()) as f64;
+            for (x, y) in mappings.iter() {
+                cost += update_cost(stores, &src_arena.tree(&x), &dst_arena.tree(&y));
+            }
+            cost
+        }
+        for pair in [
+            example_zs_paper(),
+            example_gt_java_code(),
+            example_gt_slides(),
+            example_action(),
+        ] {
+            let (stores, src, dst) = vpair_to_stores(pair);
+            let zs = ZsMatcher::<DefaultMappingStore<u16>, Arena<_>>::matchh(&stores, src, dst);
+            let apted =
+                AptedMatcher::<DefaultMappingStore<u16>, Arena<_>>::matchh(&stores, src, dst);
+            let zs_cost = cost(&stores, &zs.src_arena, &zs.dst_arena, &zs.mappings);
+            let apted_cost = cost(&stores, &apted.src_arena, &apted.dst_arena, &apted.mappings);
+            assert!(
+                (zs_cost - apted_cost).abs() < 1e-9,
+                "{} != {}",
+                zs_cost,
+                apted_cost
+            );
+        }
+    }
+}
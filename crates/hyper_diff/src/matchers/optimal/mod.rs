@@ -1 +1,57 @@
+use crate::decompressed_tree_store::PostOrderKeyRoots;
+use crate::matchers::mapping_store::MonoMappingStore;
+use hyperast::PrimInt;
+use hyperast::types::HyperAST;
+
+pub mod apted;
 pub mod zs;
+
+/// Optimal matchers usable as the last chance matcher of the greedy bottom-up matcher,
+/// see [`crate::matchers::heuristic::gt::greedy_bottom_up_matcher::GreedyBottomUpMatcher::execute_with`]
+pub trait OptimalMatcher {
+    fn match_with<HAST, SD, DD, M>(stores: HAST, src_arena: SD, dst_arena: DD) -> M
+    where
+        M: MonoMappingStore + Default,
+        M::Src: PrimInt,
+        M::Dst: PrimInt,
+        SD: PostOrderKeyRoots<HAST, M::Src>,
+        DD: PostOrderKeyRoots<HAST, M::Dst>,
+        HAST: HyperAST + Copy,
+        HAST::Label: Eq;
+}
+
+/// Zhang and Shasha, see [`zs::ZsMatcher`]
+pub struct Zs;
+
+impl OptimalMatcher for Zs {
+    fn match_with<HAST, SD, DD, M>(stores: HAST, src_arena: SD, dst_arena: DD) -> M
+    where
+        M: MonoMappingStore + Default,
+        M::Src: PrimInt,
+        M::Dst: PrimInt,
+        SD: PostOrderKeyRoots<HAST, M::Src>,
+        DD: PostOrderKeyRoots<HAST, M::Dst>,
+        HAST: HyperAST + Copy,
+        HAST::Label: Eq,
+    {
+        zs::ZsMatcher::<M, SD, DD>::match_with(stores, src_arena, dst_arena)
+    }
+}
+
+/// APTED, see [`apted::AptedMatcher`]
+pub struct Apted;
+
+impl OptimalMatcher for Apted {
+    fn match_with<HAST, SD, DD, M>(stores: HAST, src_arena: SD, dst_arena: DD) -> M
+    where
+        M: MonoMappingStore + Default,
+        M::Src: PrimInt,
+        M::Dst: PrimInt,
+        SD: PostOrderKeyRoots<HAST, M::Src>,
+        DD: PostOrderKeyRoots<HAST, M::Dst>,
+        HAST: HyperAST + Copy,
+        HAST::Label: Eq,
+    {
+        apted::AptedMatcher::<M, SD, DD>::match_with(stores, src_arena, dst_arena)
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{
+    decompressed_tree_store::{CompletePostOrder, ShallowDecompressedTreeStore},
+    matchers::{
+        Decompressible, Mapper,
+        heuristic::gt::{
+            hybrid_bottom_up_matcher::HybridBottomUpMatcher,
+            simple_bottom_up_matcher::SimpleBottomUpMatcher,
+        },
+        mapping_store::{MappingStore, VecStore},
+        optimal::Apted,
+    },
+    tests::examples::example_unstable,
+};
+
+use hyperast::store::SimpleStores;
+use hyperast::test_utils::simple_tree::{LS, NS, TStore, Tree, vpair_to_stores};
+use hyperast::types::DecompressedFrom as _;
+
+type Stores<'a> = &'a SimpleStores<TStore, NS<Tree>, LS<u16>>;
+type DS<'a> = Decompressible<Stores<'a>, CompletePostOrder<u16, u32>>;
+
+/// Maps the leaves of [`example_unstable`] by label, then runs the bottom-up matcher.
+///
+/// In both trees, `y` contains `b` and `c`, so `y` should be matched,
+/// but not `x` with the `y` of dst, as they only share `a`.
+fn check_unstable(
+    execute: impl for<'a> Fn(&mut Mapper<Stores<'a>, DS<'a>, DS<'a>, VecStore<u32>>),
+) {
+    let (stores, src, dst) = vpair_to_stores(example_unstable());
+    let hyperast = &stores;
+    let src_arena = Decompressible::<_, CompletePostOrder<_, u32>>::decompress(hyperast, &src);
+    let dst_arena = Decompressible::<_, CompletePostOrder<_, u32>>::decompress(hyperast, &dst);
+    let mut mapper = Mapper {
+        hyperast,
+        mapping: crate::matchers::Mapping {
+            src_arena,
+            dst_arena,
+            mappings: VecStore::<u32>::default(),
+        },
+    };
+    mapper.mapping.mappings.topit(
+        mapper.mapping.src_arena.len(),
+        mapper.mapping.dst_arena.len(),
+    );
+    let src = mapper.src_arena.root();
+    let dst = mapper.dst_arena.root();
+    let src_x = mapper.src_arena.child(&src, &[0]);
+    let src_a = mapper.src_arena.child(&src, &[0, 0]);
+    let src_y = mapper.src_arena.child(&src, &[1]);
+    let src_b = mapper.src_arena.child(&src, &[1, 0]);
+    let src_c = mapper.src_arena.child(&src, &[1, 1]);
+    let dst_y = mapper.dst_arena.child(&dst, &[1]);
+    let dst_a = mapper.dst_arena.child(&dst, &[1, 0]);
+    let dst_b = mapper.dst_arena.child(&dst, &[1, 1]);
+    let dst_c = mapper.dst_arena.child(&dst, &[1, 2]);
+    mapper.mapping.mappings.link(src_a, dst_a);
+    mapper.mapping.mappings.link(src_b, dst_b);
+    mapper.mapping.mappings.link(src_c, dst_c);
+
+    execute(&mut mapper);
+
+    assert!(mapper.mappings.has(&src, &dst));
+    assert!(mapper.mappings.has(&src_y, &dst_y));
+    assert!(!mapper.mappings.has(&src_x, &dst_y));
+    assert!(mapper.mappings.has(&src_a, &dst_a));
+}
+
+#[test]
+fn test_simple_bottom_up() {
+    check_unstable(|mapper| SimpleBottomUpMatcher::<_, _, _, _>::execute(mapper));
+}
+
+#[test]
+fn test_hybrid_bottom_up() {
+    check_unstable(|mapper| HybridBottomUpMatcher::<_, _, _, _>::execute(mapper));
+}
+
+#[test]
+fn test_hybrid_bottom_up_apted() {
+    check_unstable(|mapper| HybridBottomUpMatcher::<_, _, _, _>::execute_with::<Apted>(mapper));
+}
+
+#[test]
+fn test_hybrid_bottom_up_simple_recovery() {
+    // with a threshold of 0, the last chance match always uses the simple recovery
+    check_unstable(|mapper| HybridBottomUpMatcher::<_, _, _, _, 0>::execute(mapper));
+}
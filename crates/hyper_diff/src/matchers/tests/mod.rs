@@ -4,5 +4,8 @@
 #[cfg(test)]
 pub mod zs_tests;
 
+#[cfg(test)]
+pub mod bottom_up_tests;
+
 #[cfg(test)]
 pub mod instability;
@@ -12,7 +12,10 @@ use crate::{
     },
     decompressed_tree_store::{CompletePostOrder, ShallowDecompressedTreeStore},
     matchers::mapping_store::{DefaultMappingStore, MappingStore},
-    tests::examples::{example_action, example_action2, example_gt_java_code},
+    tests::{
+        examples::{example_action, example_action2, example_gt_java_code},
+        simple_examples::{example_move_rename_action, example_reorder_action},
+    },
     tree::simple_tree::{DisplayTree, NS, vpair_to_stores},
 };
 use hyperast::types::{
@@ -832,3 +835,111 @@ fn test_with_zs_custom_example() {
     let then = *root.last().unwrap(); //ActionsVec::apply_actions(actions.iter(), *src, &mut node_store);
     assert_eq!(then, dst);
 }
+
+#[test]
+fn test_with_move_rename_example() {
+    let (stores, src, dst) = vpair_to_stores(example_move_rename_action());
+    let label_store = &stores.label_store;
+    let src_arena = Decompressible::<_, CompletePostOrder<_, IdD>>::decompress(&stores, &src);
+    let dst_arena = Decompressible::<_, CompletePostOrder<_, IdD>>::decompress(&stores, &dst);
+
+    let mut ms = DefaultMappingStore::default();
+    let actions = {
+        let src = &(src_arena.root());
+        let dst = &(dst_arena.root());
+        ms.topit(src_arena.len(), dst_arena.len());
+        let from_src = |path: &[u8]| src_arena.child(src, path);
+        let from_dst = |path: &[u8]| dst_arena.child(dst, path);
+        ms.link(from_src(&[]), from_dst(&[]));
+        ms.link(from_src(&[0]), from_dst(&[0]));
+        ms.link(from_src(&[0, 0]), from_dst(&[1, 1]));
+        ms.link(from_src(&[1]), from_dst(&[1]));
+        ms.link(from_src(&[1, 0]), from_dst(&[1, 0]));
+        ms.link(from_src(&[1, 1]), from_dst(&[1, 2]));
+
+        let dst_arena2: SimpleBfsMapper<
+            _,
+            Decompressible<_, CompletePostOrder<u16, u16>>,
+            &Decompressible<_, CompletePostOrder<u16, u16>>,
+        > = SimpleBfsMapper::with_store(&stores, &dst_arena);
+        let actions =
+            ScriptGenerator::_compute_actions(&stores, &src_arena, &dst_arena2, &ms).unwrap();
+
+        log::debug!("{:?}", actions);
+
+        let mov =
+            make_move::<Tree, CompressedTreePath<_>>((&[0, 0], &[0, 0, 0]), (&[1, 1], &[0, 1, 1]));
+        // the rename designates the moved node, so it must come after the move
+        let upd = make_update::<Tree, CompressedTreePath<_>>(
+            label_store.get("g").unwrap(),
+            (&[1, 1], &[0, 1, 1]),
+        );
+        let mov = actions
+            .iter()
+            .position(|a| a == &mov)
+            .expect("move f to b.1");
+        let upd = actions
+            .iter()
+            .position(|a| a == &upd)
+            .expect("rename f to g");
+        assert!(mov < upd);
+
+        assert_eq!(2, actions.len());
+        actions
+    };
+
+    let mut stores = stores;
+    let mut root = vec![src];
+    apply_actions::<_, NS<Tree>, _>(actions, &mut root, &mut stores.node_store);
+    assert_eq!(*root.last().unwrap(), dst);
+}
+
+#[test]
+fn test_with_reorder_example() {
+    let (stores, src, dst) = vpair_to_stores(example_reorder_action());
+    let src_arena = Decompressible::<_, CompletePostOrder<_, IdD>>::decompress(&stores, &src);
+    let dst_arena = Decompressible::<_, CompletePostOrder<_, IdD>>::decompress(&stores, &dst);
+
+    let mut ms = DefaultMappingStore::default();
+    let actions = {
+        let src = &(src_arena.root());
+        let dst = &(dst_arena.root());
+        ms.topit(src_arena.len(), dst_arena.len());
+        let from_src = |path: &[u8]| src_arena.child(src, path);
+        let from_dst = |path: &[u8]| dst_arena.child(dst, path);
+        ms.link(from_src(&[]), from_dst(&[]));
+        ms.link(from_src(&[0]), from_dst(&[1]));
+        ms.link(from_src(&[0, 0]), from_dst(&[1, 2]));
+        ms.link(from_src(&[0, 1]), from_dst(&[1, 0]));
+        ms.link(from_src(&[0, 2]), from_dst(&[1, 1]));
+
+        let dst_arena2: SimpleBfsMapper<
+            _,
+            Decompressible<_, CompletePostOrder<u16, u16>>,
+            &Decompressible<_, CompletePostOrder<u16, u16>>,
+        > = SimpleBfsMapper::with_store(&stores, &dst_arena);
+        let actions =
+            ScriptGenerator::_compute_actions(&stores, &src_arena, &dst_arena2, &ms).unwrap();
+
+        log::debug!("{:?}", actions);
+
+        let ins = make_insert::<Tree, CompressedTreePath<_>>(
+            dst_arena.original(&from_dst(&[0])),
+            (&[0], &[0, 0]),
+        );
+        assert!(actions.has_actions(&[ins])); // ins y at a.0
+
+        // the destination of the reorder is in dst, ie. p is at 1 after the insert of y
+        let mov =
+            make_move::<Tree, CompressedTreePath<_>>((&[0, 0], &[0, 1, 0]), (&[1, 2], &[0, 1, 2]));
+        assert!(actions.has_actions(&[mov])); // mov b to p.2
+
+        assert_eq!(2, actions.len());
+        actions
+    };
+
+    let mut stores = stores;
+    let mut root = vec![src];
+    apply_actions::<_, NS<Tree>, _>(actions, &mut root, &mut stores.node_store);
+    assert_eq!(*root.last().unwrap(), dst);
+}
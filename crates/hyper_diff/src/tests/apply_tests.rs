@@ -0,0 +1,143 @@
+//! Checks that applying, on the source tree, the edit script computed between two trees
+//! rebuilds the destination tree.
+//!
+//! As the test node store deduplicates structurally equal subtrees,
+//! comparing the identifiers of the rebuilt tree and of the destination tree is enough.
+use hyperast::test_utils::simple_tree::SimpleTree;
+
+use crate::{
+    actions::{
+        action_vec::{ActionsVec, apply},
+        script_generator2::{ScriptGenerator, SimpleAction},
+    },
+    decompressed_tree_store::{CompletePostOrder, bfs_wrapper::SimpleBfsMapper},
+    matchers::{Decompressible, mapping_store::DefaultMappingStore, optimal::zs::ZsMatcher},
+    tests::{examples::*, simple_examples::*},
+    tree::{
+        simple_tree::{DisplayTree, NS, Tree, vpair_to_stores},
+        tree_path::CompressedTreePath,
+    },
+};
+
+type IdD = u16;
+
+fn check_apply(pair: (SimpleTree<u8>, SimpleTree<u8>)) {
+    let (stores, src, dst) = vpair_to_stores(pair);
+    log::debug!(
+        "src tree:\n{:?}",
+        DisplayTree::new(&stores.label_store, &stores.node_store, src)
+    );
+    log::debug!(
+        "dst tree:\n{:?}",
+        DisplayTree::new(&stores.label_store, &stores.node_store, dst)
+    );
+    let actions: ActionsVec<SimpleAction<u16, CompressedTreePath<u8>, u16>> = {
+        let ZsMatcher {
+            src_arena,
+            dst_arena,
+            mappings,
+        } = ZsMatcher::<DefaultMappingStore<IdD>, Decompressible<_, CompletePostOrder<_, IdD>>>::matchh(
+            &stores, src, dst,
+        );
+        let dst_arena: SimpleBfsMapper<
+            _,
+            Decompressible<_, CompletePostOrder<u16, IdD>>,
+            &Decompressible<_, CompletePostOrder<u16, IdD>>,
+        > = SimpleBfsMapper::with_store(&stores, &dst_arena);
+        ScriptGenerator::_compute_actions(&stores, &src_arena, &dst_arena, &mappings).unwrap()
+    };
+    log::debug!("{:?}", actions);
+
+    let mut stores = stores;
+    let then = apply::<Tree, NS<Tree>, _>(actions, src, &mut stores.node_store);
+    log::debug!(
+        "then tree:\n{:?}",
+        DisplayTree::new(&stores.label_store, &stores.node_store, then)
+    );
+    assert_eq!(then, dst);
+}
+
+#[test]
+fn test_apply_same() {
+    check_apply((example_action().0, example_action().0));
+}
+
+#[test]
+fn test_apply_delete() {
+    check_apply(example_delete_action());
+}
+
+#[test]
+fn test_apply_rename() {
+    check_apply(example_rename_action());
+}
+
+#[test]
+fn test_apply_move() {
+    check_apply(example_move_action());
+}
+
+#[test]
+fn test_apply_subdelete() {
+    check_apply(example_subdelete_action());
+}
+
+#[test]
+fn test_apply_subdelete2() {
+    check_apply(example_subdelete_action2());
+}
+
+#[test]
+fn test_apply_simple() {
+    check_apply(example_simple());
+}
+
+#[test]
+fn test_apply_leaf_swap() {
+    check_apply(example_leaf_swap());
+}
+
+#[test]
+fn test_apply_leaf_label_swap() {
+    check_apply(example_leaf_label_swap());
+}
+
+#[test]
+fn test_apply_moves() {
+    check_apply(example_move());
+    check_apply(example_move1());
+    check_apply(example_move2());
+    check_apply(example_move3());
+}
+
+#[test]
+fn test_apply_zs_paper() {
+    check_apply(example_zs_paper());
+}
+
+#[test]
+fn test_apply_gt_java_code() {
+    check_apply(example_gt_java_code());
+}
+
+#[test]
+fn test_apply_gt_slides() {
+    check_apply(example_gt_slides());
+}
+
+#[test]
+fn test_apply_gumtree() {
+    check_apply(example_gumtree());
+    check_apply(example_gumtree_ambiguous());
+}
+
+#[test]
+fn test_apply_bottom_up() {
+    check_apply(example_bottom_up());
+}
+
+#[test]
+fn test_apply_action_examples() {
+    check_apply(example_action());
+    check_apply(example_action2());
+}
@@ -40,6 +40,8 @@ pub mod action_generator2_simple_tests;
 pub mod action_generator2_tests;
 pub mod action_generator_tests;
 #[cfg(test)]
+pub mod apply_tests;
+#[cfg(test)]
 pub mod examples;
 pub mod hungarian_tests;
 #[cfg(test)]
@@ -118,3 +118,48 @@ pub(crate) fn example_subdelete_action2() -> (ST<u8>, ST<u8>) {
     ]);
     (src, dst)
 }
+
+/// example of move with rename
+///
+/// 0:f is move to b.1 and renamed to g
+pub(crate) fn example_move_rename_action() -> (ST<u8>, ST<u8>) {
+    let src = tree!(
+        0,"a"; [
+            tree!(0, "e"; [
+                tree!(0, "f")]),
+            tree!(0, "b"; [
+                tree!(0, "c"),
+                tree!(0, "d")]),
+    ]);
+    let dst = tree!(
+        0,"a"; [
+            tree!(0, "e"),
+            tree!(0, "b"; [
+                tree!(0, "c"),
+                tree!(0, "g"),
+                tree!(0, "d")]),
+    ]);
+    (src, dst)
+}
+
+/// example of reorder after an insert
+///
+/// 0.0:b is move to 1.2, after y is inserted at 0
+pub(crate) fn example_reorder_action() -> (ST<u8>, ST<u8>) {
+    let src = tree!(
+        0,"a"; [
+            tree!(0, "p"; [
+                tree!(0, "b"),
+                tree!(0, "c"),
+                tree!(0, "d")]),
+    ]);
+    let dst = tree!(
+        0,"a"; [
+            tree!(0, "y"),
+            tree!(0, "p"; [
+                tree!(0, "c"),
+                tree!(0, "d"),
+                tree!(0, "b")]),
+    ]);
+    (src, dst)
+}
@@ -0,0 +1,109 @@
+use hyper_diff::{actions::gumtree_format::GumtreeDiff, algorithms};
+use hyperast::store::SimpleStores;
+use hyperast_gen_ts_java::{
+    legion_with_refs::{self, JavaTreeGen},
+    types::TStore,
+};
+
+#[test]
+fn test_rename_field() {
+    let src = b"class A { int a; }";
+    let dst = b"class A { int b; }";
+    let mut stores = SimpleStores::<TStore>::default();
+    let mut md_cache = Default::default();
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+    let tree = match legion_with_refs::tree_sitter_parse(src) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    let src = java_tree_gen.generate_file(b"", src, tree.walk());
+    let tree = match legion_with_refs::tree_sitter_parse(dst) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    let dst = java_tree_gen.generate_file(b"", dst, tree.walk());
+
+    let result = algorithms::gumtree::diff(
+        &stores,
+        &src.local.compressed_node,
+        &dst.local.compressed_node,
+    );
+    let diff = GumtreeDiff::new(
+        result.mapper.hyperast,
+        &result.mapper.mapping,
+        result.actions.as_ref().unwrap(),
+    );
+
+    assert!(!diff.matches.is_empty());
+    let update = diff
+        .actions
+        .iter()
+        .find(|a| a.action == "update-node")
+        .expect("the renaming should be an update");
+    assert!(update.tree.contains(": a [14,15]"), "{}", update.tree);
+    assert_eq!(update.label.as_deref(), Some("b"));
+
+    let json: serde_json::Value = serde_json::from_str(&diff.to_json()).unwrap();
+    assert_eq!(
+        json["actions"].as_array().unwrap().len(),
+        diff.actions.len()
+    );
+    assert!(json["matches"][0]["dest"].is_string());
+
+    let xml = diff.to_xml();
+    assert!(xml.starts_with("<?xml"));
+    assert!(xml.contains(r#"<action type="update-node" tree=""#));
+    assert!(xml.contains(r#"label="b"/>"#));
+}
+
+#[test]
+fn test_reorder_methods() {
+    // the class moves from the first to the second declaration of the file,
+    // so the parent of the moved method is at different positions in src and dst
+    let src = b"class A { void f() {} void g() {} }";
+    let dst = b"class B {} class A { void g() {} void f() {} }";
+    let mut stores = SimpleStores::<TStore>::default();
+    let mut md_cache = Default::default();
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+    let tree = match legion_with_refs::tree_sitter_parse(src) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    let src = java_tree_gen.generate_file(b"", src, tree.walk());
+    let tree = match legion_with_refs::tree_sitter_parse(dst) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    let dst = java_tree_gen.generate_file(b"", dst, tree.walk());
+
+    let result = algorithms::gumtree::diff(
+        &stores,
+        &src.local.compressed_node,
+        &dst.local.compressed_node,
+    );
+    let diff = GumtreeDiff::new(
+        result.mapper.hyperast,
+        &result.mapper.mapping,
+        result.actions.as_ref().unwrap(),
+    );
+
+    let moves: Vec<_> = diff
+        .actions
+        .iter()
+        .filter(|a| a.action == "move-tree")
+        .collect();
+    let [reorder] = moves
+        .iter()
+        .filter(|a| a.tree.starts_with("method_declaration"))
+        .collect::<Vec<_>>()[..]
+    else {
+        panic!("a single method should be moved: {:#?}", diff.actions)
+    };
+    // the class body of A in dst, its children being `{`, g, f and `}` separated by spaces
+    assert_eq!(reorder.parent.as_deref(), Some("class_body [19,46]"));
+    match &reorder.tree[..] {
+        "method_declaration [10,21]" => assert_eq!(reorder.at, Some(4)),
+        "method_declaration [22,33]" => assert_eq!(reorder.at, Some(2)),
+        tree => panic!("unexpected moved tree {}", tree),
+    }
+}
@@ -121,6 +121,7 @@ where
     HAST::TS:
         EnabledTypeStore<Ty2 = Acc::Type> + hyperast::types::RoleStore<IdF = IdF, Role = Role>,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithRoles,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithMetrics,
     HAST::IdN: Copy,
     Acc: tree_gen::WithChildren<HAST::IdN> + tree_gen::WithRole<Role> + types::Typed,
     &'acc Acc: hyperast::tree_gen::WithLabel,
@@ -134,6 +135,7 @@ where
     HAST::TS:
         EnabledTypeStore<Ty2 = Acc::Type> + hyperast::types::RoleStore<IdF = IdF, Role = Role>,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithRoles,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithMetrics,
     HAST::IdN: Copy,
     Acc: tree_gen::WithChildren<HAST::IdN> + tree_gen::WithRole<Role> + types::Typed,
     &'acc Acc: hyperast::tree_gen::WithLabel,
@@ -353,6 +355,7 @@ where
     HAST::TS:
         EnabledTypeStore<Ty2 = Acc::Type> + hyperast::types::RoleStore<IdF = IdF, Role = Role>,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithRoles,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithMetrics,
     HAST::IdN: Copy,
     Acc: tree_gen::WithChildren<HAST::IdN> + tree_gen::WithRole<Role> + types::Typed,
     &'acc Acc: hyperast::tree_gen::WithLabel,
@@ -452,6 +455,7 @@ where
     HAST::TS:
         EnabledTypeStore<Ty2 = Acc::Type> + hyperast::types::RoleStore<IdF = IdF, Role = Role>,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithRoles,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithMetrics,
     HAST::IdN: Copy,
     Acc: tree_gen::WithChildren<HAST::IdN> + tree_gen::WithRole<Role> + types::Typed,
     &'acc Acc: hyperast::tree_gen::WithLabel,
@@ -573,6 +577,31 @@ where
             super::BiCow::B("".into())
         }
     }
+
+    /// The node under construction only has the metrics derived from its children,
+    /// the named ones being derived when it is finished.
+    fn metric(&self, metric: &crate::Metric) -> Option<f64> {
+        use hyperast::types::{WithMetrics, WithStats};
+        if let Some(id) = self.pos.node() {
+            let n = self.stores.resolve(id);
+            return match metric {
+                crate::Metric::Size => Some(n.size() as f64),
+                crate::Metric::Height => Some(n.height() as f64),
+                crate::Metric::Lines => Some(n.line_count() as f64),
+                crate::Metric::Named(name) => n.get_metric(name),
+            };
+        }
+        let children = self.acc.children().iter().map(|x| self.stores.resolve(x));
+        match metric {
+            crate::Metric::Size => Some(children.map(|n| n.size()).sum::<usize>() as f64 + 1.0),
+            crate::Metric::Height => {
+                let height = children.map(|n| n.height()).max().unwrap_or(0);
+                Some(height as f64 + 1.0)
+            }
+            crate::Metric::Lines => Some(children.map(|n| n.line_count()).sum::<usize>() as f64),
+            crate::Metric::Named(_) => None,
+        }
+    }
 }
 
 impl<'acc, 'l, HAST, Acc> Node<HAST, &'acc Acc>
@@ -581,6 +610,7 @@ where
     HAST::TS:
         EnabledTypeStore<Ty2 = Acc::Type> + hyperast::types::RoleStore<IdF = IdF, Role = Role>,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithRoles,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithMetrics,
     HAST::IdN: Copy,
     Acc: tree_gen::WithChildren<HAST::IdN> + tree_gen::WithRole<Role> + types::Typed,
     &'acc Acc: hyperast::tree_gen::WithLabel,
@@ -3,8 +3,8 @@ use crate::CNLending;
 use super::{Cursor, Node as _, Status, Symbol, TreeCursorStep};
 use hyperast::position::TreePath;
 use hyperast::types::{
-    HyperASTShared, HyperType, LabelStore, Labeled, NodeStore, RoleStore, Tree, WithPrecompQueries,
-    WithRoles,
+    HyperASTShared, HyperType, LabelStore, Labeled, NodeStore, RoleStore, Tree, WithMetrics,
+    WithPrecompQueries, WithRoles,
 };
 use hyperast::{
     position::TreePathMut,
@@ -12,6 +12,9 @@ use hyperast::{
 };
 pub type TreeCursor<'hast, HAST> = Node<'hast, HAST>;
 
+/// Looks up the named metrics that are not stored in nodes, e.g. computed lazily by scripts
+pub type MetricLookup<'hast, IdN> = &'hast dyn Fn(&IdN, &str) -> Option<f64>;
+
 pub struct Node<
     'hast,
     HAST: HyperASTShared,
@@ -22,6 +25,8 @@ pub struct Node<
 > {
     pub stores: &'hast HAST,
     pub pos: P,
+    /// consulted for the named metrics missing from nodes
+    pub metrics: Option<MetricLookup<'hast, HAST::IdN>>,
 }
 
 #[derive(Clone)]
@@ -42,7 +47,16 @@ impl<'hast, HAST: HyperAST> Node<'hast, HAST> {
         stores: &'hast HAST,
         pos: hyperast::position::StructuralPosition<HAST::IdN, HAST::Idx>,
     ) -> Self {
-        Self { stores, pos }
+        Self {
+            stores,
+            pos,
+            metrics: None,
+        }
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricLookup<'hast, HAST::IdN>) -> Self {
+        self.metrics = Some(metrics);
+        self
     }
 }
 
@@ -51,6 +65,7 @@ impl<'hast, HAST: HyperAST> Clone for Node<'hast, HAST> {
         Self {
             stores: self.stores,
             pos: self.pos.clone(),
+            metrics: self.metrics,
         }
     }
 }
@@ -104,6 +119,7 @@ where
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     type NR = self::Node<'hast, HAST>;
@@ -115,6 +131,7 @@ where
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     type Node = self::Node<'hast, HAST>;
@@ -281,6 +298,7 @@ where
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     fn role(&self) -> Option<<HAST::TS as RoleStore>::Role> {
@@ -350,6 +368,7 @@ where
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     fn symbol(&self) -> Symbol {
@@ -423,6 +442,20 @@ where
         }
         super::BiCow::B("".into()) // TODO check if it is the right behavior
     }
+
+    fn metric(&self, metric: &crate::Metric) -> Option<f64> {
+        use hyperast::types::WithStats;
+        let id = self.pos.node().unwrap();
+        let n = self.stores.node_store().resolve(id);
+        match metric {
+            crate::Metric::Size => Some(n.size() as f64),
+            crate::Metric::Height => Some(n.height() as f64),
+            crate::Metric::Lines => Some(n.line_count() as f64),
+            crate::Metric::Named(name) => n
+                .get_metric(name)
+                .or_else(|| self.metrics.and_then(|metrics| metrics(id, name))),
+        }
+    }
 }
 
 impl<'hast, HAST: HyperAST> Node<'hast, HAST>
@@ -431,6 +464,7 @@ where
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     fn child_by_role(&mut self, role: <HAST::TS as RoleStore>::Role) -> Option<()> {
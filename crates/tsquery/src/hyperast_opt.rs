@@ -2,7 +2,8 @@ use super::{Status, Symbol, TreeCursorStep};
 use hyperast::position::structural_pos::{self, AAA, BBB};
 use hyperast::types::{HyperAST, LangRef, TypeStore};
 use hyperast::types::{
-    HyperASTShared, HyperType, LabelStore, Labeled, RoleStore, Tree, WithPrecompQueries, WithRoles,
+    HyperASTShared, HyperType, LabelStore, Labeled, RoleStore, Tree, WithMetrics,
+    WithPrecompQueries, WithRoles,
 };
 
 pub struct TreeCursor<'hast, HAST: HyperASTShared> {
@@ -130,6 +131,7 @@ where
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     type NR = self::NodeRef<'a, 'hast, HAST>;
@@ -141,6 +143,7 @@ where
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     type Node = self::Node<'hast, HAST>;
@@ -322,6 +325,7 @@ where
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     fn symbol(&self) -> Symbol {
@@ -389,6 +393,10 @@ where
     ) -> super::BiCow<'s, 'l, str> {
         text(self.stores, &self.pos)
     }
+
+    fn metric(&self, m: &crate::Metric) -> Option<f64> {
+        metric(self.stores, &self.pos, m)
+    }
 }
 
 impl<'a, 'b, 'hast, HAST: HyperAST> super::TextLending<'a> for self::NodeRef<'b, 'hast, HAST> {
@@ -414,6 +422,7 @@ where
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     fn symbol(&self) -> Symbol {
@@ -476,6 +485,10 @@ where
     ) -> super::BiCow<'s, 'l, str> {
         text(self.stores, &self.pos)
     }
+
+    fn metric(&self, m: &crate::Metric) -> Option<f64> {
+        metric(self.stores, &self.pos, m)
+    }
 }
 
 impl<'hast, HAST: HyperAST> Node<'hast, HAST>
@@ -550,6 +563,24 @@ where
     }
 }
 
+fn metric<'hast, HAST: HyperAST>(
+    stores: &'hast HAST,
+    pos: &impl AAA<HAST::IdN, HAST::Idx>,
+    metric: &crate::Metric,
+) -> Option<f64>
+where
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
+{
+    use hyperast::types::WithStats;
+    let n = resolve(stores, pos);
+    match metric {
+        crate::Metric::Size => Some(n.size() as f64),
+        crate::Metric::Height => Some(n.height() as f64),
+        crate::Metric::Lines => Some(n.line_count() as f64),
+        crate::Metric::Named(name) => n.get_metric(name),
+    }
+}
+
 fn role<'hast, HAST: HyperAST>(
     stores: &'hast HAST,
     pos: &mut impl AAA<HAST::IdN, HAST::Idx>,
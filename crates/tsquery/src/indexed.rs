@@ -406,7 +406,7 @@ impl<Node> CaptureListPool<Node> {
 }
 
 #[repr(transparent)]
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct PatternId(usize);
 
 impl PatternId {
@@ -24,6 +24,8 @@ pub mod default_impls;
 pub mod hyperast_cursor;
 #[cfg(feature = "hyperast")]
 pub mod hyperast_opt;
+#[cfg(feature = "hyperast")]
+pub mod match_cache;
 pub mod stepped_query;
 pub mod stepped_query_imm;
 pub mod tsg;
@@ -43,6 +45,8 @@ pub use indexed::CaptureId;
 pub use indexed::PatternId;
 pub use indexed::Symbol;
 
+pub use predicate::Metric;
+
 use std::borrow::Borrow;
 use std::fmt::Debug;
 use std::ops::Deref;
@@ -83,6 +87,7 @@ pub struct Query {
     property_predicates: predicate::PropertyPredicates,
     property_settings: predicate::PropertySettings,
     general_predicates: predicate::GeneralPredicates,
+    metric_predicates: predicate::MetricPredicates,
     immediate_predicates: Vec<predicate::ImmediateTextPredicate>,
     precomputed_patterns: Option<query::PrecomputedPatterns>,
     used_precomputed: Precomps,
@@ -289,6 +294,13 @@ pub trait Node: for<'a> TextLending<'a> + Clone + PartialEq {
     /// Natural ordering over the position of source code elements (represented by `self` and `other`)
     fn compare(&self, other: &Self) -> std::cmp::Ordering;
     fn text<'s, 'l>(&'s self, text_provider: <Self as TextLending<'l>>::TP) -> BiCow<'s, 'l, str>;
+
+    /// The value of `metric` on `self`, compared by predicates such as `#size>?`,
+    /// None if not available, in which case such predicates do not hold
+    fn metric(&self, _metric: &Metric) -> Option<f64> {
+        None
+    }
+
     fn text_equal<'s, 'l>(
         &'s self,
         text_provider: <Self as TextLending<'l>>::TP,
@@ -382,6 +394,10 @@ where
     fn text<'s, 'l>(&'s self, text_provider: <Self as TextLending<'l>>::TP) -> BiCow<'s, 'l, str> {
         (*self).text(text_provider)
     }
+
+    fn metric(&self, metric: &Metric) -> Option<f64> {
+        (*self).metric(metric)
+    }
 }
 
 impl<'query, Cursor: self::Cursor> Iterator for QueryCursor<'query, Cursor, Cursor::Node>
@@ -397,6 +413,9 @@ where
                 self.cursor.text_provider(),
                 self.query
                     .text_predicates_for_pattern_id(result.pattern_index),
+            ) && result.satisfies_metric_predicates(
+                self.query
+                    .metric_predicates_for_pattern_id(result.pattern_index),
             ) {
                 return Some(result);
             }
@@ -470,6 +489,21 @@ impl<Node: self::Node> QueryMatch<Node> {
         })
     }
 
+    /// All the nodes of the capture must satisfy the comparison
+    pub(crate) fn satisfies_metric_predicates<'a>(
+        &self,
+        mut metric_predicates: impl Iterator<Item = &'a predicate::MetricPredicate>,
+    ) -> bool {
+        metric_predicates.all(|predicate| {
+            let mut nodes = self.nodes_for_capture_index(predicate.capture);
+            nodes.all(|node| match node.metric(&predicate.metric) {
+                Some(x) if predicate.greater => x > predicate.value,
+                Some(x) => x < predicate.value,
+                None => false,
+            })
+        })
+    }
+
     pub fn nodes_for_capture_index<'a>(
         &'a self,
         index: CaptureId,
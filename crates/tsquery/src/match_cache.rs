@@ -0,0 +1,286 @@
+//! Counts of matches memoized by subtree.
+//!
+//! Identical subtrees are deduplicated by the HyperAST,
+//! so the files and directories left untouched by a commit keep the same identifiers.
+//! The matches of local patterns (see [`Query::is_local_pattern`]) only depend on the subtree where they start,
+//! thus their counts in such subtrees are computed once and reused across commits.
+//!
+//! The cache is bounded, the counts that were not used recently being forgotten first.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::RwLock;
+
+use hyperast::position::TreePath;
+use hyperast::types::{
+    HyperAST, HyperType, NodeId, RoleStore, WithChildren, WithMetrics, WithPrecompQueries,
+    WithRoles,
+};
+
+use crate::hyperast_cursor::{MetricLookup, TreeCursor};
+use crate::{PatternId, Query};
+
+type Key<IdN> = (u64, PatternId, IdN);
+
+/// Number of matches, keyed by query hash, pattern and subtree
+pub struct MatchCache<IdN> {
+    /// maximum number of counts kept, rounded up to an even number
+    capacity: usize,
+    counts: RwLock<Generations<IdN>>,
+}
+
+/// Counts are inserted in the current generation, which replaces the previous one once half full,
+/// the counts of the previous generation being moved back to the current one when used.
+struct Generations<IdN> {
+    current: HashMap<Key<IdN>, u64>,
+    previous: HashMap<Key<IdN>, u64>,
+}
+
+impl<IdN> Default for MatchCache<IdN> {
+    fn default() -> Self {
+        Self::with_capacity(1 << 20)
+    }
+}
+
+impl<IdN> MatchCache<IdN> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            counts: RwLock::new(Generations {
+                current: Default::default(),
+                previous: Default::default(),
+            }),
+        }
+    }
+}
+
+impl<IdN: Hash + Eq + Copy> MatchCache<IdN> {
+    pub fn get(&self, query: u64, pattern: PatternId, node: IdN) -> Option<u64> {
+        let key = (query, pattern, node);
+        let counts = self.counts.read().unwrap();
+        if let Some(count) = counts.current.get(&key) {
+            return Some(*count);
+        }
+        counts.previous.get(&key)?;
+        drop(counts);
+        let mut counts = self.counts.write().unwrap();
+        let count = counts.previous.remove(&key)?;
+        self.insert_current(&mut counts, key, count);
+        Some(count)
+    }
+
+    pub fn insert(&self, query: u64, pattern: PatternId, node: IdN, count: u64) {
+        let mut counts = self.counts.write().unwrap();
+        self.insert_current(&mut counts, (query, pattern, node), count);
+    }
+
+    fn insert_current(&self, counts: &mut Generations<IdN>, key: Key<IdN>, count: u64) {
+        counts.previous.remove(&key);
+        if counts.current.len() >= self.capacity.div_ceil(2) {
+            counts.previous = std::mem::take(&mut counts.current);
+        }
+        counts.current.insert(key, count);
+    }
+
+    pub fn len(&self) -> usize {
+        let counts = self.counts.read().unwrap();
+        counts.current.len() + counts.previous.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A query split between its local patterns, counted file by file with a [`MatchCache`],
+/// and its other patterns, counted over the whole tree.
+///
+/// Directories cannot be matched by tree-sitter patterns,
+/// so the counts of a directory are the sums of the counts of its children.
+pub struct MemoizedQuery {
+    hash: u64,
+    /// the enabled patterns of the original query, by enabled index
+    patterns: Vec<PatternId>,
+    local: Option<Query>,
+    others: Option<Query>,
+}
+
+impl MemoizedQuery {
+    /// `hash` identifies `query` in the cache, eg. a hash of its source and language
+    pub fn new(query: &Query, hash: u64) -> Self {
+        let mut patterns = vec![PatternId::NONE; query.enabled_pattern_count()];
+        for i in 0..query.pattern_count() {
+            let pid = PatternId::new(i);
+            if let Some(j) = query.enabled_pattern_index(pid) {
+                patterns[j as usize] = pid;
+            }
+        }
+        let mut local = query.clone();
+        let mut others = query.clone();
+        for pid in &patterns {
+            if query.is_local_pattern(*pid) {
+                others.disable_pattern(*pid);
+            } else {
+                local.disable_pattern(*pid);
+            }
+        }
+        Self {
+            hash,
+            patterns,
+            local: (local.enabled_pattern_count() > 0).then_some(local),
+            others: (others.enabled_pattern_count() > 0).then_some(others),
+        }
+    }
+
+    /// Counts the matches in `root` of each enabled pattern, by enabled index of the original query.
+    ///
+    /// `metrics` is consulted for the named metrics missing from nodes,
+    /// see [`TreeCursor::with_metrics`].
+    /// `stop` is given the counts after each match and each file,
+    /// if it returns true the partial counts are returned as an error.
+    pub fn count<HAST>(
+        &self,
+        stores: &HAST,
+        root: HAST::IdN,
+        cache: &MatchCache<HAST::IdN>,
+        metrics: Option<MetricLookup<'_, HAST::IdN>>,
+        mut stop: impl FnMut(&[u64]) -> bool,
+    ) -> Result<Vec<u64>, Vec<u64>>
+    where
+        HAST: HyperAST,
+        HAST::IdN: std::fmt::Debug + Copy + Hash + Eq + NodeId<IdN = HAST::IdN>,
+        HAST::TS: RoleStore,
+        <HAST::TS as RoleStore>::IdF: Into<u16> + From<u16>,
+        for<'t> <HAST as hyperast::types::AstLending<'t>>::RT:
+            WithRoles + WithPrecompQueries + WithMetrics,
+    {
+        let pos = hyperast::position::StructuralPosition::new(root);
+        let mut cursor = TreeCursor::new(stores, pos);
+        cursor.metrics = metrics;
+        let mut result = vec![0; self.patterns.len()];
+        if let Some(others) = &self.others {
+            let completed = self.matches(cursor.clone(), others, &mut result, &mut stop);
+            if !completed {
+                return Err(result);
+            }
+        }
+        if let Some(local) = &self.local {
+            let counted = self.count_local(cursor, local, cache, &mut result, &mut stop);
+            if counted.is_none() {
+                return Err(result);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Adds the counts of the node of `cursor` to `result` and returns them, or None if stopped
+    fn count_local<HAST>(
+        &self,
+        cursor: TreeCursor<'_, HAST>,
+        query: &Query,
+        cache: &MatchCache<HAST::IdN>,
+        result: &mut [u64],
+        stop: &mut impl FnMut(&[u64]) -> bool,
+    ) -> Option<Vec<u64>>
+    where
+        HAST: HyperAST,
+        HAST::IdN: std::fmt::Debug + Copy + Hash + Eq + NodeId<IdN = HAST::IdN>,
+        HAST::TS: RoleStore,
+        <HAST::TS as RoleStore>::IdF: Into<u16> + From<u16>,
+        for<'t> <HAST as hyperast::types::AstLending<'t>>::RT:
+            WithRoles + WithPrecompQueries + WithMetrics,
+    {
+        let locals = || {
+            let patterns = self.patterns.iter().enumerate();
+            patterns.filter(|(_, pid)| query.enabled_pattern_index(**pid).is_some())
+        };
+        let stores = cursor.stores;
+        let root = *cursor.pos.node().unwrap();
+        let cached: Option<Vec<_>> = locals()
+            .map(|(i, pid)| Some((i, cache.get(self.hash, *pid, root)?)))
+            .collect();
+        if let Some(cached) = cached {
+            let mut counts = vec![0; self.patterns.len()];
+            for (i, count) in cached {
+                counts[i] = count;
+                result[i] += count;
+            }
+            return Some(counts);
+        }
+        let mut counts = vec![0; self.patterns.len()];
+        if stores.resolve_type(&root).is_directory() {
+            let node = stores.resolve(&root);
+            let children: Vec<HAST::IdN> = node.children().map_or(vec![], |cs| cs.collect());
+            for child in children {
+                let mut cursor = cursor.clone();
+                cursor.pos = hyperast::position::StructuralPosition::new(child);
+                let child = self.count_local(cursor, query, cache, result, stop)?;
+                counts.iter_mut().zip(child).for_each(|(x, y)| *x += y);
+            }
+        } else {
+            let before = result.to_vec();
+            if !self.matches(cursor, query, result, stop) {
+                return None;
+            }
+            counts
+                .iter_mut()
+                .zip(result.iter().zip(before))
+                .for_each(|(x, (r, b))| *x = r - b);
+            if stop(result) {
+                return None;
+            }
+        }
+        for (i, pid) in locals() {
+            cache.insert(self.hash, *pid, root, counts[i]);
+        }
+        Some(counts)
+    }
+
+    /// Adds the matches of `query` in the node of `cursor` to `result`, returns false if stopped
+    fn matches<HAST>(
+        &self,
+        cursor: TreeCursor<'_, HAST>,
+        query: &Query,
+        result: &mut [u64],
+        stop: &mut impl FnMut(&[u64]) -> bool,
+    ) -> bool
+    where
+        HAST: HyperAST,
+        HAST::IdN: std::fmt::Debug + Copy + Hash + Eq + NodeId<IdN = HAST::IdN>,
+        HAST::TS: RoleStore,
+        <HAST::TS as RoleStore>::IdF: Into<u16> + From<u16>,
+        for<'t> <HAST as hyperast::types::AstLending<'t>>::RT:
+            WithRoles + WithPrecompQueries + WithMetrics,
+    {
+        for m in query.matches(cursor) {
+            let Some(i) = self.patterns.iter().position(|pid| *pid == m.pattern_index) else {
+                continue;
+            };
+            result[i] += 1;
+            if stop(result) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_cache() {
+        let cache = MatchCache::<u32>::with_capacity(4);
+        let pattern = PatternId::new(0);
+        for node in 0..10 {
+            cache.insert(0, pattern, node, node as u64);
+            // used in each iteration, so never forgotten
+            assert_eq!(cache.get(0, pattern, 0), Some(0));
+            assert!(cache.len() <= 4);
+        }
+        assert_eq!(cache.get(0, pattern, 9), Some(9));
+        assert_eq!(cache.get(0, pattern, 1), None);
+        assert_eq!(cache.get(1, pattern, 9), None);
+    }
+}
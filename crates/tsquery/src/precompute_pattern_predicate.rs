@@ -49,6 +49,7 @@ where
         + tree_gen::WithChildren<HAST::IdN>,
     for<'c> &'c Acc: tree_gen::WithLabel<L = &'c str>,
     for<'t> types::LendT<'t, HAST>: types::WithRoles,
+    for<'t> types::LendT<'t, HAST>: types::WithMetrics,
     HAST::IdN: types::NodeId<IdN = HAST::IdN>,
 {
     type Acc = Acc;
@@ -98,6 +99,7 @@ where
         + tree_gen::WithChildren<HAST::IdN>,
     for<'c> &'c Acc: tree_gen::WithLabel<L = &'c str>,
     for<'t> types::LendT<'t, HAST>: types::WithRoles,
+    for<'t> types::LendT<'t, HAST>: types::WithMetrics,
     HAST::IdN: types::NodeId<IdN = HAST::IdN>,
 {
     const GRAPHING: bool = false;
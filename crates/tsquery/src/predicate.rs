@@ -42,6 +42,7 @@ pub type TextPredicateCaptures = PerPattern<TextPredicateCapture>;
 pub type GeneralPredicates = PerPattern<QueryPredicate>;
 pub type PropertyPredicates = PerPattern<(tree_sitter::QueryProperty, IsPositive)>;
 pub type PropertySettings = PerPattern<tree_sitter::QueryProperty>;
+pub(crate) type MetricPredicates = PerPattern<MetricPredicate>;
 type IsPositive = bool;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -55,6 +56,113 @@ pub struct QueryPredicate {
     pub args: Box<[QueryPredicateArg]>,
 }
 
+/// A numerical property of a node, compared by predicates such as `#size>?`
+#[derive(Debug, PartialEq, Clone)]
+pub enum Metric {
+    Size,
+    Height,
+    Lines,
+    /// `#metric>? @c "name" value`, eg. a metric derived by a preprocessing script
+    Named(Box<str>),
+}
+
+/// Compares the `metric` of the nodes of `capture` with `value`
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct MetricPredicate {
+    pub(crate) capture: crate::CaptureId,
+    pub(crate) metric: Metric,
+    pub(crate) greater: bool,
+    pub(crate) value: f64,
+}
+
+impl MetricPredicate {
+    /// `#size>?`, `#height>?`, `#lines>?` and `#metric>?`, or their `<?` counterparts
+    pub(crate) fn parse_operator(operator: &str) -> Option<(&str, bool)> {
+        let (name, greater) = if let Some(name) = operator.strip_suffix(GREATER) {
+            (name, true)
+        } else {
+            (operator.strip_suffix(LESS)?, false)
+        };
+        matches!(name, "size" | "height" | "lines" | "metric").then_some((name, greater))
+    }
+
+    pub(crate) fn remap(&mut self, capture_map: &[crate::CaptureId]) {
+        self.capture = capture_map[self.capture.to_usize()];
+    }
+}
+
+// tree-sitter does not accept `>` and `<` in predicate names,
+// so they are replaced in the source of queries by characters of the same length,
+// these spellings are reserved, see [`escape_metric_predicates`]
+const GREATER: &str = "!?";
+const LESS: &str = ".?";
+
+/// Renames comparisons such as `#size>?` into names accepted by tree-sitter, without changing offsets.
+///
+/// Fails on the escaped spellings, eg. `#size!?`, when written in `source`.
+pub(crate) fn escape_metric_predicates(
+    source: &str,
+) -> Result<std::borrow::Cow<'_, str>, crate::QueryError> {
+    if ![">?", "<?", GREATER, LESS]
+        .iter()
+        .any(|x| source.contains(x))
+    {
+        return Ok(source.into());
+    }
+    let mut result = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        result.push(c);
+        match c {
+            // skip string literals and comments
+            '"' => {
+                while let Some(c) = chars.next() {
+                    result.push(c);
+                    if c == '\\' {
+                        result.extend(chars.next());
+                    } else if c == '"' {
+                        break;
+                    }
+                }
+            }
+            ';' => {
+                while let Some(c) = chars.next_if(|c| *c != '\n') {
+                    result.push(c);
+                }
+            }
+            '#' => {
+                // the characters of identifiers in tree-sitter queries
+                let is_ident = |c: &char| c.is_alphanumeric() || "_-.?!".contains(*c);
+                let start = result.len();
+                while let Some(c) = chars.next_if(is_ident) {
+                    result.push(c);
+                }
+                let name = &result[start..];
+                if let Some((metric, greater)) = MetricPredicate::parse_operator(name) {
+                    let row = result.matches('\n').count();
+                    let op = if greater { ">?" } else { "<?" };
+                    let message = format!("Invalid predicate #{name}, use #{metric}{op} instead");
+                    return Err(crate::predicate_error(row, message));
+                }
+                let escaped = match chars.peek() {
+                    Some('>') => GREATER,
+                    Some('<') => LESS,
+                    _ => continue,
+                };
+                let mut lookahead = chars.clone();
+                lookahead.next();
+                if lookahead.peek() == Some(&'?') {
+                    result.push_str(escaped);
+                    chars = lookahead;
+                    chars.next();
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok(result.into())
+}
+
 impl<P: Debug> PerPattern<P> {
     pub fn preds_for_patern_id<'a>(
         &'a self,
@@ -135,6 +243,12 @@ impl Clone for PerPattern<tree_sitter::QueryProperty> {
     }
 }
 
+impl Clone for PerPattern<MetricPredicate> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
 impl Clone for PerPattern<ImmediateTextPredicate> {
     fn clone(&self) -> Self {
         Self(self.0.clone())
@@ -24,6 +24,8 @@ use crate::QueryErrorKind;
 use crate::ffi;
 use crate::indexed;
 use crate::indexed::PredStepId;
+use crate::predicate::Metric;
+use crate::predicate::MetricPredicate;
 use crate::predicate::PerPatternBuilder;
 use crate::predicate_error;
 use crate::utils::Array;
@@ -767,6 +769,13 @@ impl Query {
     ) -> impl Iterator<Item = &'a TextPredicateCapture> {
         self.text_predicates.preds_for_patern_id(pattern_index)
     }
+
+    pub(crate) fn metric_predicates_for_pattern_id<'a>(
+        &'a self,
+        pattern_index: indexed::PatternId,
+    ) -> impl Iterator<Item = &'a MetricPredicate> {
+        self.metric_predicates.preds_for_patern_id(pattern_index)
+    }
 }
 
 impl Query {
@@ -854,6 +863,12 @@ impl Query {
                 }
             }
             query.text_predicates.extend(q.text_predicates);
+            for p in q.metric_predicates.iter_mut() {
+                for p in p {
+                    p.remap(&capture_map);
+                }
+            }
+            query.metric_predicates.extend(q.metric_predicates);
             q.property_predicates.check_empty();
             query.property_predicates.extend(q.property_predicates);
             q.general_predicates.check_empty();
@@ -869,7 +884,9 @@ impl Query {
         Ok(query)
     }
     pub fn new(source: &str, language: Language) -> Result<Self, QueryError> {
-        let ptr: *mut ffi::TSQuery = Self::init_tsquery(source, language)?;
+        // same offsets, thus `source` remains valid for the rest of the parsing
+        let escaped = crate::predicate::escape_metric_predicates(source)?;
+        let ptr: *mut ffi::TSQuery = Self::init_tsquery(&escaped, language)?;
         let query: *mut super::ffi_extra::TSQuery = unsafe { std::mem::transmute(ptr) };
         let ptr = {
             struct TSQueryDrop(*mut ffi::TSQuery);
@@ -893,6 +910,7 @@ impl Query {
         let mut property_predicates_vec = PerPatternBuilder::with_patt_count(pattern_count);
         let mut property_settings_vec = PerPatternBuilder::with_patt_count(pattern_count);
         let mut general_predicates_vec = PerPatternBuilder::with_patt_count(pattern_count);
+        let mut metric_predicates_vec = PerPatternBuilder::with_patt_count(pattern_count);
         let mut immediate_predicates = vec![];
         let mut immediate_pred_steps = vec![];
 
@@ -964,6 +982,7 @@ impl Query {
             property_predicates_vec.prep();
             property_settings_vec.prep();
             general_predicates_vec.prep();
+            metric_predicates_vec.prep();
             let mut immediate_matches_calls = vec![];
             for p in predicate_steps.split(|s| s.type_ == TYPE_DONE) {
                 if p.is_empty() {
@@ -1136,6 +1155,17 @@ impl Query {
                         immediate_matches_calls.push((operator_name, p1));
                         // dbg!(&immediate_matches_calls);
                     }
+                    op if MetricPredicate::parse_operator(op).is_some() => {
+                        let (metric, greater) = MetricPredicate::parse_operator(op).unwrap();
+                        metric_predicates_vec.push(Self::parse_metric_predicate(
+                            row,
+                            metric,
+                            greater,
+                            &capture_names,
+                            &string_values,
+                            &p[1..],
+                        )?)
+                    }
                     _ => general_predicates_vec.push(crate::predicate::QueryPredicate {
                         operator: operator_name.to_string().into(),
                         args: p[1..]
@@ -1180,6 +1210,7 @@ impl Query {
 
         let text_predicates = text_predicates_vec.build();
         let general_predicates = general_predicates_vec.build();
+        let metric_predicates = metric_predicates_vec.build();
         let property_predicates = property_predicates_vec.build();
         let property_settings = property_settings_vec.build();
 
@@ -1235,6 +1266,7 @@ impl Query {
             capture_quantifiers_vec,
             text_predicates,
             general_predicates,
+            metric_predicates,
             property_predicates,
             property_settings,
             immediate_predicates,
@@ -1474,6 +1506,24 @@ impl Query {
         let i = self.enabled_pattern_map[pid.to_usize()];
         (i != u16::MAX).then_some(i)
     }
+    /// The matches of a local pattern only depend on the subtree where they start,
+    /// ie. its root is a single node, neither a wildcard nor constrained by a field or a supertype,
+    /// so they can be counted once per subtree, see [`crate::match_cache`]
+    pub fn is_local_pattern(&self, pid: PatternId) -> bool {
+        if self.patterns[pid].is_non_local {
+            return false;
+        }
+        self.pattern_map
+            .iter()
+            .filter(|pattern| pattern.pattern_index == pid)
+            .all(|pattern| {
+                let step = &self.steps[pattern.step_index];
+                let constrained = step.is_wildcard()
+                    || step.field().is_some()
+                    || step.supertype_symbol().is_some();
+                pattern.is_rooted && (step.done() || !constrained)
+            })
+    }
     pub fn with_one_pattern_enabled(mut self, i: u16) -> Result<Self, Self> {
         if i == u16::MAX
             || self.enabled_pattern_count() == 0
@@ -1579,6 +1629,70 @@ impl Query {
         }
     }
 
+    /// `(#size>? @c 200)`, or `(#metric>? @c "name" 10)` for named metrics
+    fn parse_metric_predicate(
+        row: usize,
+        metric: &str,
+        greater: bool,
+        capture_names: &[&str],
+        string_values: &[&str],
+        args: &[ffi::TSQueryPredicateStep],
+    ) -> Result<MetricPredicate, QueryError> {
+        let function_name = format!("{metric}{}", if greater { ">?" } else { "<?" });
+        let expected = if metric == "metric" { 3 } else { 2 };
+        if args.len() != expected {
+            return Err(predicate_error(
+                row,
+                format!(
+                    "Wrong number of arguments to #{function_name} predicate. Expected {expected}, got {}.",
+                    args.len()
+                ),
+            ));
+        }
+        if args[0].type_ != ffi::TSQueryPredicateStepTypeCapture {
+            return Err(predicate_error(
+                row,
+                format!(
+                    "First argument to #{function_name} predicate must be a capture name. Got literal \"{}\".",
+                    string_values[args[0].value_id as usize],
+                ),
+            ));
+        }
+        let mut literals = args[1..].iter().map(|arg| {
+            if arg.type_ == ffi::TSQueryPredicateStepTypeCapture {
+                return Err(predicate_error(
+                    row,
+                    format!(
+                        "Arguments to #{function_name} predicate must be literals. Got capture @{}.",
+                        capture_names[arg.value_id as usize],
+                    ),
+                ));
+            }
+            Ok(string_values[arg.value_id as usize])
+        });
+        let metric = match metric {
+            "size" => Metric::Size,
+            "height" => Metric::Height,
+            "lines" => Metric::Lines,
+            _ => Metric::Named(literals.next().unwrap()?.into()),
+        };
+        let value = literals.next().unwrap()?;
+        let value = value.parse().map_err(|_| {
+            predicate_error(
+                row,
+                format!(
+                    "Last argument to #{function_name} predicate must be a number. Got \"{value}\"."
+                ),
+            )
+        })?;
+        Ok(MetricPredicate {
+            capture: CaptureId::new(args[0].value_id),
+            metric,
+            greater,
+            value,
+        })
+    }
+
     fn compute_immediate_preds(
         i: usize,
         max_pattern_byte: usize,
@@ -1867,6 +1981,7 @@ impl Debug for Query {
             .field("property_predicates", &self.property_predicates)
             .field("property_settings", &self.property_settings)
             .field("general_predicates", &self.general_predicates)
+            .field("metric_predicates", &self.metric_predicates)
             .field("immediate_predicates", &self.immediate_predicates)
             .field("precomputed_patterns", &self.precomputed_patterns)
             .field("used_precomputed", &self.used_precomputed)
@@ -2117,6 +2232,53 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_local_patterns() {
+        let is_local = |s: &str| {
+            let query = Query::new(s, tree_sitter_java::language()).unwrap();
+            query.is_local_pattern(PatternId::new(0))
+        };
+        assert!(is_local("(class_declaration)"));
+        assert!(is_local("(method_declaration name: (identifier) @name)"));
+        assert!(!is_local("(_ (block)) @x"));
+        assert!(!is_local("((import_declaration) (class_declaration))"));
+    }
+
+    #[test]
+    fn test_metric_preds() {
+        let preds = |s: &str| {
+            let query = Query::new(s, tree_sitter_java::language()).unwrap();
+            let preds = query.metric_predicates_for_pattern_id(PatternId::new(0));
+            preds
+                .map(|p| (p.metric.clone(), p.greater, p.value))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            preds("((method_declaration) @m (#size>? @m 200))"),
+            vec![(Metric::Size, true, 200.0)]
+        );
+        assert_eq!(
+            preds("((block) @b (#height>? @b 5) (#lines<? @b 30))"),
+            vec![(Metric::Height, true, 5.0), (Metric::Lines, false, 30.0)]
+        );
+        assert_eq!(
+            preds("((method_declaration) @m (#metric>? @m \"mcc\" 10))"),
+            vec![(Metric::Named("mcc".into()), true, 10.0)]
+        );
+        // not touched within strings and comments
+        assert!(preds("((string_literal) @s (#eq? @s \"#size>?\")) ; #size>?").is_empty());
+        assert!(Query::new("((block) @b (#size>? @b))", tree_sitter_java::language()).is_err());
+        // the escaped spellings are reserved
+        let Err(err) = Query::new("((block) @b (#size!? @b 3))", tree_sitter_java::language())
+        else {
+            panic!("#size!? should be rejected")
+        };
+        assert_eq!(err.kind, QueryErrorKind::Predicate);
+        assert!(Query::new("((block) @b (#lines.? @b 3))", tree_sitter_java::language()).is_err());
+        // but not within strings
+        assert!(preds("((string_literal) @s (#eq? @s \"#size!?\"))").is_empty());
+    }
 }
 
 #[allow(unused)]
@@ -2,8 +2,8 @@
 //! The query matcher used here is largely inspired by tree_sitter (query.c).
 
 use hyperast::types::{
-    HyperAST, HyperASTShared, RoleStore, WithPrecompQueries, WithRoles, WithSerialization,
-    WithStats,
+    HyperAST, HyperASTShared, RoleStore, WithMetrics, WithPrecompQueries, WithRoles,
+    WithSerialization, WithStats,
 };
 use std::{fmt::Debug, hash::Hash};
 #[cfg(feature = "tsg")]
@@ -46,7 +46,8 @@ impl<'tree, HAST: HyperAST> crate::Node for Node<'tree, HAST>
 where
     HAST::IdN: std::fmt::Debug + Copy,
     HAST::TS: RoleStore,
-    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles + WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT:
+        WithRoles + WithPrecompQueries + WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
@@ -90,6 +91,10 @@ where
     ) -> super::BiCow<'s, 'l, str> {
         self.0.text(text_provider)
     }
+
+    fn metric(&self, metric: &crate::Metric) -> Option<f64> {
+        self.0.metric(metric)
+    }
 }
 
 impl<'tree, HAST: HyperAST> crate::WithField for Node<'tree, HAST>
@@ -105,6 +110,7 @@ where
     HAST::IdN: std::fmt::Debug + Copy,
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles + WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     type NR = Self;
@@ -116,6 +122,7 @@ where
     HAST::IdN: std::fmt::Debug + Copy,
     HAST::TS: RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles + WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     HAST::IdN: hyperast::types::NodeId<IdN = HAST::IdN>,
 {
     type Node = Self;
@@ -6,8 +6,8 @@ use hyperast::{
     position::TreePathMut,
     tree_gen::{self, WithLabel},
     types::{
-        self, ETypeStore, HyperAST, HyperASTShared, Role, RoleStore, WithRoles, WithSerialization,
-        WithStats,
+        self, ETypeStore, HyperAST, HyperASTShared, Role, RoleStore, WithMetrics, WithRoles,
+        WithSerialization, WithStats,
     },
 };
 use std::{fmt::Debug, vec};
@@ -92,6 +92,7 @@ where
     HAST::TS: ETypeStore<Ty2 = Acc::Type>,
     HAST::TS: hyperast::types::RoleStore<IdF = IdF, Role = Role>,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithRoles,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: hyperast::types::WithMetrics,
     HAST::IdN: Copy,
     Acc: tree_gen::WithChildren<HAST::IdN> + tree_gen::WithRole<Role> + types::Typed,
     &'acc Acc: WithLabel,
@@ -137,6 +138,10 @@ where
     ) -> super::BiCow<'s, 'l, str> {
         self.0.text(text_provider)
     }
+
+    fn metric(&self, metric: &crate::Metric) -> Option<f64> {
+        self.0.metric(metric)
+    }
 }
 
 impl<'acc, HAST: HyperAST, Acc> crate::WithField for Node<HAST, &'acc Acc>
@@ -152,6 +157,7 @@ where
     HAST::TS: ETypeStore<Ty2 = Acc::Type> + RoleStore<IdF = IdF, Role = Role>,
     HAST::IdN: Copy,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     Acc: hyperast::tree_gen::WithRole<Role>,
     Acc: hyperast::tree_gen::WithChildren<HAST::IdN>,
     Acc: hyperast::types::Typed,
@@ -167,6 +173,7 @@ where
     HAST::TS: ETypeStore<Ty2 = Acc::Type> + RoleStore<IdF = IdF, Role = Role>,
     HAST::IdN: Copy,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     Acc: hyperast::tree_gen::WithRole<Role>,
     Acc: hyperast::tree_gen::WithChildren<HAST::IdN>,
     Acc: hyperast::types::Typed,
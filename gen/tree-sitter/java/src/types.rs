@@ -175,6 +175,8 @@ impl LangRef<Type> for Java {
             &Type::Spaces
         } else if t == TStore::DIRECTORY {
             &Type::Directory
+        } else if t == TStore::META_DIR {
+            &Type::GradleDirectory
         } else {
             &S_T_L[t as usize]
         }
@@ -242,7 +244,7 @@ impl HyperType for Type {
     }
 
     fn is_directory(&self) -> bool {
-        self == &Type::Directory
+        self == &Type::Directory || self == &Type::GradleDirectory
     }
 
     fn is_file(&self) -> bool {
@@ -1045,6 +1047,7 @@ pub enum Type {
     FormalParametersRepeat1,
     ReceiverParameterRepeat1,
     TypeIdentifier,
+    GradleDirectory = TStore::META_DIR,
     Directory = TStore::DIRECTORY,
     Spaces = TStore::SPACES,
     _ERROR = TStore::_ERROR,
@@ -1374,6 +1377,7 @@ impl Type {
             318u16 => Type::FormalParametersRepeat1,
             319u16 => Type::ReceiverParameterRepeat1,
             320u16 => Type::TypeIdentifier,
+            TStore::META_DIR => Type::GradleDirectory,
             TStore::DIRECTORY => Type::Directory,
             TStore::SPACES => Type::Spaces,
             TStore::_ERROR => Type::_ERROR,
@@ -1706,6 +1710,7 @@ impl Type {
             "formal_parameters_repeat1" => Type::FormalParametersRepeat1,
             "receiver_parameter_repeat1" => Type::ReceiverParameterRepeat1,
             "type_identifier" => Type::TypeIdentifier,
+            "GradleDirectory" => Type::GradleDirectory,
             "Directory" => Type::Directory,
             "Spaces" => Type::Spaces,
             "_ERROR" => Type::_ERROR,
@@ -2036,6 +2041,7 @@ impl Type {
             Type::FormalParametersRepeat1 => "formal_parameters_repeat1",
             Type::ReceiverParameterRepeat1 => "receiver_parameter_repeat1",
             Type::TypeIdentifier => "type_identifier",
+            Type::GradleDirectory => "GradleDirectory",
             Type::Directory => "Directory",
             Type::Spaces => "Spaces",
             Type::_ERROR => "_ERROR",
@@ -0,0 +1,59 @@
+use hyperast::tree_gen::NoOpMore;
+use tree_sitter::Parser;
+
+use crate::{legion::tree_sitter_parse, types::TStore};
+
+type PythonTreeGen<'store, 'cache, HAST, Acc> =
+    crate::legion::PythonTreeGen<'store, 'cache, TStore, NoOpMore<HAST, Acc>, true>;
+type SimpleStores = hyperast::store::SimpleStores<TStore>;
+
+static EX: &str = r#"
+import os
+
+
+class Walker:
+    def __init__(self, root):
+        self.root = root
+
+    def walk(self):
+        for d, _, files in os.walk(self.root):
+            if files:
+                yield d, \
+                    files
+        # done
+"#;
+
+#[test]
+pub(crate) fn python_tree_sitter_simple() {
+    let mut parser = Parser::new();
+
+    {
+        parser.set_language(&crate::language()).unwrap();
+    }
+
+    let text = { EX.as_bytes() };
+    let tree = parser.parse(text, None).unwrap();
+    println!("{}", tree.root_node().to_sexp());
+}
+
+#[test]
+pub(crate) fn python_simple_test() {
+    let text = { EX.as_bytes() };
+    let tree = match tree_sitter_parse(text) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    println!("{:#?}", tree.root_node().to_sexp());
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = PythonTreeGen::new(&mut stores, &mut md_cache);
+    let x = tree_gen.generate_file(b"", text, tree.walk()).local;
+    let x = x.compressed_node;
+    println!("{}", hyperast::nodes::SyntaxSerializer::new(&stores, x));
+    println!("{}", hyperast::nodes::SexpSerializer::new(&stores, x));
+    // indentation is significant, it must be reproduced verbatim
+    assert_eq!(
+        hyperast::nodes::TextSerializer::new(&stores, x).to_string(),
+        EX
+    );
+}
@@ -0,0 +1,2267 @@
+use std::fmt::Display;
+
+use hyperast::{
+    tree_gen::utils_ts::TsEnableTS,
+    types::{
+        AAAA, AnyType, HyperType, LangRef, NodeId, TypeStore, TypeTrait, TypeU16, TypedNodeId,
+    },
+};
+
+#[cfg(feature = "impl")]
+mod legion_impls {
+
+    use super::*;
+    use hyperast::tree_gen::utils_ts::TsEnableTS;
+    use hyperast::tree_gen::utils_ts::TsType;
+
+    use hyperast::types::{LangWrapper, RoleStore};
+
+    impl TsEnableTS for TStore {
+        fn obtain_type<'a, N: hyperast::tree_gen::parser::NodeWithU16TypeId>(
+            n: &N,
+        ) -> <Self as hyperast::types::ETypeStore>::Ty2 {
+            let k = n.kind_id();
+            // kinds unknown to the generated types come from another version of the grammar
+            Type::from_u16(k).unwrap_or(Type::ERROR)
+        }
+
+        fn try_obtain_type<N: hyperast::tree_gen::parser::NodeWithU16TypeId>(
+            n: &N,
+        ) -> Option<Self::Ty2> {
+            let k = n.kind_id();
+            Type::from_u16(k)
+        }
+    }
+
+    impl TsType for Type {
+        fn spaces() -> Self {
+            Self::Spaces
+        }
+
+        fn is_repeat(&self) -> bool {
+            self.is_repeat()
+        }
+    }
+
+    impl TypeStore for TStore {
+        type Ty = TypeU16<Python>;
+    }
+    impl<'a> PythonEnabledTypeStore for TStore {
+        fn resolve(t: Self::Ty) -> Type {
+            t.e()
+        }
+    }
+
+    impl<'a> hyperast::types::ETypeStore for TStore {
+        type Ty2 = Type;
+
+        fn intern(ty: Self::Ty2) -> Self::Ty {
+            TType::new(ty)
+        }
+    }
+
+    impl RoleStore for TStore {
+        type IdF = u16;
+
+        type Role = hyperast::types::Role;
+
+        fn resolve_field(_lang: LangWrapper<Self::Ty>, field_id: Self::IdF) -> Self::Role {
+            let s = crate::language()
+                .field_name_for_id(field_id)
+                .ok_or_else(|| format!("{}", field_id))
+                .unwrap();
+            hyperast::types::Role::try_from(s).expect(s)
+        }
+
+        fn intern_role(_lang: LangWrapper<Self::Ty>, role: Self::Role) -> Self::IdF {
+            let field_name = role.to_string();
+            crate::language()
+                .field_id_for_name(field_name)
+                .unwrap()
+                .into()
+        }
+    }
+}
+
+#[cfg(feature = "impl")]
+fn id_for_node_kind(kind: &str, named: bool) -> u16 {
+    crate::language().id_for_node_kind(kind, named)
+}
+#[cfg(not(feature = "impl"))]
+fn id_for_node_kind(kind: &str, named: bool) -> u16 {
+    unimplemented!("need treesitter grammar")
+}
+
+#[cfg(feature = "impl")]
+pub trait PythonEnabledTypeStore:
+    hyperast::types::ETypeStore<Ty2 = Type> + Clone + hyperast::tree_gen::utils_ts::TsEnableTS
+{
+    // fn intern(t: Type) -> Self::Ty;
+    fn resolve(t: Self::Ty) -> Type;
+}
+
+#[cfg(not(feature = "impl"))]
+pub trait PythonEnabledTypeStore: TypeStore {
+    // fn intern(t: Type) -> Self::Ty;
+    fn resolve(t: Self::Ty) -> Type;
+}
+
+impl Type {
+    pub fn resolve(t: u16) -> Self {
+        assert!(t < COUNT);
+        unsafe { std::mem::transmute(t) }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct TIdN<IdN>(IdN);
+
+impl<IdN: Clone + Eq + hyperast::types::AAAA + std::hash::Hash> NodeId for TIdN<IdN> {
+    type IdN = IdN;
+
+    fn as_id(&self) -> &Self::IdN {
+        &self.0
+    }
+
+    unsafe fn from_id(id: Self::IdN) -> Self {
+        Self(id)
+    }
+
+    unsafe fn from_ref_id(_id: &Self::IdN) -> &Self {
+        todo!()
+    }
+}
+
+impl<IdN: Clone + Eq + AAAA + std::hash::Hash> TypedNodeId for TIdN<IdN> {
+    type Ty = Type;
+    type TyErazed = TType;
+    fn unerase(ty: Self::TyErazed) -> Self::Ty {
+        ty.e()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct TStore;
+
+impl Default for TStore {
+    fn default() -> Self {
+        Self
+    }
+}
+
+type TypeInternalSize = u16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct T(TypeInternalSize);
+
+#[derive(Debug)]
+pub struct Lang;
+pub type Python = Lang;
+
+impl Python {
+    pub const INST: Python = Lang;
+}
+
+pub fn as_any(t: &Type) -> AnyType {
+    let t = <Python as hyperast::types::Lang<Type>>::to_u16(*t);
+    let t = <Python as hyperast::types::Lang<Type>>::make(t);
+    let t: &'static dyn HyperType = t;
+    t.into()
+}
+
+impl LangRef<AnyType> for Python {
+    fn make(&self, _t: u16) -> &'static AnyType {
+        panic!()
+        // &From::<&'static dyn HyperType>::from(&S_T_L[t as usize])
+    }
+    fn to_u16(&self, t: AnyType) -> u16 {
+        // t as u16
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Python>()
+    }
+
+    fn ts_symbol(&self, t: AnyType) -> u16 {
+        // TODO check lang
+        id_for_node_kind(t.as_static_str(), t.is_named())
+    }
+}
+
+impl LangRef<Type> for Python {
+    fn make(&self, t: u16) -> &'static Type {
+        if t == TStore::ERROR {
+            &Type::ERROR
+        } else if t == TStore::_ERROR {
+            &Type::_ERROR
+        } else if t == TStore::SPACES {
+            &Type::Spaces
+        } else if t == TStore::DIRECTORY {
+            &Type::Directory
+        } else {
+            &S_T_L[t as usize]
+        }
+    }
+    fn to_u16(&self, t: Type) -> u16 {
+        t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Python>()
+    }
+
+    fn ts_symbol(&self, t: Type) -> u16 {
+        id_for_node_kind(t.as_static_str(), t.is_named())
+    }
+}
+
+impl LangRef<TType> for Lang {
+    fn make(&self, t: u16) -> &'static TType {
+        // TODO could make one safe, but not priority
+        unsafe { std::mem::transmute(&S_T_L[t as usize]) }
+    }
+    fn to_u16(&self, t: TType) -> u16 {
+        t.e() as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Lang>()
+    }
+
+    fn ts_symbol(&self, t: TType) -> u16 {
+        id_for_node_kind(t.as_static_str(), t.is_named())
+    }
+}
+
+impl hyperast::types::Lang<Type> for Python {
+    fn make(t: u16) -> &'static Type {
+        Lang.make(t)
+    }
+    fn to_u16(t: Type) -> u16 {
+        Lang.to_u16(t)
+    }
+}
+
+pub use hyperast::types::Role;
+
+impl HyperType for Type {
+    fn generic_eq(&self, other: &dyn HyperType) -> bool
+    where
+        Self: 'static + PartialEq + Sized,
+    {
+        // Do a type-safe casting. If the types are different,
+        // return false, otherwise test the values for equality.
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn is_directory(&self) -> bool {
+        self == &Type::Directory
+    }
+    fn is_file(&self) -> bool {
+        self == &Type::Module
+    }
+
+    fn is_spaces(&self) -> bool {
+        self == &Type::Spaces
+    }
+
+    fn is_syntax(&self) -> bool {
+        self == &Type::LParen // "("
+            || self == &Type::RParen // ")"
+            || self == &Type::Comma // ","
+            || self == &Type::Dot // "."
+            || self == &Type::Colon // ":"
+            || self == &Type::LBracket // "["
+            || self == &Type::RBracket // "]"
+            || self == &Type::LBrace // "{"
+            || self == &Type::RBrace // "}"
+            || self == &Type::Eq // "="
+            || self == &Type::DashGt // "->"
+            || self == &Type::At // "@"
+            || self == &Type::Def // "def"
+            || self == &Type::Class // "class"
+            || self == &Type::Import // "import"
+            || self == &Type::From // "from"
+            || self == &Type::As // "as"
+            || self == &Type::If // "if"
+            || self == &Type::Elif // "elif"
+            || self == &Type::Else // "else"
+            || self == &Type::For // "for"
+            || self == &Type::In // "in"
+            || self == &Type::While // "while"
+            || self == &Type::Try // "try"
+            || self == &Type::Except // "except"
+            || self == &Type::Finally // "finally"
+            || self == &Type::With // "with"
+            || self == &Type::Return // "return"
+            || self == &Type::Lambda_ // "lambda"
+            || self == &Type::Yield_ // "yield"
+            || self == &Type::Await_ // "await"
+            || self == &Type::Async // "async"
+            || self == &Type::Match // "match"
+            || self == &Type::Case // "case"
+            || self == &Type::Pass // "pass"
+            || self == &Type::Del // "del"
+            || self == &Type::Raise // "raise"
+            || self == &Type::Assert // "assert"
+            || self == &Type::Global // "global"
+            || self == &Type::Nonlocal // "nonlocal"
+    }
+
+    fn is_statement(&self) -> bool {
+        self.is_declarative_statement()
+            || self.is_structural_statement()
+            || self.is_simple_statement()
+            || self.is_block_related()
+    }
+
+    fn as_shared(&self) -> hyperast::types::Shared {
+        use hyperast::types::Shared;
+        match self {
+            Type::ClassDefinition => Shared::TypeDeclaration,
+            Type::Comment => Shared::Comment,
+            Type::Identifier => Shared::Identifier,
+            _ => Shared::Other,
+        }
+    }
+
+    fn as_abstract(&self) -> hyperast::types::Abstracts {
+        use hyperast::types::Abstract;
+        Abstract::Expression.when(self.is_expression())
+            | Abstract::Statement.when(HyperType::is_statement(self))
+            | Abstract::Executable.when(self.is_executable_member())
+            | Abstract::Declaration.when(self.is_type_declaration())
+            | Abstract::Literal.when(self.is_literal())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_static(&self) -> &'static dyn HyperType {
+        let t = <Python as hyperast::types::Lang<Type>>::to_u16(*self);
+        let t = <Python as hyperast::types::Lang<Type>>::make(t);
+        t
+    }
+
+    fn as_static_str(&self) -> &'static str {
+        self.to_str()
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.is_hidden()
+    }
+
+    fn is_supertype(&self) -> bool {
+        self.is_supertype()
+    }
+
+    fn is_named(&self) -> bool {
+        self.is_named()
+    }
+    fn get_lang(&self) -> hyperast::types::LangWrapper<Self>
+    where
+        Self: Sized,
+    {
+        hyperast::types::LangWrapper::from(&Lang as &(dyn LangRef<Self> + 'static))
+    }
+
+    fn lang_ref(&self) -> hyperast::types::LangWrapper<AnyType> {
+        hyperast::types::LangWrapper::from(&Lang as &(dyn LangRef<AnyType> + 'static))
+    }
+}
+impl TypeTrait for Type {
+    type Lang = Python;
+
+    fn is_fork(&self) -> bool {
+        false
+    }
+
+    fn is_literal(&self) -> bool {
+        match self {
+            Type::Integer
+            | Type::Float
+            | Type::String
+            | Type::ConcatenatedString
+            | Type::True
+            | Type::False
+            | Type::None
+            | Type::Ellipsis => true,
+            _ => false,
+        }
+    }
+
+    fn is_primitive(&self) -> bool {
+        false
+    }
+
+    fn is_type_declaration(&self) -> bool {
+        self == &Type::ClassDefinition
+    }
+
+    fn is_identifier(&self) -> bool {
+        self == &Type::Identifier
+    }
+
+    fn is_instance_ref(&self) -> bool {
+        false
+    }
+
+    fn is_type_body(&self) -> bool {
+        false
+    }
+
+    fn is_value_member(&self) -> bool {
+        false
+    }
+
+    fn is_executable_member(&self) -> bool {
+        self == &Type::FunctionDefinition
+    }
+
+    fn is_statement(&self) -> bool {
+        HyperType::is_statement(self)
+    }
+
+    fn is_declarative_statement(&self) -> bool {
+        match self {
+            Type::ImportStatement
+            | Type::ImportFromStatement
+            | Type::FutureImportStatement
+            | Type::GlobalStatement
+            | Type::NonlocalStatement
+            | Type::TypeAliasStatement => true,
+            _ => false,
+        }
+    }
+
+    fn is_structural_statement(&self) -> bool {
+        match self {
+            Type::IfStatement
+            | Type::ForStatement
+            | Type::WhileStatement
+            | Type::TryStatement
+            | Type::WithStatement
+            | Type::MatchStatement
+            | Type::FunctionDefinition
+            | Type::ClassDefinition
+            | Type::DecoratedDefinition => true,
+            _ => false,
+        }
+    }
+
+    fn is_block_related(&self) -> bool {
+        match self {
+            Type::Block
+            | Type::ElifClause
+            | Type::ElseClause
+            | Type::ExceptClause
+            | Type::ExceptGroupClause
+            | Type::FinallyClause
+            | Type::CaseClause => true,
+            _ => false,
+        }
+    }
+
+    fn is_simple_statement(&self) -> bool {
+        match self {
+            Type::ExpressionStatement
+            | Type::ReturnStatement
+            | Type::DeleteStatement
+            | Type::RaiseStatement
+            | Type::PassStatement
+            | Type::BreakStatement
+            | Type::ContinueStatement
+            | Type::AssertStatement
+            | Type::PrintStatement
+            | Type::ExecStatement => true,
+            _ => false,
+        }
+    }
+
+    fn is_local_declare(&self) -> bool {
+        false
+    }
+
+    fn is_parameter(&self) -> bool {
+        match self {
+            Type::Identifier
+            | Type::DefaultParameter
+            | Type::TypedParameter
+            | Type::TypedDefaultParameter
+            | Type::ListSplatPattern
+            | Type::DictionarySplatPattern => true,
+            _ => false,
+        }
+    }
+
+    fn is_parameter_list(&self) -> bool {
+        self == &Type::Parameters || self == &Type::LambdaParameters
+    }
+
+    fn is_argument_list(&self) -> bool {
+        self == &Type::ArgumentList
+    }
+
+    fn is_expression(&self) -> bool {
+        match self {
+            Type::NamedExpression
+            | Type::NotOperator
+            | Type::BooleanOperator
+            | Type::BinaryOperator
+            | Type::UnaryOperator
+            | Type::ComparisonOperator
+            | Type::Lambda
+            | Type::ConditionalExpression
+            | Type::Await
+            | Type::Attribute
+            | Type::Subscript
+            | Type::Call
+            | Type::List
+            | Type::Set
+            | Type::Tuple
+            | Type::Dictionary
+            | Type::ListComprehension
+            | Type::DictionaryComprehension
+            | Type::SetComprehension
+            | Type::GeneratorExpression
+            | Type::ParenthesizedExpression => true,
+            x => x.is_literal(),
+        }
+    }
+
+    fn is_comment(&self) -> bool {
+        self == &Type::Comment
+    }
+}
+
+const COUNT: u16 = 275;
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl TryFrom<&str> for Type {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Type::from_str(value).ok_or(())
+    }
+}
+
+impl Type {
+    pub(crate) fn is_repeat(&self) -> bool {
+        *self == Type::ModuleRepeat1
+            || *self == Type::_SimpleStatementsRepeat1
+            || *self == Type::ImportPrefixRepeat1
+            || *self == Type::_ImportListRepeat1
+            || *self == Type::PrintStatementRepeat1
+            || *self == Type::AssertStatementRepeat1
+            || *self == Type::IfStatementRepeat1
+            || *self == Type::MatchStatementRepeat1
+            || *self == Type::_MatchBlockRepeat1
+            || *self == Type::CaseClauseRepeat1
+            || *self == Type::TryStatementRepeat1
+            || *self == Type::TryStatementRepeat2
+            || *self == Type::WithClauseRepeat1
+            || *self == Type::GlobalStatementRepeat1
+            || *self == Type::TypeParameterRepeat1
+            || *self == Type::ArgumentListRepeat1
+            || *self == Type::DecoratedDefinitionRepeat1
+            || *self == Type::DottedNameRepeat1
+            || *self == Type::UnionPatternRepeat1
+            || *self == Type::DictPatternRepeat1
+            || *self == Type::_ParametersRepeat1
+            || *self == Type::_PatternsRepeat1
+            || *self == Type::ComparisonOperatorRepeat1
+            || *self == Type::SubscriptRepeat1
+            || *self == Type::DictionaryRepeat1
+            || *self == Type::_ComprehensionClausesRepeat1
+            || *self == Type::_CollectionElementsRepeat1
+            || *self == Type::ForInClauseRepeat1
+            || *self == Type::ConcatenatedStringRepeat1
+            || *self == Type::StringRepeat1
+            || *self == Type::StringContentRepeat1
+            || *self == Type::FormatSpecifierRepeat1
+    }
+
+    /// tokens emitted by the external scanner to delimit logical lines and blocks
+    pub(crate) fn is_layout(&self) -> bool {
+        *self == Type::_Newline || *self == Type::_Indent || *self == Type::_Dedent
+    }
+}
+
+impl hyperast::types::LLang<TType> for Python {
+    type I = u16;
+
+    type E = Type;
+
+    const TE: &[Self::E] = S_T_L;
+
+    fn as_lang_wrapper() -> hyperast::types::LangWrapper<TType> {
+        From::<&'static (dyn LangRef<_>)>::from(&Lang)
+    }
+}
+
+pub type TType = TypeU16<Lang>;
+
+impl From<u16> for Type {
+    fn from(value: u16) -> Self {
+        S_T_L[value as usize]
+    }
+}
+impl Into<TypeU16<Python>> for Type {
+    fn into(self) -> TypeU16<Python> {
+        TypeU16::new(self)
+    }
+}
+
+impl Into<u16> for Type {
+    fn into(self) -> u16 {
+        self as u16
+    }
+}
+
+#[repr(u16)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Type {
+    End,
+    Identifier,
+    SemiColon,
+    Import,
+    Dot,
+    From,
+    TS0,
+    LParen,
+    RParen,
+    Comma,
+    As,
+    Star,
+    Print,
+    GtGt,
+    Assert,
+    ColonEq,
+    Return,
+    Del,
+    Raise,
+    Pass,
+    Break,
+    Continue,
+    If,
+    Colon,
+    Elif,
+    Else,
+    Match,
+    Case,
+    Async,
+    For,
+    In,
+    While,
+    Try,
+    Except,
+    TS1,
+    Finally,
+    With,
+    Def,
+    DashGt,
+    StarStar,
+    Global,
+    Nonlocal,
+    Exec,
+    Type_,
+    Eq,
+    Class,
+    LBracket,
+    RBracket,
+    At,
+    Dash,
+    TS2,
+    Pipe,
+    LBrace,
+    RBrace,
+    Plus,
+    Not,
+    And,
+    Or,
+    Slash,
+    Percent,
+    SlashSlash,
+    Amp,
+    Caret,
+    LtLt,
+    Tilde,
+    Is,
+    LT,
+    LTEq,
+    EqEq,
+    BangEq,
+    GTEq,
+    GT,
+    LtGt,
+    Lambda_,
+    PlusEq,
+    DashEq,
+    StarEq,
+    SlashEq,
+    AtEq,
+    SlashSlashEq,
+    PercentEq,
+    StarStarEq,
+    GtGtEq,
+    LtLtEq,
+    AmpEq,
+    CaretEq,
+    PipeEq,
+    Yield_,
+    Ellipsis,
+    EscapeSequence,
+    TS5,
+    FormatSpecifierToken1,
+    TypeConversion,
+    Integer,
+    Float,
+    Await_,
+    True,
+    False,
+    None,
+    Comment,
+    LineContinuation,
+    _Newline,
+    _Indent,
+    _Dedent,
+    StringStart,
+    _StringContent,
+    EscapeInterpolation,
+    StringEnd,
+    Module,
+    _Statement,
+    _SimpleStatements,
+    ImportStatement,
+    ImportPrefix,
+    RelativeImport,
+    FutureImportStatement,
+    ImportFromStatement,
+    _ImportList,
+    AliasedImport,
+    WildcardImport,
+    PrintStatement,
+    Chevron,
+    AssertStatement,
+    ExpressionStatement,
+    NamedExpression,
+    _NamedExpressionLhs,
+    ReturnStatement,
+    DeleteStatement,
+    RaiseStatement,
+    PassStatement,
+    BreakStatement,
+    ContinueStatement,
+    IfStatement,
+    ElifClause,
+    ElseClause,
+    MatchStatement,
+    Block_,
+    CaseClause,
+    ForStatement,
+    WhileStatement,
+    TryStatement,
+    ExceptClause,
+    ExceptGroupClause,
+    FinallyClause,
+    WithStatement,
+    WithClause,
+    WithItem,
+    FunctionDefinition,
+    Parameters,
+    LambdaParameters,
+    ListSplat,
+    DictionarySplat,
+    GlobalStatement,
+    NonlocalStatement,
+    ExecStatement,
+    TypeAliasStatement,
+    ClassDefinition,
+    TypeParameter,
+    ParenthesizedListSplat,
+    ArgumentList,
+    DecoratedDefinition,
+    Decorator,
+    Block,
+    ExpressionList,
+    DottedName,
+    CasePattern,
+    _SimplePattern,
+    AsPattern_,
+    UnionPattern,
+    ListPattern_,
+    TuplePattern_,
+    DictPattern,
+    _KeyValuePattern,
+    KeywordPattern,
+    SplatPattern,
+    ClassPattern,
+    ComplexPattern,
+    _Parameters,
+    _Patterns,
+    Parameter,
+    Pattern,
+    TuplePattern,
+    ListPattern,
+    DefaultParameter,
+    TypedDefaultParameter,
+    ListSplatPattern,
+    DictionarySplatPattern,
+    AsPattern,
+    _ExpressionWithinForInClause,
+    Expression,
+    PrimaryExpression,
+    NotOperator,
+    BooleanOperator,
+    BinaryOperator,
+    UnaryOperator,
+    TS4,
+    TS3,
+    ComparisonOperator,
+    Lambda,
+    Lambda__,
+    Assignment,
+    AugmentedAssignment,
+    PatternList,
+    _RightHandSide,
+    Yield,
+    Attribute,
+    Subscript,
+    Slice,
+    Call,
+    TypedParameter,
+    Type,
+    SplatType,
+    GenericType,
+    UnionType,
+    ConstrainedType,
+    MemberType,
+    KeywordArgument,
+    List,
+    Set,
+    Tuple,
+    Dictionary,
+    Pair,
+    ListComprehension,
+    DictionaryComprehension,
+    SetComprehension,
+    GeneratorExpression,
+    _ComprehensionClauses,
+    ParenthesizedExpression,
+    _CollectionElements,
+    ForInClause,
+    IfClause,
+    ConditionalExpression,
+    ConcatenatedString,
+    String,
+    StringContent,
+    Interpolation,
+    _FExpression,
+    _NotEscapeSequence,
+    FormatSpecifier,
+    Await,
+    PositionalSeparator,
+    KeywordSeparator,
+    ModuleRepeat1,
+    _SimpleStatementsRepeat1,
+    ImportPrefixRepeat1,
+    _ImportListRepeat1,
+    PrintStatementRepeat1,
+    AssertStatementRepeat1,
+    IfStatementRepeat1,
+    MatchStatementRepeat1,
+    _MatchBlockRepeat1,
+    CaseClauseRepeat1,
+    TryStatementRepeat1,
+    TryStatementRepeat2,
+    WithClauseRepeat1,
+    GlobalStatementRepeat1,
+    TypeParameterRepeat1,
+    ArgumentListRepeat1,
+    DecoratedDefinitionRepeat1,
+    DottedNameRepeat1,
+    UnionPatternRepeat1,
+    DictPatternRepeat1,
+    _ParametersRepeat1,
+    _PatternsRepeat1,
+    ComparisonOperatorRepeat1,
+    SubscriptRepeat1,
+    DictionaryRepeat1,
+    _ComprehensionClausesRepeat1,
+    _CollectionElementsRepeat1,
+    ForInClauseRepeat1,
+    ConcatenatedStringRepeat1,
+    StringRepeat1,
+    StringContentRepeat1,
+    FormatSpecifierRepeat1,
+    AsPatternTarget,
+    FormatExpression,
+    Directory = TStore::DIRECTORY,
+    Spaces = TStore::SPACES,
+    _ERROR = TStore::_ERROR,
+    ERROR = TStore::ERROR,
+}
+
+impl Type {
+    pub fn from_u16(t: u16) -> Option<Type> {
+        Some(match t {
+            0u16 => Type::End,
+            1u16 => Type::Identifier,
+            2u16 => Type::SemiColon,
+            3u16 => Type::Import,
+            4u16 => Type::Dot,
+            5u16 => Type::From,
+            6u16 => Type::TS0,
+            7u16 => Type::LParen,
+            8u16 => Type::RParen,
+            9u16 => Type::Comma,
+            10u16 => Type::As,
+            11u16 => Type::Star,
+            12u16 => Type::Print,
+            13u16 => Type::GtGt,
+            14u16 => Type::Assert,
+            15u16 => Type::ColonEq,
+            16u16 => Type::Return,
+            17u16 => Type::Del,
+            18u16 => Type::Raise,
+            19u16 => Type::Pass,
+            20u16 => Type::Break,
+            21u16 => Type::Continue,
+            22u16 => Type::If,
+            23u16 => Type::Colon,
+            24u16 => Type::Elif,
+            25u16 => Type::Else,
+            26u16 => Type::Match,
+            27u16 => Type::Case,
+            28u16 => Type::Async,
+            29u16 => Type::For,
+            30u16 => Type::In,
+            31u16 => Type::While,
+            32u16 => Type::Try,
+            33u16 => Type::Except,
+            34u16 => Type::TS1,
+            35u16 => Type::Finally,
+            36u16 => Type::With,
+            37u16 => Type::Def,
+            38u16 => Type::DashGt,
+            39u16 => Type::StarStar,
+            40u16 => Type::Global,
+            41u16 => Type::Nonlocal,
+            42u16 => Type::Exec,
+            43u16 => Type::Type_,
+            44u16 => Type::Eq,
+            45u16 => Type::Class,
+            46u16 => Type::LBracket,
+            47u16 => Type::RBracket,
+            48u16 => Type::At,
+            49u16 => Type::Dash,
+            50u16 => Type::TS2,
+            51u16 => Type::Pipe,
+            52u16 => Type::LBrace,
+            53u16 => Type::RBrace,
+            54u16 => Type::Plus,
+            55u16 => Type::Not,
+            56u16 => Type::And,
+            57u16 => Type::Or,
+            58u16 => Type::Slash,
+            59u16 => Type::Percent,
+            60u16 => Type::SlashSlash,
+            61u16 => Type::Amp,
+            62u16 => Type::Caret,
+            63u16 => Type::LtLt,
+            64u16 => Type::Tilde,
+            65u16 => Type::Is,
+            66u16 => Type::LT,
+            67u16 => Type::LTEq,
+            68u16 => Type::EqEq,
+            69u16 => Type::BangEq,
+            70u16 => Type::GTEq,
+            71u16 => Type::GT,
+            72u16 => Type::LtGt,
+            73u16 => Type::Lambda_,
+            74u16 => Type::PlusEq,
+            75u16 => Type::DashEq,
+            76u16 => Type::StarEq,
+            77u16 => Type::SlashEq,
+            78u16 => Type::AtEq,
+            79u16 => Type::SlashSlashEq,
+            80u16 => Type::PercentEq,
+            81u16 => Type::StarStarEq,
+            82u16 => Type::GtGtEq,
+            83u16 => Type::LtLtEq,
+            84u16 => Type::AmpEq,
+            85u16 => Type::CaretEq,
+            86u16 => Type::PipeEq,
+            87u16 => Type::Yield_,
+            88u16 => Type::Ellipsis,
+            89u16 => Type::EscapeSequence,
+            90u16 => Type::TS5,
+            91u16 => Type::FormatSpecifierToken1,
+            92u16 => Type::TypeConversion,
+            93u16 => Type::Integer,
+            94u16 => Type::Float,
+            95u16 => Type::Await_,
+            96u16 => Type::True,
+            97u16 => Type::False,
+            98u16 => Type::None,
+            99u16 => Type::Comment,
+            100u16 => Type::LineContinuation,
+            101u16 => Type::_Newline,
+            102u16 => Type::_Indent,
+            103u16 => Type::_Dedent,
+            104u16 => Type::StringStart,
+            105u16 => Type::_StringContent,
+            106u16 => Type::EscapeInterpolation,
+            107u16 => Type::StringEnd,
+            108u16 => Type::Module,
+            109u16 => Type::_Statement,
+            110u16 => Type::_SimpleStatements,
+            111u16 => Type::ImportStatement,
+            112u16 => Type::ImportPrefix,
+            113u16 => Type::RelativeImport,
+            114u16 => Type::FutureImportStatement,
+            115u16 => Type::ImportFromStatement,
+            116u16 => Type::_ImportList,
+            117u16 => Type::AliasedImport,
+            118u16 => Type::WildcardImport,
+            119u16 => Type::PrintStatement,
+            120u16 => Type::Chevron,
+            121u16 => Type::AssertStatement,
+            122u16 => Type::ExpressionStatement,
+            123u16 => Type::NamedExpression,
+            124u16 => Type::_NamedExpressionLhs,
+            125u16 => Type::ReturnStatement,
+            126u16 => Type::DeleteStatement,
+            127u16 => Type::RaiseStatement,
+            128u16 => Type::PassStatement,
+            129u16 => Type::BreakStatement,
+            130u16 => Type::ContinueStatement,
+            131u16 => Type::IfStatement,
+            132u16 => Type::ElifClause,
+            133u16 => Type::ElseClause,
+            134u16 => Type::MatchStatement,
+            135u16 => Type::Block_,
+            136u16 => Type::CaseClause,
+            137u16 => Type::ForStatement,
+            138u16 => Type::WhileStatement,
+            139u16 => Type::TryStatement,
+            140u16 => Type::ExceptClause,
+            141u16 => Type::ExceptGroupClause,
+            142u16 => Type::FinallyClause,
+            143u16 => Type::WithStatement,
+            144u16 => Type::WithClause,
+            145u16 => Type::WithItem,
+            146u16 => Type::FunctionDefinition,
+            147u16 => Type::Parameters,
+            148u16 => Type::LambdaParameters,
+            149u16 => Type::ListSplat,
+            150u16 => Type::DictionarySplat,
+            151u16 => Type::GlobalStatement,
+            152u16 => Type::NonlocalStatement,
+            153u16 => Type::ExecStatement,
+            154u16 => Type::TypeAliasStatement,
+            155u16 => Type::ClassDefinition,
+            156u16 => Type::TypeParameter,
+            157u16 => Type::ParenthesizedListSplat,
+            158u16 => Type::ArgumentList,
+            159u16 => Type::DecoratedDefinition,
+            160u16 => Type::Decorator,
+            161u16 => Type::Block,
+            162u16 => Type::ExpressionList,
+            163u16 => Type::DottedName,
+            164u16 => Type::CasePattern,
+            165u16 => Type::_SimplePattern,
+            166u16 => Type::AsPattern_,
+            167u16 => Type::UnionPattern,
+            168u16 => Type::ListPattern_,
+            169u16 => Type::TuplePattern_,
+            170u16 => Type::DictPattern,
+            171u16 => Type::_KeyValuePattern,
+            172u16 => Type::KeywordPattern,
+            173u16 => Type::SplatPattern,
+            174u16 => Type::ClassPattern,
+            175u16 => Type::ComplexPattern,
+            176u16 => Type::_Parameters,
+            177u16 => Type::_Patterns,
+            178u16 => Type::Parameter,
+            179u16 => Type::Pattern,
+            180u16 => Type::TuplePattern,
+            181u16 => Type::ListPattern,
+            182u16 => Type::DefaultParameter,
+            183u16 => Type::TypedDefaultParameter,
+            184u16 => Type::ListSplatPattern,
+            185u16 => Type::DictionarySplatPattern,
+            186u16 => Type::AsPattern,
+            187u16 => Type::_ExpressionWithinForInClause,
+            188u16 => Type::Expression,
+            189u16 => Type::PrimaryExpression,
+            190u16 => Type::NotOperator,
+            191u16 => Type::BooleanOperator,
+            192u16 => Type::BinaryOperator,
+            193u16 => Type::UnaryOperator,
+            194u16 => Type::TS4,
+            195u16 => Type::TS3,
+            196u16 => Type::ComparisonOperator,
+            197u16 => Type::Lambda,
+            198u16 => Type::Lambda__,
+            199u16 => Type::Assignment,
+            200u16 => Type::AugmentedAssignment,
+            201u16 => Type::PatternList,
+            202u16 => Type::_RightHandSide,
+            203u16 => Type::Yield,
+            204u16 => Type::Attribute,
+            205u16 => Type::Subscript,
+            206u16 => Type::Slice,
+            207u16 => Type::Call,
+            208u16 => Type::TypedParameter,
+            209u16 => Type::Type,
+            210u16 => Type::SplatType,
+            211u16 => Type::GenericType,
+            212u16 => Type::UnionType,
+            213u16 => Type::ConstrainedType,
+            214u16 => Type::MemberType,
+            215u16 => Type::KeywordArgument,
+            216u16 => Type::List,
+            217u16 => Type::Set,
+            218u16 => Type::Tuple,
+            219u16 => Type::Dictionary,
+            220u16 => Type::Pair,
+            221u16 => Type::ListComprehension,
+            222u16 => Type::DictionaryComprehension,
+            223u16 => Type::SetComprehension,
+            224u16 => Type::GeneratorExpression,
+            225u16 => Type::_ComprehensionClauses,
+            226u16 => Type::ParenthesizedExpression,
+            227u16 => Type::_CollectionElements,
+            228u16 => Type::ForInClause,
+            229u16 => Type::IfClause,
+            230u16 => Type::ConditionalExpression,
+            231u16 => Type::ConcatenatedString,
+            232u16 => Type::String,
+            233u16 => Type::StringContent,
+            234u16 => Type::Interpolation,
+            235u16 => Type::_FExpression,
+            236u16 => Type::_NotEscapeSequence,
+            237u16 => Type::FormatSpecifier,
+            238u16 => Type::Await,
+            239u16 => Type::PositionalSeparator,
+            240u16 => Type::KeywordSeparator,
+            241u16 => Type::ModuleRepeat1,
+            242u16 => Type::_SimpleStatementsRepeat1,
+            243u16 => Type::ImportPrefixRepeat1,
+            244u16 => Type::_ImportListRepeat1,
+            245u16 => Type::PrintStatementRepeat1,
+            246u16 => Type::AssertStatementRepeat1,
+            247u16 => Type::IfStatementRepeat1,
+            248u16 => Type::MatchStatementRepeat1,
+            249u16 => Type::_MatchBlockRepeat1,
+            250u16 => Type::CaseClauseRepeat1,
+            251u16 => Type::TryStatementRepeat1,
+            252u16 => Type::TryStatementRepeat2,
+            253u16 => Type::WithClauseRepeat1,
+            254u16 => Type::GlobalStatementRepeat1,
+            255u16 => Type::TypeParameterRepeat1,
+            256u16 => Type::ArgumentListRepeat1,
+            257u16 => Type::DecoratedDefinitionRepeat1,
+            258u16 => Type::DottedNameRepeat1,
+            259u16 => Type::UnionPatternRepeat1,
+            260u16 => Type::DictPatternRepeat1,
+            261u16 => Type::_ParametersRepeat1,
+            262u16 => Type::_PatternsRepeat1,
+            263u16 => Type::ComparisonOperatorRepeat1,
+            264u16 => Type::SubscriptRepeat1,
+            265u16 => Type::DictionaryRepeat1,
+            266u16 => Type::_ComprehensionClausesRepeat1,
+            267u16 => Type::_CollectionElementsRepeat1,
+            268u16 => Type::ForInClauseRepeat1,
+            269u16 => Type::ConcatenatedStringRepeat1,
+            270u16 => Type::StringRepeat1,
+            271u16 => Type::StringContentRepeat1,
+            272u16 => Type::FormatSpecifierRepeat1,
+            273u16 => Type::AsPatternTarget,
+            274u16 => Type::FormatExpression,
+            TStore::DIRECTORY => Type::Directory,
+            TStore::SPACES => Type::Spaces,
+            TStore::_ERROR => Type::_ERROR,
+            TStore::ERROR => Type::ERROR,
+            _ => return None,
+        })
+    }
+    #[allow(unreachable_patterns)]
+    pub fn from_str(t: &str) -> Option<Type> {
+        Some(match t {
+            "end" => Type::End,
+            "identifier" => Type::Identifier,
+            ";" => Type::SemiColon,
+            "import" => Type::Import,
+            "." => Type::Dot,
+            "from" => Type::From,
+            "__future__" => Type::TS0,
+            "(" => Type::LParen,
+            ")" => Type::RParen,
+            "," => Type::Comma,
+            "as" => Type::As,
+            "*" => Type::Star,
+            "print" => Type::Print,
+            ">>" => Type::GtGt,
+            "assert" => Type::Assert,
+            ":=" => Type::ColonEq,
+            "return" => Type::Return,
+            "del" => Type::Del,
+            "raise" => Type::Raise,
+            "pass" => Type::Pass,
+            "break" => Type::Break,
+            "continue" => Type::Continue,
+            "if" => Type::If,
+            ":" => Type::Colon,
+            "elif" => Type::Elif,
+            "else" => Type::Else,
+            "match" => Type::Match,
+            "case" => Type::Case,
+            "async" => Type::Async,
+            "for" => Type::For,
+            "in" => Type::In,
+            "while" => Type::While,
+            "try" => Type::Try,
+            "except" => Type::Except,
+            "except*" => Type::TS1,
+            "finally" => Type::Finally,
+            "with" => Type::With,
+            "def" => Type::Def,
+            "->" => Type::DashGt,
+            "**" => Type::StarStar,
+            "global" => Type::Global,
+            "nonlocal" => Type::Nonlocal,
+            "exec" => Type::Exec,
+            "type" => Type::Type_,
+            "=" => Type::Eq,
+            "class" => Type::Class,
+            "[" => Type::LBracket,
+            "]" => Type::RBracket,
+            "@" => Type::At,
+            "-" => Type::Dash,
+            "_" => Type::TS2,
+            "|" => Type::Pipe,
+            "{" => Type::LBrace,
+            "}" => Type::RBrace,
+            "+" => Type::Plus,
+            "not" => Type::Not,
+            "and" => Type::And,
+            "or" => Type::Or,
+            "/" => Type::Slash,
+            "%" => Type::Percent,
+            "//" => Type::SlashSlash,
+            "&" => Type::Amp,
+            "^" => Type::Caret,
+            "<<" => Type::LtLt,
+            "~" => Type::Tilde,
+            "is" => Type::Is,
+            "<" => Type::LT,
+            "<=" => Type::LTEq,
+            "==" => Type::EqEq,
+            "!=" => Type::BangEq,
+            ">=" => Type::GTEq,
+            ">" => Type::GT,
+            "<>" => Type::LtGt,
+            "lambda" => Type::Lambda_,
+            "+=" => Type::PlusEq,
+            "-=" => Type::DashEq,
+            "*=" => Type::StarEq,
+            "/=" => Type::SlashEq,
+            "@=" => Type::AtEq,
+            "//=" => Type::SlashSlashEq,
+            "%=" => Type::PercentEq,
+            "**=" => Type::StarStarEq,
+            ">>=" => Type::GtGtEq,
+            "<<=" => Type::LtLtEq,
+            "&=" => Type::AmpEq,
+            "^=" => Type::CaretEq,
+            "|=" => Type::PipeEq,
+            "yield" => Type::Yield_,
+            "ellipsis" => Type::Ellipsis,
+            "escape_sequence" => Type::EscapeSequence,
+            "\\" => Type::TS5,
+            "format_specifier_token1" => Type::FormatSpecifierToken1,
+            "type_conversion" => Type::TypeConversion,
+            "integer" => Type::Integer,
+            "float" => Type::Float,
+            "await" => Type::Await_,
+            "true" => Type::True,
+            "false" => Type::False,
+            "none" => Type::None,
+            "comment" => Type::Comment,
+            "line_continuation" => Type::LineContinuation,
+            "_newline" => Type::_Newline,
+            "_indent" => Type::_Indent,
+            "_dedent" => Type::_Dedent,
+            "string_start" => Type::StringStart,
+            "_string_content" => Type::_StringContent,
+            "escape_interpolation" => Type::EscapeInterpolation,
+            "string_end" => Type::StringEnd,
+            "module" => Type::Module,
+            "_statement" => Type::_Statement,
+            "_simple_statements" => Type::_SimpleStatements,
+            "import_statement" => Type::ImportStatement,
+            "import_prefix" => Type::ImportPrefix,
+            "relative_import" => Type::RelativeImport,
+            "future_import_statement" => Type::FutureImportStatement,
+            "import_from_statement" => Type::ImportFromStatement,
+            "_import_list" => Type::_ImportList,
+            "aliased_import" => Type::AliasedImport,
+            "wildcard_import" => Type::WildcardImport,
+            "print_statement" => Type::PrintStatement,
+            "chevron" => Type::Chevron,
+            "assert_statement" => Type::AssertStatement,
+            "expression_statement" => Type::ExpressionStatement,
+            "named_expression" => Type::NamedExpression,
+            "_named_expression_lhs" => Type::_NamedExpressionLhs,
+            "return_statement" => Type::ReturnStatement,
+            "delete_statement" => Type::DeleteStatement,
+            "raise_statement" => Type::RaiseStatement,
+            "pass_statement" => Type::PassStatement,
+            "break_statement" => Type::BreakStatement,
+            "continue_statement" => Type::ContinueStatement,
+            "if_statement" => Type::IfStatement,
+            "elif_clause" => Type::ElifClause,
+            "else_clause" => Type::ElseClause,
+            "match_statement" => Type::MatchStatement,
+            "block" => Type::Block_,
+            "case_clause" => Type::CaseClause,
+            "for_statement" => Type::ForStatement,
+            "while_statement" => Type::WhileStatement,
+            "try_statement" => Type::TryStatement,
+            "except_clause" => Type::ExceptClause,
+            "except_group_clause" => Type::ExceptGroupClause,
+            "finally_clause" => Type::FinallyClause,
+            "with_statement" => Type::WithStatement,
+            "with_clause" => Type::WithClause,
+            "with_item" => Type::WithItem,
+            "function_definition" => Type::FunctionDefinition,
+            "parameters" => Type::Parameters,
+            "lambda_parameters" => Type::LambdaParameters,
+            "list_splat" => Type::ListSplat,
+            "dictionary_splat" => Type::DictionarySplat,
+            "global_statement" => Type::GlobalStatement,
+            "nonlocal_statement" => Type::NonlocalStatement,
+            "exec_statement" => Type::ExecStatement,
+            "type_alias_statement" => Type::TypeAliasStatement,
+            "class_definition" => Type::ClassDefinition,
+            "type_parameter" => Type::TypeParameter,
+            "parenthesized_list_splat" => Type::ParenthesizedListSplat,
+            "argument_list" => Type::ArgumentList,
+            "decorated_definition" => Type::DecoratedDefinition,
+            "decorator" => Type::Decorator,
+            "block" => Type::Block,
+            "expression_list" => Type::ExpressionList,
+            "dotted_name" => Type::DottedName,
+            "case_pattern" => Type::CasePattern,
+            "_simple_pattern" => Type::_SimplePattern,
+            "as_pattern" => Type::AsPattern_,
+            "union_pattern" => Type::UnionPattern,
+            "list_pattern" => Type::ListPattern_,
+            "tuple_pattern" => Type::TuplePattern_,
+            "dict_pattern" => Type::DictPattern,
+            "_key_value_pattern" => Type::_KeyValuePattern,
+            "keyword_pattern" => Type::KeywordPattern,
+            "splat_pattern" => Type::SplatPattern,
+            "class_pattern" => Type::ClassPattern,
+            "complex_pattern" => Type::ComplexPattern,
+            "_parameters" => Type::_Parameters,
+            "_patterns" => Type::_Patterns,
+            "parameter" => Type::Parameter,
+            "pattern" => Type::Pattern,
+            "tuple_pattern" => Type::TuplePattern,
+            "list_pattern" => Type::ListPattern,
+            "default_parameter" => Type::DefaultParameter,
+            "typed_default_parameter" => Type::TypedDefaultParameter,
+            "list_splat_pattern" => Type::ListSplatPattern,
+            "dictionary_splat_pattern" => Type::DictionarySplatPattern,
+            "as_pattern" => Type::AsPattern,
+            "_expression_within_for_in_clause" => Type::_ExpressionWithinForInClause,
+            "expression" => Type::Expression,
+            "primary_expression" => Type::PrimaryExpression,
+            "not_operator" => Type::NotOperator,
+            "boolean_operator" => Type::BooleanOperator,
+            "binary_operator" => Type::BinaryOperator,
+            "unary_operator" => Type::UnaryOperator,
+            "not in" => Type::TS4,
+            "is not" => Type::TS3,
+            "comparison_operator" => Type::ComparisonOperator,
+            "lambda" => Type::Lambda,
+            "lambda" => Type::Lambda__,
+            "assignment" => Type::Assignment,
+            "augmented_assignment" => Type::AugmentedAssignment,
+            "pattern_list" => Type::PatternList,
+            "_right_hand_side" => Type::_RightHandSide,
+            "yield" => Type::Yield,
+            "attribute" => Type::Attribute,
+            "subscript" => Type::Subscript,
+            "slice" => Type::Slice,
+            "call" => Type::Call,
+            "typed_parameter" => Type::TypedParameter,
+            "type" => Type::Type,
+            "splat_type" => Type::SplatType,
+            "generic_type" => Type::GenericType,
+            "union_type" => Type::UnionType,
+            "constrained_type" => Type::ConstrainedType,
+            "member_type" => Type::MemberType,
+            "keyword_argument" => Type::KeywordArgument,
+            "list" => Type::List,
+            "set" => Type::Set,
+            "tuple" => Type::Tuple,
+            "dictionary" => Type::Dictionary,
+            "pair" => Type::Pair,
+            "list_comprehension" => Type::ListComprehension,
+            "dictionary_comprehension" => Type::DictionaryComprehension,
+            "set_comprehension" => Type::SetComprehension,
+            "generator_expression" => Type::GeneratorExpression,
+            "_comprehension_clauses" => Type::_ComprehensionClauses,
+            "parenthesized_expression" => Type::ParenthesizedExpression,
+            "_collection_elements" => Type::_CollectionElements,
+            "for_in_clause" => Type::ForInClause,
+            "if_clause" => Type::IfClause,
+            "conditional_expression" => Type::ConditionalExpression,
+            "concatenated_string" => Type::ConcatenatedString,
+            "string" => Type::String,
+            "string_content" => Type::StringContent,
+            "interpolation" => Type::Interpolation,
+            "_f_expression" => Type::_FExpression,
+            "_not_escape_sequence" => Type::_NotEscapeSequence,
+            "format_specifier" => Type::FormatSpecifier,
+            "await" => Type::Await,
+            "positional_separator" => Type::PositionalSeparator,
+            "keyword_separator" => Type::KeywordSeparator,
+            "module_repeat1" => Type::ModuleRepeat1,
+            "_simple_statements_repeat1" => Type::_SimpleStatementsRepeat1,
+            "import_prefix_repeat1" => Type::ImportPrefixRepeat1,
+            "_import_list_repeat1" => Type::_ImportListRepeat1,
+            "print_statement_repeat1" => Type::PrintStatementRepeat1,
+            "assert_statement_repeat1" => Type::AssertStatementRepeat1,
+            "if_statement_repeat1" => Type::IfStatementRepeat1,
+            "match_statement_repeat1" => Type::MatchStatementRepeat1,
+            "_match_block_repeat1" => Type::_MatchBlockRepeat1,
+            "case_clause_repeat1" => Type::CaseClauseRepeat1,
+            "try_statement_repeat1" => Type::TryStatementRepeat1,
+            "try_statement_repeat2" => Type::TryStatementRepeat2,
+            "with_clause_repeat1" => Type::WithClauseRepeat1,
+            "global_statement_repeat1" => Type::GlobalStatementRepeat1,
+            "type_parameter_repeat1" => Type::TypeParameterRepeat1,
+            "argument_list_repeat1" => Type::ArgumentListRepeat1,
+            "decorated_definition_repeat1" => Type::DecoratedDefinitionRepeat1,
+            "dotted_name_repeat1" => Type::DottedNameRepeat1,
+            "union_pattern_repeat1" => Type::UnionPatternRepeat1,
+            "dict_pattern_repeat1" => Type::DictPatternRepeat1,
+            "_parameters_repeat1" => Type::_ParametersRepeat1,
+            "_patterns_repeat1" => Type::_PatternsRepeat1,
+            "comparison_operator_repeat1" => Type::ComparisonOperatorRepeat1,
+            "subscript_repeat1" => Type::SubscriptRepeat1,
+            "dictionary_repeat1" => Type::DictionaryRepeat1,
+            "_comprehension_clauses_repeat1" => Type::_ComprehensionClausesRepeat1,
+            "_collection_elements_repeat1" => Type::_CollectionElementsRepeat1,
+            "for_in_clause_repeat1" => Type::ForInClauseRepeat1,
+            "concatenated_string_repeat1" => Type::ConcatenatedStringRepeat1,
+            "string_repeat1" => Type::StringRepeat1,
+            "string_content_repeat1" => Type::StringContentRepeat1,
+            "format_specifier_repeat1" => Type::FormatSpecifierRepeat1,
+            "as_pattern_target" => Type::AsPatternTarget,
+            "format_expression" => Type::FormatExpression,
+            "Directory" => Type::Directory,
+            "Spaces" => Type::Spaces,
+            "_ERROR" => Type::_ERROR,
+            "ERROR" => Type::ERROR,
+            _ => return None,
+        })
+    }
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Type::End => "end",
+            Type::Identifier => "identifier",
+            Type::SemiColon => ";",
+            Type::Import => "import",
+            Type::Dot => ".",
+            Type::From => "from",
+            Type::TS0 => "__future__",
+            Type::LParen => "(",
+            Type::RParen => ")",
+            Type::Comma => ",",
+            Type::As => "as",
+            Type::Star => "*",
+            Type::Print => "print",
+            Type::GtGt => ">>",
+            Type::Assert => "assert",
+            Type::ColonEq => ":=",
+            Type::Return => "return",
+            Type::Del => "del",
+            Type::Raise => "raise",
+            Type::Pass => "pass",
+            Type::Break => "break",
+            Type::Continue => "continue",
+            Type::If => "if",
+            Type::Colon => ":",
+            Type::Elif => "elif",
+            Type::Else => "else",
+            Type::Match => "match",
+            Type::Case => "case",
+            Type::Async => "async",
+            Type::For => "for",
+            Type::In => "in",
+            Type::While => "while",
+            Type::Try => "try",
+            Type::Except => "except",
+            Type::TS1 => "except*",
+            Type::Finally => "finally",
+            Type::With => "with",
+            Type::Def => "def",
+            Type::DashGt => "->",
+            Type::StarStar => "**",
+            Type::Global => "global",
+            Type::Nonlocal => "nonlocal",
+            Type::Exec => "exec",
+            Type::Type_ => "type",
+            Type::Eq => "=",
+            Type::Class => "class",
+            Type::LBracket => "[",
+            Type::RBracket => "]",
+            Type::At => "@",
+            Type::Dash => "-",
+            Type::TS2 => "_",
+            Type::Pipe => "|",
+            Type::LBrace => "{",
+            Type::RBrace => "}",
+            Type::Plus => "+",
+            Type::Not => "not",
+            Type::And => "and",
+            Type::Or => "or",
+            Type::Slash => "/",
+            Type::Percent => "%",
+            Type::SlashSlash => "//",
+            Type::Amp => "&",
+            Type::Caret => "^",
+            Type::LtLt => "<<",
+            Type::Tilde => "~",
+            Type::Is => "is",
+            Type::LT => "<",
+            Type::LTEq => "<=",
+            Type::EqEq => "==",
+            Type::BangEq => "!=",
+            Type::GTEq => ">=",
+            Type::GT => ">",
+            Type::LtGt => "<>",
+            Type::Lambda_ => "lambda",
+            Type::PlusEq => "+=",
+            Type::DashEq => "-=",
+            Type::StarEq => "*=",
+            Type::SlashEq => "/=",
+            Type::AtEq => "@=",
+            Type::SlashSlashEq => "//=",
+            Type::PercentEq => "%=",
+            Type::StarStarEq => "**=",
+            Type::GtGtEq => ">>=",
+            Type::LtLtEq => "<<=",
+            Type::AmpEq => "&=",
+            Type::CaretEq => "^=",
+            Type::PipeEq => "|=",
+            Type::Yield_ => "yield",
+            Type::Ellipsis => "ellipsis",
+            Type::EscapeSequence => "escape_sequence",
+            Type::TS5 => "\\",
+            Type::FormatSpecifierToken1 => "format_specifier_token1",
+            Type::TypeConversion => "type_conversion",
+            Type::Integer => "integer",
+            Type::Float => "float",
+            Type::Await_ => "await",
+            Type::True => "true",
+            Type::False => "false",
+            Type::None => "none",
+            Type::Comment => "comment",
+            Type::LineContinuation => "line_continuation",
+            Type::_Newline => "_newline",
+            Type::_Indent => "_indent",
+            Type::_Dedent => "_dedent",
+            Type::StringStart => "string_start",
+            Type::_StringContent => "_string_content",
+            Type::EscapeInterpolation => "escape_interpolation",
+            Type::StringEnd => "string_end",
+            Type::Module => "module",
+            Type::_Statement => "_statement",
+            Type::_SimpleStatements => "_simple_statements",
+            Type::ImportStatement => "import_statement",
+            Type::ImportPrefix => "import_prefix",
+            Type::RelativeImport => "relative_import",
+            Type::FutureImportStatement => "future_import_statement",
+            Type::ImportFromStatement => "import_from_statement",
+            Type::_ImportList => "_import_list",
+            Type::AliasedImport => "aliased_import",
+            Type::WildcardImport => "wildcard_import",
+            Type::PrintStatement => "print_statement",
+            Type::Chevron => "chevron",
+            Type::AssertStatement => "assert_statement",
+            Type::ExpressionStatement => "expression_statement",
+            Type::NamedExpression => "named_expression",
+            Type::_NamedExpressionLhs => "_named_expression_lhs",
+            Type::ReturnStatement => "return_statement",
+            Type::DeleteStatement => "delete_statement",
+            Type::RaiseStatement => "raise_statement",
+            Type::PassStatement => "pass_statement",
+            Type::BreakStatement => "break_statement",
+            Type::ContinueStatement => "continue_statement",
+            Type::IfStatement => "if_statement",
+            Type::ElifClause => "elif_clause",
+            Type::ElseClause => "else_clause",
+            Type::MatchStatement => "match_statement",
+            Type::Block_ => "block",
+            Type::CaseClause => "case_clause",
+            Type::ForStatement => "for_statement",
+            Type::WhileStatement => "while_statement",
+            Type::TryStatement => "try_statement",
+            Type::ExceptClause => "except_clause",
+            Type::ExceptGroupClause => "except_group_clause",
+            Type::FinallyClause => "finally_clause",
+            Type::WithStatement => "with_statement",
+            Type::WithClause => "with_clause",
+            Type::WithItem => "with_item",
+            Type::FunctionDefinition => "function_definition",
+            Type::Parameters => "parameters",
+            Type::LambdaParameters => "lambda_parameters",
+            Type::ListSplat => "list_splat",
+            Type::DictionarySplat => "dictionary_splat",
+            Type::GlobalStatement => "global_statement",
+            Type::NonlocalStatement => "nonlocal_statement",
+            Type::ExecStatement => "exec_statement",
+            Type::TypeAliasStatement => "type_alias_statement",
+            Type::ClassDefinition => "class_definition",
+            Type::TypeParameter => "type_parameter",
+            Type::ParenthesizedListSplat => "parenthesized_list_splat",
+            Type::ArgumentList => "argument_list",
+            Type::DecoratedDefinition => "decorated_definition",
+            Type::Decorator => "decorator",
+            Type::Block => "block",
+            Type::ExpressionList => "expression_list",
+            Type::DottedName => "dotted_name",
+            Type::CasePattern => "case_pattern",
+            Type::_SimplePattern => "_simple_pattern",
+            Type::AsPattern_ => "as_pattern",
+            Type::UnionPattern => "union_pattern",
+            Type::ListPattern_ => "list_pattern",
+            Type::TuplePattern_ => "tuple_pattern",
+            Type::DictPattern => "dict_pattern",
+            Type::_KeyValuePattern => "_key_value_pattern",
+            Type::KeywordPattern => "keyword_pattern",
+            Type::SplatPattern => "splat_pattern",
+            Type::ClassPattern => "class_pattern",
+            Type::ComplexPattern => "complex_pattern",
+            Type::_Parameters => "_parameters",
+            Type::_Patterns => "_patterns",
+            Type::Parameter => "parameter",
+            Type::Pattern => "pattern",
+            Type::TuplePattern => "tuple_pattern",
+            Type::ListPattern => "list_pattern",
+            Type::DefaultParameter => "default_parameter",
+            Type::TypedDefaultParameter => "typed_default_parameter",
+            Type::ListSplatPattern => "list_splat_pattern",
+            Type::DictionarySplatPattern => "dictionary_splat_pattern",
+            Type::AsPattern => "as_pattern",
+            Type::_ExpressionWithinForInClause => "_expression_within_for_in_clause",
+            Type::Expression => "expression",
+            Type::PrimaryExpression => "primary_expression",
+            Type::NotOperator => "not_operator",
+            Type::BooleanOperator => "boolean_operator",
+            Type::BinaryOperator => "binary_operator",
+            Type::UnaryOperator => "unary_operator",
+            Type::TS4 => "not in",
+            Type::TS3 => "is not",
+            Type::ComparisonOperator => "comparison_operator",
+            Type::Lambda => "lambda",
+            Type::Lambda__ => "lambda",
+            Type::Assignment => "assignment",
+            Type::AugmentedAssignment => "augmented_assignment",
+            Type::PatternList => "pattern_list",
+            Type::_RightHandSide => "_right_hand_side",
+            Type::Yield => "yield",
+            Type::Attribute => "attribute",
+            Type::Subscript => "subscript",
+            Type::Slice => "slice",
+            Type::Call => "call",
+            Type::TypedParameter => "typed_parameter",
+            Type::Type => "type",
+            Type::SplatType => "splat_type",
+            Type::GenericType => "generic_type",
+            Type::UnionType => "union_type",
+            Type::ConstrainedType => "constrained_type",
+            Type::MemberType => "member_type",
+            Type::KeywordArgument => "keyword_argument",
+            Type::List => "list",
+            Type::Set => "set",
+            Type::Tuple => "tuple",
+            Type::Dictionary => "dictionary",
+            Type::Pair => "pair",
+            Type::ListComprehension => "list_comprehension",
+            Type::DictionaryComprehension => "dictionary_comprehension",
+            Type::SetComprehension => "set_comprehension",
+            Type::GeneratorExpression => "generator_expression",
+            Type::_ComprehensionClauses => "_comprehension_clauses",
+            Type::ParenthesizedExpression => "parenthesized_expression",
+            Type::_CollectionElements => "_collection_elements",
+            Type::ForInClause => "for_in_clause",
+            Type::IfClause => "if_clause",
+            Type::ConditionalExpression => "conditional_expression",
+            Type::ConcatenatedString => "concatenated_string",
+            Type::String => "string",
+            Type::StringContent => "string_content",
+            Type::Interpolation => "interpolation",
+            Type::_FExpression => "_f_expression",
+            Type::_NotEscapeSequence => "_not_escape_sequence",
+            Type::FormatSpecifier => "format_specifier",
+            Type::Await => "await",
+            Type::PositionalSeparator => "positional_separator",
+            Type::KeywordSeparator => "keyword_separator",
+            Type::ModuleRepeat1 => "module_repeat1",
+            Type::_SimpleStatementsRepeat1 => "_simple_statements_repeat1",
+            Type::ImportPrefixRepeat1 => "import_prefix_repeat1",
+            Type::_ImportListRepeat1 => "_import_list_repeat1",
+            Type::PrintStatementRepeat1 => "print_statement_repeat1",
+            Type::AssertStatementRepeat1 => "assert_statement_repeat1",
+            Type::IfStatementRepeat1 => "if_statement_repeat1",
+            Type::MatchStatementRepeat1 => "match_statement_repeat1",
+            Type::_MatchBlockRepeat1 => "_match_block_repeat1",
+            Type::CaseClauseRepeat1 => "case_clause_repeat1",
+            Type::TryStatementRepeat1 => "try_statement_repeat1",
+            Type::TryStatementRepeat2 => "try_statement_repeat2",
+            Type::WithClauseRepeat1 => "with_clause_repeat1",
+            Type::GlobalStatementRepeat1 => "global_statement_repeat1",
+            Type::TypeParameterRepeat1 => "type_parameter_repeat1",
+            Type::ArgumentListRepeat1 => "argument_list_repeat1",
+            Type::DecoratedDefinitionRepeat1 => "decorated_definition_repeat1",
+            Type::DottedNameRepeat1 => "dotted_name_repeat1",
+            Type::UnionPatternRepeat1 => "union_pattern_repeat1",
+            Type::DictPatternRepeat1 => "dict_pattern_repeat1",
+            Type::_ParametersRepeat1 => "_parameters_repeat1",
+            Type::_PatternsRepeat1 => "_patterns_repeat1",
+            Type::ComparisonOperatorRepeat1 => "comparison_operator_repeat1",
+            Type::SubscriptRepeat1 => "subscript_repeat1",
+            Type::DictionaryRepeat1 => "dictionary_repeat1",
+            Type::_ComprehensionClausesRepeat1 => "_comprehension_clauses_repeat1",
+            Type::_CollectionElementsRepeat1 => "_collection_elements_repeat1",
+            Type::ForInClauseRepeat1 => "for_in_clause_repeat1",
+            Type::ConcatenatedStringRepeat1 => "concatenated_string_repeat1",
+            Type::StringRepeat1 => "string_repeat1",
+            Type::StringContentRepeat1 => "string_content_repeat1",
+            Type::FormatSpecifierRepeat1 => "format_specifier_repeat1",
+            Type::AsPatternTarget => "as_pattern_target",
+            Type::FormatExpression => "format_expression",
+            Type::Directory => "Directory",
+            Type::Spaces => "Spaces",
+            Type::_ERROR => "_ERROR",
+            Type::ERROR => "ERROR",
+        }
+    }
+    pub fn is_hidden(&self) -> bool {
+        match self {
+            Type::End => true,
+            Type::FormatSpecifierToken1 => true,
+            Type::_Newline => true,
+            Type::_Indent => true,
+            Type::_Dedent => true,
+            Type::_StringContent => true,
+            Type::_Statement => true,
+            Type::_SimpleStatements => true,
+            Type::_ImportList => true,
+            Type::_NamedExpressionLhs => true,
+            Type::_SimplePattern => true,
+            Type::_KeyValuePattern => true,
+            Type::_Parameters => true,
+            Type::_Patterns => true,
+            Type::Parameter => true,
+            Type::Pattern => true,
+            Type::_ExpressionWithinForInClause => true,
+            Type::Expression => true,
+            Type::PrimaryExpression => true,
+            Type::_RightHandSide => true,
+            Type::_ComprehensionClauses => true,
+            Type::_CollectionElements => true,
+            Type::_FExpression => true,
+            Type::_NotEscapeSequence => true,
+            Type::ModuleRepeat1 => true,
+            Type::_SimpleStatementsRepeat1 => true,
+            Type::ImportPrefixRepeat1 => true,
+            Type::_ImportListRepeat1 => true,
+            Type::PrintStatementRepeat1 => true,
+            Type::AssertStatementRepeat1 => true,
+            Type::IfStatementRepeat1 => true,
+            Type::MatchStatementRepeat1 => true,
+            Type::_MatchBlockRepeat1 => true,
+            Type::CaseClauseRepeat1 => true,
+            Type::TryStatementRepeat1 => true,
+            Type::TryStatementRepeat2 => true,
+            Type::WithClauseRepeat1 => true,
+            Type::GlobalStatementRepeat1 => true,
+            Type::TypeParameterRepeat1 => true,
+            Type::ArgumentListRepeat1 => true,
+            Type::DecoratedDefinitionRepeat1 => true,
+            Type::DottedNameRepeat1 => true,
+            Type::UnionPatternRepeat1 => true,
+            Type::DictPatternRepeat1 => true,
+            Type::_ParametersRepeat1 => true,
+            Type::_PatternsRepeat1 => true,
+            Type::ComparisonOperatorRepeat1 => true,
+            Type::SubscriptRepeat1 => true,
+            Type::DictionaryRepeat1 => true,
+            Type::_ComprehensionClausesRepeat1 => true,
+            Type::_CollectionElementsRepeat1 => true,
+            Type::ForInClauseRepeat1 => true,
+            Type::ConcatenatedStringRepeat1 => true,
+            Type::StringRepeat1 => true,
+            Type::StringContentRepeat1 => true,
+            Type::FormatSpecifierRepeat1 => true,
+            _ => false,
+        }
+    }
+    pub fn is_supertype(&self) -> bool {
+        match self {
+            Type::Parameter => true,
+            Type::Pattern => true,
+            Type::Expression => true,
+            Type::PrimaryExpression => true,
+            _ => false,
+        }
+    }
+    pub fn is_named(&self) -> bool {
+        match self {
+            Type::Identifier => true,
+            Type::Ellipsis => true,
+            Type::EscapeSequence => true,
+            Type::TypeConversion => true,
+            Type::Integer => true,
+            Type::Float => true,
+            Type::True => true,
+            Type::False => true,
+            Type::None => true,
+            Type::Comment => true,
+            Type::LineContinuation => true,
+            Type::StringStart => true,
+            Type::EscapeInterpolation => true,
+            Type::StringEnd => true,
+            Type::Module => true,
+            Type::ImportStatement => true,
+            Type::ImportPrefix => true,
+            Type::RelativeImport => true,
+            Type::FutureImportStatement => true,
+            Type::ImportFromStatement => true,
+            Type::AliasedImport => true,
+            Type::WildcardImport => true,
+            Type::PrintStatement => true,
+            Type::Chevron => true,
+            Type::AssertStatement => true,
+            Type::ExpressionStatement => true,
+            Type::NamedExpression => true,
+            Type::ReturnStatement => true,
+            Type::DeleteStatement => true,
+            Type::RaiseStatement => true,
+            Type::PassStatement => true,
+            Type::BreakStatement => true,
+            Type::ContinueStatement => true,
+            Type::IfStatement => true,
+            Type::ElifClause => true,
+            Type::ElseClause => true,
+            Type::MatchStatement => true,
+            Type::Block_ => true,
+            Type::CaseClause => true,
+            Type::ForStatement => true,
+            Type::WhileStatement => true,
+            Type::TryStatement => true,
+            Type::ExceptClause => true,
+            Type::ExceptGroupClause => true,
+            Type::FinallyClause => true,
+            Type::WithStatement => true,
+            Type::WithClause => true,
+            Type::WithItem => true,
+            Type::FunctionDefinition => true,
+            Type::Parameters => true,
+            Type::LambdaParameters => true,
+            Type::ListSplat => true,
+            Type::DictionarySplat => true,
+            Type::GlobalStatement => true,
+            Type::NonlocalStatement => true,
+            Type::ExecStatement => true,
+            Type::TypeAliasStatement => true,
+            Type::ClassDefinition => true,
+            Type::TypeParameter => true,
+            Type::ParenthesizedListSplat => true,
+            Type::ArgumentList => true,
+            Type::DecoratedDefinition => true,
+            Type::Decorator => true,
+            Type::Block => true,
+            Type::ExpressionList => true,
+            Type::DottedName => true,
+            Type::CasePattern => true,
+            Type::AsPattern_ => true,
+            Type::UnionPattern => true,
+            Type::ListPattern_ => true,
+            Type::TuplePattern_ => true,
+            Type::DictPattern => true,
+            Type::KeywordPattern => true,
+            Type::SplatPattern => true,
+            Type::ClassPattern => true,
+            Type::ComplexPattern => true,
+            Type::Parameter => true,
+            Type::Pattern => true,
+            Type::TuplePattern => true,
+            Type::ListPattern => true,
+            Type::DefaultParameter => true,
+            Type::TypedDefaultParameter => true,
+            Type::ListSplatPattern => true,
+            Type::DictionarySplatPattern => true,
+            Type::AsPattern => true,
+            Type::Expression => true,
+            Type::PrimaryExpression => true,
+            Type::NotOperator => true,
+            Type::BooleanOperator => true,
+            Type::BinaryOperator => true,
+            Type::UnaryOperator => true,
+            Type::ComparisonOperator => true,
+            Type::Lambda => true,
+            Type::Lambda__ => true,
+            Type::Assignment => true,
+            Type::AugmentedAssignment => true,
+            Type::PatternList => true,
+            Type::Yield => true,
+            Type::Attribute => true,
+            Type::Subscript => true,
+            Type::Slice => true,
+            Type::Call => true,
+            Type::TypedParameter => true,
+            Type::Type => true,
+            Type::SplatType => true,
+            Type::GenericType => true,
+            Type::UnionType => true,
+            Type::ConstrainedType => true,
+            Type::MemberType => true,
+            Type::KeywordArgument => true,
+            Type::List => true,
+            Type::Set => true,
+            Type::Tuple => true,
+            Type::Dictionary => true,
+            Type::Pair => true,
+            Type::ListComprehension => true,
+            Type::DictionaryComprehension => true,
+            Type::SetComprehension => true,
+            Type::GeneratorExpression => true,
+            Type::ParenthesizedExpression => true,
+            Type::ForInClause => true,
+            Type::IfClause => true,
+            Type::ConditionalExpression => true,
+            Type::ConcatenatedString => true,
+            Type::String => true,
+            Type::StringContent => true,
+            Type::Interpolation => true,
+            Type::FormatSpecifier => true,
+            Type::Await => true,
+            Type::PositionalSeparator => true,
+            Type::KeywordSeparator => true,
+            Type::AsPatternTarget => true,
+            Type::FormatExpression => true,
+            _ => false,
+        }
+    }
+}
+
+#[test]
+fn test_tslanguage_and_type_identity() {
+    let l = crate::language();
+    assert_eq!(l.node_kind_count(), S_T_L.len());
+    for id in 0..l.node_kind_count() {
+        let kind = l.node_kind_for_id(id as u16).unwrap();
+        let ty = Type::from_u16(id as u16).unwrap();
+        assert_eq!(ty.to_str(), kind);
+        assert_eq!(ty.is_hidden(), !l.node_kind_is_visible(id as u16));
+    }
+    assert_eq!(Type::from_u16(l.node_kind_count() as u16), None);
+}
+
+const S_T_L: &'static [Type] = &[
+    Type::End,
+    Type::Identifier,
+    Type::SemiColon,
+    Type::Import,
+    Type::Dot,
+    Type::From,
+    Type::TS0,
+    Type::LParen,
+    Type::RParen,
+    Type::Comma,
+    Type::As,
+    Type::Star,
+    Type::Print,
+    Type::GtGt,
+    Type::Assert,
+    Type::ColonEq,
+    Type::Return,
+    Type::Del,
+    Type::Raise,
+    Type::Pass,
+    Type::Break,
+    Type::Continue,
+    Type::If,
+    Type::Colon,
+    Type::Elif,
+    Type::Else,
+    Type::Match,
+    Type::Case,
+    Type::Async,
+    Type::For,
+    Type::In,
+    Type::While,
+    Type::Try,
+    Type::Except,
+    Type::TS1,
+    Type::Finally,
+    Type::With,
+    Type::Def,
+    Type::DashGt,
+    Type::StarStar,
+    Type::Global,
+    Type::Nonlocal,
+    Type::Exec,
+    Type::Type_,
+    Type::Eq,
+    Type::Class,
+    Type::LBracket,
+    Type::RBracket,
+    Type::At,
+    Type::Dash,
+    Type::TS2,
+    Type::Pipe,
+    Type::LBrace,
+    Type::RBrace,
+    Type::Plus,
+    Type::Not,
+    Type::And,
+    Type::Or,
+    Type::Slash,
+    Type::Percent,
+    Type::SlashSlash,
+    Type::Amp,
+    Type::Caret,
+    Type::LtLt,
+    Type::Tilde,
+    Type::Is,
+    Type::LT,
+    Type::LTEq,
+    Type::EqEq,
+    Type::BangEq,
+    Type::GTEq,
+    Type::GT,
+    Type::LtGt,
+    Type::Lambda_,
+    Type::PlusEq,
+    Type::DashEq,
+    Type::StarEq,
+    Type::SlashEq,
+    Type::AtEq,
+    Type::SlashSlashEq,
+    Type::PercentEq,
+    Type::StarStarEq,
+    Type::GtGtEq,
+    Type::LtLtEq,
+    Type::AmpEq,
+    Type::CaretEq,
+    Type::PipeEq,
+    Type::Yield_,
+    Type::Ellipsis,
+    Type::EscapeSequence,
+    Type::TS5,
+    Type::FormatSpecifierToken1,
+    Type::TypeConversion,
+    Type::Integer,
+    Type::Float,
+    Type::Await_,
+    Type::True,
+    Type::False,
+    Type::None,
+    Type::Comment,
+    Type::LineContinuation,
+    Type::_Newline,
+    Type::_Indent,
+    Type::_Dedent,
+    Type::StringStart,
+    Type::_StringContent,
+    Type::EscapeInterpolation,
+    Type::StringEnd,
+    Type::Module,
+    Type::_Statement,
+    Type::_SimpleStatements,
+    Type::ImportStatement,
+    Type::ImportPrefix,
+    Type::RelativeImport,
+    Type::FutureImportStatement,
+    Type::ImportFromStatement,
+    Type::_ImportList,
+    Type::AliasedImport,
+    Type::WildcardImport,
+    Type::PrintStatement,
+    Type::Chevron,
+    Type::AssertStatement,
+    Type::ExpressionStatement,
+    Type::NamedExpression,
+    Type::_NamedExpressionLhs,
+    Type::ReturnStatement,
+    Type::DeleteStatement,
+    Type::RaiseStatement,
+    Type::PassStatement,
+    Type::BreakStatement,
+    Type::ContinueStatement,
+    Type::IfStatement,
+    Type::ElifClause,
+    Type::ElseClause,
+    Type::MatchStatement,
+    Type::Block_,
+    Type::CaseClause,
+    Type::ForStatement,
+    Type::WhileStatement,
+    Type::TryStatement,
+    Type::ExceptClause,
+    Type::ExceptGroupClause,
+    Type::FinallyClause,
+    Type::WithStatement,
+    Type::WithClause,
+    Type::WithItem,
+    Type::FunctionDefinition,
+    Type::Parameters,
+    Type::LambdaParameters,
+    Type::ListSplat,
+    Type::DictionarySplat,
+    Type::GlobalStatement,
+    Type::NonlocalStatement,
+    Type::ExecStatement,
+    Type::TypeAliasStatement,
+    Type::ClassDefinition,
+    Type::TypeParameter,
+    Type::ParenthesizedListSplat,
+    Type::ArgumentList,
+    Type::DecoratedDefinition,
+    Type::Decorator,
+    Type::Block,
+    Type::ExpressionList,
+    Type::DottedName,
+    Type::CasePattern,
+    Type::_SimplePattern,
+    Type::AsPattern_,
+    Type::UnionPattern,
+    Type::ListPattern_,
+    Type::TuplePattern_,
+    Type::DictPattern,
+    Type::_KeyValuePattern,
+    Type::KeywordPattern,
+    Type::SplatPattern,
+    Type::ClassPattern,
+    Type::ComplexPattern,
+    Type::_Parameters,
+    Type::_Patterns,
+    Type::Parameter,
+    Type::Pattern,
+    Type::TuplePattern,
+    Type::ListPattern,
+    Type::DefaultParameter,
+    Type::TypedDefaultParameter,
+    Type::ListSplatPattern,
+    Type::DictionarySplatPattern,
+    Type::AsPattern,
+    Type::_ExpressionWithinForInClause,
+    Type::Expression,
+    Type::PrimaryExpression,
+    Type::NotOperator,
+    Type::BooleanOperator,
+    Type::BinaryOperator,
+    Type::UnaryOperator,
+    Type::TS4,
+    Type::TS3,
+    Type::ComparisonOperator,
+    Type::Lambda,
+    Type::Lambda__,
+    Type::Assignment,
+    Type::AugmentedAssignment,
+    Type::PatternList,
+    Type::_RightHandSide,
+    Type::Yield,
+    Type::Attribute,
+    Type::Subscript,
+    Type::Slice,
+    Type::Call,
+    Type::TypedParameter,
+    Type::Type,
+    Type::SplatType,
+    Type::GenericType,
+    Type::UnionType,
+    Type::ConstrainedType,
+    Type::MemberType,
+    Type::KeywordArgument,
+    Type::List,
+    Type::Set,
+    Type::Tuple,
+    Type::Dictionary,
+    Type::Pair,
+    Type::ListComprehension,
+    Type::DictionaryComprehension,
+    Type::SetComprehension,
+    Type::GeneratorExpression,
+    Type::_ComprehensionClauses,
+    Type::ParenthesizedExpression,
+    Type::_CollectionElements,
+    Type::ForInClause,
+    Type::IfClause,
+    Type::ConditionalExpression,
+    Type::ConcatenatedString,
+    Type::String,
+    Type::StringContent,
+    Type::Interpolation,
+    Type::_FExpression,
+    Type::_NotEscapeSequence,
+    Type::FormatSpecifier,
+    Type::Await,
+    Type::PositionalSeparator,
+    Type::KeywordSeparator,
+    Type::ModuleRepeat1,
+    Type::_SimpleStatementsRepeat1,
+    Type::ImportPrefixRepeat1,
+    Type::_ImportListRepeat1,
+    Type::PrintStatementRepeat1,
+    Type::AssertStatementRepeat1,
+    Type::IfStatementRepeat1,
+    Type::MatchStatementRepeat1,
+    Type::_MatchBlockRepeat1,
+    Type::CaseClauseRepeat1,
+    Type::TryStatementRepeat1,
+    Type::TryStatementRepeat2,
+    Type::WithClauseRepeat1,
+    Type::GlobalStatementRepeat1,
+    Type::TypeParameterRepeat1,
+    Type::ArgumentListRepeat1,
+    Type::DecoratedDefinitionRepeat1,
+    Type::DottedNameRepeat1,
+    Type::UnionPatternRepeat1,
+    Type::DictPatternRepeat1,
+    Type::_ParametersRepeat1,
+    Type::_PatternsRepeat1,
+    Type::ComparisonOperatorRepeat1,
+    Type::SubscriptRepeat1,
+    Type::DictionaryRepeat1,
+    Type::_ComprehensionClausesRepeat1,
+    Type::_CollectionElementsRepeat1,
+    Type::ForInClauseRepeat1,
+    Type::ConcatenatedStringRepeat1,
+    Type::StringRepeat1,
+    Type::StringContentRepeat1,
+    Type::FormatSpecifierRepeat1,
+    Type::AsPatternTarget,
+    Type::FormatExpression,
+];
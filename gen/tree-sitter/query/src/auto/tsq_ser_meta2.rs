@@ -5,6 +5,7 @@ use hyperast::types;
 use hyperast::types::Childrn as _;
 use hyperast::types::HyperAST;
 use hyperast::types::HyperType as _;
+use hyperast::types::WithMetrics;
 use hyperast::types::WithPrecompQueries;
 use hyperast::types::WithRoles;
 
@@ -51,6 +52,7 @@ where
     HAST::TS: hyperast::types::RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     <HAST::TS as hyperast::types::RoleStore>::IdF: Into<u16> + From<u16>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -70,6 +72,7 @@ where
     HAST::TS: hyperast::types::RoleStore,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithRoles,
     for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithPrecompQueries,
+    for<'t> <HAST as hyperast::types::AstLending<'t>>::RT: WithMetrics,
     <HAST::TS as hyperast::types::RoleStore>::IdF: Into<u16> + From<u16>,
 {
     fn serialize(
@@ -0,0 +1,25 @@
+#[cfg(all(feature = "impl", feature = "legion"))]
+pub mod legion;
+
+#[cfg(test)]
+mod tests;
+
+pub mod types;
+
+#[cfg(feature = "legion")]
+mod tnode {
+    pub use hyperast::tree_gen::utils_ts::TNode;
+}
+
+#[cfg(feature = "legion")]
+pub use tnode::TNode;
+
+#[cfg(feature = "impl")]
+pub fn language() -> tree_sitter::Language {
+    tree_sitter::Language::new(tree_sitter_rust::LANGUAGE)
+}
+
+#[cfg(feature = "impl")]
+pub fn node_types() -> &'static str {
+    tree_sitter_rust::NODE_TYPES
+}
@@ -0,0 +1,68 @@
+use hyperast::tree_gen::NoOpMore;
+use tree_sitter::Parser;
+
+use crate::{legion::tree_sitter_parse, types::TStore};
+
+type RustTreeGen<'store, 'cache, HAST, Acc> =
+    crate::legion::RustTreeGen<'store, 'cache, TStore, NoOpMore<HAST, Acc>, true>;
+type SimpleStores = hyperast::store::SimpleStores<TStore>;
+
+static EX: &str = r##"//! crate doc
+use std::collections::HashMap;
+
+/* block
+   comment */
+#[derive(Debug, Default)]
+pub struct Counter<'a> {
+    counts: HashMap<&'a str, usize>,
+}
+
+macro_rules! count {
+    ($c:expr, $($w:expr),*) => { $($c.add($w);)* };
+}
+
+impl<'a> Counter<'a> {
+    /// adds a word
+    pub fn add(&mut self, w: &'a str) -> usize {
+        let n = self.counts.entry(w).or_default();
+        *n += 1;
+        *n
+    }
+}
+
+fn main() {
+    let mut c = Counter::default();
+    count!(c, "a", r#"b"#, 'c'.to_string().as_str());
+}
+"##;
+
+#[test]
+pub(crate) fn rust_tree_sitter_simple() {
+    let mut parser = Parser::new();
+
+    {
+        parser.set_language(&crate::language()).unwrap();
+    }
+
+    let text = { EX.as_bytes() };
+    let tree = parser.parse(text, None).unwrap();
+    assert!(!tree.root_node().has_error());
+}
+
+#[test]
+pub(crate) fn rust_simple_test() {
+    let text = { EX.as_bytes() };
+    let tree = match tree_sitter_parse(text) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = RustTreeGen::new(&mut stores, &mut md_cache);
+    let x = tree_gen.generate_file(b"", text, tree.walk()).local;
+    let x = x.compressed_node;
+    assert_eq!(
+        hyperast::nodes::TextSerializer::new(&stores, x).to_string(),
+        EX
+    );
+}
@@ -0,0 +1,54 @@
+use hyperast::tree_gen::NoOpMore;
+use tree_sitter::Parser;
+
+use crate::{legion::tree_sitter_parse, types::TStore};
+
+type TomlTreeGen<'store, 'cache, HAST, Acc> =
+    crate::legion::TomlTreeGen<'store, 'cache, TStore, NoOpMore<HAST, Acc>, true>;
+type SimpleStores = hyperast::store::SimpleStores<TStore>;
+
+static EX: &str = r#"[workspace]
+members = [
+    "crates/*",
+    'gen/tree-sitter/toml', # a comment
+]
+
+[package]
+name = "example"
+version = "0.1.0"
+"quoted.key" = { path = "../quoted", optional = true }
+
+[[bin]]
+name = "example"
+"#;
+
+#[test]
+pub(crate) fn toml_tree_sitter_simple() {
+    let mut parser = Parser::new();
+
+    {
+        parser.set_language(&crate::language()).unwrap();
+    }
+
+    let text = { EX.as_bytes() };
+    let tree = parser.parse(text, None).unwrap();
+    assert!(!tree.root_node().has_error());
+}
+
+#[test]
+pub(crate) fn toml_simple_test() {
+    let text = { EX.as_bytes() };
+    let tree = match tree_sitter_parse(text) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = TomlTreeGen::new(&mut stores, &mut md_cache);
+    let x = tree_gen.generate_file(b"", text, tree.walk()).local;
+    let x = x.compressed_node;
+    assert_eq!(
+        hyperast::nodes::TextSerializer::new(&stores, x).to_string(),
+        EX
+    );
+}
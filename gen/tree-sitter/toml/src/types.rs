@@ -0,0 +1,952 @@
+use std::fmt::Display;
+
+use hyperast::{
+    tree_gen::utils_ts::TsEnableTS,
+    types::{
+        AAAA, AnyType, HyperType, LangRef, NodeId, TypeStore, TypeTrait, TypeU16, TypedNodeId,
+    },
+};
+
+#[cfg(feature = "impl")]
+mod legion_impls {
+
+    use super::*;
+    use hyperast::tree_gen::utils_ts::TsEnableTS;
+    use hyperast::tree_gen::utils_ts::TsType;
+
+    use hyperast::types::{LangWrapper, RoleStore};
+
+    impl TsEnableTS for TStore {
+        fn obtain_type<'a, N: hyperast::tree_gen::parser::NodeWithU16TypeId>(
+            n: &N,
+        ) -> <Self as hyperast::types::ETypeStore>::Ty2 {
+            let k = n.kind_id();
+            // kinds unknown to the generated types come from another version of the grammar
+            Type::from_u16(k).unwrap_or(Type::ERROR)
+        }
+
+        fn try_obtain_type<N: hyperast::tree_gen::parser::NodeWithU16TypeId>(
+            n: &N,
+        ) -> Option<Self::Ty2> {
+            let k = n.kind_id();
+            Type::from_u16(k)
+        }
+    }
+
+    impl TsType for Type {
+        fn spaces() -> Self {
+            Self::Spaces
+        }
+
+        fn is_repeat(&self) -> bool {
+            self.is_repeat()
+        }
+    }
+
+    impl TypeStore for TStore {
+        type Ty = TypeU16<Toml>;
+    }
+    impl<'a> TomlEnabledTypeStore for TStore {
+        fn resolve(t: Self::Ty) -> Type {
+            t.e()
+        }
+    }
+
+    impl<'a> hyperast::types::ETypeStore for TStore {
+        type Ty2 = Type;
+
+        fn intern(ty: Self::Ty2) -> Self::Ty {
+            TType::new(ty)
+        }
+    }
+
+    impl RoleStore for TStore {
+        type IdF = u16;
+
+        type Role = hyperast::types::Role;
+
+        fn resolve_field(_lang: LangWrapper<Self::Ty>, field_id: Self::IdF) -> Self::Role {
+            let s = crate::language()
+                .field_name_for_id(field_id)
+                .ok_or_else(|| format!("{}", field_id))
+                .unwrap();
+            hyperast::types::Role::try_from(s).expect(s)
+        }
+
+        fn intern_role(_lang: LangWrapper<Self::Ty>, role: Self::Role) -> Self::IdF {
+            let field_name = role.to_string();
+            crate::language()
+                .field_id_for_name(field_name)
+                .unwrap()
+                .into()
+        }
+    }
+}
+
+#[cfg(feature = "impl")]
+pub trait TomlEnabledTypeStore:
+    hyperast::types::ETypeStore<Ty2 = Type> + Clone + hyperast::tree_gen::utils_ts::TsEnableTS
+{
+    // fn intern(t: Type) -> Self::Ty;
+    fn resolve(t: Self::Ty) -> Type;
+}
+
+#[cfg(not(feature = "impl"))]
+pub trait TomlEnabledTypeStore: TypeStore {
+    // fn intern(t: Type) -> Self::Ty;
+    fn resolve(t: Self::Ty) -> Type;
+}
+
+impl Type {
+    pub fn resolve(t: u16) -> Self {
+        assert!(t < COUNT);
+        unsafe { std::mem::transmute(t) }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct TIdN<IdN>(IdN);
+
+impl<IdN: Clone + Eq + hyperast::types::AAAA + std::hash::Hash> NodeId for TIdN<IdN> {
+    type IdN = IdN;
+
+    fn as_id(&self) -> &Self::IdN {
+        &self.0
+    }
+
+    unsafe fn from_id(id: Self::IdN) -> Self {
+        Self(id)
+    }
+
+    unsafe fn from_ref_id(_id: &Self::IdN) -> &Self {
+        todo!()
+    }
+}
+
+impl<IdN: Clone + Eq + AAAA + std::hash::Hash> TypedNodeId for TIdN<IdN> {
+    type Ty = Type;
+    type TyErazed = TType;
+    fn unerase(ty: Self::TyErazed) -> Self::Ty {
+        ty.e()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct TStore;
+
+impl Default for TStore {
+    fn default() -> Self {
+        Self
+    }
+}
+
+type TypeInternalSize = u16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct T(TypeInternalSize);
+
+#[derive(Debug)]
+pub struct Lang;
+pub type Toml = Lang;
+
+impl Toml {
+    pub const INST: Toml = Lang;
+}
+
+pub fn as_any(t: &Type) -> AnyType {
+    let t = <Toml as hyperast::types::Lang<Type>>::to_u16(*t);
+    let t = <Toml as hyperast::types::Lang<Type>>::make(t);
+    let t: &'static dyn HyperType = t;
+    t.into()
+}
+
+impl LangRef<AnyType> for Toml {
+    fn make(&self, _t: u16) -> &'static AnyType {
+        panic!()
+        // &From::<&'static dyn HyperType>::from(&S_T_L[t as usize])
+    }
+    fn to_u16(&self, t: AnyType) -> u16 {
+        // t as u16
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Toml>()
+    }
+
+    fn ts_symbol(&self, t: AnyType) -> u16 {
+        // the generated types are numbered like the symbols of the grammar
+        self.to_u16(t)
+    }
+}
+
+impl LangRef<Type> for Toml {
+    fn make(&self, t: u16) -> &'static Type {
+        if t == TStore::ERROR {
+            &Type::ERROR
+        } else if t == TStore::_ERROR {
+            &Type::_ERROR
+        } else if t == TStore::SPACES {
+            &Type::Spaces
+        } else if t == TStore::DIRECTORY {
+            &Type::Directory
+        } else if t == TStore::META_DIR {
+            &Type::CargoDirectory
+        } else {
+            &S_T_L[t as usize]
+        }
+    }
+    fn to_u16(&self, t: Type) -> u16 {
+        t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Toml>()
+    }
+
+    fn ts_symbol(&self, t: Type) -> u16 {
+        t as u16
+    }
+}
+
+impl LangRef<TType> for Lang {
+    fn make(&self, t: u16) -> &'static TType {
+        // TODO could make one safe, but not priority
+        unsafe { std::mem::transmute(&S_T_L[t as usize]) }
+    }
+    fn to_u16(&self, t: TType) -> u16 {
+        t.e() as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Lang>()
+    }
+
+    fn ts_symbol(&self, t: TType) -> u16 {
+        t.e() as u16
+    }
+}
+
+impl hyperast::types::Lang<Type> for Toml {
+    fn make(t: u16) -> &'static Type {
+        Lang.make(t)
+    }
+    fn to_u16(t: Type) -> u16 {
+        Lang.to_u16(t)
+    }
+}
+
+pub use hyperast::types::Role;
+
+impl HyperType for Type {
+    fn generic_eq(&self, other: &dyn HyperType) -> bool
+    where
+        Self: 'static + PartialEq + Sized,
+    {
+        // Do a type-safe casting. If the types are different,
+        // return false, otherwise test the values for equality.
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn is_directory(&self) -> bool {
+        self == &Type::Directory || self == &Type::CargoDirectory
+    }
+    fn is_file(&self) -> bool {
+        self == &Type::Document
+    }
+
+    fn is_spaces(&self) -> bool {
+        self == &Type::Spaces
+    }
+
+    fn is_syntax(&self) -> bool {
+        self == &Type::LBracket // "["
+            || self == &Type::RBracket // "]"
+            || self == &Type::LBracketLBracket // "[["
+            || self == &Type::RBracketRBracket // "]]"
+            || self == &Type::Eq // "="
+            || self == &Type::Dot // "."
+            || self == &Type::Comma // ","
+            || self == &Type::LBrace // "{"
+            || self == &Type::RBrace // "}"
+    }
+
+    fn is_statement(&self) -> bool {
+        self.is_declarative_statement()
+            || self.is_structural_statement()
+            || self.is_simple_statement()
+            || self.is_block_related()
+    }
+
+    fn as_shared(&self) -> hyperast::types::Shared {
+        use hyperast::types::Shared;
+        match self {
+            Type::Comment => Shared::Comment,
+            Type::BareKey => Shared::Identifier,
+            _ => Shared::Other,
+        }
+    }
+
+    fn as_abstract(&self) -> hyperast::types::Abstracts {
+        use hyperast::types::Abstract;
+        Abstract::Expression.when(self.is_expression())
+            | Abstract::Statement.when(HyperType::is_statement(self))
+            | Abstract::Executable.when(self.is_executable_member())
+            | Abstract::Declaration.when(self.is_type_declaration())
+            | Abstract::Literal.when(self.is_literal())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_static(&self) -> &'static dyn HyperType {
+        let t = <Toml as hyperast::types::Lang<Type>>::to_u16(*self);
+        let t = <Toml as hyperast::types::Lang<Type>>::make(t);
+        t
+    }
+
+    fn as_static_str(&self) -> &'static str {
+        self.to_str()
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.is_hidden()
+    }
+
+    fn is_supertype(&self) -> bool {
+        self.is_supertype()
+    }
+
+    fn is_named(&self) -> bool {
+        self.is_named()
+    }
+    fn get_lang(&self) -> hyperast::types::LangWrapper<Self>
+    where
+        Self: Sized,
+    {
+        hyperast::types::LangWrapper::from(&Lang as &(dyn LangRef<Self> + 'static))
+    }
+
+    fn lang_ref(&self) -> hyperast::types::LangWrapper<AnyType> {
+        hyperast::types::LangWrapper::from(&Lang as &(dyn LangRef<AnyType> + 'static))
+    }
+}
+impl TypeTrait for Type {
+    type Lang = Toml;
+
+    fn is_fork(&self) -> bool {
+        false
+    }
+
+    fn is_literal(&self) -> bool {
+        match self {
+            Type::String
+            | Type::Integer
+            | Type::Float
+            | Type::Boolean
+            | Type::OffsetDateTime
+            | Type::LocalDateTime
+            | Type::LocalDate
+            | Type::LocalTime => true,
+            _ => false,
+        }
+    }
+
+    fn is_primitive(&self) -> bool {
+        false
+    }
+
+    fn is_type_declaration(&self) -> bool {
+        false
+    }
+
+    fn is_identifier(&self) -> bool {
+        self == &Type::BareKey
+    }
+
+    fn is_instance_ref(&self) -> bool {
+        false
+    }
+
+    fn is_type_body(&self) -> bool {
+        false
+    }
+
+    fn is_value_member(&self) -> bool {
+        self == &Type::Pair
+    }
+
+    fn is_executable_member(&self) -> bool {
+        false
+    }
+
+    fn is_statement(&self) -> bool {
+        false
+    }
+
+    fn is_declarative_statement(&self) -> bool {
+        false
+    }
+
+    fn is_structural_statement(&self) -> bool {
+        false
+    }
+
+    fn is_block_related(&self) -> bool {
+        self == &Type::Table || self == &Type::TableArrayElement
+    }
+
+    fn is_simple_statement(&self) -> bool {
+        false
+    }
+
+    fn is_local_declare(&self) -> bool {
+        false
+    }
+
+    fn is_parameter(&self) -> bool {
+        false
+    }
+
+    fn is_parameter_list(&self) -> bool {
+        false
+    }
+
+    fn is_argument_list(&self) -> bool {
+        false
+    }
+
+    fn is_expression(&self) -> bool {
+        match self {
+            Type::Array | Type::InlineTable => true,
+            x => x.is_literal(),
+        }
+    }
+
+    fn is_comment(&self) -> bool {
+        self == &Type::Comment
+    }
+}
+
+const COUNT: u16 = 71;
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl TryFrom<&str> for Type {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Type::from_str(value).ok_or(())
+    }
+}
+
+impl Type {
+    pub(crate) fn is_repeat(&self) -> bool {
+        *self == Type::DocumentRepeat1
+            || *self == Type::DocumentRepeat2
+            || *self == Type::_BasicStringRepeat1
+            || *self == Type::_MultilineBasicStringRepeat1
+            || *self == Type::_MultilineLiteralStringRepeat1
+            || *self == Type::ArrayRepeat1
+            || *self == Type::ArrayRepeat2
+            || *self == Type::InlineTableRepeat1
+    }
+
+    /// nodes mixing visible and hidden tokens, e.g. quoted strings and keys
+    pub(crate) fn is_text_leaf(&self) -> bool {
+        *self == Type::String
+            || *self == Type::QuotedKey
+    }
+}
+
+impl hyperast::types::LLang<TType> for Toml {
+    type I = u16;
+
+    type E = Type;
+
+    const TE: &[Self::E] = S_T_L;
+
+    fn as_lang_wrapper() -> hyperast::types::LangWrapper<TType> {
+        From::<&'static (dyn LangRef<_>)>::from(&Lang)
+    }
+}
+
+pub type TType = TypeU16<Lang>;
+
+impl From<u16> for Type {
+    fn from(value: u16) -> Self {
+        S_T_L[value as usize]
+    }
+}
+impl Into<TypeU16<Toml>> for Type {
+    fn into(self) -> TypeU16<Toml> {
+        TypeU16::new(self)
+    }
+}
+
+impl Into<u16> for Type {
+    fn into(self) -> u16 {
+        self as u16
+    }
+}
+
+#[repr(u16)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Type {
+    End,
+    DocumentToken1,
+    Comment,
+    LBracket,
+    RBracket,
+    LBracketLBracket,
+    RBracketRBracket,
+    Eq,
+    Dot,
+    BareKey,
+    DQuote,
+    _BasicStringToken1,
+    DQuote2,
+    DQuoteDQuoteDQuote,
+    _MultilineBasicStringToken1,
+    EscapeSequence,
+    _EscapeLineEnding,
+    SQuote,
+    _LiteralStringToken1,
+    SQuote2,
+    SQuoteSQuoteSQuote,
+    IntegerToken1,
+    IntegerToken2,
+    IntegerToken3,
+    IntegerToken4,
+    FloatToken1,
+    FloatToken2,
+    Boolean,
+    OffsetDateTime,
+    LocalDateTime,
+    LocalDate,
+    LocalTime,
+    Comma,
+    LBrace,
+    RBrace,
+    _LineEndingOrEof,
+    _MultilineBasicStringContent,
+    _MultilineBasicStringEnd,
+    _MultilineLiteralStringContent,
+    _MultilineLiteralStringEnd,
+    Document,
+    Table,
+    TableArrayElement,
+    Pair,
+    _InlinePair,
+    _Key,
+    DottedKey,
+    QuotedKey,
+    _InlineValue,
+    String,
+    _BasicString,
+    _MultilineBasicString,
+    _LiteralString,
+    _MultilineLiteralString,
+    Integer,
+    Float,
+    Array,
+    InlineTable,
+    DocumentRepeat1,
+    DocumentRepeat2,
+    _BasicStringRepeat1,
+    _MultilineBasicStringRepeat1,
+    _MultilineLiteralStringRepeat1,
+    ArrayRepeat1,
+    ArrayRepeat2,
+    InlineTableRepeat1,
+    CargoDirectory = TStore::META_DIR,
+    Directory = TStore::DIRECTORY,
+    Spaces = TStore::SPACES,
+    _ERROR = TStore::_ERROR,
+    ERROR = TStore::ERROR,
+}
+
+impl Type {
+    pub fn from_u16(t: u16) -> Option<Type> {
+        Some(match t {
+            0u16 => Type::End,
+            1u16 => Type::DocumentToken1,
+            2u16 => Type::Comment,
+            3u16 => Type::LBracket,
+            4u16 => Type::RBracket,
+            5u16 => Type::LBracketLBracket,
+            6u16 => Type::RBracketRBracket,
+            7u16 => Type::Eq,
+            8u16 => Type::Dot,
+            9u16 => Type::BareKey,
+            10u16 => Type::DQuote,
+            11u16 => Type::_BasicStringToken1,
+            12u16 => Type::DQuote2,
+            13u16 => Type::DQuoteDQuoteDQuote,
+            14u16 => Type::_MultilineBasicStringToken1,
+            15u16 => Type::EscapeSequence,
+            16u16 => Type::_EscapeLineEnding,
+            17u16 => Type::SQuote,
+            18u16 => Type::_LiteralStringToken1,
+            19u16 => Type::SQuote2,
+            20u16 => Type::SQuoteSQuoteSQuote,
+            21u16 => Type::IntegerToken1,
+            22u16 => Type::IntegerToken2,
+            23u16 => Type::IntegerToken3,
+            24u16 => Type::IntegerToken4,
+            25u16 => Type::FloatToken1,
+            26u16 => Type::FloatToken2,
+            27u16 => Type::Boolean,
+            28u16 => Type::OffsetDateTime,
+            29u16 => Type::LocalDateTime,
+            30u16 => Type::LocalDate,
+            31u16 => Type::LocalTime,
+            32u16 => Type::Comma,
+            33u16 => Type::LBrace,
+            34u16 => Type::RBrace,
+            35u16 => Type::_LineEndingOrEof,
+            36u16 => Type::_MultilineBasicStringContent,
+            37u16 => Type::_MultilineBasicStringEnd,
+            38u16 => Type::_MultilineLiteralStringContent,
+            39u16 => Type::_MultilineLiteralStringEnd,
+            40u16 => Type::Document,
+            41u16 => Type::Table,
+            42u16 => Type::TableArrayElement,
+            43u16 => Type::Pair,
+            44u16 => Type::_InlinePair,
+            45u16 => Type::_Key,
+            46u16 => Type::DottedKey,
+            47u16 => Type::QuotedKey,
+            48u16 => Type::_InlineValue,
+            49u16 => Type::String,
+            50u16 => Type::_BasicString,
+            51u16 => Type::_MultilineBasicString,
+            52u16 => Type::_LiteralString,
+            53u16 => Type::_MultilineLiteralString,
+            54u16 => Type::Integer,
+            55u16 => Type::Float,
+            56u16 => Type::Array,
+            57u16 => Type::InlineTable,
+            58u16 => Type::DocumentRepeat1,
+            59u16 => Type::DocumentRepeat2,
+            60u16 => Type::_BasicStringRepeat1,
+            61u16 => Type::_MultilineBasicStringRepeat1,
+            62u16 => Type::_MultilineLiteralStringRepeat1,
+            63u16 => Type::ArrayRepeat1,
+            64u16 => Type::ArrayRepeat2,
+            65u16 => Type::InlineTableRepeat1,
+            TStore::META_DIR => Type::CargoDirectory,
+            TStore::DIRECTORY => Type::Directory,
+            TStore::SPACES => Type::Spaces,
+            TStore::_ERROR => Type::_ERROR,
+            TStore::ERROR => Type::ERROR,
+            _ => return None,
+        })
+    }
+    #[allow(unreachable_patterns)]
+    pub fn from_str(t: &str) -> Option<Type> {
+        Some(match t {
+            "end" => Type::End,
+            "document_token1" => Type::DocumentToken1,
+            "comment" => Type::Comment,
+            "[" => Type::LBracket,
+            "]" => Type::RBracket,
+            "[[" => Type::LBracketLBracket,
+            "]]" => Type::RBracketRBracket,
+            "=" => Type::Eq,
+            "." => Type::Dot,
+            "bare_key" => Type::BareKey,
+            "\"" => Type::DQuote,
+            "_basic_string_token1" => Type::_BasicStringToken1,
+            "\"" => Type::DQuote2,
+            "\"\"\"" => Type::DQuoteDQuoteDQuote,
+            "_multiline_basic_string_token1" => Type::_MultilineBasicStringToken1,
+            "escape_sequence" => Type::EscapeSequence,
+            "escape_sequence" => Type::_EscapeLineEnding,
+            "'" => Type::SQuote,
+            "_literal_string_token1" => Type::_LiteralStringToken1,
+            "'" => Type::SQuote2,
+            "'''" => Type::SQuoteSQuoteSQuote,
+            "integer_token1" => Type::IntegerToken1,
+            "integer_token2" => Type::IntegerToken2,
+            "integer_token3" => Type::IntegerToken3,
+            "integer_token4" => Type::IntegerToken4,
+            "float_token1" => Type::FloatToken1,
+            "float_token2" => Type::FloatToken2,
+            "boolean" => Type::Boolean,
+            "offset_date_time" => Type::OffsetDateTime,
+            "local_date_time" => Type::LocalDateTime,
+            "local_date" => Type::LocalDate,
+            "local_time" => Type::LocalTime,
+            "," => Type::Comma,
+            "{" => Type::LBrace,
+            "}" => Type::RBrace,
+            "_line_ending_or_eof" => Type::_LineEndingOrEof,
+            "_multiline_basic_string_content" => Type::_MultilineBasicStringContent,
+            "_multiline_basic_string_end" => Type::_MultilineBasicStringEnd,
+            "_multiline_literal_string_content" => Type::_MultilineLiteralStringContent,
+            "_multiline_literal_string_end" => Type::_MultilineLiteralStringEnd,
+            "document" => Type::Document,
+            "table" => Type::Table,
+            "table_array_element" => Type::TableArrayElement,
+            "pair" => Type::Pair,
+            "_inline_pair" => Type::_InlinePair,
+            "_key" => Type::_Key,
+            "dotted_key" => Type::DottedKey,
+            "quoted_key" => Type::QuotedKey,
+            "_inline_value" => Type::_InlineValue,
+            "string" => Type::String,
+            "_basic_string" => Type::_BasicString,
+            "_multiline_basic_string" => Type::_MultilineBasicString,
+            "_literal_string" => Type::_LiteralString,
+            "_multiline_literal_string" => Type::_MultilineLiteralString,
+            "integer" => Type::Integer,
+            "float" => Type::Float,
+            "array" => Type::Array,
+            "inline_table" => Type::InlineTable,
+            "document_repeat1" => Type::DocumentRepeat1,
+            "document_repeat2" => Type::DocumentRepeat2,
+            "_basic_string_repeat1" => Type::_BasicStringRepeat1,
+            "_multiline_basic_string_repeat1" => Type::_MultilineBasicStringRepeat1,
+            "_multiline_literal_string_repeat1" => Type::_MultilineLiteralStringRepeat1,
+            "array_repeat1" => Type::ArrayRepeat1,
+            "array_repeat2" => Type::ArrayRepeat2,
+            "inline_table_repeat1" => Type::InlineTableRepeat1,
+            "CargoDirectory" => Type::CargoDirectory,
+            "Directory" => Type::Directory,
+            "Spaces" => Type::Spaces,
+            "_ERROR" => Type::_ERROR,
+            "ERROR" => Type::ERROR,
+            _ => return None,
+        })
+    }
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Type::End => "end",
+            Type::DocumentToken1 => "document_token1",
+            Type::Comment => "comment",
+            Type::LBracket => "[",
+            Type::RBracket => "]",
+            Type::LBracketLBracket => "[[",
+            Type::RBracketRBracket => "]]",
+            Type::Eq => "=",
+            Type::Dot => ".",
+            Type::BareKey => "bare_key",
+            Type::DQuote => "\"",
+            Type::_BasicStringToken1 => "_basic_string_token1",
+            Type::DQuote2 => "\"",
+            Type::DQuoteDQuoteDQuote => "\"\"\"",
+            Type::_MultilineBasicStringToken1 => "_multiline_basic_string_token1",
+            Type::EscapeSequence => "escape_sequence",
+            Type::_EscapeLineEnding => "escape_sequence",
+            Type::SQuote => "'",
+            Type::_LiteralStringToken1 => "_literal_string_token1",
+            Type::SQuote2 => "'",
+            Type::SQuoteSQuoteSQuote => "'''",
+            Type::IntegerToken1 => "integer_token1",
+            Type::IntegerToken2 => "integer_token2",
+            Type::IntegerToken3 => "integer_token3",
+            Type::IntegerToken4 => "integer_token4",
+            Type::FloatToken1 => "float_token1",
+            Type::FloatToken2 => "float_token2",
+            Type::Boolean => "boolean",
+            Type::OffsetDateTime => "offset_date_time",
+            Type::LocalDateTime => "local_date_time",
+            Type::LocalDate => "local_date",
+            Type::LocalTime => "local_time",
+            Type::Comma => ",",
+            Type::LBrace => "{",
+            Type::RBrace => "}",
+            Type::_LineEndingOrEof => "_line_ending_or_eof",
+            Type::_MultilineBasicStringContent => "_multiline_basic_string_content",
+            Type::_MultilineBasicStringEnd => "_multiline_basic_string_end",
+            Type::_MultilineLiteralStringContent => "_multiline_literal_string_content",
+            Type::_MultilineLiteralStringEnd => "_multiline_literal_string_end",
+            Type::Document => "document",
+            Type::Table => "table",
+            Type::TableArrayElement => "table_array_element",
+            Type::Pair => "pair",
+            Type::_InlinePair => "_inline_pair",
+            Type::_Key => "_key",
+            Type::DottedKey => "dotted_key",
+            Type::QuotedKey => "quoted_key",
+            Type::_InlineValue => "_inline_value",
+            Type::String => "string",
+            Type::_BasicString => "_basic_string",
+            Type::_MultilineBasicString => "_multiline_basic_string",
+            Type::_LiteralString => "_literal_string",
+            Type::_MultilineLiteralString => "_multiline_literal_string",
+            Type::Integer => "integer",
+            Type::Float => "float",
+            Type::Array => "array",
+            Type::InlineTable => "inline_table",
+            Type::DocumentRepeat1 => "document_repeat1",
+            Type::DocumentRepeat2 => "document_repeat2",
+            Type::_BasicStringRepeat1 => "_basic_string_repeat1",
+            Type::_MultilineBasicStringRepeat1 => "_multiline_basic_string_repeat1",
+            Type::_MultilineLiteralStringRepeat1 => "_multiline_literal_string_repeat1",
+            Type::ArrayRepeat1 => "array_repeat1",
+            Type::ArrayRepeat2 => "array_repeat2",
+            Type::InlineTableRepeat1 => "inline_table_repeat1",
+            Type::CargoDirectory => "CargoDirectory",
+            Type::Directory => "Directory",
+            Type::Spaces => "Spaces",
+            Type::_ERROR => "_ERROR",
+            Type::ERROR => "ERROR",
+        }
+    }
+    pub fn is_hidden(&self) -> bool {
+        match self {
+            Type::End => true,
+            Type::DocumentToken1 => true,
+            Type::_BasicStringToken1 => true,
+            Type::_MultilineBasicStringToken1 => true,
+            Type::_LiteralStringToken1 => true,
+            Type::IntegerToken1 => true,
+            Type::IntegerToken2 => true,
+            Type::IntegerToken3 => true,
+            Type::IntegerToken4 => true,
+            Type::FloatToken1 => true,
+            Type::FloatToken2 => true,
+            Type::_LineEndingOrEof => true,
+            Type::_MultilineBasicStringContent => true,
+            Type::_MultilineBasicStringEnd => true,
+            Type::_MultilineLiteralStringContent => true,
+            Type::_MultilineLiteralStringEnd => true,
+            Type::_InlinePair => true,
+            Type::_Key => true,
+            Type::_InlineValue => true,
+            Type::_BasicString => true,
+            Type::_MultilineBasicString => true,
+            Type::_LiteralString => true,
+            Type::_MultilineLiteralString => true,
+            Type::DocumentRepeat1 => true,
+            Type::DocumentRepeat2 => true,
+            Type::_BasicStringRepeat1 => true,
+            Type::_MultilineBasicStringRepeat1 => true,
+            Type::_MultilineLiteralStringRepeat1 => true,
+            Type::ArrayRepeat1 => true,
+            Type::ArrayRepeat2 => true,
+            Type::InlineTableRepeat1 => true,
+            _ => false,
+        }
+    }
+    pub fn is_supertype(&self) -> bool {
+        false
+    }
+    pub fn is_named(&self) -> bool {
+        match self {
+            Type::Comment => true,
+            Type::BareKey => true,
+            Type::EscapeSequence => true,
+            Type::_EscapeLineEnding => true,
+            Type::Boolean => true,
+            Type::OffsetDateTime => true,
+            Type::LocalDateTime => true,
+            Type::LocalDate => true,
+            Type::LocalTime => true,
+            Type::Document => true,
+            Type::Table => true,
+            Type::TableArrayElement => true,
+            Type::Pair => true,
+            Type::DottedKey => true,
+            Type::QuotedKey => true,
+            Type::String => true,
+            Type::Integer => true,
+            Type::Float => true,
+            Type::Array => true,
+            Type::InlineTable => true,
+            _ => false,
+        }
+    }
+}
+
+#[test]
+fn test_tslanguage_and_type_identity() {
+    let l = crate::language();
+    assert_eq!(l.node_kind_count(), S_T_L.len());
+    for id in 0..l.node_kind_count() {
+        let kind = l.node_kind_for_id(id as u16).unwrap();
+        let ty = Type::from_u16(id as u16).unwrap();
+        assert_eq!(ty.to_str(), kind);
+        assert_eq!(ty.is_hidden(), !l.node_kind_is_visible(id as u16));
+    }
+    assert_eq!(Type::from_u16(l.node_kind_count() as u16), None);
+}
+
+const S_T_L: &'static [Type] = &[
+    Type::End,
+    Type::DocumentToken1,
+    Type::Comment,
+    Type::LBracket,
+    Type::RBracket,
+    Type::LBracketLBracket,
+    Type::RBracketRBracket,
+    Type::Eq,
+    Type::Dot,
+    Type::BareKey,
+    Type::DQuote,
+    Type::_BasicStringToken1,
+    Type::DQuote2,
+    Type::DQuoteDQuoteDQuote,
+    Type::_MultilineBasicStringToken1,
+    Type::EscapeSequence,
+    Type::_EscapeLineEnding,
+    Type::SQuote,
+    Type::_LiteralStringToken1,
+    Type::SQuote2,
+    Type::SQuoteSQuoteSQuote,
+    Type::IntegerToken1,
+    Type::IntegerToken2,
+    Type::IntegerToken3,
+    Type::IntegerToken4,
+    Type::FloatToken1,
+    Type::FloatToken2,
+    Type::Boolean,
+    Type::OffsetDateTime,
+    Type::LocalDateTime,
+    Type::LocalDate,
+    Type::LocalTime,
+    Type::Comma,
+    Type::LBrace,
+    Type::RBrace,
+    Type::_LineEndingOrEof,
+    Type::_MultilineBasicStringContent,
+    Type::_MultilineBasicStringEnd,
+    Type::_MultilineLiteralStringContent,
+    Type::_MultilineLiteralStringEnd,
+    Type::Document,
+    Type::Table,
+    Type::TableArrayElement,
+    Type::Pair,
+    Type::_InlinePair,
+    Type::_Key,
+    Type::DottedKey,
+    Type::QuotedKey,
+    Type::_InlineValue,
+    Type::String,
+    Type::_BasicString,
+    Type::_MultilineBasicString,
+    Type::_LiteralString,
+    Type::_MultilineLiteralString,
+    Type::Integer,
+    Type::Float,
+    Type::Array,
+    Type::InlineTable,
+    Type::DocumentRepeat1,
+    Type::DocumentRepeat2,
+    Type::_BasicStringRepeat1,
+    Type::_MultilineBasicStringRepeat1,
+    Type::_MultilineLiteralStringRepeat1,
+    Type::ArrayRepeat1,
+    Type::ArrayRepeat2,
+    Type::InlineTableRepeat1,
+];
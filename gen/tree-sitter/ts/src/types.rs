@@ -105,7 +105,7 @@ impl<IdN: Clone + Eq + AAAA> TypedNodeId for TIdN<IdN> {
 }
 
 #[derive(Clone, Copy)]
-pub(crate) struct TStore;
+pub struct TStore;
 
 impl Default for TStore {
     fn default() -> Self {
@@ -388,7 +388,7 @@ impl Display for Type {
     }
 }
 
-type TType = hyperast::types::TypeU16<Lang>;
+pub type TType = hyperast::types::TypeU16<Lang>;
 
 impl hyperast::types::LLang<TType> for Ts {
     type I = u16;
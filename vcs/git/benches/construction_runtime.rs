@@ -31,7 +31,9 @@ fn construction_group(c: &mut Criterion) {
             b.iter_batched(
                 || {
                     let mut repositories = PreProcessedRepositories::default();
-                    repositories.register_config(p.repo.clone(), p.config);
+                    repositories
+                        .register_config(p.repo.clone(), p.config)
+                        .unwrap();
                     let repo = repositories
                         .get_config((&p.repo).clone())
                         .ok_or_else(|| "missing config for repository".to_string())
@@ -0,0 +1,206 @@
+//! Language-neutral node types, for the directories made by the [`crate::any_processor`],
+//! as their files can be of any language.
+use std::fmt::Display;
+
+use hyperast::types::{AnyType, HyperType, LangRef, LangWrapper, TypeStore, TypeU16};
+
+#[repr(u16)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Type {
+    Directory,
+}
+
+const S_T_L: &[Type] = &[Type::Directory];
+
+impl Type {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Type::Directory => "Directory",
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct Lang;
+
+pub type TType = TypeU16<Lang>;
+
+#[derive(Clone, Copy, Default)]
+pub struct TStore;
+
+impl TypeStore for TStore {
+    type Ty = TType;
+}
+
+impl hyperast::types::ETypeStore for TStore {
+    type Ty2 = Type;
+
+    fn intern(ty: Self::Ty2) -> Self::Ty {
+        TType::new(ty)
+    }
+}
+
+impl LangRef<Type> for Lang {
+    fn make(&self, t: u16) -> &'static Type {
+        &S_T_L[t as usize]
+    }
+    fn to_u16(&self, t: Type) -> u16 {
+        t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Lang>()
+    }
+
+    fn ts_symbol(&self, _t: Type) -> u16 {
+        // not produced by a tree-sitter grammar, like the directories of other languages
+        0
+    }
+}
+
+impl LangRef<TType> for Lang {
+    fn make(&self, t: u16) -> &'static TType {
+        // TODO could make one safe, but not priority
+        unsafe { std::mem::transmute(&S_T_L[t as usize]) }
+    }
+    fn to_u16(&self, t: TType) -> u16 {
+        t.e() as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Lang>()
+    }
+
+    fn ts_symbol(&self, _t: TType) -> u16 {
+        0
+    }
+}
+
+impl LangRef<AnyType> for Lang {
+    fn make(&self, _t: u16) -> &'static AnyType {
+        panic!()
+    }
+    fn to_u16(&self, t: AnyType) -> u16 {
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Lang>()
+    }
+
+    fn ts_symbol(&self, _t: AnyType) -> u16 {
+        0
+    }
+}
+
+impl hyperast::types::Lang<Type> for Lang {
+    fn make(t: u16) -> &'static Type {
+        Lang.make(t)
+    }
+    fn to_u16(t: Type) -> u16 {
+        Lang.to_u16(t)
+    }
+}
+
+impl hyperast::types::LLang<TType> for Lang {
+    type I = u16;
+
+    type E = Type;
+
+    const TE: &[Self::E] = S_T_L;
+
+    fn as_lang_wrapper() -> LangWrapper<TType> {
+        From::<&'static dyn LangRef<_>>::from(&Lang)
+    }
+}
+
+impl HyperType for Type {
+    fn generic_eq(&self, other: &dyn HyperType) -> bool
+    where
+        Self: 'static + PartialEq + Sized,
+    {
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn is_directory(&self) -> bool {
+        true
+    }
+
+    fn is_file(&self) -> bool {
+        false
+    }
+
+    fn is_spaces(&self) -> bool {
+        false
+    }
+
+    fn is_syntax(&self) -> bool {
+        false
+    }
+
+    fn is_statement(&self) -> bool {
+        false
+    }
+
+    fn as_shared(&self) -> hyperast::types::Shared {
+        hyperast::types::Shared::Other
+    }
+
+    fn as_abstract(&self) -> hyperast::types::Abstracts {
+        hyperast::types::Abstracts::empty()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_static(&self) -> &'static dyn HyperType {
+        <Lang as hyperast::types::Lang<Type>>::make(*self as u16)
+    }
+
+    fn as_static_str(&self) -> &'static str {
+        self.to_str()
+    }
+
+    fn is_hidden(&self) -> bool {
+        false
+    }
+
+    fn is_supertype(&self) -> bool {
+        false
+    }
+
+    fn is_named(&self) -> bool {
+        true
+    }
+
+    fn get_lang(&self) -> LangWrapper<Self>
+    where
+        Self: Sized,
+    {
+        LangWrapper::from(&Lang as &(dyn LangRef<Self> + 'static))
+    }
+
+    fn lang_ref(&self) -> LangWrapper<AnyType> {
+        LangWrapper::from(&Lang as &(dyn LangRef<AnyType> + 'static))
+    }
+}
+
+#[test]
+fn directory_type() {
+    use hyperast::types::ETypeStore;
+    let t = TStore::intern(Type::Directory);
+    assert!(t.is_directory());
+    assert_eq!(t.as_static_str(), "Directory");
+    assert_eq!(t.e(), Type::Directory);
+}
@@ -0,0 +1,480 @@
+//! Processes any repository, without looking for a build system.
+//!
+//! Each blob is handled by the generator matching its file extension,
+//! other blobs are skipped.
+use crate::SimpleStores;
+use crate::StackEle;
+use crate::any::Type;
+use crate::processing::erased::{
+    CommitProcessorHandle, ParametrizedCommitProcessor2Handle as PCP2Handle,
+};
+use crate::{
+    Accumulator, BasicDirAcc, DefaultMetrics, Processor,
+    git::BasicGitObject,
+    preprocessed::RepositoryProcessor,
+    processing::{
+        CacheHolding, InFiles, ObjectName, ParametrizedCommitProcessorHandle,
+        erased::ParametrizedCommitProc2, file_sys,
+    },
+};
+use git2::{Oid, Repository};
+use hyperast::types::ETypeStore as _;
+use hyperast::{
+    hashed::{IndexingHashBuilder, MetaDataHashsBuilder},
+    store::defaults::{LabelIdentifier, NodeIdentifier},
+    store::nodes::legion::eq_node,
+    types::LabelStore,
+};
+
+pub struct AnyAcc {
+    pub(crate) primary: BasicDirAcc<NodeIdentifier, LabelIdentifier, DefaultMetrics>,
+}
+
+impl From<String> for AnyAcc {
+    fn from(name: String) -> Self {
+        Self {
+            primary: BasicDirAcc::new(name),
+        }
+    }
+}
+
+impl hyperast::tree_gen::Accumulator for AnyAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, DefaultMetrics));
+    fn push(&mut self, (name, (id, metrics)): Self::Node) {
+        self.primary.push(name, id, metrics);
+    }
+}
+
+impl Accumulator for AnyAcc {
+    type Unlabeled = (NodeIdentifier, DefaultMetrics);
+}
+
+pub struct AnyProcessor<'a, 'b> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<StackEle<AnyAcc>>,
+    handle: ParametrizedCommitProcessorHandle,
+}
+
+impl<'a, 'b> AnyProcessor<'a, 'b> {
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        name: &[u8],
+        oid: git2::Oid,
+        handle: ParametrizedCommitProcessorHandle,
+    ) -> Self {
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![StackEle::new(oid, prepared, AnyAcc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            handle,
+        }
+    }
+}
+
+impl<'a, 'b> AnyProcessor<'a, 'b> {
+    /// None when no generator matches the extension of the blob
+    fn handle_blob(
+        &mut self,
+        oid: Oid,
+        name: &ObjectName,
+        parameter: Parameter,
+    ) -> Option<Result<(NodeIdentifier, DefaultMetrics), crate::ParseErr>> {
+        if file_sys::Java::matches(name) {
+            return Some(
+                self.prepro
+                    .handle_java_blob(oid, name, self.repository, parameter.java_handle)
+                    .map(|(x,)| (x.compressed_node, x.metrics)),
+            );
+        }
+        // headers are shared by C and C++ sources, they are left to the C++ generator
+        #[cfg(feature = "c")]
+        if name.as_bytes().ends_with(b".c") {
+            return Some(
+                self.prepro
+                    .handle_c_blob(oid, name, self.repository, parameter.c_handle)
+                    .map(|(x,)| (x.compressed_node, x.metrics)),
+            );
+        }
+        if file_sys::Cpp::matches(name) {
+            return Some(
+                self.prepro
+                    .handle_cpp_blob(oid, name, self.repository, parameter.cpp_handle)
+                    .map(|(x,)| (x.compressed_node, x.metrics)),
+            );
+        }
+        #[cfg(feature = "ts")]
+        if file_sys::Ts::matches(name) {
+            return Some(
+                self.prepro
+                    .handle_ts_blob(oid, name, self.repository, parameter.ts_handle)
+                    .map(|(x,)| (x.compressed_node, x.metrics)),
+            );
+        }
+        #[cfg(feature = "python")]
+        if file_sys::Python::matches(name) {
+            return Some(
+                self.prepro
+                    .handle_python_blob(oid, name, self.repository, parameter.python_handle)
+                    .map(|(x,)| (x.compressed_node, x.metrics)),
+            );
+        }
+        #[cfg(feature = "rust")]
+        if file_sys::Rust::matches(name) {
+            return Some(
+                self.prepro
+                    .handle_rust_blob(oid, name, self.repository, parameter.rust_handle)
+                    .map(|(x,)| (x.compressed_node, x.metrics)),
+            );
+        }
+        None
+    }
+}
+
+impl<'a, 'b> Processor<AnyAcc> for AnyProcessor<'a, 'b> {
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                self.handle_tree_cached(name, oid);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                let parameter = self
+                    .prepro
+                    .processing_systems
+                    .mut_or_default::<AnyProcessorHolder>()
+                    .with_parameters(self.handle.1)
+                    .parameter
+                    .clone();
+                let Some(full_node) = self.handle_blob(oid, &name, parameter) else {
+                    log::debug!("no generator for {:?}", name.try_str());
+                    return;
+                };
+                match full_node {
+                    Ok(full_node) => {
+                        let name = self.prepro.intern_object_name(&name);
+                        let w = &mut self.stack.last_mut().unwrap().acc;
+                        assert!(!w.primary.children_names.contains(&name));
+                        hyperast::tree_gen::Accumulator::push(w, (name, full_node));
+                    }
+                    Err(e) => log::warn!("skipping {:?}: {:?}", name.try_str(), e),
+                }
+            }
+        }
+    }
+
+    fn post(&mut self, oid: Oid, acc: AnyAcc) -> Option<(NodeIdentifier, DefaultMetrics)> {
+        let name = acc.primary.name.clone();
+        let full_node = make(acc, self.prepro.main_stores_mut());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<AnyProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .insert(oid, full_node.clone());
+
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            assert!(
+                !w.primary.children_names.contains(&name),
+                "{:?} {:?}",
+                w.primary.children_names,
+                name
+            );
+            hyperast::tree_gen::Accumulator::push(w, (name, full_node));
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<StackEle<AnyAcc>> {
+        &mut self.stack
+    }
+}
+
+impl<'a, 'b> AnyProcessor<'a, 'b> {
+    fn handle_tree_cached(&mut self, name: ObjectName, oid: Oid) {
+        if let Some(already) = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<AnyProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .get(&oid)
+        {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            let name = self.prepro.intern_object_name(name);
+            assert!(!w.primary.children_names.contains(&name));
+            hyperast::tree_gen::Accumulator::push(w, (name, full_node));
+            return;
+        }
+        log::debug!("tree {:?}", name.try_str());
+        let tree = self.repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        self.stack.push(StackEle::new(
+            oid,
+            prepared,
+            AnyAcc::from(TryInto::<String>::try_into(name).unwrap()),
+        ));
+    }
+}
+
+/// Directories get the language-neutral [`Type::Directory`], whatever the languages of their files
+pub(crate) fn make(acc: AnyAcc, stores: &mut SimpleStores) -> (NodeIdentifier, DefaultMetrics) {
+    let kind = Type::Directory;
+    let interned_kind = crate::any::TStore::intern(kind);
+    let label_id = stores.label_store.get_or_insert(acc.primary.name.clone());
+
+    let primary = acc
+        .primary
+        .map_metrics(|m| m.finalize(&interned_kind, &label_id, 0));
+
+    let hashable = primary.metrics.hashs.most_discriminating();
+
+    let eq = eq_node(&interned_kind, Some(&label_id), &primary.children);
+
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    if let Some(id) = insertion.occupied_id() {
+        let metrics = primary
+            .metrics
+            .map_hashs(|h| MetaDataHashsBuilder::build(h));
+        return (id, metrics);
+    }
+
+    let mut dyn_builder = hyperast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+
+    let children_is_empty = primary.children.is_empty();
+
+    let metrics = primary.persist(&mut dyn_builder, interned_kind, label_id);
+    let metrics = metrics.map_hashs(|h| h.build());
+    let hashs = metrics.add_md_metrics(&mut dyn_builder, children_is_empty);
+    hashs.persist(&mut dyn_builder);
+
+    let vacant = insertion.vacant();
+    let node_id = hyperast::store::nodes::legion::NodeStore::insert_built_after_prepare(
+        vacant,
+        dyn_builder.build(),
+    );
+
+    (node_id, metrics)
+}
+
+pub(crate) fn prepare_dir_exploration(tree: git2::Tree) -> Vec<BasicGitObject> {
+    tree.iter()
+        .rev()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect()
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub(crate) java_handle: PCP2Handle<crate::java_processor::JavaProc>,
+    pub(crate) cpp_handle: PCP2Handle<crate::cpp_processor::CppProc>,
+    #[cfg(feature = "c")]
+    pub(crate) c_handle: PCP2Handle<crate::c_processor::CProc>,
+    #[cfg(feature = "ts")]
+    pub(crate) ts_handle: PCP2Handle<crate::ts_processor::TsProc>,
+    #[cfg(feature = "python")]
+    pub(crate) python_handle: PCP2Handle<crate::python_processor::PythonProc>,
+    #[cfg(feature = "rust")]
+    pub(crate) rust_handle: PCP2Handle<crate::rust_processor::RustProc>,
+}
+
+#[derive(Default)]
+pub(crate) struct AnyProcessorHolder(Option<AnyProc>);
+pub(crate) struct AnyProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Any,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for AnyProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0;
+                // TODO enable multi configs, do the same as the one for Java
+                self.0 = Some(AnyProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+struct PreparedAnyCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+    pub(crate) handle: ParametrizedCommitProcessorHandle,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedAnyCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedAnyCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyperast::store::defaults::NodeIdentifier {
+        let name = b"";
+        let root_full_node = AnyProcessor::new(
+            self.repository,
+            prepro,
+            name,
+            self.commit_builder.tree_oid(),
+            self.handle,
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<AnyProcessorHolder>();
+        let handle = self.handle;
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.1)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+
+impl crate::processing::erased::CommitProc for AnyProc {
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+        handle: crate::processing::ParametrizedCommitProcessorHandle,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedAnyCommitProc {
+            repository,
+            commit_builder,
+            handle,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        Box::new(self.commits.iter())
+    }
+
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+
+    fn get_lang_handle(&self, lang: &str) -> Option<ParametrizedCommitProcessorHandle> {
+        if lang.eq_ignore_ascii_case("java") {
+            Some(ParametrizedCommitProcessorHandle(
+                CommitProcessorHandle(std::any::TypeId::of::<
+                    crate::java_processor::JavaProcessorHolder,
+                >()),
+                self.parameter.java_handle.0,
+            ))
+        } else if lang.eq_ignore_ascii_case("cpp") {
+            Some(ParametrizedCommitProcessorHandle(
+                CommitProcessorHandle(std::any::TypeId::of::<
+                    crate::cpp_processor::CppProcessorHolder,
+                >()),
+                self.parameter.cpp_handle.0,
+            ))
+        } else {
+            #[cfg(feature = "c")]
+            if lang.eq_ignore_ascii_case("c") {
+                return Some(ParametrizedCommitProcessorHandle(
+                    CommitProcessorHandle(std::any::TypeId::of::<
+                        crate::c_processor::CProcessorHolder,
+                    >()),
+                    self.parameter.c_handle.0,
+                ));
+            }
+            #[cfg(feature = "ts")]
+            if lang.eq_ignore_ascii_case("ts") || lang.eq_ignore_ascii_case("typescript") {
+                return Some(ParametrizedCommitProcessorHandle(
+                    CommitProcessorHandle(std::any::TypeId::of::<
+                        crate::ts_processor::TsProcessorHolder,
+                    >()),
+                    self.parameter.ts_handle.0,
+                ));
+            }
+            #[cfg(feature = "python")]
+            if lang.eq_ignore_ascii_case("python") || lang.eq_ignore_ascii_case("py") {
+                return Some(ParametrizedCommitProcessorHandle(
+                    CommitProcessorHandle(std::any::TypeId::of::<
+                        crate::python_processor::PythonProcessorHolder,
+                    >()),
+                    self.parameter.python_handle.0,
+                ));
+            }
+            #[cfg(feature = "rust")]
+            if lang.eq_ignore_ascii_case("rust") || lang.eq_ignore_ascii_case("rs") {
+                return Some(ParametrizedCommitProcessorHandle(
+                    CommitProcessorHandle(std::any::TypeId::of::<
+                        crate::rust_processor::RustProcessorHolder,
+                    >()),
+                    self.parameter.rust_handle.0,
+                ));
+            }
+            None
+        }
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for AnyProc {
+    type Holder = AnyProcessorHolder;
+}
+
+impl crate::processing::erased::ParametrizedCommitProc2 for AnyProcessorHolder {
+    type Proc = AnyProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+
+impl CacheHolding<crate::processing::caches::Any> for AnyProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Any {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Any {
+        &self.cache
+    }
+}
+
+impl CacheHolding<crate::processing::caches::Any> for AnyProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Any {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Any {
+        &self.0.as_ref().unwrap().cache
+    }
+}
@@ -0,0 +1,298 @@
+use crate::{
+    Accumulator, BasicDirAcc, DefaultMetrics, PROPAGATE_ERROR_ON_BAD_CST_NODE, ParseErr,
+    processing::ObjectName,
+};
+use enumset::EnumSet;
+use hyperast::{
+    store::defaults::{LabelIdentifier, NodeIdentifier},
+    tree_gen::NoOpMore,
+};
+use hyperast_gen_ts_rust::legion as rs_tree_gen;
+use hyperast_gen_ts_toml::{
+    legion::{self as toml_tree_gen, TomlTreeGen},
+    types::TStore,
+};
+use std::path::{Path, PathBuf};
+
+pub(crate) fn handle_cargo_toml_file<'stores, 'cache, 'b: 'stores>(
+    tree_gen: &mut TomlTreeGen<'stores, 'cache, TStore, NoOpMore<TStore, toml_tree_gen::Acc>>,
+    name: &ObjectName,
+    text: &'b [u8],
+) -> Result<CargoToml, ParseErr> {
+    let tree = match toml_tree_gen::tree_sitter_parse(text) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST: {:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(ParseErr::IllFormed);
+            } else {
+                tree
+            }
+        }
+    };
+    let manifest = Manifest::read(&tree, text);
+    let x = tree_gen
+        .generate_file(name.as_bytes(), text, tree.walk())
+        .local;
+    // TODO handle explicit target paths, ie. `path` in [lib], [[bin]], [[test]], ...
+    let (source_dirs, test_source_dirs) = if manifest.is_package {
+        (
+            vec![
+                "src".to_owned(),
+                "benches".to_owned(),
+                "examples".to_owned(),
+            ],
+            vec!["tests".to_owned()],
+        )
+    } else {
+        // a virtual manifest, ie. the root of a workspace without its own package
+        (vec![], vec![])
+    };
+    let x = CargoToml {
+        compressed_node: x.compressed_node,
+        metrics: x.metrics,
+        is_package: manifest.is_package,
+        members: manifest.members,
+        exclude: manifest.exclude,
+        source_dirs,
+        test_source_dirs,
+    };
+    Ok(x)
+}
+
+#[derive(Debug, Clone)]
+pub struct CargoToml {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: DefaultMetrics,
+    is_package: bool,
+    members: Vec<String>,
+    exclude: Vec<String>,
+    source_dirs: Vec<String>,
+    test_source_dirs: Vec<String>,
+}
+
+/// What is needed from a manifest to layout a package,
+/// read directly on the CST as the values are scattered over tables.
+#[derive(Default)]
+struct Manifest {
+    is_package: bool,
+    members: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl Manifest {
+    fn read(tree: &tree_sitter::Tree, text: &[u8]) -> Self {
+        let mut manifest = Self::default();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        for table in root.named_children(&mut cursor) {
+            if table.kind() != "table" {
+                continue;
+            }
+            let Some(key) = table.named_child(0) else {
+                continue;
+            };
+            match key_text(key, text).as_deref() {
+                Some("package") => manifest.is_package = true,
+                Some("workspace") => {
+                    let mut cursor = table.walk();
+                    for pair in table.named_children(&mut cursor) {
+                        if pair.kind() != "pair" {
+                            continue;
+                        }
+                        let (Some(key), Some(value)) = (pair.named_child(0), pair.named_child(1))
+                        else {
+                            continue;
+                        };
+                        match key_text(key, text).as_deref() {
+                            Some("members") => manifest.members = strings(value, text),
+                            Some("exclude") => manifest.exclude = strings(value, text),
+                            _ => (),
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        manifest
+    }
+}
+
+fn key_text(key: tree_sitter::Node, text: &[u8]) -> Option<String> {
+    match key.kind() {
+        "bare_key" => key.utf8_text(text).ok().map(|x| x.to_owned()),
+        "quoted_key" => key.utf8_text(text).ok().map(|x| unquote(x).to_owned()),
+        // eg. [workspace.dependencies] is not relevant for the layout
+        _ => None,
+    }
+}
+
+fn strings(array: tree_sitter::Node, text: &[u8]) -> Vec<String> {
+    if array.kind() != "array" {
+        return vec![];
+    }
+    let mut cursor = array.walk();
+    array
+        .named_children(&mut cursor)
+        .filter(|x| x.kind() == "string")
+        .filter_map(|x| x.utf8_text(text).ok())
+        .map(|x| unquote(x).trim_end_matches('/').to_owned())
+        .collect()
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim_matches('"').trim_matches('\'')
+}
+
+/// Matches the first component of a workspace member against the name of a directory,
+/// members can contain globs, eg. `crates/*`, which is the only pattern handled for now.
+pub(crate) fn strip_member(member: &Path, name: &str) -> Option<PathBuf> {
+    let mut components = member.components();
+    let first = components.next()?.as_os_str().to_str()?;
+    let matches = if let Some((prefix, suffix)) = first.split_once('*') {
+        name.len() >= prefix.len() + suffix.len()
+            && name.starts_with(prefix)
+            && name.ends_with(suffix)
+    } else {
+        first == name
+    };
+    matches.then(|| components.as_path().to_owned())
+}
+
+#[derive(Debug, Clone)]
+pub struct MD {
+    pub(crate) metrics: DefaultMetrics,
+    pub(crate) status: EnumSet<SemFlag>,
+}
+
+pub struct CargoModuleAcc {
+    pub(crate) primary: BasicDirAcc<NodeIdentifier, LabelIdentifier, DefaultMetrics>,
+    pub(crate) members: Option<Vec<PathBuf>>,
+    pub(crate) exclude: Option<Vec<PathBuf>>,
+    pub(crate) main_dirs: Option<Vec<PathBuf>>,
+    pub(crate) test_dirs: Option<Vec<PathBuf>>,
+    pub(crate) status: EnumSet<SemFlag>,
+}
+
+impl From<String> for CargoModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl CargoModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            primary: BasicDirAcc::new(name),
+            members: None,
+            exclude: None,
+            main_dirs: None,
+            test_dirs: None,
+            status: Default::default(),
+        }
+    }
+    pub(crate) fn with_content(
+        name: String,
+        members: Vec<PathBuf>,
+        exclude: Vec<PathBuf>,
+        main_dirs: Vec<PathBuf>,
+        test_dirs: Vec<PathBuf>,
+    ) -> Self {
+        let non_empty = |v: Vec<PathBuf>| if v.is_empty() { None } else { Some(v) };
+        Self {
+            primary: BasicDirAcc::new(name),
+            members: non_empty(members),
+            exclude: non_empty(exclude),
+            main_dirs: non_empty(main_dirs),
+            test_dirs: non_empty(test_dirs),
+            status: Default::default(),
+        }
+    }
+}
+
+#[derive(enumset::EnumSetType, Debug)]
+pub enum SemFlag {
+    IsCargoPackage,
+    IsCargoWorkspace,
+    HoldMainFolder,
+    HoldTestFolder,
+    HoldCargoMember,
+}
+
+impl CargoModuleAcc {
+    pub(crate) fn is_module(&self) -> bool {
+        self.status.contains(SemFlag::IsCargoPackage)
+            || self.status.contains(SemFlag::IsCargoWorkspace)
+    }
+    pub(crate) fn push_manifest(&mut self, name: LabelIdentifier, full_node: CargoToml) {
+        if full_node.is_package {
+            self.status |= SemFlag::IsCargoPackage;
+        }
+        if !full_node.members.is_empty() {
+            self.status |= SemFlag::IsCargoWorkspace;
+        }
+        assert!(!self.primary.children_names.contains(&name));
+        self.primary.children.push(full_node.compressed_node);
+        self.primary.children_names.push(name);
+        self.members = Some(full_node.members.iter().map(|x| x.into()).collect());
+        self.exclude = Some(full_node.exclude.iter().map(|x| x.into()).collect());
+        self.main_dirs = Some(full_node.source_dirs.iter().map(|x| x.into()).collect());
+        self.test_dirs = Some(
+            full_node
+                .test_source_dirs
+                .iter()
+                .map(|x| x.into())
+                .collect(),
+        );
+        self.primary.metrics.acc(full_node.metrics);
+    }
+    pub fn push_member(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
+        self.status |= SemFlag::HoldCargoMember;
+        self.primary.children.push(full_node.0);
+        self.primary.children_names.push(name);
+        self.primary.metrics.acc(full_node.1.metrics);
+    }
+    pub(crate) fn push_source_file(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: rs_tree_gen::Local,
+    ) {
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+    pub(crate) fn push_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: rs_tree_gen::Local,
+    ) {
+        self.status |= SemFlag::HoldMainFolder;
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+    pub(crate) fn push_test_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: rs_tree_gen::Local,
+    ) {
+        self.status |= SemFlag::HoldTestFolder;
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+}
+
+impl hyperast::tree_gen::Accumulator for CargoModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, MD));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        // only propagates what is held by intermediate directories
+        let s = full_node.1.status - SemFlag::IsCargoPackage - SemFlag::IsCargoWorkspace;
+        self.status |= s;
+        self.primary.children.push(full_node.0);
+        self.primary.children_names.push(name);
+        self.primary.metrics.acc(full_node.1.metrics);
+    }
+}
+
+impl Accumulator for CargoModuleAcc {
+    type Unlabeled = (NodeIdentifier, MD);
+}
@@ -0,0 +1,564 @@
+use crate::StackEle;
+use crate::processing::ParametrizedCommitProcessorHandle;
+use crate::processing::erased::{
+    CommitProcessorHandle, ParametrizedCommitProcessor2Handle as PCP2Handle,
+};
+use crate::{
+    Processor,
+    cargo::{CargoModuleAcc, MD},
+    git::BasicGitObject,
+    preprocessed::RepositoryProcessor,
+    processing::{CacheHolding, InFiles, ObjectName, erased::ParametrizedCommitProc2, file_sys},
+    rust_processor::RustProc,
+};
+use git2::{Oid, Repository};
+use hyperast::store::nodes::compo;
+use hyperast::types::ETypeStore as _;
+use hyperast::{
+    hashed::{IndexingHashBuilder, MetaDataHashsBuilder},
+    store::{
+        defaults::NodeIdentifier,
+        nodes::{EntityBuilder, legion::eq_node},
+    },
+    tree_gen::Accumulator,
+    types::LabelStore,
+};
+use hyperast_gen_ts_toml::types::Type;
+use std::{
+    iter::Peekable,
+    marker::PhantomData,
+    path::{Components, PathBuf},
+};
+
+pub type SimpleStores = hyperast::store::SimpleStores<hyperast_gen_ts_toml::types::TStore>;
+
+/// Walks a cargo project from its root,
+/// workspace members are handled as modules,
+/// and the target directories of each package (src/, tests/, ...) are handled as rust folders.
+pub struct CargoProcessor<'a, 'b, 'c, Acc> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<StackEle<Acc>>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: ParametrizedCommitProcessorHandle,
+}
+
+impl<'a, 'b, 'c> CargoProcessor<'a, 'b, 'c, CargoModuleAcc> {
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        mut dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+        handle: ParametrizedCommitProcessorHandle,
+    ) -> Self {
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree, &mut dir_path);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![StackEle::new(oid, prepared, CargoModuleAcc::new(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+        }
+    }
+}
+
+impl<'a, 'b, 'c> Processor<CargoModuleAcc> for CargoProcessor<'a, 'b, 'c, CargoModuleAcc> {
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                if file_sys::is_cargo_ignored(&name) {
+                    return;
+                }
+                self.handle_tree_cached(name, oid);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                if file_sys::CargoToml::matches(&name) {
+                    let parent_acc = &mut self.stack.last_mut().unwrap().acc;
+                    let parameters = PCP2Handle(self.handle.1, PhantomData);
+                    if let Err(err) = self.prepro.handle_cargo_toml(
+                        oid,
+                        parent_acc,
+                        name,
+                        &self.repository,
+                        parameters,
+                    ) {
+                        log::debug!("{:?}", err);
+                    }
+                } else if file_sys::Rust::matches(&name)
+                    && self.stack.last().unwrap().acc.is_module()
+                {
+                    // eg. a build.rs at the root of a package
+                    let rust_handle = self.rust_handle();
+                    match self
+                        .prepro
+                        .handle_rust_blob(oid, &name, self.repository, rust_handle)
+                    {
+                        Ok((full_node,)) => {
+                            let name = self.prepro.intern_object_name(&name);
+                            let w = &mut self.stack.last_mut().unwrap().acc;
+                            assert!(!w.primary.children_names.contains(&name));
+                            w.push_source_file(name, full_node);
+                        }
+                        Err(e) => log::warn!("skipping {:?}: {:?}", name.try_str(), e),
+                    }
+                } else {
+                    log::debug!("not a cargo manifest {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: CargoModuleAcc) -> Option<(NodeIdentifier, MD)> {
+        let name = acc.primary.name.clone();
+        let full_node = make(acc, self.prepro.main_stores_mut().mut_with_ts());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<CargoProcessorHolder>()
+            .with_parameters_mut(self.handle.1)
+            .cache
+            .object_map
+            .insert(oid, full_node.clone());
+        let name = self.prepro.intern_label(&name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            assert!(
+                !w.primary.children_names.contains(&name),
+                "{:?} {:?}",
+                w.primary.children_names,
+                name
+            );
+            push_dir(w, name, full_node);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<StackEle<CargoModuleAcc>> {
+        &mut self.stack
+    }
+}
+
+fn push_dir(
+    w: &mut CargoModuleAcc,
+    name: hyperast::store::defaults::LabelIdentifier,
+    full_node: (NodeIdentifier, MD),
+) {
+    if full_node
+        .1
+        .status
+        .contains(crate::cargo::SemFlag::IsCargoPackage)
+        || full_node
+            .1
+            .status
+            .contains(crate::cargo::SemFlag::IsCargoWorkspace)
+    {
+        w.push_member(name, full_node);
+    } else {
+        w.push((name, full_node));
+    }
+}
+
+impl<'a, 'b, 'c> CargoProcessor<'a, 'b, 'c, CargoModuleAcc> {
+    fn rust_handle(&mut self) -> PCP2Handle<RustProc> {
+        self.prepro
+            .processing_systems
+            .mut_or_default::<CargoProcessorHolder>()
+            .with_parameters_mut(self.handle.1)
+            .parameter
+            .rust_handle
+    }
+
+    fn handle_tree_cached(&mut self, name: ObjectName, oid: Oid) {
+        if let Some(s) = self.dir_path.peek() {
+            if name
+                .as_bytes()
+                .eq(std::ffi::OsStr::as_encoded_bytes(s.as_os_str()))
+            {
+                self.dir_path.next();
+                self.stack.last_mut().expect("never empty").cs.clear();
+                let tree = self.repository.find_tree(oid).unwrap();
+                let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+                self.stack.push(StackEle::new(
+                    oid,
+                    prepared,
+                    CargoModuleAcc::new(name.try_into().unwrap()),
+                ));
+            }
+            return;
+        }
+        let cargo_proc = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<CargoProcessorHolder>()
+            .with_parameters_mut(self.handle.1);
+        let rust_handle = cargo_proc.parameter.rust_handle;
+        if let Some(already) = cargo_proc.cache.object_map.get(&oid) {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            let name = self.prepro.intern_object_name(&name);
+            assert!(!w.primary.children_names.contains(&name));
+            push_dir(w, name, full_node);
+            return;
+        }
+
+        log::debug!("cargo tree {:?}", name.try_str());
+        let parent_acc = &mut self.stack.last_mut().unwrap().acc;
+        let parent_is_module = parent_acc.is_module();
+        let helper = CargoModuleHelper::from((parent_acc, &name));
+        if helper.source_directories.0 || helper.test_source_directories.0 {
+            // handle as a target directory of the package
+            let (name, (full_node,)) = self.prepro.help_handle_rust_folder(
+                &self.repository,
+                self.dir_path,
+                oid,
+                &name,
+                rust_handle,
+            );
+            let parent_acc = &mut self.stack.last_mut().unwrap().acc;
+            assert!(!parent_acc.primary.children_names.contains(&name));
+            if helper.source_directories.0 {
+                parent_acc.push_source_directory(name, full_node);
+            } else {
+                parent_acc.push_test_source_directory(name, full_node);
+            }
+            return;
+        }
+        if helper.excluded {
+            return;
+        }
+        let tree = self.repository.find_tree(oid).unwrap();
+        // a package nested in another one without being a member, eg. a test fixture,
+        // or a package in a repository without a root manifest
+        let has_manifest = tree
+            .iter()
+            .any(|x| file_sys::CargoToml::matches(&x.name_bytes().into()));
+        if helper.members.0 || !helper.members.1.is_empty() || has_manifest || !parent_is_module {
+            let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+            self.stack
+                .push(StackEle::new(oid, prepared, helper.into_acc()));
+        }
+        // TODO other directories of a package (eg. resources, docs) are not handled for now
+    }
+}
+
+use hyperast_gen_ts_toml::legion::TomlTreeGen;
+impl RepositoryProcessor {
+    fn handle_cargo_toml(
+        &mut self,
+        oid: Oid,
+        parent_acc: &mut CargoModuleAcc,
+        name: ObjectName,
+        repository: &Repository,
+        parameters: PCP2Handle<CargoProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let x = self
+            .processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::CargoToml>()
+            .handle(oid, repository, &name, parameters, |c, n, t| {
+                let line_break = if t.contains(&b'\r') {
+                    "\r\n".as_bytes().to_vec()
+                } else {
+                    "\n".as_bytes().to_vec()
+                };
+                let holder = c.mut_or_default::<CargoProcessorHolder>();
+                let cargo_proc = holder.with_parameters_mut(parameters.0);
+                let md_cache = &mut cargo_proc.manifest_cache.md_cache;
+                let mut toml_tree_gen = TomlTreeGen {
+                    line_break,
+                    stores: self.main_stores.mut_with_ts(),
+                    md_cache,
+                    more: Default::default(),
+                };
+                crate::cargo::handle_cargo_toml_file(&mut toml_tree_gen, n, t)
+            })?;
+        let name = self.intern_object_name(&name);
+        assert!(!parent_acc.primary.children_names.contains(&name));
+        parent_acc.push_manifest(name, x);
+        Ok(())
+    }
+}
+
+struct CargoModuleHelper {
+    name: String,
+    excluded: bool,
+    members: (bool, Vec<PathBuf>),
+    exclude: Vec<PathBuf>,
+    source_directories: (bool, Vec<PathBuf>),
+    test_source_directories: (bool, Vec<PathBuf>),
+}
+
+impl From<(&mut CargoModuleAcc, &ObjectName)> for CargoModuleHelper {
+    fn from((parent_acc, name): (&mut CargoModuleAcc, &ObjectName)) -> Self {
+        let name = std::str::from_utf8(name.as_bytes()).unwrap();
+        let process = |v: &Option<Vec<PathBuf>>| {
+            let mut v: Vec<_> = v
+                .iter()
+                .flatten()
+                .filter_map(|x| crate::cargo::strip_member(x, name))
+                .collect();
+            let c = vec_extract_if_polyfill::MakeExtractIf::extract_if(&mut v, |x| {
+                x.components().next().is_none()
+            })
+            .count();
+            (c > 0, v)
+        };
+        let exclude = process(&parent_acc.exclude);
+        Self {
+            name: name.to_owned(),
+            excluded: exclude.0,
+            members: process(&parent_acc.members),
+            exclude: exclude.1,
+            source_directories: process(&parent_acc.main_dirs),
+            test_source_directories: process(&parent_acc.test_dirs),
+        }
+    }
+}
+
+impl CargoModuleHelper {
+    fn into_acc(self) -> CargoModuleAcc {
+        CargoModuleAcc::with_content(
+            self.name,
+            self.members.1,
+            self.exclude,
+            self.source_directories.1,
+            self.test_source_directories.1,
+        )
+    }
+}
+
+/// priority to the Cargo.toml, it tells how the rest of the directory should be handled
+pub(crate) fn prepare_dir_exploration(
+    tree: git2::Tree,
+    dir_path: &mut Peekable<Components>,
+) -> Vec<BasicGitObject> {
+    let mut children_objects: Vec<BasicGitObject> = tree
+        .iter()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect();
+    if dir_path.peek().is_none() {
+        let p = children_objects.iter().position(|x| match x {
+            BasicGitObject::Blob(_, n) => file_sys::CargoToml::matches(&n),
+            _ => false,
+        });
+        if let Some(p) = p {
+            children_objects.swap(0, p); // priority to Cargo.toml processing
+            children_objects.reverse(); // we use it like a stack
+        }
+    }
+    children_objects
+}
+
+pub(crate) fn make(acc: CargoModuleAcc, stores: &mut SimpleStores) -> (NodeIdentifier, MD) {
+    let kind = Type::CargoDirectory;
+    let interned_kind = hyperast_gen_ts_toml::types::TStore::intern(kind);
+    let label_id = stores.label_store.get_or_insert(acc.primary.name.clone());
+
+    let primary = acc
+        .primary
+        .map_metrics(|m| m.finalize(&interned_kind, &label_id, 0));
+
+    let hashable = primary.metrics.hashs.most_discriminating();
+
+    let eq = eq_node(&interned_kind, Some(&label_id), &primary.children);
+
+    assert_eq!(primary.children_names.len(), primary.children.len());
+
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    if let Some(id) = insertion.occupied_id() {
+        let metrics = primary
+            .metrics
+            .map_hashs(|h| MetaDataHashsBuilder::build(h));
+        let status = acc.status;
+        return (id, MD { metrics, status });
+    }
+
+    log::info!(
+        "make cargo module {} {}",
+        &primary.name,
+        primary.children.len()
+    );
+
+    let mut dyn_builder = hyperast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+
+    let children_is_empty = primary.children.is_empty();
+    if !acc.status.is_empty() {
+        dyn_builder.add(compo::Flags(acc.status));
+    }
+
+    let metrics = primary.persist(&mut dyn_builder, interned_kind, label_id);
+    let metrics = metrics.map_hashs(|h| h.build());
+    let hashs = metrics.add_md_metrics(&mut dyn_builder, children_is_empty);
+    hashs.persist(&mut dyn_builder);
+
+    let vacant = insertion.vacant();
+    let node_id = hyperast::store::nodes::legion::NodeStore::insert_built_after_prepare(
+        vacant,
+        dyn_builder.build(),
+    );
+
+    let status = acc.status;
+    (node_id, MD { metrics, status })
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub(crate) rust_handle: PCP2Handle<RustProc>,
+}
+
+#[derive(Default)]
+pub(crate) struct CargoProcessorHolder(Option<CargoProc>);
+pub(crate) struct CargoProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Cargo,
+    manifest_cache: crate::processing::caches::CargoToml,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for CargoProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0;
+                // TODO enable multi configs for cargo, do the same as the one for Java
+                self.0 = Some(CargoProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    manifest_cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+
+struct PreparedCargoCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+    pub(crate) handle: ParametrizedCommitProcessorHandle,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedCargoCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedCargoCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyperast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = CargoProcessor::<CargoModuleAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+            self.handle,
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<CargoProcessorHolder>();
+        let handle = self.handle;
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.1)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+
+impl crate::processing::erased::CommitProc for CargoProc {
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+        handle: crate::processing::ParametrizedCommitProcessorHandle,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedCargoCommitProc {
+            repository,
+            commit_builder,
+            handle,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        Box::new(self.commits.iter())
+    }
+
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+
+    fn get_lang_handle(&self, lang: &str) -> Option<ParametrizedCommitProcessorHandle> {
+        if lang.eq_ignore_ascii_case("rust") || lang.eq_ignore_ascii_case("rs") {
+            Some(ParametrizedCommitProcessorHandle(
+                CommitProcessorHandle(std::any::TypeId::of::<
+                    crate::rust_processor::RustProcessorHolder,
+                >()),
+                self.parameter.rust_handle.0,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for CargoProc {
+    type Holder = CargoProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for CargoProcessorHolder {
+    type Proc = CargoProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::Cargo> for CargoProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Cargo {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Cargo {
+        &self.cache
+    }
+}
+impl CacheHolding<crate::processing::caches::CargoToml> for CargoProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::CargoToml {
+        &mut self.manifest_cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::CargoToml {
+        &self.manifest_cache
+    }
+}
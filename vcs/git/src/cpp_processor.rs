@@ -256,6 +256,14 @@ impl crate::processing::erased::CommitProc for CppProc {
         self.commits.get(&commit_oid)
     }
 
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        Box::new(self.commits.iter())
+    }
+
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+
     fn get_precomp_query(&self) -> Option<hyperast_tsquery::ZeroSepArrayStr> {
         dbg!(&self.parameter.query);
         self.parameter.query.clone()
@@ -304,7 +312,7 @@ impl CacheHolding<crate::processing::caches::Cpp> for CppProcessorHolder {
 
 #[cfg(feature = "cpp")]
 impl RepositoryProcessor {
-    fn handle_cpp_blob(
+    pub(crate) fn handle_cpp_blob(
         &mut self,
         oid: Oid,
         name: &ObjectName,
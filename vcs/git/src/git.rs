@@ -1,7 +1,8 @@
 use std::{
+    collections::{BinaryHeap, HashSet, VecDeque},
     fmt::{Debug, Display},
     fs,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     process,
 };
 
@@ -11,13 +12,14 @@ use hyperast::{position::Position, utils::Url};
 
 use crate::processing::ObjectName;
 
-pub struct Builder<'a>(git2::Revwalk<'a>, &'a git2::Repository, bool);
+/// The revwalk, the repository, the pushed commits and the hidden commits
+pub struct Builder<'a>(git2::Revwalk<'a>, &'a git2::Repository, Vec<Oid>, Vec<Oid>);
 
 impl<'a> Builder<'a> {
     pub fn new(repository: &'a Repository) -> Result<Self, git2::Error> {
         let mut rw = repository.revwalk()?;
         rw.set_sorting(git2::Sort::TOPOLOGICAL & git2::Sort::TIME)?;
-        Ok(Self(rw, repository, false))
+        Ok(Self(rw, repository, vec![], vec![]))
     }
     pub fn before(mut self, before: &str) -> Result<Self, git2::Error> {
         if before.is_empty() {
@@ -26,31 +28,223 @@ impl<'a> Builder<'a> {
         let c = retrieve_commit(self.1, before)?;
         for c in c.parents() {
             self.0.hide(c.id())?;
+            self.3.push(c.id());
         }
         Ok(self)
     }
 
+    /// Contrary to [`Builder::before`], also hides `excluded`, like `excluded..` in git
+    pub fn exclude(mut self, excluded: &str) -> Result<Self, git2::Error> {
+        if excluded.is_empty() {
+            return Ok(self);
+        }
+        let c = retrieve_commit(self.1, excluded)?;
+        self.0.hide(c.id())?;
+        self.3.push(c.id());
+        Ok(self)
+    }
+
     pub fn after(mut self, after: &str) -> Result<Self, git2::Error> {
         if after.is_empty() {
             return Ok(self);
         }
         let c = retrieve_commit(self.1, after)?;
         self.0.push(c.id())?;
-        self.2 = true;
+        self.2.push(c.id());
         Ok(self)
     }
 
+    pub fn range(self, range: &RevRange) -> Result<Self, git2::Error> {
+        self.exclude(&range.excluded)?.after(&range.included)
+    }
+
     pub fn first_parents(mut self) -> Result<Self, git2::Error> {
         self.0.simplify_first_parent()?;
         Ok(self)
     }
 
     pub fn walk(mut self) -> Result<Revwalk<'a>, git2::Error> {
-        if !self.2 {
+        if self.2.is_empty() {
             self.0.push_head()?;
         }
         Ok(self.0)
     }
+
+    /// Walks all the parents of merge commits, breadth first from the pushed commits (or HEAD),
+    /// see [`DagWalk`].
+    pub fn dag(mut self, bounds: DagBounds) -> Result<DagWalk<'a>, git2::Error> {
+        if self.2.is_empty() {
+            let head = self.1.head()?.peel_to_commit()?.id();
+            self.2.push(head);
+        }
+        let mut hidden = Hidden::new(self.1, self.3)?;
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        for tip in self.2 {
+            let commit = self.1.find_commit(tip)?;
+            if seen.insert(tip) && !hidden.contains(self.1, &commit)? {
+                queue.push_back((tip, 0));
+            }
+        }
+        Ok(DagWalk {
+            repository: self.1,
+            hidden,
+            seen,
+            queue,
+            bounds,
+        })
+    }
+}
+
+/// A range of revisions, as in `git log`:
+/// - `B` is `B` and its ancestors,
+/// - `A..B` is the ancestors of `B` (including `B`) that are not ancestors of `A` (including `A`),
+/// - `A..` is `A..HEAD`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RevRange {
+    /// empty when nothing is excluded
+    pub excluded: String,
+    /// empty for HEAD
+    pub included: String,
+}
+
+impl RevRange {
+    pub fn is_range(s: &str) -> bool {
+        s.contains("..")
+    }
+}
+
+impl std::str::FromStr for RevRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((excluded, included)) = s.split_once("..") else {
+            return Ok(Self {
+                excluded: String::new(),
+                included: s.to_string(),
+            });
+        };
+        if included.starts_with('.') {
+            return Err(format!("symmetric differences are not supported: {}", s));
+        }
+        Ok(Self {
+            excluded: excluded.to_string(),
+            included: included.to_string(),
+        })
+    }
+}
+
+impl Display for RevRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.excluded.is_empty() {
+            write!(f, "{}", self.included)
+        } else {
+            write!(f, "{}..{}", self.excluded, self.included)
+        }
+    }
+}
+
+/// Bounds of a [`DagWalk`], unbounded by default
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DagBounds {
+    /// the maximum number of parent links from a pushed commit, 0 only gives the pushed commits
+    pub depth: Option<usize>,
+    /// in seconds since the epoch, older commits and their ancestors are not walked
+    pub since: Option<i64>,
+    /// in seconds since the epoch, newer commits are skipped but their ancestors are still walked
+    pub until: Option<i64>,
+}
+
+/// A breadth first walk of the commit graph that follows all the parents of merge commits.
+///
+/// Each commit is given once, at its smallest depth,
+/// so that commits shared by several branches are only processed once.
+pub struct DagWalk<'a> {
+    repository: &'a Repository,
+    hidden: Hidden,
+    seen: HashSet<Oid>,
+    queue: VecDeque<(Oid, usize)>,
+    bounds: DagBounds,
+}
+
+impl<'a> Iterator for DagWalk<'a> {
+    type Item = Result<Oid, git2::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (oid, depth) = self.queue.pop_front()?;
+            let commit = match self.repository.find_commit(oid) {
+                Ok(commit) => commit,
+                Err(e) => return Some(Err(e)),
+            };
+            let time = commit.time().seconds();
+            if self.bounds.since.is_some_and(|since| time < since) {
+                continue;
+            }
+            if self.bounds.depth.is_none_or(|d| depth < d) {
+                for p in commit.parents() {
+                    if self.seen.contains(&p.id()) {
+                        continue;
+                    }
+                    match self.hidden.contains(self.repository, &p) {
+                        Ok(true) => continue,
+                        Ok(false) => (),
+                        Err(e) => return Some(Err(e)),
+                    }
+                    self.seen.insert(p.id());
+                    self.queue.push_back((p.id(), depth + 1));
+                }
+            }
+            if self.bounds.until.is_some_and(|until| time > until) {
+                continue;
+            }
+            return Some(Ok(oid));
+        }
+    }
+}
+
+/// The hidden commits and their ancestors, discovered lazily from the newest ones.
+///
+/// Like the revwalk of git, it relies on commits being newer than their parents,
+/// so only the ancestors newer than the asked commit are ever loaded.
+struct Hidden {
+    commits: HashSet<Oid>,
+    frontier: BinaryHeap<(i64, Oid)>,
+}
+
+impl Hidden {
+    fn new(repository: &Repository, hidden: Vec<Oid>) -> Result<Self, git2::Error> {
+        let mut frontier = BinaryHeap::new();
+        for oid in hidden {
+            let commit = repository.find_commit(oid)?;
+            frontier.push((commit.time().seconds(), oid));
+        }
+        let commits = frontier.iter().map(|(_, oid)| *oid).collect();
+        Ok(Self { commits, frontier })
+    }
+
+    fn contains(
+        &mut self,
+        repository: &Repository,
+        commit: &git2::Commit,
+    ) -> Result<bool, git2::Error> {
+        let time = commit.time().seconds();
+        while !self.commits.contains(&commit.id()) {
+            let Some((t, oid)) = self.frontier.peek().copied() else {
+                break;
+            };
+            if t < time {
+                break;
+            }
+            self.frontier.pop();
+            for p in repository.find_commit(oid)?.parents() {
+                if self.commits.insert(p.id()) {
+                    self.frontier.push((p.time().seconds(), p.id()));
+                }
+            }
+        }
+        Ok(self.commits.contains(&commit.id()))
+    }
 }
 
 /// Initialize a [git2::revwalk::Revwalk] to explore commits between before and after.
@@ -177,8 +371,7 @@ where
     <T as TryInto<Url>>::Error: std::fmt::Debug,
 {
     let url: Url = url.try_into().unwrap();
-    let mut path: PathBuf = path.into();
-    path.push(url.path.clone());
+    let path = clone_destination(path.into(), &url);
     // let url = &format!("{}{}", "https://github.com/", repo_name);
     // let path = &format!("{}{}", "/tmp/hyperastgitresources/repo/", repo_name);
     let mut callbacks = RemoteCallbacks::new();
@@ -209,13 +402,29 @@ where
     nofetch_repository(url, path)
 }
 
+/// Clones are placed at the path of their url within `root`, it must not lead out of it.
+fn clone_destination(root: PathBuf, url: &Url) -> PathBuf {
+    // the clone might not exist yet, so it cannot be canonicalized
+    let path = root.join(&url.path);
+    let within = path.starts_with(&root)
+        && !path
+            .components()
+            .any(|c| matches!(c, Component::ParentDir | Component::CurDir));
+    assert!(
+        within,
+        "{} is outside of {}",
+        path.display(),
+        root.display()
+    );
+    path
+}
+
 pub fn nofetch_repository<'a, T: TryInto<Url>, U: Into<PathBuf>>(url: T, path: U) -> Repository
 where
     <T as TryInto<Url>>::Error: std::fmt::Debug,
 {
     let url: Url = url.try_into().unwrap();
-    let mut path: PathBuf = path.into();
-    path.push(url.path.clone());
+    let path = clone_destination(path.into(), &url);
 
     let repository = up_to_date_repo(&path, None, url);
     repository.unwrap()
@@ -226,31 +435,122 @@ pub enum Forge {
     Github,
     Gitlab,
     GitlabInria,
+    /// a self-hosted instance (eg. of GitLab or Gitea), reachable in https,
+    /// hosts must be registered with [`Forge::register_self_hosted`]
+    SelfHosted(&'static str),
+    /// bare repositories on the local file system, at `<root>/<user>/<name>`,
+    /// the root must be set with [`Forge::set_local_root`]
+    Local,
+    /// repositories already on the local file system, registered with [`Repo::on_disk`],
+    /// they are processed in place without any remote operation
+    Disk,
 }
 
+/// hosts are leaked once at registration to keep [`Forge`] Copy
+static SELF_HOSTED: std::sync::RwLock<Vec<&'static str>> = std::sync::RwLock::new(vec![]);
+static LOCAL_ROOT: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+
 impl std::str::FromStr for Forge {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
-            "github.com" => Self::Github,
-            "gitlab.com" => Self::Gitlab,
+            "github.com" | "github" => Self::Github,
+            "gitlab.com" | "gitlab" => Self::Gitlab,
             "gitlab.inria.fr" => Self::GitlabInria,
-            x => return Err(format!("'{}' is not an authorize forge", x)),
+            "file" | "local" => {
+                if LOCAL_ROOT.get().is_none() {
+                    return Err("no local forge is configured".to_string());
+                }
+                Self::Local
+            }
+            "disk" => Self::Disk,
+            x => match SELF_HOSTED.read().unwrap().iter().find(|h| **h == x) {
+                Some(host) => Self::SelfHosted(host),
+                None => return Err(format!("'{}' is not an authorize forge", x)),
+            },
         })
     }
 }
 
+impl serde::Serialize for Forge {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.host())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Forge {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl Forge {
-    fn url(&self) -> &str {
+    /// Allows repositories of a self-hosted forge to be fetched, eg. `git.example.org`.
+    ///
+    /// Registering the same host multiple times gives the same forge.
+    pub fn register_self_hosted(host: &str) -> Result<Forge, String> {
+        if host.is_empty()
+            || host.contains(['/', '#', ':', '@'])
+            || host == "file"
+            || host == "local"
+            || host == "disk"
+        {
+            return Err(format!("'{}' is not a valid host", host));
+        }
+        if let Ok(forge) = host.parse() {
+            return Ok(forge);
+        }
+        let mut hosts = SELF_HOSTED.write().unwrap();
+        if let Some(host) = hosts.iter().find(|h| **h == host) {
+            return Ok(Self::SelfHosted(host));
+        }
+        let host: &'static str = Box::leak(host.to_string().into_boxed_str());
+        hosts.push(host);
+        Ok(Self::SelfHosted(host))
+    }
+
+    /// Sets the directory containing the repositories of the [`Forge::Local`],
+    /// can only be set once.
+    pub fn set_local_root(root: impl Into<PathBuf>) -> Result<(), String> {
+        let root = root.into();
+        let root = root
+            .canonicalize()
+            .map_err(|e| format!("{}: {}", root.display(), e))?;
+        LOCAL_ROOT
+            .set(root)
+            .map_err(|root| format!("local forge already at {}", root.display()))
+    }
+
+    pub fn host(&self) -> &'static str {
         match self {
-            Forge::Github => "https://github.com/",
-            Forge::Gitlab => "https://gitlab.com/",
-            Forge::GitlabInria => "https://gitlab.inria.fr/",
+            Forge::Github => "github.com",
+            Forge::Gitlab => "gitlab.com",
+            Forge::GitlabInria => "gitlab.inria.fr",
+            Forge::SelfHosted(host) => host,
+            Forge::Local => "file",
+            Forge::Disk => "disk",
+        }
+    }
+
+    fn url(&self) -> String {
+        match self {
+            Forge::Github => "https://github.com/".to_string(),
+            Forge::Gitlab => "https://gitlab.com/".to_string(),
+            Forge::GitlabInria => "https://gitlab.inria.fr/".to_string(),
+            Forge::SelfHosted(host) => format!("https://{}/", host),
+            Forge::Local => {
+                let root = LOCAL_ROOT.get().expect("local forge not configured");
+                format!("file://{}/", root.display())
+            }
+            // only reached by repositories that were not registered with their path
+            Forge::Disk => "disk://".to_string(),
         }
     }
 
-    /// panics in case `user`` or `name`` contain '/' '#'
+    /// panics in case `user`` or `name`` contain '/' '#', are empty, `.` or `..`,
+    /// or for the [`Forge::Local`], do not lead to a directory within its root
     pub fn repo(self, user: impl Into<String>, name: impl Into<String>) -> Repo {
         self.try_repo(user, name).unwrap()
     }
@@ -261,51 +561,130 @@ impl Forge {
         name: impl Into<String>,
     ) -> Result<Repo, String> {
         let user = user.into();
-        if user.contains("#") || user.contains("/") {
-            return Err("attempting to inject stuff!".to_string());
-        }
+        check_segment(&user)?;
         let name = name.into();
-        if name.contains("#") || name.contains("/") {
-            return Err("attempting to inject stuff!".to_string());
+        check_segment(&name)?;
+        if let Forge::Local = self {
+            let root = LOCAL_ROOT.get().ok_or("no local forge is configured")?;
+            let path = root.join(&user).join(&name);
+            // also resolves symlinks, that could lead out of the local forge
+            let path = path
+                .canonicalize()
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            if !path.starts_with(root) {
+                return Err(format!("{} is outside of the local forge", path.display()));
+            }
         }
         Ok(Repo {
             forge: self,
             user,
             name,
+            path: None,
         })
     }
 }
 
+/// `user`s and `name`s end up in urls and paths, so they must be plain path segments
+fn check_segment(s: &str) -> Result<(), String> {
+    if s.contains("#") || s.contains("/") || s.is_empty() || s == "." || s == ".." {
+        return Err("attempting to inject stuff!".to_string());
+    }
+    Ok(())
+}
+
 // TODO use `&'static str`s to derive with Copy
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
+/// Repositories registered with [`Repo::on_disk`] are also identified by their path,
+/// see [`crate::multi_preprocessed::PreProcessedRepositories::get_config`] to retrieve them without it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Repo {
     forge: Forge,
     user: String,
     name: String,
+    /// where a [`Forge::Disk`] repository is
+    path: Option<PathBuf>,
 }
 
 impl Repo {
+    /// An existing repository at `path`, processed in place without any remote operation.
+    ///
+    /// It is the repository `disk/<user>/<name>`, where the user is the name of the parent directory,
+    /// and the name is `name` or else the name of the directory of the repository.
+    pub fn on_disk(path: impl Into<PathBuf>, name: Option<&str>) -> Result<Repo, String> {
+        let path = path.into();
+        let path = path
+            .canonicalize()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        Repository::open(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let file_name = |x: Option<&Path>| {
+            x.and_then(|x| x.file_name())
+                .and_then(|x| x.to_str())
+                .map(|x| x.to_string())
+                .ok_or_else(|| format!("{} cannot be named", path.display()))
+        };
+        let user = file_name(path.parent())?;
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => file_name(Some(&path))?,
+        };
+        let mut repo = Forge::Disk.try_repo(user, name)?;
+        repo.path = Some(path);
+        Ok(repo)
+    }
     pub fn url(&self) -> String {
-        format!("{}{}/{}", self.forge.url(), self.user, self.name)
+        match &self.path {
+            Some(path) => format!("file://{}", path.display()),
+            None => format!("{}{}/{}", self.forge.url(), self.user, self.name),
+        }
     }
     pub fn fetch(&self) -> Repository {
+        if let Some(repository) = self.open_on_disk().expect("repository moved from disk") {
+            return repository;
+        }
         let url = self.url();
-        let path = format!("{}", "/tmp/hyperastgitresources/repo/");
+        let path = self.clone_root();
         fetch_repository(url, path)
     }
     pub fn nofetch(&self) -> Repository {
+        if let Some(repository) = self.open_on_disk().expect("repository moved from disk") {
+            return repository;
+        }
         let url = self.url();
-        let path = format!("{}", "/tmp/hyperastgitresources/repo/");
+        let path = self.clone_root();
         nofetch_repository(url, path)
     }
 
+    /// Repositories on disk are neither fetched nor cloned elsewhere,
+    /// gives None for the other repositories, and an error if it cannot be opened anymore
+    pub fn open_on_disk(&self) -> Result<Option<Repository>, git2::Error> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(None);
+        };
+        Repository::open(path).map(Some)
+    }
+
+    /// Only the path of the url is used to place clones,
+    /// so forges other than github are put apart to avoid mixing mirrors with their origin.
+    fn clone_root(&self) -> PathBuf {
+        let path = PathBuf::from("/tmp/hyperastgitresources/repo/");
+        match self.forge {
+            Forge::Github => path,
+            forge => path.join(forge.host()),
+        }
+    }
+
     pub fn fetch_to(&self, path: impl Into<PathBuf>) -> Repository {
+        if let Some(repository) = self.open_on_disk().expect("repository moved from disk") {
+            return repository;
+        }
         let url = self.url();
         let path = path.into();
         fetch_repository(url, path)
     }
 
     pub fn nofetch_to(&self, path: impl Into<PathBuf>) -> Repository {
+        if let Some(repository) = self.open_on_disk().expect("repository moved from disk") {
+            return repository;
+        }
         let url = self.url();
         let path = path.into();
         nofetch_repository(url, path)
@@ -320,11 +699,15 @@ impl Repo {
     pub fn name(&self) -> &str {
         &self.name
     }
+    /// only set for repositories registered with [`Repo::on_disk`]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
 }
 
 impl Display for Repo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}{}/{}", self.forge.url(), self.user, self.name)
+        writeln!(f, "{}", self.url())
     }
 }
 
@@ -685,3 +1068,198 @@ fn own(r: &[u8]) -> Result<String, git2::Error> {
     let r = r.map_err(|x| git2::Error::from_str(&x.to_string()));
     r.map(|s| s.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the local root can only be set once per process
+    fn local_root() -> &'static Path {
+        LOCAL_ROOT.get_or_init(|| {
+            let root = std::env::temp_dir().join(format!("hyperast_local_forge_{}", process::id()));
+            fs::create_dir_all(root.join("user/repo")).unwrap();
+            root.canonicalize().unwrap()
+        })
+    }
+
+    #[test]
+    fn try_repo_rejects_non_segments() {
+        local_root();
+        for forge in [Forge::Github, Forge::Local] {
+            for x in ["", ".", "..", "a/b", "a#b"] {
+                assert!(forge.try_repo(x, "repo").is_err(), "user {:?}", x);
+                assert!(forge.try_repo("user", x).is_err(), "name {:?}", x);
+            }
+        }
+        assert!(Forge::Github.try_repo("user", "repo").is_ok());
+    }
+
+    #[test]
+    fn try_repo_stays_in_local_root() {
+        let root = local_root();
+        assert!(Forge::Local.try_repo("user", "repo").is_ok());
+        assert!(Forge::Local.try_repo("user", "missing").is_err());
+        #[cfg(unix)]
+        {
+            let outside = root.parent().unwrap();
+            let link = root.join("user/escape");
+            if !link.exists() {
+                std::os::unix::fs::symlink(outside, &link).unwrap();
+            }
+            assert!(Forge::Local.try_repo("user", "escape").is_err());
+        }
+    }
+
+    #[test]
+    fn clone_destination_within_root() {
+        let url: Url = "https://github.com/user/repo".try_into().unwrap();
+        let path = clone_destination("/tmp/clones".into(), &url);
+        assert_eq!(path, Path::new("/tmp/clones/user/repo"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn clone_destination_parent() {
+        let url: Url = "https://github.com/../repo".try_into().unwrap();
+        clone_destination("/tmp/clones".into(), &url);
+    }
+
+    #[test]
+    #[should_panic]
+    fn clone_destination_absolute() {
+        let url: Url = "file:////etc/repo".try_into().unwrap();
+        clone_destination("/tmp/clones".into(), &url);
+    }
+
+    /// c4 -> c3 -> (c1, c2) -> c0, where c3 merges c2 into c1, commit i being at 100 * (i + 1) seconds
+    fn dag_fixture() -> (Repository, Vec<Oid>) {
+        let path = std::env::temp_dir().join(format!("hyperast_dag_{}", process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let repository = Repository::init(&path).unwrap();
+        let tree = repository.treebuilder(None).unwrap().write().unwrap();
+        let mut oids: Vec<Oid> = vec![];
+        for (i, parents) in [&[][..], &[0], &[0], &[1, 2], &[3]].into_iter().enumerate() {
+            let time = git2::Time::new(100 * (i as i64 + 1), 0);
+            let signature = git2::Signature::new("fixture", "fixture@example.org", &time).unwrap();
+            let tree = repository.find_tree(tree).unwrap();
+            let parents: Vec<_> = parents
+                .iter()
+                .map(|p| repository.find_commit(oids[*p]).unwrap())
+                .collect();
+            let parents: Vec<_> = parents.iter().collect();
+            let message = format!("c{}", i);
+            let oid = repository
+                .commit(None, &signature, &signature, &message, &tree, &parents)
+                .unwrap();
+            oids.push(oid);
+        }
+        (repository, oids)
+    }
+
+    fn dag(builder: Builder, bounds: DagBounds) -> Vec<Oid> {
+        builder.dag(bounds).unwrap().map(|x| x.unwrap()).collect()
+    }
+
+    #[test]
+    fn rev_range_parse() {
+        let range: RevRange = "a..b".parse().unwrap();
+        assert_eq!(
+            (range.excluded.as_str(), range.included.as_str()),
+            ("a", "b")
+        );
+        assert_eq!(range.to_string(), "a..b");
+        let range: RevRange = "a..".parse().unwrap();
+        assert_eq!(
+            (range.excluded.as_str(), range.included.as_str()),
+            ("a", "")
+        );
+        let range: RevRange = "b".parse().unwrap();
+        assert_eq!(
+            (range.excluded.as_str(), range.included.as_str()),
+            ("", "b")
+        );
+        assert_eq!(range.to_string(), "b");
+        assert!(RevRange::is_range("a..b"));
+        assert!(!RevRange::is_range("b"));
+        assert!("a...b".parse::<RevRange>().is_err());
+    }
+
+    #[test]
+    fn dag_walks_all_parents_once() {
+        let (repository, c) = dag_fixture();
+        let builder = Builder::new(&repository).unwrap();
+        let builder = builder.after(&c[4].to_string()).unwrap();
+        assert_eq!(
+            dag(builder, DagBounds::default()),
+            [c[4], c[3], c[1], c[2], c[0]]
+        );
+        // a first parent walk misses the merged branch
+        let builder = Builder::new(&repository).unwrap();
+        let builder = builder.after(&c[4].to_string()).unwrap();
+        let walk: Vec<_> = builder.first_parents().unwrap().walk().unwrap().collect();
+        assert_eq!(walk.len(), 4);
+        // shared ancestors of several pushed commits are only given once
+        let builder = Builder::new(&repository).unwrap();
+        let builder = builder.after(&c[1].to_string()).unwrap();
+        let builder = builder.after(&c[2].to_string()).unwrap();
+        assert_eq!(dag(builder, DagBounds::default()), [c[1], c[2], c[0]]);
+    }
+
+    #[test]
+    fn dag_hidden_commits() {
+        let (repository, c) = dag_fixture();
+        let range = format!("{}..{}", c[1], c[4]).parse().unwrap();
+        let builder = Builder::new(&repository).unwrap().range(&range).unwrap();
+        assert_eq!(dag(builder, DagBounds::default()), [c[4], c[3], c[2]]);
+        let builder = Builder::new(&repository).unwrap();
+        let builder = builder.exclude(&c[2].to_string()).unwrap();
+        let builder = builder.after(&c[3].to_string()).unwrap();
+        assert_eq!(dag(builder, DagBounds::default()), [c[3], c[1]]);
+        // contrary to exclude, before keeps the given commit
+        let builder = Builder::new(&repository).unwrap();
+        let builder = builder.before(&c[2].to_string()).unwrap();
+        let builder = builder.after(&c[4].to_string()).unwrap();
+        assert_eq!(dag(builder, DagBounds::default()), [c[4], c[3], c[1], c[2]]);
+        // a hidden tip is not walked at all
+        let range = format!("{}..{}", c[3], c[1]).parse().unwrap();
+        let builder = Builder::new(&repository).unwrap().range(&range).unwrap();
+        assert!(dag(builder, DagBounds::default()).is_empty());
+    }
+
+    #[test]
+    fn dag_bounds() {
+        let (repository, c) = dag_fixture();
+        let tip = || {
+            Builder::new(&repository)
+                .unwrap()
+                .after(&c[4].to_string())
+                .unwrap()
+        };
+        let bounds = DagBounds {
+            depth: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(dag(tip(), bounds), [c[4], c[3]]);
+        let bounds = DagBounds {
+            depth: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(dag(tip(), bounds), [c[4]]);
+        let bounds = DagBounds {
+            since: Some(150),
+            ..Default::default()
+        };
+        assert_eq!(dag(tip(), bounds), [c[4], c[3], c[1], c[2]]);
+        let bounds = DagBounds {
+            since: Some(250),
+            ..Default::default()
+        };
+        assert_eq!(dag(tip(), bounds), [c[4], c[3], c[2]]);
+        // newer commits are skipped, but not their ancestors
+        let bounds = DagBounds {
+            until: Some(250),
+            ..Default::default()
+        };
+        assert_eq!(dag(tip(), bounds), [c[1], c[0]]);
+    }
+}
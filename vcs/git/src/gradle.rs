@@ -0,0 +1,614 @@
+use crate::{Accumulator, BasicDirAcc, DefaultMetrics, ParseErr, processing::ObjectName};
+use enumset::EnumSet;
+use hyperast::store::defaults::{LabelIdentifier, NodeIdentifier};
+use hyperast_gen_ts_java::legion_with_refs as java_tree_gen;
+use std::path::PathBuf;
+
+/// There is no groovy nor kotlin generator for now,
+/// so build scripts are only read, to layout the project, and are not part of the HyperAST.
+pub(crate) fn handle_gradle_script(name: &ObjectName, text: &[u8]) -> Result<Script, ParseErr> {
+    let text = std::str::from_utf8(text)?;
+    let tokens = tokenize(text);
+    let x = if is_settings(name) {
+        Script::Settings {
+            projects: Settings::read(&tokens).projects(),
+        }
+    } else {
+        let source_sets = SourceSets::read(&tokens);
+        Script::Build {
+            source_dirs: source_sets.main.resolve("src/main/java"),
+            test_source_dirs: source_sets.test.resolve("src/test/java"),
+        }
+    };
+    Ok(x)
+}
+
+fn is_settings(name: &ObjectName) -> bool {
+    name.as_bytes().starts_with(b"settings.gradle")
+}
+
+/// What is needed from a settings or a build script to layout a project
+#[derive(Debug, Clone)]
+pub enum Script {
+    /// directories of the included projects, relative to the root project
+    Settings { projects: Vec<String> },
+    /// java source directories of the main and test source sets
+    Build {
+        source_dirs: Vec<String>,
+        test_source_dirs: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Punct(char),
+}
+
+/// Good enough for the groovy and kotlin DSLs,
+/// comments are dropped and string literals are not interpolated.
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => (),
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|c| *c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '"' | '\'' => {
+                let triple = chars.next_if_eq(&c).is_some();
+                if triple && chars.next_if_eq(&c).is_none() {
+                    // an empty string
+                    tokens.push(Token::Str(String::new()));
+                    continue;
+                }
+                let mut s = String::new();
+                while let Some(x) = chars.next() {
+                    if x == '\\' {
+                        if let Some(x) = chars.next() {
+                            s.push(x);
+                        }
+                    } else if x == c && !triple {
+                        break;
+                    } else if x == c && chars.next_if_eq(&c).is_some() {
+                        if chars.next_if_eq(&c).is_some() {
+                            break;
+                        }
+                        s.push(c);
+                        s.push(c);
+                    } else {
+                        s.push(x);
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                let mut s = c.to_string();
+                while let Some(x) = chars.next_if(|x| x.is_alphanumeric() || *x == '_') {
+                    s.push(x);
+                }
+                tokens.push(Token::Ident(s));
+            }
+            c => tokens.push(Token::Punct(c)),
+        }
+    }
+    tokens
+}
+
+fn is_ident(t: Option<&Token>, s: &str) -> bool {
+    matches!(t, Some(Token::Ident(x)) if x == s)
+}
+
+fn is_punct(t: Option<&Token>, c: char) -> bool {
+    t == Some(&Token::Punct(c))
+}
+
+fn as_str(t: Option<&Token>) -> Option<&str> {
+    match t {
+        Some(Token::Str(x)) => Some(x),
+        _ => None,
+    }
+}
+
+/// The string literals of an argument list,
+/// ie. `'a', 'b'` or `('a', 'b')` or `['a', 'b']` or `listOf("a", "b")`,
+/// starting at `i` and returning the index following the arguments.
+fn string_args(tokens: &[Token], mut i: usize) -> (Vec<String>, usize) {
+    let mut args = vec![];
+    if let Some(Token::Ident(_)) = tokens.get(i) {
+        if is_punct(tokens.get(i + 1), '(') {
+            // eg. listOf(...), files(...)
+            i += 1;
+        }
+    }
+    if is_punct(tokens.get(i), '(') || is_punct(tokens.get(i), '[') {
+        let mut depth = 0;
+        while let Some(t) = tokens.get(i) {
+            i += 1;
+            match t {
+                Token::Punct('(' | '[') => depth += 1,
+                Token::Punct(')' | ']') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                Token::Str(s) => args.push(s.clone()),
+                _ => (),
+            }
+        }
+        return (args, i);
+    }
+    while let Some(s) = as_str(tokens.get(i)) {
+        args.push(s.to_owned());
+        i += 1;
+        if !is_punct(tokens.get(i), ',') {
+            break;
+        }
+        i += 1;
+    }
+    (args, i)
+}
+
+/// The projects declared in a settings script
+#[derive(Default, Debug)]
+struct Settings {
+    /// project paths, eg. `:a:b`
+    includes: Vec<String>,
+    /// explicit project directories, eg. `project(':a').projectDir = file('x/a')`
+    project_dirs: Vec<(String, String)>,
+}
+
+impl Settings {
+    fn read(tokens: &[Token]) -> Self {
+        let mut settings = Self::default();
+        let mut i = 0;
+        while i < tokens.len() {
+            if is_ident(tokens.get(i), "include") {
+                let (args, j) = string_args(tokens, i + 1);
+                settings.includes.extend(args);
+                i = j.max(i + 1);
+            } else if is_ident(tokens.get(i), "includeFlat") {
+                log::warn!("includeFlat is not supported");
+                i += 1;
+            } else if let Some(project) = project_dir_assignment(tokens, i) {
+                let (args, j) = string_args(tokens, i + 7);
+                if let [dir] = &args[..] {
+                    settings
+                        .project_dirs
+                        .push((normalize_project_path(project), dir.clone()));
+                } else {
+                    log::warn!("unsupported projectDir for {}", project);
+                }
+                i = j;
+            } else {
+                i += 1;
+            }
+        }
+        settings
+    }
+
+    /// The directories of the included projects,
+    /// including the intermediate projects of hierarchical paths, eg. `:a` for `:a:b`
+    fn projects(&self) -> Vec<String> {
+        let mut projects: Vec<String> = vec![];
+        for include in &self.includes {
+            let path = normalize_project_path(include);
+            let mut prefix = String::new();
+            for segment in path.split(':') {
+                if !prefix.is_empty() {
+                    prefix.push(':');
+                }
+                prefix.push_str(segment);
+                let dir = self
+                    .project_dirs
+                    .iter()
+                    .find(|(p, _)| p == &prefix)
+                    .map(|(_, d)| relative_dir(d))
+                    .unwrap_or_else(|| Some(prefix.replace(':', "/")));
+                match dir {
+                    Some(dir) if !projects.contains(&dir) => projects.push(dir),
+                    Some(_) => (),
+                    None => log::warn!("unsupported project directory for {}", prefix),
+                }
+            }
+        }
+        projects
+    }
+}
+
+/// Matches `project(':a').projectDir =` at `i`
+fn project_dir_assignment(tokens: &[Token], i: usize) -> Option<&str> {
+    let matches = is_ident(tokens.get(i), "project")
+        && is_punct(tokens.get(i + 1), '(')
+        && is_punct(tokens.get(i + 3), ')')
+        && is_punct(tokens.get(i + 4), '.')
+        && is_ident(tokens.get(i + 5), "projectDir")
+        && is_punct(tokens.get(i + 6), '=');
+    matches.then(|| as_str(tokens.get(i + 2))).flatten()
+}
+
+fn normalize_project_path(path: &str) -> String {
+    path.trim_start_matches(':').to_owned()
+}
+
+/// The java directories of a source set, as declared in a build script
+#[derive(Default, Debug)]
+struct SourceSet {
+    /// eg. `srcDirs = ['src']` or `setSrcDirs(listOf("src"))`
+    replaced: Option<Vec<String>>,
+    /// eg. `srcDir 'src/gen'` or `srcDirs("src/gen")`
+    added: Vec<String>,
+}
+
+impl SourceSet {
+    fn resolve(self, default: &str) -> Vec<String> {
+        let dirs = self.replaced.unwrap_or_else(|| vec![default.to_owned()]);
+        let mut result = vec![];
+        for dir in dirs.iter().chain(&self.added) {
+            match relative_dir(dir) {
+                Some(dir) if !result.contains(&dir) => result.push(dir),
+                Some(_) => (),
+                None => log::warn!("unsupported source directory {}", dir),
+            }
+        }
+        result
+    }
+}
+
+#[derive(Default, Debug)]
+struct SourceSets {
+    main: SourceSet,
+    test: SourceSet,
+}
+
+impl SourceSets {
+    /// Handles the nested form, eg. `sourceSets { main { java { srcDirs = [...] } } }`,
+    /// the dotted form, eg. `sourceSets.main.java.srcDir(...)`,
+    /// and their mixes, with the groovy and kotlin syntaxes,
+    /// eg. `sourceSets.getByName("main") { java.srcDir(...) }` or `sourceSets["main"]`.
+    fn read(tokens: &[Token]) -> Self {
+        let mut source_sets = Self::default();
+        // the chains that open the enclosing blocks, if any
+        let mut blocks: Vec<Vec<String>> = vec![];
+        let mut i = 0;
+        while i < tokens.len() {
+            match &tokens[i] {
+                Token::Punct('{') => {
+                    // eg. the closure of a call
+                    blocks.push(vec![]);
+                    i += 1;
+                }
+                Token::Punct('}') => {
+                    blocks.pop();
+                    i += 1;
+                }
+                Token::Ident(_) => {
+                    let (chain, j) = read_chain(tokens, i);
+                    i = j;
+                    if is_punct(tokens.get(i), '{') {
+                        blocks.push(chain);
+                        i += 1;
+                        continue;
+                    }
+                    let path: Vec<&str> = blocks
+                        .iter()
+                        .flatten()
+                        .chain(&chain)
+                        .map(|x| x.as_str())
+                        .collect();
+                    let Some((set, method)) = source_set_method(&path) else {
+                        continue;
+                    };
+                    let set_name = set;
+                    let set = match set {
+                        "main" => &mut source_sets.main,
+                        "test" => &mut source_sets.test,
+                        _ => continue,
+                    };
+                    let mut replace = method == "setSrcDirs";
+                    if is_punct(tokens.get(i), '=') {
+                        replace = true;
+                        i += 1;
+                    } else if is_punct(tokens.get(i), '+') && is_punct(tokens.get(i + 1), '=') {
+                        i += 2;
+                    }
+                    let (args, j) = string_args(tokens, i);
+                    i = j;
+                    if args.is_empty() {
+                        log::warn!("unsupported source directories for {}", set_name);
+                    } else if replace {
+                        set.replaced = Some(args);
+                    } else {
+                        set.added.extend(args);
+                    }
+                }
+                _ => i += 1,
+            }
+        }
+        source_sets
+    }
+}
+
+/// Reads a chain like `a.b(...).c["d"]` starting at `i`,
+/// where the literal argument of `getByName`, `named` or an index is taken as a name.
+fn read_chain(tokens: &[Token], mut i: usize) -> (Vec<String>, usize) {
+    let mut chain = vec![];
+    loop {
+        let Some(Token::Ident(x)) = tokens.get(i) else {
+            break;
+        };
+        i += 1;
+        let by_name = x == "getByName" || x == "named" || x == "maybeCreate" || x == "create";
+        match enclosed(tokens, i, '(', ')') {
+            Some(name) if by_name => {
+                chain.push(name.to_owned());
+                i += 3;
+            }
+            _ => chain.push(x.clone()),
+        }
+        while let Some(name) = enclosed(tokens, i, '[', ']') {
+            chain.push(name.to_owned());
+            i += 3;
+        }
+        if !is_punct(tokens.get(i), '.') {
+            break;
+        }
+        i += 1;
+    }
+    (chain, i)
+}
+
+/// Matches a string literal enclosed by `open` and `close` at `i`
+fn enclosed(tokens: &[Token], i: usize, open: char, close: char) -> Option<&str> {
+    let matches = is_punct(tokens.get(i), open) && is_punct(tokens.get(i + 2), close);
+    matches.then(|| as_str(tokens.get(i + 1))).flatten()
+}
+
+/// Matches `[project.]sourceSets.<set>.java.<method>`
+fn source_set_method<'a>(path: &[&'a str]) -> Option<(&'a str, &'a str)> {
+    let path = match path {
+        ["project", rest @ ..] => rest,
+        path => path,
+    };
+    match path {
+        [
+            "sourceSets",
+            set,
+            "java",
+            method @ ("srcDir" | "srcDirs" | "setSrcDirs"),
+        ] => Some((set, method)),
+        _ => None,
+    }
+}
+
+/// Makes a directory relative to its project,
+/// paths relative to other roots are not supported for now.
+pub(crate) fn relative_dir(dir: &str) -> Option<String> {
+    let dir = dir.trim();
+    let dir = ["$projectDir/", "${projectDir}/", "${project.projectDir}/"]
+        .iter()
+        .find_map(|p| dir.strip_prefix(p))
+        .unwrap_or(dir);
+    let dir = dir.trim_start_matches("./").trim_end_matches('/');
+    if dir.is_empty() || dir.contains('$') || dir.starts_with('/') {
+        return None;
+    }
+    if dir.split('/').any(|x| x == "..") {
+        return None;
+    }
+    Some(dir.to_owned())
+}
+
+#[derive(Debug, Clone)]
+pub struct MD {
+    pub(crate) metrics: DefaultMetrics,
+    pub(crate) status: EnumSet<SemFlag>,
+}
+
+pub struct GradleModuleAcc {
+    pub(crate) primary: BasicDirAcc<NodeIdentifier, LabelIdentifier, DefaultMetrics>,
+    pub(crate) sub_projects: Option<Vec<PathBuf>>,
+    pub(crate) main_dirs: Option<Vec<PathBuf>>,
+    pub(crate) test_dirs: Option<Vec<PathBuf>>,
+    pub(crate) status: EnumSet<SemFlag>,
+}
+
+impl From<String> for GradleModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl GradleModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            primary: BasicDirAcc::new(name),
+            sub_projects: None,
+            main_dirs: None,
+            test_dirs: None,
+            status: Default::default(),
+        }
+    }
+    pub(crate) fn with_content(
+        name: String,
+        sub_projects: Vec<PathBuf>,
+        main_dirs: Vec<PathBuf>,
+        test_dirs: Vec<PathBuf>,
+    ) -> Self {
+        let non_empty = |v: Vec<PathBuf>| if v.is_empty() { None } else { Some(v) };
+        Self {
+            primary: BasicDirAcc::new(name),
+            sub_projects: non_empty(sub_projects),
+            main_dirs: non_empty(main_dirs),
+            test_dirs: non_empty(test_dirs),
+            status: Default::default(),
+        }
+    }
+    /// A project included in the settings, it has the conventional layout
+    /// until its build script (if any) tells otherwise.
+    pub(crate) fn into_project(mut self) -> Self {
+        self.status |= SemFlag::IsGradleProject;
+        self.main_dirs = Some(vec!["src/main/java".into()]);
+        self.test_dirs = Some(vec!["src/test/java".into()]);
+        self
+    }
+}
+
+#[derive(enumset::EnumSetType, Debug)]
+pub enum SemFlag {
+    IsGradleProject,
+    HoldMainFolder,
+    HoldTestFolder,
+    HoldGradleSubProject,
+}
+
+impl GradleModuleAcc {
+    pub(crate) fn is_module(&self) -> bool {
+        self.status.contains(SemFlag::IsGradleProject)
+    }
+    pub(crate) fn push_script(&mut self, script: Script) {
+        self.status |= SemFlag::IsGradleProject;
+        match script {
+            Script::Settings { projects } => {
+                self.sub_projects = Some(projects.iter().map(|x| x.into()).collect());
+                if self.main_dirs.is_none() {
+                    // the root project of a multi-project build also has the conventional layout
+                    self.main_dirs = Some(vec!["src/main/java".into()]);
+                    self.test_dirs = Some(vec!["src/test/java".into()]);
+                }
+            }
+            Script::Build {
+                source_dirs,
+                test_source_dirs,
+            } => {
+                self.main_dirs = Some(source_dirs.iter().map(|x| x.into()).collect());
+                self.test_dirs = Some(test_source_dirs.iter().map(|x| x.into()).collect());
+            }
+        }
+    }
+    pub fn push_sub_project(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
+        self.status |= SemFlag::HoldGradleSubProject;
+        self.primary.children.push(full_node.0);
+        self.primary.children_names.push(name);
+        self.primary.metrics.acc(full_node.1.metrics);
+    }
+    pub(crate) fn push_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: java_tree_gen::Local,
+    ) {
+        self.status |= SemFlag::HoldMainFolder;
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+    pub(crate) fn push_test_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: java_tree_gen::Local,
+    ) {
+        self.status |= SemFlag::HoldTestFolder;
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+}
+
+impl hyperast::tree_gen::Accumulator for GradleModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, MD));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        // only propagates what is held by intermediate directories
+        let s = full_node.1.status - SemFlag::IsGradleProject;
+        self.status |= s;
+        self.primary.children.push(full_node.0);
+        self.primary.children_names.push(name);
+        self.primary.metrics.acc(full_node.1.metrics);
+    }
+}
+
+impl Accumulator for GradleModuleAcc {
+    type Unlabeled = (NodeIdentifier, MD);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings() {
+        let text = r#"
+rootProject.name = 'demo'
+// include ':commented'
+include ':core', ':app'
+include(":libs:json",
+    ":libs:xml")
+project(':app').projectDir = file('application')
+"#;
+        let settings = Settings::read(&tokenize(text));
+        assert_eq!(
+            settings.projects(),
+            vec!["core", "application", "libs", "libs/json", "libs/xml"]
+        );
+    }
+
+    #[test]
+    fn test_source_sets() {
+        let text = r#"
+plugins { id 'java' }
+sourceSets {
+    main {
+        java {
+            srcDirs = ['src', "$projectDir/gen"]
+        }
+    }
+    test.java.srcDir 'it'
+}
+test { useJUnitPlatform() }
+"#;
+        let source_sets = SourceSets::read(&tokenize(text));
+        assert_eq!(
+            source_sets.main.resolve("src/main/java"),
+            vec!["src", "gen"]
+        );
+        assert_eq!(
+            source_sets.test.resolve("src/test/java"),
+            vec!["src/test/java", "it"]
+        );
+
+        let text = r#"
+sourceSets.getByName("main") {
+    java.setSrcDirs(listOf("src/main/kotlin"))
+}
+sourceSets["test"].java.srcDirs("src/it/java")
+"#;
+        let source_sets = SourceSets::read(&tokenize(text));
+        assert_eq!(
+            source_sets.main.resolve("src/main/java"),
+            vec!["src/main/kotlin"]
+        );
+        assert_eq!(
+            source_sets.test.resolve("src/test/java"),
+            vec!["src/test/java", "src/it/java"]
+        );
+
+        // kotlin sources are not handled
+        let text = r#"
+sourceSets.main.kotlin.srcDirs("src/main/kotlin")
+sourceSets { main { kotlin { srcDir 'gen' } } }
+"#;
+        let source_sets = SourceSets::read(&tokenize(text));
+        assert_eq!(
+            source_sets.main.resolve("src/main/java"),
+            vec!["src/main/java"]
+        );
+    }
+}
@@ -0,0 +1,526 @@
+use crate::StackEle;
+use crate::processing::ParametrizedCommitProcessorHandle;
+use crate::processing::erased::{
+    CommitProcessorHandle, ParametrizedCommitProcessor2Handle as PCP2Handle,
+};
+use crate::{
+    Processor,
+    git::BasicGitObject,
+    gradle::{GradleModuleAcc, MD},
+    java_processor::JavaProc,
+    preprocessed::RepositoryProcessor,
+    processing::{CacheHolding, InFiles, ObjectName, erased::ParametrizedCommitProc2, file_sys},
+};
+use git2::{Oid, Repository};
+use hyperast::store::nodes::compo;
+use hyperast::types::ETypeStore as _;
+use hyperast::{
+    hashed::{IndexingHashBuilder, MetaDataHashsBuilder},
+    store::{
+        defaults::NodeIdentifier,
+        nodes::{EntityBuilder, legion::eq_node},
+    },
+    tree_gen::Accumulator,
+    types::LabelStore,
+};
+use hyperast_gen_ts_java::types::Type;
+use std::{
+    iter::Peekable,
+    marker::PhantomData,
+    path::{Components, Path, PathBuf},
+};
+
+pub type SimpleStores = hyperast::store::SimpleStores<hyperast_gen_ts_java::types::TStore>;
+
+/// Walks a gradle build from its root,
+/// the projects included in the settings are handled as modules,
+/// and the java directories of the main and test source sets of each project are handled as java folders.
+/// Kotlin sources are not handled, as there is no kotlin generator.
+pub struct GradleProcessor<'a, 'b, 'c, Acc> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<StackEle<Acc>>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: ParametrizedCommitProcessorHandle,
+}
+
+impl<'a, 'b, 'c> GradleProcessor<'a, 'b, 'c, GradleModuleAcc> {
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        mut dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+        handle: ParametrizedCommitProcessorHandle,
+    ) -> Self {
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree, &mut dir_path);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![StackEle::new(oid, prepared, GradleModuleAcc::new(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+        }
+    }
+}
+
+impl<'a, 'b, 'c> Processor<GradleModuleAcc> for GradleProcessor<'a, 'b, 'c, GradleModuleAcc> {
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                if file_sys::is_gradle_ignored(&name) {
+                    return;
+                }
+                self.handle_tree_cached(name, oid);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                if file_sys::GradleScript::matches(&name) {
+                    let parent_acc = &mut self.stack.last_mut().unwrap().acc;
+                    let parameters = PCP2Handle(self.handle.1, PhantomData);
+                    if let Err(err) = self.prepro.handle_gradle_script(
+                        oid,
+                        parent_acc,
+                        name,
+                        &self.repository,
+                        parameters,
+                    ) {
+                        log::debug!("{:?}", err);
+                    }
+                } else {
+                    log::debug!("not a gradle script {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: GradleModuleAcc) -> Option<(NodeIdentifier, MD)> {
+        let name = acc.primary.name.clone();
+        let full_node = make(acc, self.prepro.main_stores_mut().mut_with_ts());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<GradleProcessorHolder>()
+            .with_parameters_mut(self.handle.1)
+            .cache
+            .object_map
+            .insert(oid, full_node.clone());
+        let name = self.prepro.intern_label(&name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            assert!(
+                !w.primary.children_names.contains(&name),
+                "{:?} {:?}",
+                w.primary.children_names,
+                name
+            );
+            push_dir(w, name, full_node);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<StackEle<GradleModuleAcc>> {
+        &mut self.stack
+    }
+}
+
+fn push_dir(
+    w: &mut GradleModuleAcc,
+    name: hyperast::store::defaults::LabelIdentifier,
+    full_node: (NodeIdentifier, MD),
+) {
+    if full_node
+        .1
+        .status
+        .contains(crate::gradle::SemFlag::IsGradleProject)
+    {
+        w.push_sub_project(name, full_node);
+    } else {
+        w.push((name, full_node));
+    }
+}
+
+impl<'a, 'b, 'c> GradleProcessor<'a, 'b, 'c, GradleModuleAcc> {
+    fn handle_tree_cached(&mut self, name: ObjectName, oid: Oid) {
+        if let Some(s) = self.dir_path.peek() {
+            if name
+                .as_bytes()
+                .eq(std::ffi::OsStr::as_encoded_bytes(s.as_os_str()))
+            {
+                self.dir_path.next();
+                self.stack.last_mut().expect("never empty").cs.clear();
+                let tree = self.repository.find_tree(oid).unwrap();
+                let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+                self.stack.push(StackEle::new(
+                    oid,
+                    prepared,
+                    GradleModuleAcc::new(name.try_into().unwrap()),
+                ));
+            }
+            return;
+        }
+        let gradle_proc = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<GradleProcessorHolder>()
+            .with_parameters_mut(self.handle.1);
+        let java_handle = gradle_proc.parameter.java_handle;
+        if let Some(already) = gradle_proc.cache.object_map.get(&oid) {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            let name = self.prepro.intern_object_name(&name);
+            assert!(!w.primary.children_names.contains(&name));
+            push_dir(w, name, full_node);
+            return;
+        }
+
+        log::debug!("gradle tree {:?}", name.try_str());
+        let parent_acc = &mut self.stack.last_mut().unwrap().acc;
+        let parent_is_module = parent_acc.is_module();
+        let helper = GradleModuleHelper::from((parent_acc, &name));
+        if helper.source_directories.0 || helper.test_source_directories.0 {
+            // handle as a java directory of a source set
+            let (name, (full_node,)) = self.prepro.help_handle_java_folder(
+                &self.repository,
+                self.dir_path,
+                oid,
+                &name,
+                java_handle,
+            );
+            let parent_acc = &mut self.stack.last_mut().unwrap().acc;
+            assert!(!parent_acc.primary.children_names.contains(&name));
+            if helper.source_directories.0 {
+                parent_acc.push_source_directory(name, full_node);
+            } else {
+                parent_acc.push_test_source_directory(name, full_node);
+            }
+            return;
+        }
+        let tree = self.repository.find_tree(oid).unwrap();
+        // a project not included in the settings, eg. a build in a test fixture,
+        // or a project in a repository without a root build
+        let has_script = tree
+            .iter()
+            .any(|x| file_sys::GradleScript::matches(&x.name_bytes().into()));
+        if helper.sub_projects.0 {
+            let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+            let acc = helper.into_acc().into_project();
+            self.stack.push(StackEle::new(oid, prepared, acc));
+        } else if !helper.sub_projects.1.is_empty()
+            || !helper.source_directories.1.is_empty()
+            || !helper.test_source_directories.1.is_empty()
+            || has_script
+            || !parent_is_module
+        {
+            let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+            self.stack
+                .push(StackEle::new(oid, prepared, helper.into_acc()));
+        }
+        // TODO other directories of a project (eg. resources, docs) are not handled for now
+    }
+}
+
+impl RepositoryProcessor {
+    fn handle_gradle_script(
+        &mut self,
+        oid: Oid,
+        parent_acc: &mut GradleModuleAcc,
+        name: ObjectName,
+        repository: &Repository,
+        parameters: PCP2Handle<GradleProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let x = self
+            .processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::GradleScript>()
+            .handle(oid, repository, &name, parameters, |_, n, t| {
+                crate::gradle::handle_gradle_script(n, t)
+            })?;
+        parent_acc.push_script(x);
+        Ok(())
+    }
+}
+
+struct GradleModuleHelper {
+    name: String,
+    sub_projects: (bool, Vec<PathBuf>),
+    source_directories: (bool, Vec<PathBuf>),
+    test_source_directories: (bool, Vec<PathBuf>),
+}
+
+impl From<(&mut GradleModuleAcc, &ObjectName)> for GradleModuleHelper {
+    fn from((parent_acc, name): (&mut GradleModuleAcc, &ObjectName)) -> Self {
+        let name = std::str::from_utf8(name.as_bytes()).unwrap();
+        let process = |v: &Option<Vec<PathBuf>>| {
+            let mut v: Vec<_> = v
+                .iter()
+                .flatten()
+                .filter_map(|x| strip_dir(x, name))
+                .collect();
+            let c = vec_extract_if_polyfill::MakeExtractIf::extract_if(&mut v, |x| {
+                x.components().next().is_none()
+            })
+            .count();
+            (c > 0, v)
+        };
+        Self {
+            name: name.to_owned(),
+            sub_projects: process(&parent_acc.sub_projects),
+            source_directories: process(&parent_acc.main_dirs),
+            test_source_directories: process(&parent_acc.test_dirs),
+        }
+    }
+}
+
+fn strip_dir(path: &Path, name: &str) -> Option<PathBuf> {
+    path.strip_prefix(name).ok().map(|x| x.to_owned())
+}
+
+impl GradleModuleHelper {
+    fn into_acc(self) -> GradleModuleAcc {
+        GradleModuleAcc::with_content(
+            self.name,
+            self.sub_projects.1,
+            self.source_directories.1,
+            self.test_source_directories.1,
+        )
+    }
+}
+
+/// priority to the settings and build scripts, they tell how the rest of the directory should be handled
+pub(crate) fn prepare_dir_exploration(
+    tree: git2::Tree,
+    dir_path: &mut Peekable<Components>,
+) -> Vec<BasicGitObject> {
+    let mut children_objects: Vec<BasicGitObject> = tree
+        .iter()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect();
+    if dir_path.peek().is_none() {
+        // settings first, then the build script
+        children_objects.sort_by_key(|x| match x {
+            BasicGitObject::Blob(_, n) if file_sys::GradleScript::matches(n) => {
+                if n.as_bytes().starts_with(b"settings.gradle") {
+                    0
+                } else {
+                    1
+                }
+            }
+            _ => 2,
+        });
+        children_objects.reverse(); // we use it like a stack
+    }
+    children_objects
+}
+
+pub(crate) fn make(acc: GradleModuleAcc, stores: &mut SimpleStores) -> (NodeIdentifier, MD) {
+    let kind = Type::GradleDirectory;
+    let interned_kind = hyperast_gen_ts_java::types::TStore::intern(kind);
+    let label_id = stores.label_store.get_or_insert(acc.primary.name.clone());
+
+    let primary = acc
+        .primary
+        .map_metrics(|m| m.finalize(&interned_kind, &label_id, 0));
+
+    let hashable = primary.metrics.hashs.most_discriminating();
+
+    let eq = eq_node(&interned_kind, Some(&label_id), &primary.children);
+
+    assert_eq!(primary.children_names.len(), primary.children.len());
+
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    if let Some(id) = insertion.occupied_id() {
+        let metrics = primary
+            .metrics
+            .map_hashs(|h| MetaDataHashsBuilder::build(h));
+        let status = acc.status;
+        return (id, MD { metrics, status });
+    }
+
+    log::info!(
+        "make gradle module {} {}",
+        &primary.name,
+        primary.children.len()
+    );
+
+    let mut dyn_builder = hyperast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+
+    let children_is_empty = primary.children.is_empty();
+    if !acc.status.is_empty() {
+        dyn_builder.add(compo::Flags(acc.status));
+    }
+
+    let metrics = primary.persist(&mut dyn_builder, interned_kind, label_id);
+    let metrics = metrics.map_hashs(|h| h.build());
+    let hashs = metrics.add_md_metrics(&mut dyn_builder, children_is_empty);
+    hashs.persist(&mut dyn_builder);
+
+    let vacant = insertion.vacant();
+    let node_id = hyperast::store::nodes::legion::NodeStore::insert_built_after_prepare(
+        vacant,
+        dyn_builder.build(),
+    );
+
+    let status = acc.status;
+    (node_id, MD { metrics, status })
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub(crate) java_handle: PCP2Handle<JavaProc>,
+}
+
+#[derive(Default)]
+pub(crate) struct GradleProcessorHolder(Option<GradleProc>);
+pub(crate) struct GradleProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Gradle,
+    script_cache: crate::processing::caches::GradleScript,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for GradleProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0;
+                // TODO enable multi configs for gradle, do the same as the one for Java
+                self.0 = Some(GradleProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    script_cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+
+struct PreparedGradleCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+    pub(crate) handle: ParametrizedCommitProcessorHandle,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedGradleCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedGradleCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyperast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = GradleProcessor::<GradleModuleAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+            self.handle,
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<GradleProcessorHolder>();
+        let handle = self.handle;
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.1)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+
+impl crate::processing::erased::CommitProc for GradleProc {
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+        handle: crate::processing::ParametrizedCommitProcessorHandle,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedGradleCommitProc {
+            repository,
+            commit_builder,
+            handle,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        Box::new(self.commits.iter())
+    }
+
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+
+    fn get_lang_handle(&self, lang: &str) -> Option<ParametrizedCommitProcessorHandle> {
+        if lang.eq_ignore_ascii_case("java") {
+            Some(ParametrizedCommitProcessorHandle(
+                CommitProcessorHandle(std::any::TypeId::of::<
+                    crate::java_processor::JavaProcessorHolder,
+                >()),
+                self.parameter.java_handle.0,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for GradleProc {
+    type Holder = GradleProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for GradleProcessorHolder {
+    type Proc = GradleProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::Gradle> for GradleProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Gradle {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Gradle {
+        &self.cache
+    }
+}
+impl CacheHolding<crate::processing::caches::GradleScript> for GradleProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::GradleScript {
+        &mut self.script_cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::GradleScript {
+        &self.script_cache
+    }
+}
@@ -516,6 +516,14 @@ impl crate::processing::erased::CommitProc for JavaProc {
         self.commits.get(&commit_oid)
     }
 
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        Box::new(self.commits.iter())
+    }
+
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+
     fn get_precomp_query(&self) -> Option<hyperast_tsquery::ZeroSepArrayStr> {
         dbg!(&self.parameter.query);
         // if self.parameter.query.is_none() {
@@ -673,7 +681,7 @@ impl RepositoryProcessor {
         (name, full_node)
     }
 
-    fn handle_java_blob(
+    pub(crate) fn handle_java_blob(
         &mut self,
         oid: Oid,
         name: &ObjectName,
@@ -1,13 +1,40 @@
 #[cfg(feature = "impact")]
 pub mod allrefs;
+#[cfg(feature = "c")]
+pub mod c;
+#[cfg(all(feature = "cargo", feature = "rust"))]
+pub mod cargo;
 pub mod cpp;
 pub mod git;
+#[cfg(all(feature = "gradle", feature = "java"))]
+pub mod gradle;
 pub mod java;
 pub mod make;
 pub mod maven;
+#[cfg(feature = "maven")]
+pub mod maven_dependencies;
+#[cfg(all(feature = "npm", feature = "ts"))]
+pub mod npm;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "rust")]
+pub mod rust;
+#[cfg(feature = "ts")]
+pub mod ts;
+
+#[cfg(feature = "any")]
+pub mod any;
+#[cfg(feature = "any")]
+pub mod any_processor;
 
+#[cfg(feature = "c")]
+pub mod c_processor;
+#[cfg(all(feature = "cargo", feature = "rust"))]
+pub mod cargo_processor;
 #[cfg(feature = "cpp")]
 pub mod cpp_processor;
+#[cfg(all(feature = "gradle", feature = "java"))]
+pub mod gradle_processor;
 #[cfg(feature = "java")]
 pub mod java_processor;
 #[cfg(feature = "make")]
@@ -16,9 +43,18 @@ pub mod make_processor;
 pub mod maven_processor;
 pub mod multi_preprocessed;
 pub mod no_space;
+#[cfg(all(feature = "npm", feature = "ts"))]
+pub mod npm_processor;
+pub mod persist;
 /// for now only tested on maven repositories with a pom in root.
 pub mod preprocessed;
 pub mod processing;
+#[cfg(feature = "python")]
+pub mod python_processor;
+#[cfg(feature = "rust")]
+pub mod rust_processor;
+#[cfg(feature = "ts")]
+pub mod ts_processor;
 mod utils;
 
 #[cfg(test)]
@@ -141,11 +177,47 @@ fn ts_lang_java() -> Option<tree_sitter::Language> {
 fn ts_lang_java() -> Option<tree_sitter::Language> {
     None
 }
+#[cfg(feature = "c")]
+fn ts_lang_c() -> Option<tree_sitter::Language> {
+    Some(hyperast_gen_ts_c::language())
+}
+#[cfg(not(feature = "c"))]
+fn ts_lang_c() -> Option<tree_sitter::Language> {
+    None
+}
+#[cfg(feature = "python")]
+fn ts_lang_python() -> Option<tree_sitter::Language> {
+    Some(hyperast_gen_ts_python::language())
+}
+#[cfg(not(feature = "python"))]
+fn ts_lang_python() -> Option<tree_sitter::Language> {
+    None
+}
+#[cfg(feature = "rust")]
+fn ts_lang_rust() -> Option<tree_sitter::Language> {
+    Some(hyperast_gen_ts_rust::language())
+}
+#[cfg(not(feature = "rust"))]
+fn ts_lang_rust() -> Option<tree_sitter::Language> {
+    None
+}
+#[cfg(feature = "ts")]
+fn ts_lang_ts() -> Option<tree_sitter::Language> {
+    Some(hyperast_gen_ts_ts::language())
+}
+#[cfg(not(feature = "ts"))]
+fn ts_lang_ts() -> Option<tree_sitter::Language> {
+    None
+}
 
 pub fn resolve_language(language: &str) -> Option<tree_sitter::Language> {
     match language {
         "Java" | "java" => ts_lang_java(),
         "Cpp" | "cpp" => ts_lang_cpp(),
+        "C" | "c" => ts_lang_c(),
+        "Ts" | "ts" | "TypeScript" | "typescript" => ts_lang_ts(),
+        "Python" | "python" | "py" => ts_lang_python(),
+        "Rust" | "rust" | "rs" => ts_lang_rust(),
         _ => None,
     }
 }
@@ -17,6 +17,11 @@ pub(crate) fn handle_makefile_file<'a>(
     name: &ObjectName,
     text: &'a [u8],
 ) -> Result<MakeFile, ()> {
+    let submodules = if name.as_bytes() == b"CMakeLists.txt" {
+        cmake_subdirectories(text)
+    } else {
+        vec![]
+    };
     log::trace!("not parsing {} bytes long Makefile", text.len()); // TODO parse the makefile
     let text = b"<proj></proj>";
     let tree = match hyperast_gen_ts_xml::legion::tree_sitter_parse_xml(text) {
@@ -35,17 +40,84 @@ pub(crate) fn handle_makefile_file<'a>(
     let x = tree_gen
         .generate_file(name.as_bytes(), text, tree.walk())
         .local;
-    // TODO extract submodules, dependencies and directories. maybe even more ie. artefact id, ...
+    // TODO extract dependencies and directories. maybe even more ie. artefact id, ...
     let x = MakeFile {
         compressed_node: x.compressed_node,
         metrics: x.metrics,
-        submodules: vec![],
+        submodules,
         source_dirs: vec![".".to_owned()],
         test_source_dirs: vec!["../tests".to_owned()],
     };
     Ok(x)
 }
 
+/// The directories added with `add_subdirectory` in a CMakeLists.txt.
+///
+/// Command names are case insensitive in CMake,
+/// directories given through variables like `${DIR}` cannot be resolved here so they are skipped.
+fn cmake_subdirectories(text: &[u8]) -> Vec<String> {
+    const CMD: &[u8] = b"add_subdirectory";
+    let mut r = vec![];
+    let mut i = 0;
+    while i + CMD.len() <= text.len() {
+        if text[i] == b'#' {
+            // skip comments
+            i += text[i..]
+                .iter()
+                .position(|c| *c == b'\n')
+                .unwrap_or(text.len() - i);
+            continue;
+        }
+        let at_word_start = i == 0 || !(text[i - 1].is_ascii_alphanumeric() || text[i - 1] == b'_');
+        if !at_word_start || !text[i..i + CMD.len()].eq_ignore_ascii_case(CMD) {
+            i += 1;
+            continue;
+        }
+        i += CMD.len();
+        let rest = &text[i..];
+        let rest = &rest[rest.iter().take_while(|c| c.is_ascii_whitespace()).count()..];
+        let Some(rest) = rest.strip_prefix(b"(") else {
+            continue;
+        };
+        let rest = &rest[rest.iter().take_while(|c| c.is_ascii_whitespace()).count()..];
+        let arg = if let Some(rest) = rest.strip_prefix(b"\"") {
+            rest.split(|c| *c == b'"').next()
+        } else {
+            rest.split(|c| c.is_ascii_whitespace() || *c == b')').next()
+        };
+        let Some(arg) = arg.and_then(|x| std::str::from_utf8(x).ok()) else {
+            continue;
+        };
+        if arg.is_empty() || arg.contains("${") {
+            continue;
+        }
+        r.push(
+            arg.trim_start_matches("./")
+                .trim_end_matches('/')
+                .to_owned(),
+        );
+    }
+    r
+}
+
+#[test]
+fn cmake_subdirectories_test() {
+    let text = br#"
+cmake_minimum_required(VERSION 3.10)
+project(foo C)
+# add_subdirectory(commented)
+add_subdirectory(src)
+ADD_SUBDIRECTORY( "lib/core" )
+add_subdirectory(./tests/ EXCLUDE_FROM_ALL)
+add_subdirectory(${THIRD_PARTY})
+my_add_subdirectory(not_a_module)
+"#;
+    assert_eq!(
+        cmake_subdirectories(text),
+        vec!["src".to_owned(), "lib/core".to_owned(), "tests".to_owned()]
+    );
+}
+
 #[derive(Debug, Clone)]
 pub struct MakeFile {
     pub compressed_node: NodeIdentifier,
@@ -164,6 +236,24 @@ impl MakeModuleAcc {
             line_count: 0,
         });
     }
+    #[cfg(feature = "c")]
+    pub(crate) fn push_c_source_file(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: hyperast_gen_ts_c::legion::Local,
+    ) {
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+    #[cfg(feature = "c")]
+    pub(crate) fn push_c_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: hyperast_gen_ts_c::legion::Local,
+    ) {
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
     pub(crate) fn push_test_source_directory(
         &mut self,
         name: LabelIdentifier,
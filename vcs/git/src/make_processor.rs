@@ -75,6 +75,13 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<MakeModuleAcc>
                 if self.dir_path.peek().is_some() {
                     return;
                 }
+                let parameter = self
+                    .prepro
+                    .processing_systems
+                    .mut_or_default::<MakeProcessorHolder>()
+                    .with_parameters(self.handle.1)
+                    .parameter
+                    .clone();
                 if crate::processing::file_sys::MakeFile::matches(&name) {
                     self.prepro
                         .help_handle_makefile(
@@ -82,17 +89,39 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<MakeModuleAcc>
                             &mut self.stack.last_mut().unwrap().acc,
                             name,
                             &self.repository,
-                            PCP2Handle(self.handle.1, std::marker::PhantomData),
+                            parameter.makefile_handle,
                         )
                         .unwrap();
-                } else if crate::processing::file_sys::Cpp::matches(&name) {
+                    return;
+                }
+                #[cfg(feature = "c")]
+                if let Some(c_handle) = parameter.c_handle {
+                    if crate::processing::file_sys::C::matches(&name) {
+                        self.prepro
+                            .help_handle_c_file2(
+                                oid,
+                                &mut self.stack.last_mut().unwrap().acc,
+                                &name,
+                                self.repository,
+                                c_handle,
+                            )
+                            .unwrap();
+                    } else {
+                        log::debug!("not c source file {:?}", name.try_str());
+                    }
+                    return;
+                }
+                let Some(cpp_handle) = parameter.cpp_handle else {
+                    return;
+                };
+                if crate::processing::file_sys::Cpp::matches(&name) {
                     self.prepro
                         .help_handle_cpp_file2(
                             oid,
                             &mut self.stack.last_mut().unwrap().acc,
                             &name,
                             self.repository,
-                            PCP2Handle(self.handle.1, std::marker::PhantomData),
+                            cpp_handle,
                         )
                         .unwrap();
                 // } else if name.ends_with(b".h") || name.ends_with(b".hpp") {
@@ -114,6 +143,7 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<MakeModuleAcc>
         self.prepro
             .processing_systems
             .mut_or_default::<MakeProcessorHolder>()
+            .with_parameters_mut(self.handle.1)
             .get_caches_mut()
             .object_map
             .insert(oid, full_node.clone());
@@ -171,7 +201,7 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool>
             .processing_systems
             .mut_or_default::<MakeProcessorHolder>()
             .with_parameters_mut(self.handle.1);
-        let cpp_handle = make_proc.parameter.cpp_handle;
+        let parameter = make_proc.parameter.clone();
         if let Some(already) = make_proc.get_caches_mut().object_map.get(&oid) {
             // reinit already computed node for post order
             let full_node = already.clone();
@@ -183,8 +213,34 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool>
         }
         log::debug!("make tree {:?}", name.try_str());
         let parent_acc = &mut self.stack.last_mut().unwrap().acc;
+        let helper = MakeModuleHelper::from((parent_acc, &name));
+        if helper.submodules.0 || !helper.submodules.1.is_empty() {
+            // a module declared by its parent, e.g. with add_subdirectory in a CMakeLists.txt,
+            // or a directory on the way to one of them
+            let tree = self.repository.find_tree(oid).unwrap();
+            let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+            self.stack.push(StackEle::new(oid, prepared, helper.into()));
+            return;
+        }
+        let parent_acc = &mut self.stack.last_mut().unwrap().acc;
+        // TODO also try to handle nested Makefiles
+        #[cfg(feature = "c")]
+        if let Some(c_handle) = parameter.c_handle {
+            let (name, (full_node,)) = self.prepro.help_handle_c_folder(
+                &self.repository,
+                &mut self.dir_path,
+                oid,
+                &name,
+                c_handle,
+            );
+            assert!(!parent_acc.primary.children_names.contains(&name));
+            parent_acc.push_c_source_directory(name, full_node);
+            return;
+        }
+        let Some(cpp_handle) = parameter.cpp_handle else {
+            return;
+        };
         if true {
-            // TODO also try to handle nested Makefiles
             let (name, (full_node,)) = self.prepro.help_handle_cpp_folder(
                 &self.repository,
                 &mut self.dir_path,
@@ -196,7 +252,6 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool>
             parent_acc.push_source_directory(name, full_node);
             return;
         }
-        let helper = MakeModuleHelper::from((parent_acc, &name));
         if helper.source_directories.0 || helper.test_source_directories.0 {
             // handle as source dir
             let (name, (full_node,)) = self.prepro.help_handle_cpp_folder(
@@ -415,11 +470,14 @@ pub(crate) fn prepare_dir_exploration(
 
 // # Pom
 
+/// Handles of the processors used for the files of Make modules,
+/// for source files the c one takes precedence when both are set.
 #[derive(Clone, PartialEq, Eq)]
 pub struct Parameter {
-    pub(crate) cpp_handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<
-        crate::cpp_processor::CppProc,
-    >,
+    pub(crate) makefile_handle: PCP2Handle<MakefileProc>,
+    pub(crate) cpp_handle: Option<PCP2Handle<crate::cpp_processor::CppProc>>,
+    #[cfg(feature = "c")]
+    pub(crate) c_handle: Option<PCP2Handle<crate::c_processor::CProc>>,
 }
 impl From<crate::processing::erased::ParametrizedCommitProcessor2Handle<MakeProc>>
     for crate::processing::erased::ParametrizedCommitProcessor2Handle<MakefileProc>
@@ -446,17 +504,23 @@ impl From<crate::processing::erased::ParametrizedCommitProcessor2Handle<MakeProc
     }
 }
 // #[derive(Default)]
-struct MakefileProcessorHolder(Option<MakefileProc>);
+pub(crate) struct MakefileProcessorHolder(Option<MakefileProc>);
 impl Default for MakefileProcessorHolder {
     fn default() -> Self {
         Self(Some(MakefileProc(None, Default::default())))
     }
 }
 
-struct MakefileProc(Option<Parameter>, crate::processing::caches::Makefile);
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct MakefileParameter {}
+
+pub(crate) struct MakefileProc(
+    Option<MakefileParameter>,
+    crate::processing::caches::Makefile,
+);
 
 impl crate::processing::erased::Parametrized for MakefileProcessorHolder {
-    type T = Parameter;
+    type T = MakefileParameter;
     fn register_param(
         &mut self,
         t: Self::T,
@@ -493,6 +557,14 @@ impl crate::processing::erased::CommitProc for MakefileProc {
     fn get_commit(&self, _commit_oid: git2::Oid) -> Option<&crate::Commit> {
         unimplemented!("required for processing at the root of a project")
     }
+
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        unimplemented!("required for processing at the root of a project")
+    }
+
+    fn restore_commit(&mut self, _commit_oid: git2::Oid, _commit: crate::Commit) {
+        unimplemented!("required for processing at the root of a project")
+    }
 }
 
 impl crate::processing::erased::CommitProcExt for MakefileProc {
@@ -537,7 +609,7 @@ impl CacheHolding<crate::processing::caches::Makefile> for MakefileProcessorHold
 
 // # Make
 #[derive(Default)]
-pub(crate) struct MakeProcessorHolder(Option<MakeProc>);
+pub(crate) struct MakeProcessorHolder(Vec<MakeProc>);
 pub(crate) struct MakeProc {
     parameter: Parameter,
     cache: crate::processing::caches::Make,
@@ -554,10 +626,8 @@ impl crate::processing::erased::Parametrized for MakeProcessorHolder {
             .iter()
             .position(|x| &x.parameter == &t)
             .unwrap_or_else(|| {
-                let l = 0; //self.0.len();
-                // self.0.push(MakeProc(t));
-                // TODO enable multi configs for cpp, do the same as the one for Java
-                self.0 = Some(MakeProc {
+                let l = self.0.len();
+                self.0.push(MakeProc {
                     parameter: t,
                     cache: Default::default(),
                     commits: Default::default(),
@@ -624,15 +694,35 @@ impl crate::processing::erased::CommitProc for MakeProc {
         self.commits.get(&commit_oid)
     }
 
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        Box::new(self.commits.iter())
+    }
+
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+
     fn get_lang_handle(&self, lang: &str) -> Option<ParametrizedCommitProcessorHandle> {
-        dbg!(self.parameter.cpp_handle.0.0);
         if lang.eq_ignore_ascii_case("cpp") {
+            let cpp_handle = self.parameter.cpp_handle?;
             Some(ParametrizedCommitProcessorHandle(
                 CommitProcessorHandle(std::any::TypeId::of::<
                     crate::cpp_processor::CppProcessorHolder,
                 >()),
-                self.parameter.cpp_handle.0,
+                cpp_handle.0,
             ))
+        } else if lang.eq_ignore_ascii_case("c") {
+            #[cfg(feature = "c")]
+            return self.parameter.c_handle.map(|c_handle| {
+                ParametrizedCommitProcessorHandle(
+                    CommitProcessorHandle(std::any::TypeId::of::<
+                        crate::c_processor::CProcessorHolder,
+                    >()),
+                    c_handle.0,
+                )
+            });
+            #[cfg(not(feature = "c"))]
+            None
         } else if lang.eq_ignore_ascii_case("java") {
             if cfg!(debug_assertions) {
                 unimplemented!()
@@ -655,16 +745,14 @@ impl crate::processing::erased::ParametrizedCommitProc2 for MakeProcessorHolder
         &mut self,
         parameters: crate::processing::erased::ConfigParametersHandle,
     ) -> &mut Self::Proc {
-        assert_eq!(0, parameters.0);
-        self.0.as_mut().unwrap()
+        &mut self.0[parameters.0]
     }
 
     fn with_parameters(
         &self,
         parameters: crate::processing::erased::ConfigParametersHandle,
     ) -> &Self::Proc {
-        assert_eq!(0, parameters.0);
-        self.0.as_ref().unwrap()
+        &self.0[parameters.0]
     }
 }
 
@@ -676,12 +764,3 @@ impl CacheHolding<crate::processing::caches::Make> for MakeProc {
         &self.cache
     }
 }
-
-impl CacheHolding<crate::processing::caches::Make> for MakeProcessorHolder {
-    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Make {
-        &mut self.0.as_mut().unwrap().cache
-    }
-    fn get_caches(&self) -> &crate::processing::caches::Make {
-        &self.0.as_ref().unwrap().cache
-    }
-}
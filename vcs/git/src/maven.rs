@@ -6,6 +6,7 @@ use enumset::EnumSet;
 use hyperast::{
     position::{StructuralPosition, TreePath, TreePathMut},
     store::defaults::{LabelIdentifier, NodeIdentifier},
+    store::persist::Persist,
     tree_gen::SubTreeMetrics,
     types::{Childrn, LabelStore as _, Labeled, Tree, Typed, WithChildren},
 };
@@ -17,6 +18,7 @@ use hyperast_gen_ts_xml::{
 use num::ToPrimitive;
 use std::{
     fmt::{self, Debug},
+    io::{self, Read, Write},
     ops::AddAssign,
     path::PathBuf,
 };
@@ -41,17 +43,74 @@ pub(crate) fn handle_pom_file<'a>(
     let x = tree_gen
         .generate_file(name.as_bytes(), text, tree.walk())
         .local;
-    // TODO extract submodules, dependencies and directories. maybe even more ie. artefact id, ...
+    let model = PomModel::read(tree_gen.stores, x.compressed_node);
+    let source_dirs = source_dir(model.source_directory.as_deref(), "src/main/java");
+    let test_source_dirs = source_dir(model.test_source_directory.as_deref(), "src/test/java");
+    let submodules = model
+        .modules
+        .iter()
+        .filter_map(|x| {
+            let x = x.strip_suffix("pom.xml").unwrap_or(x);
+            let dir = relative_dir(x);
+            if dir.is_none() {
+                log::warn!("ignoring module {:?} of {:?}", x, name.try_str());
+            }
+            dir
+        })
+        .collect();
     let x = POM {
         compressed_node: x.compressed_node,
         metrics: x.metrics,
-        submodules: vec![],
-        source_dirs: vec!["src/main/java".to_owned()],
-        test_source_dirs: vec!["src/test/java".to_owned()],
+        submodules,
+        source_dirs,
+        test_source_dirs,
+        model,
     };
     Ok(x)
 }
 
+/// Falls back to the maven default when the directory is not declared or cannot be handled,
+/// eg. when it uses properties or is outside of the module.
+fn source_dir(declared: Option<&str>, default: &str) -> Vec<String> {
+    let dir = declared.and_then(|x| {
+        let dir = relative_dir(x);
+        if dir.is_none() {
+            log::warn!("unsupported source directory {:?}", x);
+        }
+        dir
+    });
+    vec![dir.unwrap_or_else(|| default.to_owned())]
+}
+
+/// Normalizes a path declared in a pom.xml relatively to its module,
+/// returns None if it is not inside the module or still contains properties.
+fn relative_dir(path: &str) -> Option<String> {
+    let path = ["${project.basedir}", "${basedir}"]
+        .iter()
+        .find_map(|x| path.strip_prefix(x))
+        .unwrap_or(path);
+    if path.contains("${") {
+        return None;
+    }
+    let path: PathBuf = std::path::Path::new(path)
+        .components()
+        .filter(|x| {
+            !matches!(
+                x,
+                std::path::Component::CurDir | std::path::Component::RootDir
+            )
+        })
+        .collect();
+    if path.as_os_str().is_empty()
+        || path
+            .components()
+            .any(|x| !matches!(x, std::path::Component::Normal(_)))
+    {
+        return None;
+    }
+    path.to_str().map(|x| x.to_owned())
+}
+
 #[derive(Debug, Clone)]
 pub struct POM {
     pub compressed_node: NodeIdentifier,
@@ -59,6 +118,362 @@ pub struct POM {
     submodules: Vec<String>,
     source_dirs: Vec<String>,
     test_source_dirs: Vec<String>,
+    pub model: PomModel,
+}
+
+/// The identity of a maven artifact, ie. groupId:artifactId:version
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Coordinates {
+    pub group_id: Option<String>,
+    pub artifact_id: Option<String>,
+    pub version: Option<String>,
+}
+
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let o = |x: &Option<String>| x.clone().unwrap_or_default();
+        write!(
+            f,
+            "{}:{}:{}",
+            o(&self.group_id),
+            o(&self.artifact_id),
+            o(&self.version)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Parent {
+    pub coordinates: Coordinates,
+    pub relative_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dependency {
+    pub coordinates: Coordinates,
+    /// the `<type>` of the dependency, jar when not declared
+    pub kind: Option<String>,
+    pub classifier: Option<String>,
+    pub scope: Option<String>,
+    pub optional: bool,
+}
+
+/// What is extracted from a pom.xml,
+/// it is also attached to the node of the maven module declaring it.
+///
+/// The groupId and version are inherited from the parent when not declared,
+/// and the properties declared in the pom are substituted in the coordinates of dependencies.
+/// What comes from the parents, ie. their properties and managed dependencies,
+/// is resolved later, see [`crate::maven_dependencies`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PomModel {
+    pub coordinates: Coordinates,
+    pub parent: Option<Parent>,
+    pub modules: Vec<String>,
+    pub source_directory: Option<String>,
+    pub test_source_directory: Option<String>,
+    /// as declared, ie. without the builtin `project.*` properties
+    pub properties: Vec<(String, String)>,
+    pub dependencies: Vec<Dependency>,
+    /// the dependencies of the `<dependencyManagement>` section
+    pub dependency_management: Vec<Dependency>,
+}
+
+impl PomModel {
+    /// Reads the model out of the HyperAST of a pom.xml, ie. a [`Type::Document`]
+    pub fn read<TS>(stores: &hyperast::store::SimpleStores<TS>, pom: NodeIdentifier) -> Self {
+        let reader = PomReader { stores };
+        let Some(project) = reader
+            .children(pom)
+            .into_iter()
+            .find(|x| reader.kind(*x) == Some(Type::Element))
+        else {
+            return Self::default();
+        };
+        let parent = reader.child(project, "parent").map(|x| Parent {
+            coordinates: reader.coordinates(x),
+            relative_path: reader.child_text(x, "relativePath"),
+        });
+        let mut coordinates = reader.coordinates(project);
+        if let Some(parent) = &parent {
+            let inherited = &parent.coordinates;
+            coordinates.group_id = coordinates.group_id.or(inherited.group_id.clone());
+            coordinates.version = coordinates.version.or(inherited.version.clone());
+        }
+        let declared: Vec<(String, String)> = reader
+            .child(project, "properties")
+            .map(|x| {
+                reader
+                    .elements(x)
+                    .into_iter()
+                    .filter_map(|x| Some((reader.tag(x)?.to_owned(), reader.text(x))))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut properties = declared.clone();
+        let builtins = [
+            ("project.groupId", &coordinates.group_id),
+            ("project.artifactId", &coordinates.artifact_id),
+            ("project.version", &coordinates.version),
+        ];
+        for (k, v) in builtins {
+            if let Some(v) = v {
+                properties.push((k.to_owned(), v.clone()));
+            }
+        }
+        let modules = reader
+            .child(project, "modules")
+            .map(|x| reader.children_texts(x, "module"))
+            .unwrap_or_default();
+        let build = reader.child(project, "build");
+        let dependencies = reader
+            .child(project, "dependencies")
+            .map(|x| reader.dependencies(x, &properties))
+            .unwrap_or_default();
+        let dependency_management = reader
+            .child(project, "dependencyManagement")
+            .and_then(|x| reader.child(x, "dependencies"))
+            .map(|x| reader.dependencies(x, &properties))
+            .unwrap_or_default();
+        Self {
+            coordinates,
+            parent,
+            modules,
+            source_directory: build.and_then(|x| reader.child_text(x, "sourceDirectory")),
+            test_source_directory: build.and_then(|x| reader.child_text(x, "testSourceDirectory")),
+            properties: declared,
+            dependencies,
+            dependency_management,
+        }
+    }
+}
+
+pub(crate) fn interpolate(mut s: String, properties: &[(String, String)]) -> String {
+    // few passes, as properties can reference other properties
+    for _ in 0..4 {
+        if !s.contains("${") {
+            break;
+        }
+        for (k, v) in properties {
+            s = s.replace(&format!("${{{}}}", k), v);
+        }
+    }
+    s
+}
+
+type XmlNodeRef<'a> = hyperast::store::nodes::legion::HashedNodeRef<'a, NodeIdentifier>;
+
+/// Navigates the XML HyperAST of a pom.xml by element names
+struct PomReader<'a, TS> {
+    stores: &'a hyperast::store::SimpleStores<TS>,
+}
+
+impl<'a, TS> PomReader<'a, TS> {
+    /// the type component is the interned one, ie. [`hyperast_gen_ts_xml::types::TType`]
+    fn resolve(&self, id: NodeIdentifier) -> Option<(XmlNodeRef<'a>, Type)> {
+        (self.stores.node_store).try_resolve_typed2::<hyperast_gen_ts_xml::types::Lang>(&id)
+    }
+
+    fn kind(&self, id: NodeIdentifier) -> Option<Type> {
+        Some(self.resolve(id)?.1)
+    }
+
+    fn children(&self, id: NodeIdentifier) -> Vec<NodeIdentifier> {
+        let Some((n, _)) = self.resolve(id) else {
+            return vec![];
+        };
+        n.children()
+            .map(|x| x.iter_children().collect())
+            .unwrap_or_default()
+    }
+
+    fn label(&self, id: NodeIdentifier) -> Option<&'a str> {
+        let n = self.resolve(id)?.0;
+        let l = *n.try_get_label()?;
+        Some(self.stores.label_store.resolve(&l))
+    }
+
+    /// the elements directly contained in `element`
+    fn elements(&self, element: NodeIdentifier) -> Vec<NodeIdentifier> {
+        let mut r = vec![];
+        for x in self.children(element) {
+            match self.kind(x) {
+                Some(Type::Element) => r.push(x),
+                Some(Type::Content) => r.extend(
+                    self.children(x)
+                        .into_iter()
+                        .filter(|x| self.kind(*x) == Some(Type::Element)),
+                ),
+                _ => (),
+            }
+        }
+        r
+    }
+
+    /// the name of `element`, eg. `artifactId` for `<artifactId>...</artifactId>`
+    fn tag(&self, element: NodeIdentifier) -> Option<&'a str> {
+        let tag = self
+            .children(element)
+            .into_iter()
+            .find(|x| matches!(self.kind(*x), Some(Type::STag | Type::EmptyElemTag)))?;
+        let name = self
+            .children(tag)
+            .into_iter()
+            .find(|x| self.kind(*x) == Some(Type::Name))?;
+        self.label(name)
+    }
+
+    /// the trimmed textual content of `element`
+    fn text(&self, element: NodeIdentifier) -> String {
+        let mut r = String::new();
+        for x in self.children(element) {
+            match self.kind(x) {
+                Some(Type::CharData) => r.push_str(self.label(x).unwrap_or_default()),
+                Some(Type::Content) => {
+                    for x in self.children(x) {
+                        if self.kind(x) == Some(Type::CharData) {
+                            r.push_str(self.label(x).unwrap_or_default());
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        r.trim().to_owned()
+    }
+
+    fn child(&self, element: NodeIdentifier, tag: &str) -> Option<NodeIdentifier> {
+        self.elements(element)
+            .into_iter()
+            .find(|x| self.tag(*x) == Some(tag))
+    }
+
+    fn child_text(&self, element: NodeIdentifier, tag: &str) -> Option<String> {
+        self.child(element, tag)
+            .map(|x| self.text(x))
+            .filter(|x| !x.is_empty())
+    }
+
+    fn children_texts(&self, element: NodeIdentifier, tag: &str) -> Vec<String> {
+        self.elements(element)
+            .into_iter()
+            .filter(|x| self.tag(*x) == Some(tag))
+            .map(|x| self.text(x))
+            .filter(|x| !x.is_empty())
+            .collect()
+    }
+
+    fn coordinates(&self, element: NodeIdentifier) -> Coordinates {
+        Coordinates {
+            group_id: self.child_text(element, "groupId"),
+            artifact_id: self.child_text(element, "artifactId"),
+            version: self.child_text(element, "version"),
+        }
+    }
+
+    /// the `<dependency>` elements of a `<dependencies>` element,
+    /// with the given properties substituted in their coordinates
+    fn dependencies(
+        &self,
+        element: NodeIdentifier,
+        properties: &[(String, String)],
+    ) -> Vec<Dependency> {
+        let resolve = |x: Option<String>| x.map(|x| interpolate(x, properties));
+        self.elements(element)
+            .into_iter()
+            .filter(|x| self.tag(*x) == Some("dependency"))
+            .map(|x| {
+                let Coordinates {
+                    group_id,
+                    artifact_id,
+                    version,
+                } = self.coordinates(x);
+                Dependency {
+                    coordinates: Coordinates {
+                        group_id: resolve(group_id),
+                        artifact_id: resolve(artifact_id),
+                        version: resolve(version),
+                    },
+                    kind: self.child_text(x, "type"),
+                    classifier: resolve(self.child_text(x, "classifier")),
+                    scope: self.child_text(x, "scope"),
+                    optional: self.child_text(x, "optional").as_deref() == Some("true"),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Persist for Coordinates {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.group_id.persist(w)?;
+        self.artifact_id.persist(w)?;
+        self.version.persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        Ok(Self {
+            group_id: Persist::restore(r)?,
+            artifact_id: Persist::restore(r)?,
+            version: Persist::restore(r)?,
+        })
+    }
+}
+
+impl Persist for Parent {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.coordinates.persist(w)?;
+        self.relative_path.persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        Ok(Self {
+            coordinates: Persist::restore(r)?,
+            relative_path: Persist::restore(r)?,
+        })
+    }
+}
+
+impl Persist for Dependency {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.coordinates.persist(w)?;
+        self.kind.persist(w)?;
+        self.classifier.persist(w)?;
+        self.scope.persist(w)?;
+        self.optional.persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        Ok(Self {
+            coordinates: Persist::restore(r)?,
+            kind: Persist::restore(r)?,
+            classifier: Persist::restore(r)?,
+            scope: Persist::restore(r)?,
+            optional: Persist::restore(r)?,
+        })
+    }
+}
+
+impl Persist for PomModel {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.coordinates.persist(w)?;
+        self.parent.persist(w)?;
+        self.modules.persist(w)?;
+        self.source_directory.persist(w)?;
+        self.test_source_directory.persist(w)?;
+        self.properties.persist(w)?;
+        self.dependencies.persist(w)?;
+        self.dependency_management.persist(w)
+    }
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        Ok(Self {
+            coordinates: Persist::restore(r)?,
+            parent: Persist::restore(r)?,
+            modules: Persist::restore(r)?,
+            source_directory: Persist::restore(r)?,
+            test_source_directory: Persist::restore(r)?,
+            properties: Persist::restore(r)?,
+            dependencies: Persist::restore(r)?,
+            dependency_management: Persist::restore(r)?,
+        })
+    }
 }
 
 pub struct IterMavenModules2<'a> {
@@ -204,6 +619,8 @@ pub struct MavenModuleAcc {
     pub(crate) test_dirs: Option<Vec<PathBuf>>,
     pub(crate) status: EnumSet<SemFlag>,
     pub(crate) scripting_acc: std::option::Option<hyperast::scripting::Acc>,
+    /// from the pom.xml of the module, persisted as a component of the module node
+    pub(crate) pom: Option<PomModel>,
 }
 
 impl From<String> for MavenModuleAcc {
@@ -222,6 +639,7 @@ impl MavenModuleAcc {
             test_dirs: None,
             status: Default::default(),
             scripting_acc: None,
+            pom: None,
         }
     }
     pub(crate) fn with_content(
@@ -250,6 +668,7 @@ impl MavenModuleAcc {
             },
             status: Default::default(),
             scripting_acc: None,
+            pom: None,
         }
     }
 }
@@ -278,6 +697,7 @@ impl MavenModuleAcc {
         );
         self.sub_modules = Some(full_node.submodules.iter().map(|x| x.into()).collect());
         self.primary.metrics.acc(full_node.metrics);
+        self.pom = Some(full_node.model);
     }
     pub fn push_submodule(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
         if full_node.1.status.contains(SemFlag::HoldMavenSubModule)
@@ -512,3 +932,138 @@ impl hyperast::tree_gen::Accumulator for MavenModuleAcc {
 impl Accumulator for MavenModuleAcc {
     type Unlabeled = (NodeIdentifier, MD);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POM_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+    <modelVersion>4.0.0</modelVersion>
+    <parent>
+        <groupId>org.example</groupId>
+        <artifactId>parent</artifactId>
+        <version>1.2.0</version>
+        <relativePath>../pom.xml</relativePath>
+    </parent>
+    <artifactId>core</artifactId>
+    <properties>
+        <junit.version>5.10.0</junit.version>
+    </properties>
+    <modules>
+        <module>api</module>
+        <module>impl/pom.xml</module>
+        <module>../outside</module>
+    </modules>
+    <dependencies>
+        <dependency>
+            <groupId>org.junit.jupiter</groupId>
+            <artifactId>junit-jupiter</artifactId>
+            <version>${junit.version}</version>
+            <scope>test</scope>
+        </dependency>
+        <dependency>
+            <groupId>${project.groupId}</groupId>
+            <artifactId>util</artifactId>
+            <version>${project.version}</version>
+            <optional>true</optional>
+        </dependency>
+    </dependencies>
+    <dependencyManagement>
+        <dependencies>
+            <dependency>
+                <groupId>org.example</groupId>
+                <artifactId>api</artifactId>
+                <version>${project.version}</version>
+                <type>test-jar</type>
+                <classifier>tests</classifier>
+            </dependency>
+        </dependencies>
+    </dependencyManagement>
+    <build>
+        <sourceDirectory>${project.basedir}/src/java</sourceDirectory>
+    </build>
+</project>
+"#;
+
+    #[test]
+    fn test_pom_model() {
+        let text = POM_XML.as_bytes();
+        let mut stores = hyperast::store::SimpleStores::<TStore>::default();
+        let mut tree_gen = XmlTreeGen {
+            line_break: "\n".as_bytes().to_vec(),
+            stores: &mut stores,
+        };
+        let tree = match XmlTreeGen::<TStore>::tree_sitter_parse(text) {
+            Ok(t) => t,
+            Err(t) => t,
+        };
+        let x = tree_gen.generate_file(b"pom.xml", text, tree.walk()).local;
+        let model = PomModel::read(&stores, x.compressed_node);
+
+        let coordinates = |g: &str, a: &str, v: &str| Coordinates {
+            group_id: Some(g.to_owned()),
+            artifact_id: Some(a.to_owned()),
+            version: Some(v.to_owned()),
+        };
+        assert_eq!(
+            model.coordinates,
+            coordinates("org.example", "core", "1.2.0")
+        );
+        let parent = model.parent.as_ref().unwrap();
+        assert_eq!(
+            parent.coordinates,
+            coordinates("org.example", "parent", "1.2.0")
+        );
+        assert_eq!(parent.relative_path.as_deref(), Some("../pom.xml"));
+        assert_eq!(model.modules, ["api", "impl/pom.xml", "../outside"]);
+        assert_eq!(
+            model.dependencies,
+            [
+                Dependency {
+                    coordinates: coordinates("org.junit.jupiter", "junit-jupiter", "5.10.0"),
+                    scope: Some("test".to_owned()),
+                    ..Default::default()
+                },
+                Dependency {
+                    coordinates: coordinates("org.example", "util", "1.2.0"),
+                    optional: true,
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(
+            model.properties,
+            [("junit.version".to_owned(), "5.10.0".to_owned())]
+        );
+        assert_eq!(
+            model.dependency_management,
+            [Dependency {
+                coordinates: coordinates("org.example", "api", "1.2.0"),
+                kind: Some("test-jar".to_owned()),
+                classifier: Some("tests".to_owned()),
+                ..Default::default()
+            }]
+        );
+        assert_eq!(
+            model.source_directory.as_deref(),
+            Some("${project.basedir}/src/java")
+        );
+        assert_eq!(model.test_source_directory, None);
+
+        assert_eq!(
+            source_dir(model.source_directory.as_deref(), "src/main/java"),
+            ["src/java"]
+        );
+        assert_eq!(
+            source_dir(model.test_source_directory.as_deref(), "src/test/java"),
+            ["src/test/java"]
+        );
+        let modules: Vec<_> = model
+            .modules
+            .iter()
+            .filter_map(|x| relative_dir(x.strip_suffix("pom.xml").unwrap_or(x)))
+            .collect();
+        assert_eq!(modules, ["api", "impl"]);
+    }
+}
@@ -0,0 +1,511 @@
+//! The dependency graph of the maven modules of a commit,
+//! computed from the [`PomModel`] attached to the module nodes, ie. without parsing the pom.xml files again.
+use crate::maven::{Coordinates, Dependency, PomModel};
+use hyperast::store::defaults::NodeIdentifier;
+use hyperast::types::{Childrn, LabelStore as _, Labeled, WithChildren};
+use hyperast_gen_ts_xml::types::{Lang as XmlLang, Type};
+use std::collections::BTreeMap;
+
+/// A maven module, ie. a directory with a pom.xml
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Module {
+    /// relative to the root of the repository, empty for the root module
+    pub path: String,
+    pub coordinates: Coordinates,
+    pub parent: Option<Coordinates>,
+}
+
+/// A dependency declared by a module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    /// the coordinates of the declaring module
+    pub from: Coordinates,
+    pub to: Coordinates,
+    /// the `<type>` of the dependency, jar when not declared
+    pub kind: Option<String>,
+    pub classifier: Option<String>,
+    pub scope: Option<String>,
+    pub optional: bool,
+    /// the index of the depended upon module, if it is a module of the same commit
+    pub module: Option<usize>,
+}
+
+impl Edge {
+    pub fn is_internal(&self) -> bool {
+        self.module.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyGraph {
+    pub modules: Vec<Module>,
+    pub edges: Vec<Edge>,
+}
+
+impl DependencyGraph {
+    /// Computes the graph of the modules found under `root`,
+    /// usually the root of a commit processed with a maven configuration.
+    /// Returns None when there is no pom.xml at `root`, ie. it is not a maven project.
+    pub fn compute(stores: &crate::SimpleStores, root: NodeIdentifier) -> Option<Self> {
+        let mut models = vec![];
+        collect_modules(stores, root, &mut vec![], &mut models);
+        if !models.first().is_some_and(|(path, _)| path.is_empty()) {
+            return None;
+        }
+        Some(Self::from_models(&models))
+    }
+
+    /// The dependencies are resolved as in the effective pom of each module,
+    /// with what is inherited from the parents that are modules of the same commit,
+    /// ie. their properties and managed dependencies.
+    fn from_models(models: &[(String, PomModel)]) -> Self {
+        let modules: Vec<Module> = models
+            .iter()
+            .map(|(path, model)| Module {
+                path: path.clone(),
+                coordinates: model.coordinates.clone(),
+                parent: model.parent.as_ref().map(|x| x.coordinates.clone()),
+            })
+            .collect();
+        let mut edges = vec![];
+        for (i, (_, model)) in models.iter().enumerate() {
+            let inherited = Inherited::new(models, i);
+            for dependency in &model.dependencies {
+                let dependency = inherited.resolve(dependency);
+                // versions of sibling modules are often given through properties,
+                // so only the groupId and artifactId are used to resolve them
+                let module = modules
+                    .iter()
+                    .position(|x| same_artifact(&x.coordinates, &dependency.coordinates));
+                edges.push(Edge {
+                    from: model.coordinates.clone(),
+                    to: dependency.coordinates,
+                    kind: dependency.kind,
+                    classifier: dependency.classifier,
+                    scope: dependency.scope,
+                    optional: dependency.optional,
+                    module,
+                });
+            }
+        }
+        Self { modules, edges }
+    }
+
+    /// The dependencies between modules of the commit
+    pub fn internal(&self) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(|x| x.is_internal())
+    }
+
+    /// The dependencies towards artifacts built elsewhere
+    pub fn external(&self) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(|x| !x.is_internal())
+    }
+}
+
+fn collect_modules(
+    stores: &crate::SimpleStores,
+    id: NodeIdentifier,
+    path: &mut Vec<String>,
+    out: &mut Vec<(String, PomModel)>,
+) {
+    // the type component is the interned one, ie. [`hyperast_gen_ts_xml::types::TType`]
+    let resolve = |id: &NodeIdentifier| stores.node_store.try_resolve_typed2::<XmlLang>(id);
+    let Some((n, Type::MavenDirectory)) = resolve(&id) else {
+        return;
+    };
+    if let Ok(model) = n.get_component::<PomModel>() {
+        out.push((path.join("/"), model.clone()));
+    }
+    let Some(cs) = n.children() else {
+        return;
+    };
+    for x in cs.iter_children() {
+        let Some((c, Type::MavenDirectory)) = resolve(&x) else {
+            continue;
+        };
+        let Some(label) = c.try_get_label() else {
+            continue;
+        };
+        let name = stores.label_store.resolve(label);
+        path.push(name.to_owned());
+        collect_modules(stores, x, path, out);
+        path.pop();
+    }
+}
+
+fn same_artifact(a: &Coordinates, b: &Coordinates) -> bool {
+    a.group_id == b.group_id && a.artifact_id == b.artifact_id
+}
+
+fn find_module(models: &[(String, PomModel)], coordinates: &Coordinates) -> Option<usize> {
+    models
+        .iter()
+        .position(|(_, x)| same_artifact(&x.coordinates, coordinates))
+}
+
+/// What a module gets from itself and its ancestors,
+/// where the nearest declaration wins.
+/// Parents outside of the commit, eg. in a remote repository, are not resolved.
+struct Inherited<'a> {
+    properties: Vec<(String, String)>,
+    managed: Vec<&'a Dependency>,
+}
+
+impl<'a> Inherited<'a> {
+    fn new(models: &'a [(String, PomModel)], module: usize) -> Self {
+        // the module then its ancestors, stopping on cycles
+        let mut chain: Vec<usize> = vec![];
+        let mut current = Some(module);
+        while let Some(i) = current.filter(|i| !chain.contains(i)) {
+            chain.push(i);
+            current =
+                (models[i].1.parent.as_ref()).and_then(|x| find_module(models, &x.coordinates));
+        }
+        let model = &models[module].1;
+        let mut properties: Vec<(String, String)> = chain
+            .iter()
+            .flat_map(|i| models[*i].1.properties.iter().cloned())
+            .collect();
+        let parent = model.parent.as_ref().map(|x| &x.coordinates);
+        let builtins = [
+            ("project.groupId", model.coordinates.group_id.as_ref()),
+            ("project.artifactId", model.coordinates.artifact_id.as_ref()),
+            ("project.version", model.coordinates.version.as_ref()),
+            (
+                "project.parent.groupId",
+                parent.and_then(|x| x.group_id.as_ref()),
+            ),
+            (
+                "project.parent.version",
+                parent.and_then(|x| x.version.as_ref()),
+            ),
+        ];
+        for (k, v) in builtins {
+            if let Some(v) = v {
+                properties.push((k.to_owned(), v.clone()));
+            }
+        }
+        let mut inherited = Self {
+            properties,
+            managed: vec![],
+        };
+        for i in chain {
+            for managed in &models[i].1.dependency_management {
+                // the managed dependencies of a bill of materials of the same commit
+                let bom = (managed.scope.as_deref() == Some("import"))
+                    .then(|| find_module(models, &inherited.interpolate(managed).coordinates))
+                    .flatten();
+                match bom {
+                    Some(bom) => inherited
+                        .managed
+                        .extend(&models[bom].1.dependency_management),
+                    None => inherited.managed.push(managed),
+                }
+            }
+        }
+        inherited
+    }
+
+    fn interpolate(&self, dependency: &Dependency) -> Dependency {
+        let resolve = |x: &Option<String>| {
+            x.clone()
+                .map(|x| crate::maven::interpolate(x, &self.properties))
+        };
+        let coordinates = &dependency.coordinates;
+        Dependency {
+            coordinates: Coordinates {
+                group_id: resolve(&coordinates.group_id),
+                artifact_id: resolve(&coordinates.artifact_id),
+                version: resolve(&coordinates.version),
+            },
+            classifier: resolve(&dependency.classifier),
+            ..dependency.clone()
+        }
+    }
+
+    /// Substitutes the properties,
+    /// then takes the version and scope from the managed dependency when they are not declared.
+    fn resolve(&self, dependency: &Dependency) -> Dependency {
+        let mut dependency = self.interpolate(dependency);
+        let key = management_key(&dependency);
+        let managed = (self.managed.iter())
+            .map(|x| self.interpolate(x))
+            .find(|x| management_key(x) == key);
+        if let Some(managed) = managed {
+            let version = &mut dependency.coordinates.version;
+            *version = version.take().or(managed.coordinates.version);
+            dependency.scope = dependency.scope.or(managed.scope);
+        }
+        dependency
+    }
+}
+
+/// Managed dependencies are identified by groupId, artifactId, type and classifier
+fn management_key(
+    dependency: &Dependency,
+) -> (&Option<String>, &Option<String>, &str, &Option<String>) {
+    (
+        &dependency.coordinates.group_id,
+        &dependency.coordinates.artifact_id,
+        dependency.kind.as_deref().unwrap_or("jar"),
+        &dependency.classifier,
+    )
+}
+
+/// A dependency whose version changed between two commits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upgrade {
+    pub from: Coordinates,
+    pub before: Coordinates,
+    pub after: Coordinates,
+}
+
+/// The dependencies added, removed and upgraded between two commits,
+/// matched by the groupId and artifactId of the declaring module,
+/// and by the groupId, artifactId, type, classifier and scope of the dependency.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyChanges {
+    pub added: Vec<Edge>,
+    pub removed: Vec<Edge>,
+    /// also contains downgrades, versions are not ordered
+    pub upgraded: Vec<Upgrade>,
+}
+
+type EdgeKey = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    String,
+    Option<String>,
+    Option<String>,
+);
+
+fn edge_key(edge: &Edge) -> EdgeKey {
+    (
+        edge.from.group_id.clone(),
+        edge.from.artifact_id.clone(),
+        edge.to.group_id.clone(),
+        edge.to.artifact_id.clone(),
+        edge.kind.clone().unwrap_or_else(|| "jar".to_owned()),
+        edge.classifier.clone(),
+        edge.scope.clone(),
+    )
+}
+
+impl DependencyChanges {
+    pub fn compute(before: &DependencyGraph, after: &DependencyGraph) -> Self {
+        let index = |g: &DependencyGraph| -> BTreeMap<EdgeKey, Edge> {
+            g.edges.iter().map(|x| (edge_key(x), x.clone())).collect()
+        };
+        let mut before = index(before);
+        let mut changes = Self::default();
+        for (key, edge) in index(after) {
+            match before.remove(&key) {
+                None => changes.added.push(edge),
+                Some(old) if old.to.version != edge.to.version => changes.upgraded.push(Upgrade {
+                    from: edge.from,
+                    before: old.to,
+                    after: edge.to,
+                }),
+                Some(_) => (),
+            }
+        }
+        changes.removed = before.into_values().collect();
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.upgraded.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maven::Parent;
+
+    fn coordinates(g: &str, a: &str, v: &str) -> Coordinates {
+        Coordinates {
+            group_id: Some(g.to_owned()),
+            artifact_id: Some(a.to_owned()),
+            version: Some(v.to_owned()),
+        }
+    }
+
+    fn edge(to: Coordinates) -> Edge {
+        Edge {
+            from: coordinates("org.example", "core", "1.0"),
+            to,
+            kind: None,
+            classifier: None,
+            scope: None,
+            optional: false,
+            module: None,
+        }
+    }
+
+    #[test]
+    fn test_dependency_changes() {
+        let before = DependencyGraph {
+            modules: vec![],
+            edges: vec![
+                edge(coordinates("com.google.guava", "guava", "31.0")),
+                edge(coordinates("junit", "junit", "4.13")),
+                edge(coordinates("org.slf4j", "slf4j-api", "2.0.0")),
+            ],
+        };
+        let after = DependencyGraph {
+            modules: vec![],
+            edges: vec![
+                edge(coordinates("com.google.guava", "guava", "32.1")),
+                edge(coordinates("org.junit.jupiter", "junit-jupiter", "5.10.0")),
+                edge(coordinates("org.slf4j", "slf4j-api", "2.0.0")),
+            ],
+        };
+        let changes = DependencyChanges::compute(&before, &after);
+        assert_eq!(
+            changes.added,
+            [edge(coordinates(
+                "org.junit.jupiter",
+                "junit-jupiter",
+                "5.10.0"
+            ))]
+        );
+        assert_eq!(
+            changes.removed,
+            [edge(coordinates("junit", "junit", "4.13"))]
+        );
+        assert_eq!(
+            changes.upgraded,
+            [Upgrade {
+                from: coordinates("org.example", "core", "1.0"),
+                before: coordinates("com.google.guava", "guava", "31.0"),
+                after: coordinates("com.google.guava", "guava", "32.1"),
+            }]
+        );
+        assert!(DependencyChanges::compute(&after, &after).is_empty());
+
+        // the tests of a module are a distinct artifact
+        let tests = Edge {
+            kind: Some("test-jar".to_owned()),
+            classifier: Some("tests".to_owned()),
+            scope: Some("test".to_owned()),
+            ..edge(coordinates("org.slf4j", "slf4j-api", "2.0.0"))
+        };
+        let mut with_tests = after.clone();
+        with_tests.edges.push(tests.clone());
+        let changes = DependencyChanges::compute(&after, &with_tests);
+        assert_eq!(changes.added, [tests]);
+        assert!(changes.removed.is_empty() && changes.upgraded.is_empty());
+    }
+
+    fn dependency(g: &str, a: &str, v: Option<&str>) -> Dependency {
+        Dependency {
+            coordinates: Coordinates {
+                group_id: Some(g.to_owned()),
+                artifact_id: Some(a.to_owned()),
+                version: v.map(|x| x.to_owned()),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn property(k: &str, v: &str) -> (String, String) {
+        (k.to_owned(), v.to_owned())
+    }
+
+    #[test]
+    fn test_inherited_versions() {
+        let root = PomModel {
+            coordinates: coordinates("org.example", "parent", "1.0"),
+            properties: vec![property("guava.version", "31.0")],
+            dependency_management: vec![
+                dependency("com.google.guava", "guava", Some("${guava.version}")),
+                Dependency {
+                    scope: Some("test".to_owned()),
+                    ..dependency("junit", "junit", Some("4.13"))
+                },
+                Dependency {
+                    kind: Some("pom".to_owned()),
+                    scope: Some("import".to_owned()),
+                    ..dependency("org.example", "bom", Some("1.0"))
+                },
+            ],
+            ..Default::default()
+        };
+        let bom = PomModel {
+            coordinates: coordinates("org.example", "bom", "1.0"),
+            dependency_management: vec![dependency("org.slf4j", "slf4j-api", Some("2.0.0"))],
+            ..Default::default()
+        };
+        let parent = |x: &PomModel| {
+            Some(Parent {
+                coordinates: x.coordinates.clone(),
+                relative_path: None,
+            })
+        };
+        let core = PomModel {
+            coordinates: coordinates("org.example", "core", "1.0"),
+            parent: parent(&root),
+            // overrides the property of the parent
+            properties: vec![property("guava.version", "32.1")],
+            dependencies: vec![
+                dependency("com.google.guava", "guava", None),
+                dependency("junit", "junit", None),
+                dependency("org.slf4j", "slf4j-api", None),
+                dependency("org.example", "api", Some("${project.version}")),
+            ],
+            ..Default::default()
+        };
+        let api = PomModel {
+            coordinates: coordinates("org.example", "api", "1.0"),
+            parent: parent(&root),
+            dependencies: vec![dependency("com.google.guava", "guava", None)],
+            ..Default::default()
+        };
+        let models = [
+            ("".to_owned(), root),
+            ("bom".to_owned(), bom),
+            ("core".to_owned(), core),
+            ("api".to_owned(), api),
+        ];
+        let graph = DependencyGraph::from_models(&models);
+        let from = |x: &str| coordinates("org.example", x, "1.0");
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|x| (x.from.clone(), x.to.clone(), x.scope.as_deref(), x.module))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                (
+                    from("core"),
+                    coordinates("com.google.guava", "guava", "32.1"),
+                    None,
+                    None
+                ),
+                (
+                    from("core"),
+                    coordinates("junit", "junit", "4.13"),
+                    Some("test"),
+                    None
+                ),
+                (
+                    from("core"),
+                    coordinates("org.slf4j", "slf4j-api", "2.0.0"),
+                    None,
+                    None
+                ),
+                (from("core"), from("api"), None, Some(3)),
+                (
+                    from("api"),
+                    coordinates("com.google.guava", "guava", "31.0"),
+                    None,
+                    None
+                ),
+            ]
+        );
+    }
+}
@@ -276,8 +276,7 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool>
                 .unwrap();
             }
         }
-        // check if module or src/main/java or src/test/java
-        // TODO use maven pom.xml to find source_dir  and tests_dir ie. ignore resources, maybe also tests
+        // check if module or source directories declared in the pom.xml (by default src/main/java or src/test/java)
         // TODO maybe at some point try to handle maven modules and source dirs that reference parent directory in their path
 
         // TODO check it we can use more info from context and prepare analysis more specifically
@@ -421,6 +420,9 @@ pub(crate) fn make(mut acc: MavenModuleAcc, stores: &mut SimpleStores) -> (NodeI
     if !acc.status.is_empty() {
         dyn_builder.add(compo::Flags(acc.status));
     }
+    if let Some(pom) = acc.pom {
+        dyn_builder.add(pom);
+    }
     let metrics = primary.persist(&mut dyn_builder, interned_kind, label_id);
     let metrics = metrics.map_hashs(|h| h.build());
     let hashs = metrics.add_md_metrics(&mut dyn_builder, children_is_empty);
@@ -651,6 +653,14 @@ impl crate::processing::erased::CommitProc for PomProc {
     fn get_commit(&self, _commit_oid: git2::Oid) -> Option<&crate::Commit> {
         unimplemented!("required for processing at the root of a project")
     }
+
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        unimplemented!("required for processing at the root of a project")
+    }
+
+    fn restore_commit(&mut self, _commit_oid: git2::Oid, _commit: crate::Commit) {
+        unimplemented!("required for processing at the root of a project")
+    }
 }
 
 impl crate::processing::erased::CommitProcExt for PomProc {
@@ -781,6 +791,14 @@ impl crate::processing::erased::CommitProc for MavenProc {
         self.commits.get(&commit_oid)
     }
 
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        Box::new(self.commits.iter())
+    }
+
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+
     fn get_lang_handle(&self, lang: &str) -> Option<ParametrizedCommitProcessorHandle> {
         if lang.eq_ignore_ascii_case("java") {
             Some(ParametrizedCommitProcessorHandle(
@@ -1,11 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use hyperast::store::nodes::DefaultNodeIdentifier as NodeIdentifier;
 
 use crate::processing::ConfiguredRepo2;
 use crate::{
     Commit, SimpleStores,
-    git::Repo,
+    git::{DagBounds, Repo, RevRange},
     maven::MavenModuleAcc,
     maven_processor::make,
     preprocessed::RepositoryProcessor,
@@ -22,9 +22,37 @@ pub struct PreProcessedRepositories {
     // pub commits: HashMap<RepoConfig, HashMap<git2::Oid, Commit>>,
     pub processor: RepositoryProcessor,
     // pub processing_ordered_commits: HashMap<String,Vec<git2::Oid>>,
-    configs: HashMap<Repo, ParametrizedCommitProcessorHandle>,
+    pub(crate) configs: HashMap<Repo, ParametrizedCommitProcessorHandle>,
+    /// position of the last checkpoint or log segment, see [`crate::persist`]
+    pub(crate) persisted: Option<hyperast::store::persist::Checkpoint>,
+    /// commits of each repository already in the checkpoint or log
+    pub(crate) persisted_commits: HashMap<Repo, HashSet<git2::Oid>>,
+    /// where processed commits are persisted, see [`PreProcessedRepositories::persist_in`]
+    pub(crate) log_writer: Option<crate::persist::LogWriter>,
 }
 
+/// Why a repository configuration could not be registered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// the features processing this configuration are not enabled in this build
+    Disabled(RepoConfig),
+    /// the processors of this configuration do not take the given parameter
+    Unsupported(RepoConfig, &'static str),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Disabled(config) => write!(f, "{config:?} is not enabled in this build"),
+            Self::Unsupported(config, parameter) => {
+                write!(f, "{config:?} does not support a {parameter}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
 // #[derive(Default)]
 // pub struct CommitsPerSys {
 //     pub maven: HashMap<git2::Oid, Commit>,
@@ -119,9 +147,15 @@ impl PreProcessedRepositories {
         proc.get_commit(*commit_oid)
     }
 
-    pub fn register_config(&mut self, repo: Repo, config: RepoConfig) -> ConfiguredRepoHandle2 {
+    /// Registering a repository created with [`Repo::on_disk`] processes it in place,
+    /// ie. the handles given by [`Self::get_config`] open it without any remote operation.
+    pub fn register_config(
+        &mut self,
+        repo: Repo,
+        config: RepoConfig,
+    ) -> Result<ConfiguredRepoHandle2, ConfigError> {
         use crate::processing::erased::Parametrized;
-        let r = match config {
+        let mut r = match config {
             RepoConfig::JavaMaven => {
                 let processor_map = &mut self.processor.processing_systems;
                 let t = crate::java_processor::Parameter::faster();
@@ -139,6 +173,18 @@ impl PreProcessedRepositories {
                 });
                 ConfiguredRepoHandle2 { spec: repo, config }
             }
+            #[cfg(all(feature = "gradle", feature = "java"))]
+            RepoConfig::JavaGradle => {
+                let processor_map = &mut self.processor.processing_systems;
+                let t = crate::java_processor::Parameter::faster();
+                use crate::java_processor::JavaProcessorHolder;
+                let h_java = processor_map.mut_or_default::<JavaProcessorHolder>();
+                let java_handle = CommitProcExt::register_param(h_java, t);
+                use crate::gradle_processor::GradleProcessorHolder;
+                let h = processor_map.mut_or_default::<GradleProcessorHolder>();
+                let config = h.register_param(crate::gradle_processor::Parameter { java_handle });
+                ConfiguredRepoHandle2 { spec: repo, config }
+            }
             RepoConfig::CppMake => {
                 let q: &[&str] = &["(translation_unit)"];
                 let t = crate::cpp_processor::Parameter {
@@ -149,18 +195,149 @@ impl PreProcessedRepositories {
                     .processing_systems
                     .mut_or_default::<crate::cpp_processor::CppProcessorHolder>();
                 let cpp_handle = crate::processing::erased::CommitProcExt::register_param(h_cpp, t);
+                let h_makefile =
+                    self.processor
+                        .processing_systems
+                        .mut_or_default::<crate::make_processor::MakefileProcessorHolder>();
+                let makefile_handle = crate::processing::erased::CommitProcExt::register_param(
+                    h_makefile,
+                    crate::make_processor::MakefileParameter {},
+                );
                 let h = self
                     .processor
                     .processing_systems
                     .mut_or_default::<crate::make_processor::MakeProcessorHolder>();
-                let config = h.register_param(crate::make_processor::Parameter { cpp_handle });
+                let config = h.register_param(crate::make_processor::Parameter {
+                    makefile_handle,
+                    cpp_handle: Some(cpp_handle),
+                    #[cfg(feature = "c")]
+                    c_handle: None,
+                });
+                ConfiguredRepoHandle2 { spec: repo, config }
+            }
+            #[cfg(feature = "c")]
+            RepoConfig::CMake => {
+                let t = crate::c_processor::Parameter {};
+                let h_c = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::c_processor::CProcessorHolder>();
+                let c_handle = crate::processing::erased::CommitProcExt::register_param(h_c, t);
+                let h_makefile =
+                    self.processor
+                        .processing_systems
+                        .mut_or_default::<crate::make_processor::MakefileProcessorHolder>();
+                let makefile_handle = crate::processing::erased::CommitProcExt::register_param(
+                    h_makefile,
+                    crate::make_processor::MakefileParameter {},
+                );
+                let h = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::make_processor::MakeProcessorHolder>();
+                let config = h.register_param(crate::make_processor::Parameter {
+                    makefile_handle,
+                    cpp_handle: None,
+                    c_handle: Some(c_handle),
+                });
+                ConfiguredRepoHandle2 { spec: repo, config }
+            }
+            #[cfg(all(feature = "npm", feature = "ts"))]
+            RepoConfig::TsNpm => {
+                let t = crate::ts_processor::Parameter {};
+                let h_ts = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::ts_processor::TsProcessorHolder>();
+                let ts_handle = crate::processing::erased::CommitProcExt::register_param(h_ts, t);
+                let h = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::npm_processor::NpmProcessorHolder>();
+                let config = h.register_param(crate::npm_processor::Parameter { ts_handle });
+                ConfiguredRepoHandle2 { spec: repo, config }
+            }
+            #[cfg(feature = "python")]
+            RepoConfig::PythonPip => {
+                let t = crate::python_processor::Parameter {};
+                let h = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::python_processor::PythonProcessorHolder>();
+                let config = h.register_param(t);
+                ConfiguredRepoHandle2 { spec: repo, config }
+            }
+            #[cfg(all(feature = "cargo", feature = "rust"))]
+            RepoConfig::RustCargo => {
+                let t = crate::rust_processor::Parameter {};
+                let h_rust = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::rust_processor::RustProcessorHolder>();
+                let rust_handle = crate::processing::erased::CommitProcExt::register_param(h_rust, t);
+                let h = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::cargo_processor::CargoProcessorHolder>();
+                let config = h.register_param(crate::cargo_processor::Parameter { rust_handle });
                 ConfiguredRepoHandle2 { spec: repo, config }
             }
-            _ => todo!(),
+            #[cfg(feature = "any")]
+            RepoConfig::Any => {
+                let processor_map = &mut self.processor.processing_systems;
+                use crate::java_processor::JavaProcessorHolder;
+                let h_java = processor_map.mut_or_default::<JavaProcessorHolder>();
+                let t = crate::java_processor::Parameter::faster();
+                let java_handle = CommitProcExt::register_param(h_java, t);
+                let q: &[&str] = &["(translation_unit)"];
+                let t = crate::cpp_processor::Parameter {
+                    query: Some(q.into()),
+                };
+                let h_cpp =
+                    processor_map.mut_or_default::<crate::cpp_processor::CppProcessorHolder>();
+                let cpp_handle = CommitProcExt::register_param(h_cpp, t);
+                #[cfg(feature = "c")]
+                let h_c = processor_map.mut_or_default::<crate::c_processor::CProcessorHolder>();
+                #[cfg(feature = "c")]
+                let c_handle = CommitProcExt::register_param(h_c, crate::c_processor::Parameter {});
+                #[cfg(feature = "ts")]
+                let h_ts = processor_map.mut_or_default::<crate::ts_processor::TsProcessorHolder>();
+                #[cfg(feature = "ts")]
+                let ts_handle =
+                    CommitProcExt::register_param(h_ts, crate::ts_processor::Parameter {});
+                #[cfg(feature = "python")]
+                let h_python = processor_map
+                    .mut_or_default::<crate::python_processor::PythonProcessorHolder>();
+                #[cfg(feature = "python")]
+                let python_handle =
+                    CommitProcExt::register_param(h_python, crate::python_processor::Parameter {});
+                #[cfg(feature = "rust")]
+                let h_rust =
+                    processor_map.mut_or_default::<crate::rust_processor::RustProcessorHolder>();
+                #[cfg(feature = "rust")]
+                let rust_handle =
+                    CommitProcExt::register_param(h_rust, crate::rust_processor::Parameter {});
+                let h = processor_map.mut_or_default::<crate::any_processor::AnyProcessorHolder>();
+                let config = h.register_param(crate::any_processor::Parameter {
+                    java_handle,
+                    cpp_handle,
+                    #[cfg(feature = "c")]
+                    c_handle,
+                    #[cfg(feature = "ts")]
+                    ts_handle,
+                    #[cfg(feature = "python")]
+                    python_handle,
+                    #[cfg(feature = "rust")]
+                    rust_handle,
+                });
+                ConfiguredRepoHandle2 { spec: repo, config }
+            }
+            #[allow(unreachable_patterns)]
+            _ => return Err(ConfigError::Disabled(config)),
         };
 
-        self.configs.insert(r.spec.clone(), r.config);
-        r
+        self.insert_config(&mut r);
+        Ok(r)
     }
 
     pub fn register_config_with_prepro(
@@ -168,9 +345,9 @@ impl PreProcessedRepositories {
         repo: Repo,
         config: RepoConfig,
         prepro: std::sync::Arc<str>,
-    ) -> ConfiguredRepoHandle2 {
+    ) -> Result<ConfiguredRepoHandle2, ConfigError> {
         use crate::processing::erased::Parametrized;
-        let r = match config {
+        let mut r = match config {
             RepoConfig::JavaMaven => {
                 let processor_map = &mut self.processor.processing_systems;
                 use crate::java_processor::JavaProcessorHolder;
@@ -197,6 +374,22 @@ impl PreProcessedRepositories {
                     }),
                 }
             }
+            #[cfg(all(feature = "gradle", feature = "java"))]
+            RepoConfig::JavaGradle => {
+                let processor_map = &mut self.processor.processing_systems;
+                use crate::java_processor::JavaProcessorHolder;
+                let h_java = processor_map.mut_or_default::<JavaProcessorHolder>();
+                let t = crate::java_processor::Parameter {
+                    prepro: Some(prepro),
+                    query: None,
+                    tsg: None,
+                };
+                let java_handle = CommitProcExt::register_param(h_java, t);
+                use crate::gradle_processor::GradleProcessorHolder;
+                let h = processor_map.mut_or_default::<GradleProcessorHolder>();
+                let config = h.register_param(crate::gradle_processor::Parameter { java_handle });
+                ConfiguredRepoHandle2 { spec: repo, config }
+            }
             RepoConfig::CppMake => {
                 let t = crate::cpp_processor::Parameter { query: None };
                 let h_cpp = self
@@ -204,17 +397,30 @@ impl PreProcessedRepositories {
                     .processing_systems
                     .mut_or_default::<crate::cpp_processor::CppProcessorHolder>();
                 let cpp_handle = crate::processing::erased::CommitProcExt::register_param(h_cpp, t);
+                let h_makefile =
+                    self.processor
+                        .processing_systems
+                        .mut_or_default::<crate::make_processor::MakefileProcessorHolder>();
+                let makefile_handle = crate::processing::erased::CommitProcExt::register_param(
+                    h_makefile,
+                    crate::make_processor::MakefileParameter {},
+                );
                 let h = self
                     .processor
                     .processing_systems
                     .mut_or_default::<crate::make_processor::MakeProcessorHolder>();
-                let config = h.register_param(crate::make_processor::Parameter { cpp_handle });
+                let config = h.register_param(crate::make_processor::Parameter {
+                    makefile_handle,
+                    cpp_handle: Some(cpp_handle),
+                    #[cfg(feature = "c")]
+                    c_handle: None,
+                });
                 ConfiguredRepoHandle2 { spec: repo, config }
             }
-            _ => todo!(),
+            _ => return Err(ConfigError::Unsupported(config, "preprocessing script")),
         };
-        self.configs.insert(r.spec.clone(), r.config);
-        r
+        self.insert_config(&mut r);
+        Ok(r)
     }
 
     pub fn register_config_with_prequeries(
@@ -222,9 +428,9 @@ impl PreProcessedRepositories {
         repo: Repo,
         config: RepoConfig,
         query: &[&str],
-    ) -> ConfiguredRepoHandle2 {
+    ) -> Result<ConfiguredRepoHandle2, ConfigError> {
         use crate::processing::erased::Parametrized;
-        let r = match config {
+        let mut r = match config {
             RepoConfig::JavaMaven => {
                 let processor_map = &mut self.processor.processing_systems;
                 use crate::java_processor::JavaProcessorHolder;
@@ -251,6 +457,22 @@ impl PreProcessedRepositories {
                     }),
                 }
             }
+            #[cfg(all(feature = "gradle", feature = "java"))]
+            RepoConfig::JavaGradle => {
+                let processor_map = &mut self.processor.processing_systems;
+                use crate::java_processor::JavaProcessorHolder;
+                let h_java = processor_map.mut_or_default::<JavaProcessorHolder>();
+                let t = crate::java_processor::Parameter {
+                    prepro: None,
+                    query: Some(query.into()),
+                    tsg: None,
+                };
+                let java_handle = CommitProcExt::register_param(h_java, t);
+                use crate::gradle_processor::GradleProcessorHolder;
+                let h = processor_map.mut_or_default::<GradleProcessorHolder>();
+                let config = h.register_param(crate::gradle_processor::Parameter { java_handle });
+                ConfiguredRepoHandle2 { spec: repo, config }
+            }
             RepoConfig::CppMake => {
                 let t = crate::cpp_processor::Parameter {
                     query: Some(query.into()),
@@ -260,17 +482,30 @@ impl PreProcessedRepositories {
                     .processing_systems
                     .mut_or_default::<crate::cpp_processor::CppProcessorHolder>();
                 let cpp_handle = crate::processing::erased::CommitProcExt::register_param(h_cpp, t);
+                let h_makefile =
+                    self.processor
+                        .processing_systems
+                        .mut_or_default::<crate::make_processor::MakefileProcessorHolder>();
+                let makefile_handle = crate::processing::erased::CommitProcExt::register_param(
+                    h_makefile,
+                    crate::make_processor::MakefileParameter {},
+                );
                 let h = self
                     .processor
                     .processing_systems
                     .mut_or_default::<crate::make_processor::MakeProcessorHolder>();
-                let config = h.register_param(crate::make_processor::Parameter { cpp_handle });
+                let config = h.register_param(crate::make_processor::Parameter {
+                    makefile_handle,
+                    cpp_handle: Some(cpp_handle),
+                    #[cfg(feature = "c")]
+                    c_handle: None,
+                });
                 ConfiguredRepoHandle2 { spec: repo, config }
             }
-            _ => todo!(),
+            _ => return Err(ConfigError::Unsupported(config, "precomputed query")),
         };
-        self.configs.insert(r.spec.clone(), r.config);
-        r
+        self.insert_config(&mut r);
+        Ok(r)
     }
 
     pub fn register_config_with_tsg(
@@ -278,9 +513,9 @@ impl PreProcessedRepositories {
         repo: Repo,
         config: RepoConfig,
         tsg: std::sync::Arc<str>,
-    ) -> ConfiguredRepoHandle2 {
+    ) -> Result<ConfiguredRepoHandle2, ConfigError> {
         use crate::processing::erased::Parametrized;
-        let r = match config {
+        let mut r = match config {
             RepoConfig::JavaMaven => {
                 let processor_map = &mut self.processor.processing_systems;
                 use crate::java_processor::JavaProcessorHolder;
@@ -305,19 +540,57 @@ impl PreProcessedRepositories {
                 });
                 ConfiguredRepoHandle2 { spec: repo, config }
             }
-            RepoConfig::CppMake => {
-                unimplemented!()
+            #[cfg(all(feature = "gradle", feature = "java"))]
+            RepoConfig::JavaGradle => {
+                let processor_map = &mut self.processor.processing_systems;
+                use crate::java_processor::JavaProcessorHolder;
+                let h_java = processor_map.mut_or_default::<JavaProcessorHolder>();
+                let t = crate::java_processor::Parameter {
+                    prepro: None,
+                    query: None,
+                    tsg: Some(tsg),
+                };
+                let java_handle = CommitProcExt::register_param(h_java, t);
+                use crate::gradle_processor::GradleProcessorHolder;
+                let h = processor_map.mut_or_default::<GradleProcessorHolder>();
+                let config = h.register_param(crate::gradle_processor::Parameter { java_handle });
+                ConfiguredRepoHandle2 { spec: repo, config }
             }
-            _ => todo!(),
+            _ => return Err(ConfigError::Unsupported(config, "tsg")),
         };
+        self.insert_config(&mut r);
+        Ok(r)
+    }
+
+    /// A spec without path, as given by routes, keeps the path of the repository on disk
+    /// registered with the same forge, user and name, and `r` is updated accordingly.
+    fn insert_config(&mut self, r: &mut ConfiguredRepoHandle2) {
+        if let Some(registered) = self.get_config(r.spec.clone()) {
+            r.spec = registered.spec;
+        }
         self.configs.insert(r.spec.clone(), r.config);
-        r
     }
 
+    /// A `repo` without path, as given by routes, also retrieves the repository on disk
+    /// registered with the same forge, user and name, unless there are several of them.
     pub fn get_config(&self, repo: Repo) -> Option<ConfiguredRepoHandle2> {
-        self.configs
-            .get(&repo)
-            .map(|&config| ConfiguredRepoHandle2 { config, spec: repo })
+        let (spec, &config) = self.configs.get_key_value(&repo).or_else(|| {
+            if repo.path().is_some() {
+                return None;
+            }
+            let mut on_disk = self.configs.iter().filter(|(spec, _)| {
+                spec.path().is_some()
+                    && spec.forge() == repo.forge()
+                    && spec.user() == repo.user()
+                    && spec.name() == repo.name()
+            });
+            let found = on_disk.next();
+            on_disk.next().is_none().then_some(found).flatten()
+        })?;
+        Some(ConfiguredRepoHandle2 {
+            config,
+            spec: spec.clone(),
+        })
     }
 
     pub fn get_precomp_query(
@@ -349,7 +622,9 @@ impl PreProcessedRepositories {
         repository: &ConfiguredRepo2,
         size: usize,
     ) -> Vec<git2::Oid> {
-        self.processor.pre_pro(rw, repository, size)
+        let r = self.processor.pre_pro(rw, repository, size);
+        self.persist_processed();
+        r
     }
 
     pub fn pre_process_with_limit(
@@ -360,8 +635,11 @@ impl PreProcessedRepositories {
         // dir_path: &str,
         limit: usize,
     ) -> Result<Vec<git2::Oid>, git2::Error> {
-        self.processor
-            .pre_process_with_limit(repository, before, after, limit)
+        let r = self
+            .processor
+            .pre_process_with_limit(repository, before, after, limit);
+        self.persist_processed();
+        r
     }
 
     pub fn ensure_pre_processed_with_limit(
@@ -376,6 +654,27 @@ impl PreProcessedRepositories {
             .ensure_pre_processed_with_limit(repository, before, after, limit)
     }
 
+    pub fn pre_process_range(
+        &mut self,
+        repository: &ConfiguredRepo2,
+        range: &RevRange,
+        bounds: DagBounds,
+    ) -> Result<Vec<git2::Oid>, git2::Error> {
+        let r = self.processor.pre_process_range(repository, range, bounds);
+        self.persist_processed();
+        r
+    }
+
+    pub fn ensure_pre_processed_range(
+        &self,
+        repository: &ConfiguredRepo2,
+        range: &RevRange,
+        bounds: DagBounds,
+    ) -> Result<Result<Vec<git2::Oid>, Vec<git2::Oid>>, git2::Error> {
+        self.processor
+            .ensure_pre_processed_range(repository, range, bounds)
+    }
+
     // pub fn pre_process_with_config2(
     //     &mut self,
     //     repository: &mut ConfiguredRepo2,
@@ -0,0 +1,61 @@
+use hyperast::store::defaults::{LabelIdentifier, NodeIdentifier};
+use hyperast_gen_ts_ts::legion as ts_tree_gen;
+
+use crate::{Accumulator, BasicDirAcc, DefaultMetrics};
+
+/// Accumulates the content of an npm package,
+/// ie. a directory containing a `package.json`.
+pub struct NpmModuleAcc {
+    pub(crate) primary: BasicDirAcc<NodeIdentifier, LabelIdentifier, DefaultMetrics>,
+}
+
+impl From<String> for NpmModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl NpmModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            primary: BasicDirAcc::new(name),
+        }
+    }
+}
+
+impl NpmModuleAcc {
+    pub fn push_submodule(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: (NodeIdentifier, DefaultMetrics),
+    ) {
+        self.primary.push(name, full_node.0, full_node.1);
+    }
+    pub(crate) fn push_source_file(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: ts_tree_gen::Local,
+    ) {
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+    pub(crate) fn push_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: ts_tree_gen::Local,
+    ) {
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+}
+
+impl hyperast::tree_gen::Accumulator for NpmModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, DefaultMetrics));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        self.push_submodule(name, full_node);
+    }
+}
+
+impl Accumulator for NpmModuleAcc {
+    type Unlabeled = (NodeIdentifier, DefaultMetrics);
+}
@@ -0,0 +1,413 @@
+use crate::StackEle;
+use crate::processing::erased::CommitProcessorHandle;
+use crate::{DefaultMetrics, processing::file_sys};
+use crate::{
+    Processor,
+    git::BasicGitObject,
+    npm::NpmModuleAcc,
+    preprocessed::RepositoryProcessor,
+    processing::{
+        CacheHolding, InFiles, ObjectName, ParametrizedCommitProcessorHandle,
+        erased::ParametrizedCommitProc2,
+    },
+};
+use git2::{Oid, Repository};
+use hyperast::types::ETypeStore as _;
+use hyperast::{
+    hashed::{IndexingHashBuilder, MetaDataHashsBuilder},
+    store::{defaults::NodeIdentifier, nodes::legion::eq_node},
+    types::LabelStore,
+};
+use hyperast_gen_ts_ts::types::Type;
+use std::{
+    iter::Peekable,
+    path::{Components, PathBuf},
+};
+
+pub type SimpleStores = hyperast::store::SimpleStores<hyperast_gen_ts_ts::types::TStore>;
+
+pub struct NpmProcessor<'a, 'b, 'c, Acc> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<StackEle<Acc>>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: ParametrizedCommitProcessorHandle,
+}
+
+impl<'a, 'b, 'c, Acc: From<String>> NpmProcessor<'a, 'b, 'c, Acc> {
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+        handle: ParametrizedCommitProcessorHandle,
+    ) -> Self {
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![StackEle::new(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+        }
+    }
+}
+
+impl<'a, 'b, 'c> Processor<NpmModuleAcc> for NpmProcessor<'a, 'b, 'c, NpmModuleAcc> {
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                if file_sys::is_npm_ignored(&name) {
+                    return;
+                }
+                self.handle_tree_cached(name, oid);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                if file_sys::Ts::matches(&name) {
+                    let ts_handle = self.ts_handle();
+                    match self
+                        .prepro
+                        .handle_ts_blob(oid, &name, self.repository, ts_handle)
+                    {
+                        Ok((full_node,)) => {
+                            let name = self.prepro.intern_object_name(&name);
+                            let w = &mut self.stack.last_mut().unwrap().acc;
+                            assert!(!w.primary.children_names.contains(&name));
+                            w.push_source_file(name, full_node);
+                        }
+                        Err(e) => log::warn!("skipping {:?}: {:?}", name.try_str(), e),
+                    }
+                } else {
+                    // TODO parse the package.json, there is no json generator yet
+                    log::debug!("not ts source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: NpmModuleAcc) -> Option<(NodeIdentifier, DefaultMetrics)> {
+        let name = acc.primary.name.clone();
+        let full_node = make(acc, self.prepro.main_stores_mut().mut_with_ts());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .insert(oid, full_node.clone());
+
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            assert!(
+                !w.primary.children_names.contains(&name),
+                "{:?} {:?}",
+                w.primary.children_names,
+                name
+            );
+            w.push_submodule(name, full_node);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<StackEle<NpmModuleAcc>> {
+        &mut self.stack
+    }
+}
+
+impl<'a, 'b, 'c> NpmProcessor<'a, 'b, 'c, NpmModuleAcc> {
+    fn ts_handle(
+        &mut self,
+    ) -> crate::processing::erased::ParametrizedCommitProcessor2Handle<crate::ts_processor::TsProc>
+    {
+        self.prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>()
+            .with_parameters_mut(self.handle.1)
+            .parameter
+            .ts_handle
+    }
+
+    fn handle_tree_cached(&mut self, name: ObjectName, oid: Oid) {
+        if let Some(s) = self.dir_path.peek() {
+            if name
+                .as_bytes()
+                .eq(std::ffi::OsStr::as_encoded_bytes(s.as_os_str()))
+            {
+                self.dir_path.next();
+                self.stack.last_mut().expect("never empty").cs.clear();
+                let tree = self.repository.find_tree(oid).unwrap();
+                let prepared = prepare_dir_exploration(tree);
+                self.stack.push(StackEle::new(
+                    oid,
+                    prepared,
+                    NpmModuleAcc::new(name.try_into().unwrap()),
+                ));
+            }
+            return;
+        }
+        let npm_proc = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>()
+            .with_parameters_mut(self.handle.1);
+        let ts_handle = npm_proc.parameter.ts_handle;
+        if let Some(already) = npm_proc.get_caches_mut().object_map.get(&oid) {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            let name = self.prepro.intern_object_name(name);
+            assert!(!w.primary.children_names.contains(&name));
+            w.push_submodule(name, full_node);
+            return;
+        }
+        log::debug!("npm tree {:?}", name.try_str());
+        let tree = self.repository.find_tree(oid).unwrap();
+        let is_package = tree
+            .iter()
+            .any(|x| file_sys::PackageJson::matches(&x.name_bytes().into()));
+        if is_package {
+            // handle as npm package
+            let prepared = prepare_dir_exploration(tree);
+            self.stack.push(StackEle::new(
+                oid,
+                prepared,
+                NpmModuleAcc::new(name.try_into().unwrap()),
+            ));
+            return;
+        }
+        // TODO look for nested packages, eg. in the packages/ directory of monorepos
+        let (name, (full_node,)) = self.prepro.help_handle_ts_folder(
+            self.repository,
+            self.dir_path,
+            oid,
+            &name,
+            ts_handle,
+        );
+        let parent_acc = &mut self.stack.last_mut().unwrap().acc;
+        assert!(!parent_acc.primary.children_names.contains(&name));
+        parent_acc.push_source_directory(name, full_node);
+    }
+}
+
+pub(crate) fn make(
+    acc: NpmModuleAcc,
+    stores: &mut SimpleStores,
+) -> (NodeIdentifier, DefaultMetrics) {
+    let kind = Type::Directory;
+    let interned_kind = hyperast_gen_ts_ts::types::TStore::intern(kind);
+    let label_id = stores.label_store.get_or_insert(acc.primary.name.clone());
+
+    let primary = acc
+        .primary
+        .map_metrics(|m| m.finalize(&interned_kind, &label_id, 0));
+
+    let hashable = primary.metrics.hashs.most_discriminating();
+
+    let eq = eq_node(&interned_kind, Some(&label_id), &primary.children);
+
+    assert_eq!(primary.children_names.len(), primary.children.len());
+
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    if let Some(id) = insertion.occupied_id() {
+        let metrics = primary
+            .metrics
+            .map_hashs(|h| MetaDataHashsBuilder::build(h));
+        return (id, metrics);
+    }
+
+    log::info!(
+        "make npm package {} {}",
+        &primary.name,
+        primary.children.len()
+    );
+
+    let mut dyn_builder = hyperast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+
+    let children_is_empty = primary.children.is_empty();
+
+    let metrics = primary.persist(&mut dyn_builder, interned_kind, label_id);
+    let metrics = metrics.map_hashs(|h| h.build());
+    let hashs = metrics.add_md_metrics(&mut dyn_builder, children_is_empty);
+    hashs.persist(&mut dyn_builder);
+
+    let vacant = insertion.vacant();
+    let node_id = hyperast::store::nodes::legion::NodeStore::insert_built_after_prepare(
+        vacant,
+        dyn_builder.build(),
+    );
+
+    (node_id, metrics)
+}
+
+/// the package.json is not processed for now, so the order does not matter
+pub(crate) fn prepare_dir_exploration(tree: git2::Tree) -> Vec<BasicGitObject> {
+    tree.iter()
+        .rev()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect()
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub(crate) ts_handle:
+        crate::processing::erased::ParametrizedCommitProcessor2Handle<crate::ts_processor::TsProc>,
+}
+
+#[derive(Default)]
+pub(crate) struct NpmProcessorHolder(Option<NpmProc>);
+pub(crate) struct NpmProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Npm,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for NpmProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0;
+                // TODO enable multi configs for npm, do the same as the one for Java
+                self.0 = Some(NpmProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+struct PreparedNpmCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+    pub(crate) handle: ParametrizedCommitProcessorHandle,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedNpmCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedNpmCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyperast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = NpmProcessor::<NpmModuleAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+            self.handle,
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>();
+        let handle = self.handle;
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.1)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+
+impl crate::processing::erased::CommitProc for NpmProc {
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+        handle: crate::processing::ParametrizedCommitProcessorHandle,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedNpmCommitProc {
+            repository,
+            commit_builder,
+            handle,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        Box::new(self.commits.iter())
+    }
+
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+
+    fn get_lang_handle(&self, lang: &str) -> Option<ParametrizedCommitProcessorHandle> {
+        if lang.eq_ignore_ascii_case("ts") || lang.eq_ignore_ascii_case("typescript") {
+            Some(ParametrizedCommitProcessorHandle(
+                CommitProcessorHandle(
+                    std::any::TypeId::of::<crate::ts_processor::TsProcessorHolder>(),
+                ),
+                self.parameter.ts_handle.0,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for NpmProc {
+    type Holder = NpmProcessorHolder;
+}
+
+impl crate::processing::erased::ParametrizedCommitProc2 for NpmProcessorHolder {
+    type Proc = NpmProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+
+impl CacheHolding<crate::processing::caches::Npm> for NpmProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Npm {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Npm {
+        &self.cache
+    }
+}
+
+impl CacheHolding<crate::processing::caches::Npm> for NpmProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Npm {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Npm {
+        &self.0.as_ref().unwrap().cache
+    }
+}
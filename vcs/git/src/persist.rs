@@ -0,0 +1,520 @@
+//! Checkpoints of [`PreProcessedRepositories`] on disk.
+//!
+//! A checkpoint is made of a snapshot of the main stores (see [`hyperast::store::persist`]),
+//! a log of the nodes added since the snapshot,
+//! the table of processed commits of each registered repository,
+//! and a log of the commits processed since the table.
+//! Nodes keep their identifiers, so the `ast_root` of restored commits stay valid.
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+
+use hyperast::store::persist::{Checkpoint, Codecs, Persist};
+use hyperast::utils::Bytes;
+
+use crate::processing::erased::ParametrizedCommitProcessorHandle;
+use crate::{Commit, SimpleStores, git::Repo, multi_preprocessed::PreProcessedRepositories};
+
+pub const SNAPSHOT_FILE: &str = "store.bin";
+pub const LOG_FILE: &str = "store.log";
+pub const COMMITS_FILE: &str = "commits.bin";
+pub const COMMITS_LOG_FILE: &str = "commits.log";
+
+/// Codecs for all the components produced by the processors of this crate.
+pub fn codecs() -> Codecs {
+    let mut r = Codecs::default();
+    r.register::<hyperast_gen_ts_java::types::TType>("java_type")
+        .register::<hyperast_gen_ts_cpp::types::TType>("cpp_type")
+        .register::<hyperast_gen_ts_xml::types::TType>("xml_type")
+        .register::<hyperast::store::nodes::compo::Flags<enumset::EnumSet<crate::maven::SemFlag>>>(
+            "maven_flags",
+        )
+        .register::<crate::maven::PomModel>("maven_pom");
+    #[cfg(all(feature = "gradle", feature = "java"))]
+    r.register::<hyperast::store::nodes::compo::Flags<enumset::EnumSet<crate::gradle::SemFlag>>>(
+        "gradle_flags",
+    );
+    #[cfg(feature = "c")]
+    r.register::<hyperast_gen_ts_c::types::TType>("c_type");
+    #[cfg(feature = "ts")]
+    r.register::<hyperast_gen_ts_ts::types::TType>("ts_type");
+    #[cfg(feature = "python")]
+    r.register::<hyperast_gen_ts_python::types::TType>("python_type");
+    #[cfg(feature = "rust")]
+    r.register::<hyperast_gen_ts_rust::types::TType>("rust_type");
+    #[cfg(feature = "toml")]
+    r.register::<hyperast_gen_ts_toml::types::TType>("toml_type");
+    #[cfg(all(feature = "cargo", feature = "rust"))]
+    r.register::<hyperast::store::nodes::compo::Flags<enumset::EnumSet<crate::cargo::SemFlag>>>(
+        "cargo_flags",
+    );
+    #[cfg(feature = "any")]
+    r.register::<crate::any::TType>("any_type");
+    r
+}
+
+fn persist_oid(oid: &git2::Oid, w: &mut dyn Write) -> io::Result<()> {
+    w.write_all(oid.as_bytes())
+}
+
+fn restore_oid(r: &mut dyn Read) -> io::Result<git2::Oid> {
+    let mut b = [0; 20];
+    r.read_exact(&mut b)?;
+    git2::Oid::from_bytes(&b).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+impl Commit {
+    fn persist(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.parents.len().persist(w)?;
+        for p in &self.parents {
+            persist_oid(p, w)?;
+        }
+        self.processing_time.persist(w)?;
+        (self.memory_used.bytes() as i64).persist(w)?;
+        self.ast_root.persist(w)?;
+        persist_oid(&self.tree_oid, w)
+    }
+
+    fn restore(r: &mut dyn Read) -> io::Result<Self> {
+        let parents = (0..usize::restore(r)?)
+            .map(|_| restore_oid(r))
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            parents,
+            processing_time: u128::restore(r)?,
+            memory_used: Bytes::from(i64::restore(r)? as isize),
+            ast_root: Persist::restore(r)?,
+            tree_oid: restore_oid(r)?,
+        })
+    }
+}
+
+impl PreProcessedRepositories {
+    /// Writes a full snapshot of the main stores and the commits processed
+    /// for each registered repository in `dir`, the logs are emptied.
+    ///
+    /// The previous snapshot is only replaced once the new one is complete.
+    pub fn checkpoint(&mut self, dir: &Path) -> io::Result<()> {
+        // segments still being appended must not end up after the emptied log
+        self.flush_log();
+        // the logs are emptied, a failure below must not be followed by appends
+        self.persisted = None;
+        std::fs::create_dir_all(dir)?;
+        write_atomically(&dir.join(SNAPSHOT_FILE), |w| {
+            self.processor.main_stores.write_snapshot(&codecs(), w)
+        })?;
+        // segments remaining after a crash here are skipped on restore as they are already in the snapshot
+        File::create(dir.join(LOG_FILE))?;
+        write_atomically(&dir.join(COMMITS_FILE), |w| self.write_commits(w))?;
+        // records remaining after a crash here are restored twice, which is harmless
+        File::create(dir.join(COMMITS_LOG_FILE))?;
+        self.persisted = Some(self.processor.main_stores.checkpoint());
+        self.persisted_commits.clear();
+        self.write_new_commits(&mut io::sink())?;
+        Ok(())
+    }
+
+    /// Appends to the logs of `dir` the nodes added and the commits processed since the last checkpoint.
+    ///
+    /// Falls back to a full [`PreProcessedRepositories::checkpoint`] if there is none yet.
+    pub fn append_log(&mut self, dir: &Path) -> io::Result<()> {
+        match self.take_segment() {
+            Ok(Some(segment)) => segment.append_to(dir).inspect_err(|_| {
+                // the next append cannot follow a missing segment
+                self.persisted = None;
+            }),
+            Ok(None) => self.checkpoint(dir),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Serializes the nodes added and the commits processed since the last checkpoint or segment,
+    /// returns None if there is no checkpoint yet.
+    fn take_segment(&mut self) -> io::Result<Option<LogSegment>> {
+        let Some(since) = self.persisted else {
+            return Ok(None);
+        };
+        let mut segment = LogSegment::default();
+        if let Err(e) = self.write_segment(since, &mut segment) {
+            // part of the segment might be considered persisted
+            self.persisted = None;
+            return Err(e);
+        }
+        Ok(Some(segment))
+    }
+
+    fn write_segment(&mut self, since: Checkpoint, segment: &mut LogSegment) -> io::Result<()> {
+        if since != self.processor.main_stores.checkpoint() {
+            let next = self.processor.main_stores.write_log_segment(
+                &codecs(),
+                &since,
+                &mut segment.nodes,
+            )?;
+            self.persisted = Some(next);
+        }
+        self.write_new_commits(&mut segment.commits)
+    }
+
+    /// From now on, the nodes and commits added by each pre-processing are appended to the logs of `dir`
+    /// by a background thread, see [`PreProcessedRepositories::append_log`].
+    ///
+    /// Makes a checkpoint first if there is none yet, ie. if nothing was restored.
+    pub fn persist_in(&mut self, dir: PathBuf) -> io::Result<()> {
+        if self.persisted.is_none() {
+            self.checkpoint(&dir)?;
+        }
+        self.log_writer = Some(LogWriter::new(dir));
+        Ok(())
+    }
+
+    /// Waits for the segments of the pre-processings done so far to be appended to the logs.
+    pub fn flush_log(&self) {
+        if let Some(writer) = &self.log_writer {
+            writer.flush();
+        }
+    }
+
+    /// Called once a pre-processing is done, nothing is written if nothing was added.
+    ///
+    /// Only serializes the segment, it is appended by the background thread,
+    /// so that the disk is not waited for while the repositories are locked.
+    pub(crate) fn persist_processed(&mut self) {
+        let Some(writer) = &self.log_writer else {
+            return;
+        };
+        let dir = writer.dir.clone();
+        if writer.failed.load(Ordering::Acquire) {
+            // later segments cannot be replayed without the failed one
+            self.persisted = None;
+        }
+        match self.take_segment() {
+            Ok(Some(segment)) if segment.is_empty() => (),
+            Ok(Some(segment)) => {
+                if let Some(writer) = &self.log_writer {
+                    writer.append(segment)
+                }
+            }
+            Ok(None) => match self.checkpoint(&dir) {
+                Ok(()) => log::info!("checkpointed stores in {}", dir.display()),
+                Err(e) => log::error!("failed to checkpoint stores in {}: {}", dir.display(), e),
+            },
+            Err(e) => log::error!("failed to serialize a log segment: {}", e),
+        }
+    }
+
+    /// Restores a checkpoint written by [`PreProcessedRepositories::checkpoint`]
+    /// and the logs appended by [`PreProcessedRepositories::append_log`],
+    /// returns false if `dir` does not contain any.
+    ///
+    /// Repositories must be registered beforehand, commits of other repositories are skipped.
+    /// Must be done before anything is processed, the current stores are replaced.
+    /// Nothing is changed if an error is returned.
+    pub fn restore(&mut self, dir: &Path) -> io::Result<bool> {
+        let path = dir.join(SNAPSHOT_FILE);
+        if !path.exists() {
+            return Ok(false);
+        }
+        let log_path = dir.join(LOG_FILE);
+        let log = read_log(&log_path)?;
+        let mut r = BufReader::new(File::open(path)?);
+        let main_stores = SimpleStores::read_snapshot_with_log(&codecs(), &mut r, &log)?;
+        let mut commits = vec![];
+        let commits_path = dir.join(COMMITS_FILE);
+        if commits_path.exists() {
+            self.read_commits(&mut BufReader::new(File::open(commits_path)?), &mut commits)?;
+        }
+        let commits_log_path = dir.join(COMMITS_LOG_FILE);
+        let commits_log = read_log(&commits_log_path)?;
+        let commits_log_len = hyperast::store::persist::log_len(&commits_log);
+        let mut skipped = HashSet::new();
+        let mut records = &commits_log[..commits_log_len];
+        while !records.is_empty() {
+            let len = usize::restore(&mut records)?;
+            let (mut record, rest) = records.split_at(len);
+            records = rest;
+            let key = String::restore(&mut record)?;
+            let oid = restore_oid(&mut record)?;
+            let commit = Commit::restore(&mut record)?;
+            match self.config_of(&key)? {
+                Some(config) => commits.push((config, oid, commit)),
+                None => {
+                    skipped.insert(key);
+                }
+            }
+        }
+        for key in skipped {
+            log::warn!("skipping commits of unregistered repository {}", key);
+        }
+        // everything is read, the truncated ends of the logs can be dropped
+        truncate(&log_path, hyperast::store::persist::log_len(&log))?;
+        truncate(&commits_log_path, commits_log_len)?;
+        self.processor.main_stores = main_stores;
+        for (config, oid, commit) in commits {
+            self.processor
+                .processing_systems
+                .by_id_mut(&config.0)
+                .expect("checked while reading")
+                .get_mut(config.1)
+                .restore_commit(oid, commit);
+        }
+        self.persisted = Some(self.processor.main_stores.checkpoint());
+        self.persisted_commits.clear();
+        self.write_new_commits(&mut io::sink())?;
+        Ok(true)
+    }
+
+    /// The configuration of the registered repository identified by `key`, if any,
+    /// fails if it has no processor.
+    fn config_of(&self, key: &str) -> io::Result<Option<ParametrizedCommitProcessorHandle>> {
+        let Some((repo, config)) = self.configs.iter().find(|(repo, _)| repo_key(repo) == key)
+        else {
+            return Ok(None);
+        };
+        if self.processor.processing_systems.by_id(&config.0).is_none() {
+            return Err(missing_processor(repo));
+        }
+        Ok(Some(*config))
+    }
+
+    fn write_commits(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.configs.len().persist(w)?;
+        for (repo, config) in &self.configs {
+            repo_key(repo).persist(w)?;
+            let proc = self
+                .processor
+                .processing_systems
+                .by_id(&config.0)
+                .ok_or_else(|| missing_processor(repo))?
+                .get(config.1);
+            let commits: Vec<_> = proc.commits().collect();
+            commits.len().persist(w)?;
+            for (oid, commit) in commits {
+                persist_oid(oid, w)?;
+                commit.persist(w)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a record for each commit that is not persisted yet, and marks it as persisted.
+    ///
+    /// Records are prefixed by their length, like log segments.
+    fn write_new_commits(&mut self, w: &mut dyn Write) -> io::Result<()> {
+        for (repo, config) in &self.configs {
+            let proc = self
+                .processor
+                .processing_systems
+                .by_id(&config.0)
+                .ok_or_else(|| missing_processor(repo))?
+                .get(config.1);
+            let persisted = self.persisted_commits.entry(repo.clone()).or_default();
+            for (oid, commit) in proc.commits() {
+                if persisted.contains(oid) {
+                    continue;
+                }
+                let mut record = vec![];
+                repo_key(repo).persist(&mut record)?;
+                persist_oid(oid, &mut record)?;
+                commit.persist(&mut record)?;
+                record.len().persist(w)?;
+                w.write_all(&record)?;
+                persisted.insert(*oid);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_commits(
+        &self,
+        r: &mut dyn Read,
+        commits: &mut Vec<(ParametrizedCommitProcessorHandle, git2::Oid, Commit)>,
+    ) -> io::Result<()> {
+        for _ in 0..usize::restore(r)? {
+            let key = String::restore(r)?;
+            let config = self.config_of(&key)?;
+            if config.is_none() {
+                log::warn!("skipping commits of unregistered repository {}", key);
+            }
+            for _ in 0..usize::restore(r)? {
+                let oid = restore_oid(r)?;
+                let commit = Commit::restore(r)?;
+                if let Some(config) = config {
+                    commits.push((config, oid, commit));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The nodes added and the commits processed since the previous segment, serialized.
+#[derive(Default)]
+struct LogSegment {
+    nodes: Vec<u8>,
+    commits: Vec<u8>,
+}
+
+impl LogSegment {
+    fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.commits.is_empty()
+    }
+
+    /// Segments and records are length prefixed, so consecutive segments can be appended at once.
+    fn extend(&mut self, other: LogSegment) {
+        self.nodes.extend(other.nodes);
+        self.commits.extend(other.commits);
+    }
+
+    fn append_to(&self, dir: &Path) -> io::Result<()> {
+        append(&dir.join(LOG_FILE), &self.nodes)?;
+        // only written once the nodes they refer to are persisted
+        append(&dir.join(COMMITS_LOG_FILE), &self.commits)
+    }
+}
+
+enum LogMessage {
+    Append(LogSegment),
+    /// answered once the previous segments are appended
+    Flush(mpsc::Sender<()>),
+}
+
+/// Appends log segments to a store directory from a background thread.
+///
+/// Segments waiting while the disk is busy are appended together.
+/// Once an append fails, the next segments are dropped until a flush,
+/// [`PreProcessedRepositories::persist_processed`] then falls back to a full checkpoint.
+pub(crate) struct LogWriter {
+    dir: PathBuf,
+    tx: mpsc::Sender<LogMessage>,
+    failed: Arc<AtomicBool>,
+}
+
+impl LogWriter {
+    fn new(dir: PathBuf) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let failed = Arc::new(AtomicBool::new(false));
+        let thread_dir = dir.clone();
+        let thread_failed = failed.clone();
+        std::thread::Builder::new()
+            .name("store log".to_string())
+            .spawn(move || {
+                let dir = thread_dir;
+                while let Ok(msg) = rx.recv() {
+                    let mut segment = match msg {
+                        LogMessage::Append(segment) => segment,
+                        LogMessage::Flush(ack) => {
+                            thread_failed.store(false, Ordering::Release);
+                            let _ = ack.send(());
+                            continue;
+                        }
+                    };
+                    let mut flush = None;
+                    while let Ok(msg) = rx.try_recv() {
+                        match msg {
+                            LogMessage::Append(next) => segment.extend(next),
+                            LogMessage::Flush(ack) => {
+                                flush = Some(ack);
+                                break;
+                            }
+                        }
+                    }
+                    if !thread_failed.load(Ordering::Acquire) {
+                        match segment.append_to(&dir) {
+                            Ok(()) => log::info!(
+                                "appended processed commits to the log of {}",
+                                dir.display()
+                            ),
+                            Err(e) => {
+                                log::error!(
+                                    "failed to append to the log of {}: {}",
+                                    dir.display(),
+                                    e
+                                );
+                                thread_failed.store(true, Ordering::Release);
+                            }
+                        }
+                    }
+                    if let Some(ack) = flush {
+                        thread_failed.store(false, Ordering::Release);
+                        let _ = ack.send(());
+                    }
+                }
+            })
+            .expect("a thread appending to the store log");
+        Self { dir, tx, failed }
+    }
+
+    fn append(&self, segment: LogSegment) {
+        if self.tx.send(LogMessage::Append(segment)).is_err() {
+            log::error!("the log of {} is not appended anymore", self.dir.display());
+            self.failed.store(true, Ordering::Release);
+        }
+    }
+
+    /// Waits for the segments sent so far to be appended.
+    fn flush(&self) {
+        let (ack, done) = mpsc::channel();
+        if self.tx.send(LogMessage::Flush(ack)).is_ok() {
+            let _ = done.recv();
+        }
+    }
+}
+
+fn read_log(path: &Path) -> io::Result<Vec<u8>> {
+    match std::fs::read(path) {
+        Ok(log) => Ok(log),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e),
+    }
+}
+
+/// Drops what follows the complete segments of a log, before appending new segments.
+fn truncate(path: &Path, len: usize) -> io::Result<()> {
+    if path.exists() && std::fs::metadata(path)?.len() > len as u64 {
+        OpenOptions::new()
+            .write(true)
+            .open(path)?
+            .set_len(len as u64)?;
+    }
+    Ok(())
+}
+
+fn append(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if bytes.is_empty() {
+        return Ok(());
+    }
+    let mut f = OpenOptions::new().create(true).append(true).open(path)?;
+    f.write_all(bytes)?;
+    f.sync_data()
+}
+
+fn missing_processor(repo: &Repo) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("no processor for the configuration of {}", repo_key(repo)),
+    )
+}
+
+/// Writes in a temporary file, then replaces `path` once complete.
+fn write_atomically(
+    path: &Path,
+    f: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let mut w = BufWriter::new(File::create(&tmp)?);
+    f(&mut w)?;
+    w.into_inner()?.sync_data()?;
+    std::fs::rename(tmp, path)
+}
+
+fn repo_key(repo: &Repo) -> String {
+    if let Some(path) = repo.path() {
+        return path.display().to_string();
+    }
+    format!("{}/{}/{}", repo.forge().host(), repo.user(), repo.name())
+}
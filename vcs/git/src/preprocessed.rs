@@ -15,8 +15,11 @@ use hyperast::{
 use log::info;
 
 use crate::{
-    Commit, DefaultMetrics,
-    git::{all_commits_between, all_first_parents_between, retrieve_commit},
+    Commit,
+    git::{
+        Builder, DagBounds, RevRange, all_commits_between, all_first_parents_between,
+        retrieve_commit,
+    },
     processing::{ConfiguredRepo2, file_sys},
 };
 
@@ -157,7 +160,10 @@ impl RepositoryProcessor {
             "commits to retrieve: {:?}",
             all_commits_between(&repository.repo, before, after).map(|x| x.count())
         );
-        let rw = all_commits_between(&repository.repo, before, after)?;
+        let rw = Builder::new(&repository.repo)?
+            .before(before)?
+            .after(after)?
+            .dag(DagBounds::default())?;
         let mut rw = rw.map(|x| x.unwrap()).take(limit).peekable();
         let r = self.ensure_prepro(&mut rw, repository);
         Ok(r)
@@ -198,12 +204,43 @@ impl RepositoryProcessor {
             "commits to process {before} {after}: {:?}",
             all_commits_between(&repository.repo, before, after).map(|x| x.count())
         );
-        let rw = all_commits_between(&repository.repo, before, after)?;
+        // the whole graph is walked, so that the commits of merged branches are also processed
+        let rw = Builder::new(&repository.repo)?
+            .before(before)?
+            .after(after)?
+            .dag(DagBounds::default())?;
         let mut rw = rw.take(limit).map(|x| x.unwrap());
         let r = self.pre_pro(&mut rw, repository, usize::MAX);
         Ok(r)
     }
 
+    /// Processes the commits of `range`, following all the parents of merge commits,
+    /// see [`DagWalk`](crate::git::DagWalk).
+    pub fn pre_process_range(
+        &mut self,
+        repository: &ConfiguredRepo2,
+        range: &RevRange,
+        bounds: DagBounds,
+    ) -> Result<Vec<git2::Oid>, git2::Error> {
+        let rw = Builder::new(&repository.repo)?.range(range)?.dag(bounds)?;
+        let mut rw = rw.map(|x| x.unwrap());
+        let r = self.pre_pro(&mut rw, repository, usize::MAX);
+        log::info!("processed {} commits in {range}", r.len());
+        Ok(r)
+    }
+
+    pub fn ensure_pre_processed_range(
+        &self,
+        repository: &ConfiguredRepo2,
+        range: &RevRange,
+        bounds: DagBounds,
+    ) -> Result<Result<Vec<git2::Oid>, Vec<git2::Oid>>, git2::Error> {
+        let rw = Builder::new(&repository.repo)?.range(range)?.dag(bounds)?;
+        let mut rw = rw.map(|x| x.unwrap()).peekable();
+        let r = self.ensure_prepro(&mut rw, repository);
+        Ok(r)
+    }
+
     pub fn pre_pro(
         &mut self,
         rw: &mut impl Iterator<Item = git2::Oid>,
@@ -213,12 +250,17 @@ impl RepositoryProcessor {
         let mut r = Vec::with_capacity(rw.size_hint().0);
         for _ in 0..size {
             let Some(oid) = rw.next() else { break };
-            let builder = crate::preprocessed::CommitBuilder::start(&repository.repo, oid);
             let commit_processor = self
                 .processing_systems
                 .by_id_mut(&repository.config.0)
                 .unwrap()
                 .get_mut(repository.config.1);
+            if commit_processor.get_commit(oid).is_some() {
+                // already processed, eg. shared by several branches of a merge
+                r.push(oid);
+                continue;
+            }
+            let builder = crate::preprocessed::CommitBuilder::start(&repository.repo, oid);
             let _id = commit_processor
                 .prepare_processing(&repository.repo, builder, repository.config)
                 .process(self);
@@ -762,7 +804,7 @@ impl CommitProcessor<file_sys::Make> for RepositoryProcessor {
 ///   - [ ] ts
 #[cfg(feature = "npm")]
 impl CommitProcessor<file_sys::Npm> for RepositoryProcessor {
-    type Module = (NodeIdentifier, DefaultMetrics);
+    type Module = (NodeIdentifier, crate::DefaultMetrics);
     fn handle_module<'a, 'b, const RMS: bool>(
         &mut self,
         _repository: &'a Repository,
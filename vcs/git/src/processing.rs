@@ -9,15 +9,22 @@ pub use erased::ParametrizedCommitProcessorHandle;
 
 pub enum BuildSystem {
     Maven,
+    Gradle,
     Make,
     Npm,
+    Pip,
+    Cargo,
     None,
 }
 
 pub enum ProcessingConfig<P> {
     JavaMaven { limit: usize, dir_path: P },
+    JavaGradle { limit: usize, dir_path: P },
     CppMake { limit: usize, dir_path: P },
+    CMake { limit: usize, dir_path: P },
     TsNpm { limit: usize, dir_path: P },
+    PythonPip { limit: usize, dir_path: P },
+    RustCargo { limit: usize, dir_path: P },
     Any { limit: usize, dir_path: P },
 }
 
@@ -26,8 +33,12 @@ pub enum ProcessingConfig<P> {
 #[derive(serde::Deserialize, Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub enum RepoConfig {
     CppMake,
+    CMake,
     JavaMaven,
+    JavaGradle,
     TsNpm,
+    PythonPip,
+    RustCargo,
     Any,
 }
 
@@ -38,12 +49,22 @@ impl std::str::FromStr for RepoConfig {
         Ok(match s {
             "Cpp" => Self::CppMake,
             "cpp" => Self::CppMake,
+            "C" => Self::CMake,
+            "c" => Self::CMake,
             "Java" => Self::JavaMaven,
             "java" => Self::JavaMaven,
+            "Gradle" => Self::JavaGradle,
+            "gradle" => Self::JavaGradle,
             "typescript" => Self::TsNpm,
             "javascript" => Self::TsNpm,
             "Ts" => Self::TsNpm,
             "ts" => Self::TsNpm,
+            "Python" => Self::PythonPip,
+            "python" => Self::PythonPip,
+            "py" => Self::PythonPip,
+            "Rust" => Self::RustCargo,
+            "rust" => Self::RustCargo,
+            "rs" => Self::RustCargo,
             "any" => Self::Any,
             x => return Err(format!("'{}' is not anvailable config", x)),
         })
@@ -57,12 +78,34 @@ impl From<&RepoConfig> for ProcessingConfig<&'static str> {
                 limit: 3,
                 dir_path: "",
             },
+            RepoConfig::CMake => Self::CMake {
+                limit: 3,
+                dir_path: "",
+            },
             RepoConfig::JavaMaven => Self::JavaMaven {
                 limit: 3,
                 dir_path: "",
             },
-            RepoConfig::TsNpm => todo!(),
-            RepoConfig::Any => todo!(),
+            RepoConfig::JavaGradle => Self::JavaGradle {
+                limit: 3,
+                dir_path: "",
+            },
+            RepoConfig::TsNpm => Self::TsNpm {
+                limit: 3,
+                dir_path: "",
+            },
+            RepoConfig::PythonPip => Self::PythonPip {
+                limit: 3,
+                dir_path: "",
+            },
+            RepoConfig::RustCargo => Self::RustCargo {
+                limit: 3,
+                dir_path: "",
+            },
+            RepoConfig::Any => Self::Any {
+                limit: 3,
+                dir_path: "",
+            },
         }
     }
 }
@@ -316,6 +359,119 @@ pub(crate) mod caches {
         }
     }
 
+    #[cfg(feature = "c")]
+    #[derive(Default)]
+    pub struct C {
+        pub(crate) md_cache: hyperast_gen_ts_c::legion::MDCache,
+        pub object_map: NamedMap<(hyperast_gen_ts_c::legion::Local,)>,
+    }
+
+    #[cfg(feature = "c")]
+    impl super::ObjectMapper for C {
+        type K = (git2::Oid, ObjectName);
+
+        type V = (hyperast_gen_ts_c::legion::Local,);
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[cfg(feature = "ts")]
+    #[derive(Default)]
+    pub struct Ts {
+        pub(crate) md_cache: hyperast_gen_ts_ts::legion::MDCache,
+        pub object_map: NamedMap<(hyperast_gen_ts_ts::legion::Local,)>,
+    }
+
+    #[cfg(feature = "ts")]
+    impl super::ObjectMapper for Ts {
+        type K = (git2::Oid, ObjectName);
+
+        type V = (hyperast_gen_ts_ts::legion::Local,);
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[cfg(feature = "python")]
+    #[derive(Default)]
+    pub struct Python {
+        pub(crate) md_cache: hyperast_gen_ts_python::legion::MDCache,
+        pub object_map: NamedMap<(hyperast_gen_ts_python::legion::Local,)>,
+        /// package roots, ie. directories containing a pyproject.toml or a setup.py
+        pub package_map: OidMap<(NodeIdentifier, crate::DefaultMetrics)>,
+    }
+
+    #[cfg(feature = "python")]
+    impl super::ObjectMapper for Python {
+        type K = (git2::Oid, ObjectName);
+
+        type V = (hyperast_gen_ts_python::legion::Local,);
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    /// the manifests of python package roots, ie. their pyproject.toml
+    #[cfg(feature = "python")]
+    #[derive(Default)]
+    pub struct PyProject {
+        pub(crate) md_cache: hyperast_gen_ts_toml::legion::MDCache,
+        pub object_map: OidMap<crate::python::PyProjectToml>,
+    }
+
+    #[cfg(feature = "python")]
+    impl super::ObjectMapper for PyProject {
+        type K = git2::Oid;
+
+        type V = crate::python::PyProjectToml;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[cfg(feature = "rust")]
+    #[derive(Default)]
+    pub struct Rust {
+        pub(crate) md_cache: hyperast_gen_ts_rust::legion::MDCache,
+        pub object_map: NamedMap<(hyperast_gen_ts_rust::legion::Local,)>,
+    }
+
+    #[cfg(feature = "rust")]
+    impl super::ObjectMapper for Rust {
+        type K = (git2::Oid, ObjectName);
+
+        type V = (hyperast_gen_ts_rust::legion::Local,);
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
     #[derive(Default)]
     pub struct Maven {
         pub object_map: OidMap<(NodeIdentifier, crate::maven::MD)>,
@@ -340,6 +496,61 @@ pub(crate) mod caches {
         }
     }
 
+    #[cfg(all(feature = "cargo", feature = "rust"))]
+    #[derive(Default)]
+    pub struct Cargo {
+        pub object_map: OidMap<(NodeIdentifier, crate::cargo::MD)>,
+    }
+
+    #[cfg(all(feature = "cargo", feature = "rust"))]
+    #[derive(Default)]
+    pub struct CargoToml {
+        pub(crate) md_cache: hyperast_gen_ts_toml::legion::MDCache,
+        pub object_map: OidMap<crate::cargo::CargoToml>,
+    }
+
+    #[cfg(all(feature = "cargo", feature = "rust"))]
+    impl super::ObjectMapper for CargoToml {
+        type K = git2::Oid;
+
+        type V = crate::cargo::CargoToml;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[cfg(all(feature = "gradle", feature = "java"))]
+    #[derive(Default)]
+    pub struct Gradle {
+        pub object_map: OidMap<(NodeIdentifier, crate::gradle::MD)>,
+    }
+
+    #[cfg(all(feature = "gradle", feature = "java"))]
+    #[derive(Default)]
+    pub struct GradleScript {
+        pub object_map: OidMap<crate::gradle::Script>,
+    }
+
+    #[cfg(all(feature = "gradle", feature = "java"))]
+    impl super::ObjectMapper for GradleScript {
+        type K = git2::Oid;
+
+        type V = crate::gradle::Script;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
     #[derive(Default)]
     pub struct Make {
         pub object_map: OidMap<(NodeIdentifier, crate::make::MD)>,
@@ -364,6 +575,17 @@ pub(crate) mod caches {
         }
     }
 
+    #[cfg(all(feature = "npm", feature = "ts"))]
+    #[derive(Default)]
+    pub struct Npm {
+        pub object_map: OidMap<(NodeIdentifier, crate::DefaultMetrics)>,
+    }
+
+    #[derive(Default)]
+    pub struct Any {
+        pub object_map: OidMap<(NodeIdentifier, crate::DefaultMetrics)>,
+    }
+
     // // any
     // pub object_map_any: OidMap<(NodeIdentifier, DefaultMetrics)>,
     // // maven
@@ -402,9 +624,6 @@ pub mod file_sys {
     // TODO move these things to their respective modules
     use super::{CachesHolding, ObjectName};
 
-    // /// The default file system, directories and files
-    // pub struct Any;
-
     /// The maven scheme https://maven.apache.org/guides/introduction/introduction-to-the-standard-directory-layout.html ,
     /// made of nested maven modules.
     /// Each maven module has a config file (often a pom.xml),
@@ -449,7 +668,7 @@ pub mod file_sys {
     }
 
     /// The make scheme,
-    /// It contains a Makefile and different directories, often src/ or lib/, tests/ or tests/, and also third-party/ docs/ script/,
+    /// It contains a Makefile (or a CMakeLists.txt) and different directories, often src/ or lib/, tests/ or tests/, and also third-party/ docs/ script/,
     /// but it is mostly community and programming language dependent.
     #[cfg(feature = "make")]
     pub struct Make;
@@ -467,7 +686,7 @@ pub mod file_sys {
 
     impl super::InFiles for MakeFile {
         fn matches(name: &ObjectName) -> bool {
-            name.0.eq(b"Makefile")
+            name.0.eq(b"Makefile") || name.0.eq(b"CMakeLists.txt")
         }
     }
 
@@ -498,12 +717,217 @@ pub mod file_sys {
         }
     }
 
+    #[cfg(feature = "c")]
+    pub struct C;
+
+    #[cfg(feature = "c")]
+    impl CachesHolding for C {
+        type Caches = super::caches::C;
+    }
+
+    #[cfg(feature = "c")]
+    impl super::InFiles for C {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.ends_with(b".c") || name.0.ends_with(b".h")
+        }
+    }
+
     /// The npm scheme,
     /// it contains a package.json then,
     /// in its simplest form contains an index.js and a src/ directory,
     /// or is a collection of packages that contains a packages/ directory where each package is located
     #[cfg(feature = "npm")]
     pub struct Npm;
+
+    #[cfg(feature = "npm")]
+    impl CachesHolding for Npm {
+        type Caches = super::caches::Npm;
+    }
+
+    #[cfg(feature = "npm")]
+    pub struct PackageJson;
+
+    #[cfg(feature = "npm")]
+    impl super::InFiles for PackageJson {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"package.json")
+        }
+    }
+
+    /// Directories that are never part of the sources of an npm package
+    #[cfg(feature = "npm")]
+    pub(crate) fn is_npm_ignored(name: &ObjectName) -> bool {
+        name.0.eq(b"node_modules") || name.0.eq(b".git")
+    }
+
+    #[cfg(feature = "ts")]
+    pub struct Ts;
+
+    #[cfg(feature = "ts")]
+    impl CachesHolding for Ts {
+        type Caches = super::caches::Ts;
+    }
+
+    /// The typescript grammar is a superset of javascript,
+    /// but jsx and tsx need their own grammar.
+    #[cfg(feature = "ts")]
+    impl super::InFiles for Ts {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.ends_with(b".ts")
+                || name.0.ends_with(b".mts")
+                || name.0.ends_with(b".cts")
+                || name.0.ends_with(b".js")
+                || name.0.ends_with(b".mjs")
+                || name.0.ends_with(b".cjs")
+        }
+    }
+
+    #[cfg(feature = "python")]
+    pub struct Python;
+
+    #[cfg(feature = "python")]
+    impl CachesHolding for Python {
+        type Caches = super::caches::Python;
+    }
+
+    #[cfg(feature = "python")]
+    impl super::InFiles for Python {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.ends_with(b".py") || name.0.ends_with(b".pyi")
+        }
+    }
+
+    /// Marks the root of a python package,
+    /// either declared with a pyproject.toml (PEP 517/518) or with a legacy setup.py
+    #[cfg(feature = "python")]
+    pub struct PyProject;
+
+    #[cfg(feature = "python")]
+    impl CachesHolding for PyProject {
+        type Caches = super::caches::PyProject;
+    }
+
+    #[cfg(feature = "python")]
+    impl super::InFiles for PyProject {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"pyproject.toml") || name.0.eq(b"setup.py")
+        }
+    }
+
+    /// Directories that are never part of the sources of a python package,
+    /// ie. caches, virtual environments and build outputs
+    #[cfg(feature = "python")]
+    pub(crate) fn is_python_ignored(name: &ObjectName) -> bool {
+        name.0.eq(b".git")
+            || name.0.eq(b"__pycache__")
+            || name.0.eq(b".venv")
+            || name.0.eq(b"venv")
+            || name.0.eq(b".tox")
+            || name.0.eq(b".mypy_cache")
+            || name.0.eq(b".pytest_cache")
+            || name.0.ends_with(b".egg-info")
+    }
+
+    /// The cargo scheme https://doc.rust-lang.org/cargo/guide/project-layout.html ,
+    /// made of packages, possibly gathered in a workspace.
+    /// Each package has a Cargo.toml,
+    /// a src/ directory that contains production code,
+    /// a tests/ directory that contains integration tests,
+    /// and optionally benches/ and examples/ directories.
+    /// A workspace lists its member packages in the Cargo.toml at its root.
+    #[cfg(all(feature = "cargo", feature = "rust"))]
+    pub struct Cargo;
+
+    #[cfg(all(feature = "cargo", feature = "rust"))]
+    impl CachesHolding for Cargo {
+        type Caches = super::caches::Cargo;
+    }
+
+    #[cfg(all(feature = "cargo", feature = "rust"))]
+    pub struct CargoToml;
+
+    #[cfg(all(feature = "cargo", feature = "rust"))]
+    impl CachesHolding for CargoToml {
+        type Caches = super::caches::CargoToml;
+    }
+
+    #[cfg(all(feature = "cargo", feature = "rust"))]
+    impl super::InFiles for CargoToml {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"Cargo.toml")
+        }
+    }
+
+    /// Directories that are never part of the sources of a cargo package,
+    /// ie. build outputs
+    #[cfg(all(feature = "cargo", feature = "rust"))]
+    pub(crate) fn is_cargo_ignored(name: &ObjectName) -> bool {
+        name.0.eq(b".git") || name.0.eq(b"target")
+    }
+
+    /// The gradle scheme https://docs.gradle.org/current/userguide/multi_project_builds.html ,
+    /// made of projects, possibly gathered in a multi-project build.
+    /// The settings.gradle at the root of a build includes its projects,
+    /// the build.gradle of each project can declare its source sets,
+    /// by default src/main/java and src/test/java.
+    /// Both can also be written with the kotlin DSL, ie. settings.gradle.kts and build.gradle.kts.
+    /// Only java sources are processed, there is no kotlin generator,
+    /// so kotlin source directories (eg. src/main/kotlin) are skipped.
+    #[cfg(all(feature = "gradle", feature = "java"))]
+    pub struct Gradle;
+
+    #[cfg(all(feature = "gradle", feature = "java"))]
+    impl CachesHolding for Gradle {
+        type Caches = super::caches::Gradle;
+    }
+
+    #[cfg(all(feature = "gradle", feature = "java"))]
+    pub struct GradleScript;
+
+    #[cfg(all(feature = "gradle", feature = "java"))]
+    impl CachesHolding for GradleScript {
+        type Caches = super::caches::GradleScript;
+    }
+
+    #[cfg(all(feature = "gradle", feature = "java"))]
+    impl super::InFiles for GradleScript {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"settings.gradle")
+                || name.0.eq(b"settings.gradle.kts")
+                || name.0.eq(b"build.gradle")
+                || name.0.eq(b"build.gradle.kts")
+        }
+    }
+
+    /// Directories that are never part of the sources of a gradle project,
+    /// ie. build outputs and caches
+    #[cfg(all(feature = "gradle", feature = "java"))]
+    pub(crate) fn is_gradle_ignored(name: &ObjectName) -> bool {
+        name.0.eq(b".git") || name.0.eq(b"build") || name.0.eq(b".gradle")
+    }
+
+    #[cfg(feature = "rust")]
+    pub struct Rust;
+
+    #[cfg(feature = "rust")]
+    impl CachesHolding for Rust {
+        type Caches = super::caches::Rust;
+    }
+
+    #[cfg(feature = "rust")]
+    impl super::InFiles for Rust {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.ends_with(b".rs")
+        }
+    }
+
+    /// The default file system, directories and files,
+    /// each file being handled by the generator matching its extension.
+    pub struct Any;
+
+    impl CachesHolding for Any {
+        type Caches = super::caches::Any;
+    }
 }
 
 impl crate::preprocessed::RepositoryProcessor {
@@ -64,6 +64,10 @@ pub trait CommitProc {
     ) -> Box<dyn PreparedCommitProc + 'repo>;
 
     fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit>;
+    /// commits already processed, used when writing a checkpoint
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_>;
+    /// reinserts a commit read from a checkpoint
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit);
     fn get_precomp_query(&self) -> Option<hyperast_tsquery::ZeroSepArrayStr> {
         None
     }
@@ -151,6 +155,14 @@ fn t() {
         fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
             unimplemented!("required for processing at the root of a project")
         }
+
+        fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+            unimplemented!("required for processing at the root of a project")
+        }
+
+        fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+            unimplemented!("required for processing at the root of a project")
+        }
     }
     impl ParametrizedCommitProc for P0 {
         fn get_mut(&mut self, parameters: ConfigParametersHandle) -> &mut dyn CommitProc {
@@ -339,6 +351,14 @@ mod spreaded {
             fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
                 todo!()
             }
+
+            fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+                todo!()
+            }
+
+            fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+                todo!()
+            }
         }
         impl CommitProcExt for P {
             type Holder = P0;
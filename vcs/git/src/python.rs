@@ -0,0 +1,194 @@
+use std::time::Instant;
+
+use crate::{
+    Accumulator, BasicDirAcc, DefaultMetrics, FailedParsing, FileProcessingResult,
+    PROPAGATE_ERROR_ON_BAD_CST_NODE, ParseErr, SuccessProcessing, processing::ObjectName,
+};
+
+use hyperast::{
+    hashed::SyntaxNodeHashs,
+    store::defaults::{LabelIdentifier, NodeIdentifier},
+    tree_gen::{NoOpMore, SubTreeMetrics},
+};
+
+use hyperast_gen_ts_python::{legion as py_tree_gen, types::TStore};
+use hyperast_gen_ts_toml::{self as toml, legion::TomlTreeGen};
+
+pub(crate) fn handle_python_file<'stores, 'cache, 'b: 'stores>(
+    tree_gen: &mut py_tree_gen::PythonTreeGen<
+        'stores,
+        'cache,
+        TStore,
+        NoOpMore<TStore, py_tree_gen::Acc>,
+    >,
+    name: &ObjectName,
+    text: &'b [u8],
+) -> FileProcessingResult<py_tree_gen::FNode> {
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(&hyperast_gen_ts_python::language())
+        .unwrap();
+    let time = Instant::now();
+    let tree = parser.parse(text, None);
+    let parsing_time = time.elapsed();
+    let Some(tree) = tree else {
+        unimplemented!("You set a timeout or an cancel flag, so it now requires special handling.")
+    };
+    if tree.root_node().has_error() {
+        log::warn!("bad CST: {:?}", name.try_str());
+        if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+            return Err(FailedParsing {
+                parsing_time,
+                tree,
+                error: "CST contains parsing errors",
+            });
+        }
+    };
+    let time = Instant::now();
+    let node = tree_gen.generate_file(name.as_bytes(), text, tree.walk());
+    let processing_time = time.elapsed();
+    Ok(SuccessProcessing {
+        parsing_time,
+        processing_time,
+        node,
+    })
+}
+
+/// Parses the pyproject.toml of a package root with the toml generator.
+///
+/// NOTE a setup.py is a regular python module, so it goes through [`handle_python_file`].
+pub(crate) fn handle_pyproject_file<'stores, 'cache, 'b: 'stores>(
+    tree_gen: &mut TomlTreeGen<
+        'stores,
+        'cache,
+        toml::types::TStore,
+        NoOpMore<toml::types::TStore, toml::legion::Acc>,
+    >,
+    name: &ObjectName,
+    text: &'b [u8],
+) -> Result<PyProjectToml, ParseErr> {
+    let tree = match toml::legion::tree_sitter_parse(text) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST: {:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(ParseErr::IllFormed);
+            } else {
+                tree
+            }
+        }
+    };
+    let x = tree_gen
+        .generate_file(name.as_bytes(), text, tree.walk())
+        .local;
+    Ok(PyProjectToml {
+        compressed_node: x.compressed_node,
+        metrics: x.metrics,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct PyProjectToml {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: DefaultMetrics,
+}
+
+pub struct PythonAcc {
+    pub(crate) primary:
+        BasicDirAcc<NodeIdentifier, LabelIdentifier, SubTreeMetrics<SyntaxNodeHashs<u32>>>,
+}
+
+impl PythonAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            primary: BasicDirAcc::new(name),
+        }
+    }
+}
+
+impl From<String> for PythonAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl PythonAcc {
+    pub(crate) fn push(&mut self, name: LabelIdentifier, full_node: py_tree_gen::Local) {
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+}
+
+impl hyperast::tree_gen::Accumulator for PythonAcc {
+    type Node = (LabelIdentifier, (py_tree_gen::Local,));
+    fn push(&mut self, (name, (full_node,)): Self::Node) {
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+}
+
+impl Accumulator for PythonAcc {
+    type Unlabeled = (py_tree_gen::Local,);
+}
+
+/// Accumulates the content of a python package root,
+/// ie. a directory containing a `pyproject.toml` or a `setup.py`.
+pub struct PyPackageAcc {
+    pub(crate) primary: BasicDirAcc<NodeIdentifier, LabelIdentifier, DefaultMetrics>,
+}
+
+impl From<String> for PyPackageAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl PyPackageAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            primary: BasicDirAcc::new(name),
+        }
+    }
+}
+
+impl PyPackageAcc {
+    pub(crate) fn push_manifest(&mut self, name: LabelIdentifier, full_node: PyProjectToml) {
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+    pub fn push_submodule(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: (NodeIdentifier, DefaultMetrics),
+    ) {
+        self.primary.push(name, full_node.0, full_node.1);
+    }
+    pub(crate) fn push_source_file(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: py_tree_gen::Local,
+    ) {
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+    pub(crate) fn push_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: py_tree_gen::Local,
+    ) {
+        self.primary
+            .push(name, full_node.compressed_node, full_node.metrics);
+    }
+}
+
+impl hyperast::tree_gen::Accumulator for PyPackageAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, DefaultMetrics));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        self.push_submodule(name, full_node);
+    }
+}
+
+impl Accumulator for PyPackageAcc {
+    type Unlabeled = (NodeIdentifier, DefaultMetrics);
+}
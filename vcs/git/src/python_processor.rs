@@ -0,0 +1,698 @@
+use crate::{
+    DefaultMetrics, Processor, StackEle,
+    git::BasicGitObject,
+    preprocessed::RepositoryProcessor,
+    processing::{
+        CacheHolding, InFiles, ObjectName, ParametrizedCommitProcessorHandle,
+        erased::{CommitProcessorHandle, ParametrizedCommitProc2}, file_sys,
+    },
+    python::{PyPackageAcc, PythonAcc},
+};
+use git2::{Oid, Repository};
+use hyperast::{
+    store::{defaults::NodeIdentifier, nodes::legion::eq_node},
+    types::{ETypeStore as _, LabelStore},
+};
+use hyperast_gen_ts_python::{legion as py_gen, types::Type};
+use std::{
+    iter::Peekable,
+    path::{Components, PathBuf},
+};
+
+pub(crate) fn prepare_dir_exploration(tree: git2::Tree) -> Vec<BasicGitObject> {
+    tree.iter()
+        .rev()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect()
+}
+
+pub type SimpleStores = hyperast::store::SimpleStores<hyperast_gen_ts_python::types::TStore>;
+
+pub struct PythonProcessor<'repo, 'prepro, 'd, 'c, Acc> {
+    repository: &'repo Repository,
+    prepro: &'prepro mut RepositoryProcessor,
+    stack: Vec<StackEle<Acc>>,
+    pub dir_path: &'d mut Peekable<Components<'c>>,
+    parameters: &'d crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+}
+
+impl<'repo, 'b, 'd, 'c, Acc: From<String>> PythonProcessor<'repo, 'b, 'd, 'c, Acc> {
+    pub(crate) fn new(
+        repository: &'repo Repository,
+        prepro: &'b mut RepositoryProcessor,
+        dir_path: &'d mut Peekable<Components<'c>>,
+        name: &ObjectName,
+        oid: git2::Oid,
+        parameters: &'d crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+    ) -> Self {
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        let name = name.try_into().unwrap();
+        let stack = vec![StackEle::new(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            parameters,
+        }
+    }
+}
+
+impl<'repo, 'b, 'd, 'c> Processor<PythonAcc> for PythonProcessor<'repo, 'b, 'd, 'c, PythonAcc> {
+    fn pre(&mut self, current_object: BasicGitObject) {
+        match current_object {
+            BasicGitObject::Tree(oid, name) => {
+                if file_sys::is_python_ignored(&name) {
+                    return;
+                }
+                self.handle_tree_cached(oid, name);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if file_sys::Python::matches(&name) {
+                    if let Err(e) = self.prepro.help_handle_python_file(
+                        oid,
+                        &mut self.stack.last_mut().unwrap().acc,
+                        &name,
+                        self.repository,
+                        *self.parameters,
+                    ) {
+                        log::warn!("skipping {:?}: {:?}", name.try_str(), e);
+                    }
+                } else {
+                    log::debug!("not python source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: PythonAcc) -> Option<(py_gen::Local,)> {
+        let name = acc.primary.name.clone();
+        let key = (oid, name.as_bytes().into());
+        let full_node = make(acc, self.prepro.main_stores_mut().mut_with_ts());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<PythonProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .insert(key, (full_node.clone(),));
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some((full_node,))
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            assert!(
+                !w.primary.children_names.contains(&name),
+                "{:?} {:?}",
+                w.primary.children_names,
+                name
+            );
+            w.push(name, full_node.clone());
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<StackEle<PythonAcc>> {
+        &mut self.stack
+    }
+}
+
+impl<'repo, 'prepro, 'd, 'c> PythonProcessor<'repo, 'prepro, 'd, 'c, PythonAcc> {
+    fn handle_tree_cached(&mut self, oid: Oid, name: ObjectName) {
+        if let Some(already) = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<PythonProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .get(&(oid, name.clone()))
+        {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            let name = self.prepro.intern_object_name(&name);
+            assert!(!w.primary.children_names.contains(&name));
+            hyperast::tree_gen::Accumulator::push(w, (name, full_node));
+        } else {
+            log::debug!("tree {:?}", name.try_str());
+            let tree = self.repository.find_tree(oid).unwrap();
+            let prepared: Vec<BasicGitObject> = prepare_dir_exploration(tree);
+            self.stack.push(StackEle::new(
+                oid,
+                prepared,
+                PythonAcc::new(name.try_into().unwrap()),
+            ));
+        }
+    }
+}
+
+/// Walks a repository from its root,
+/// a directory containing a `pyproject.toml` or a `setup.py` is a package root,
+/// other directories are plain python folders.
+pub struct PyProjectProcessor<'repo, 'prepro, 'c, Acc> {
+    repository: &'repo Repository,
+    prepro: &'prepro mut RepositoryProcessor,
+    stack: Vec<StackEle<Acc>>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: ParametrizedCommitProcessorHandle,
+}
+
+impl<'repo, 'prepro, 'c, Acc: From<String>> PyProjectProcessor<'repo, 'prepro, 'c, Acc> {
+    pub fn new(
+        repository: &'repo Repository,
+        prepro: &'prepro mut RepositoryProcessor,
+        dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+        handle: ParametrizedCommitProcessorHandle,
+    ) -> Self {
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![StackEle::new(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+        }
+    }
+}
+
+impl<'repo, 'prepro, 'c> Processor<PyPackageAcc>
+    for PyProjectProcessor<'repo, 'prepro, 'c, PyPackageAcc>
+{
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                if file_sys::is_python_ignored(&name) {
+                    return;
+                }
+                self.handle_tree_cached(name, oid);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                if file_sys::Python::matches(&name) {
+                    // NOTE setup.py is a regular python source file
+                    let handle = self.python_handle();
+                    match self
+                        .prepro
+                        .handle_python_blob(oid, &name, self.repository, handle)
+                    {
+                        Ok((full_node,)) => {
+                            let name = self.prepro.intern_object_name(&name);
+                            let w = &mut self.stack.last_mut().unwrap().acc;
+                            assert!(!w.primary.children_names.contains(&name));
+                            w.push_source_file(name, full_node);
+                        }
+                        Err(e) => log::warn!("skipping {:?}: {:?}", name.try_str(), e),
+                    }
+                } else if name.as_bytes() == b"pyproject.toml" {
+                    let handle = self.python_handle();
+                    let acc = &mut self.stack.last_mut().unwrap().acc;
+                    if let Err(e) =
+                        self.prepro
+                            .handle_pyproject_toml(oid, acc, name, self.repository, handle)
+                    {
+                        log::warn!("skipping pyproject.toml: {:?}", e);
+                    }
+                } else {
+                    log::debug!("not python source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+
+    fn post(&mut self, oid: Oid, acc: PyPackageAcc) -> Option<(NodeIdentifier, DefaultMetrics)> {
+        let name = acc.primary.name.clone();
+        let full_node = make_package(acc, self.prepro.main_stores_mut().mut_with_ts());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<PythonProcessorHolder>()
+            .get_caches_mut()
+            .package_map
+            .insert(oid, full_node.clone());
+
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            assert!(
+                !w.primary.children_names.contains(&name),
+                "{:?} {:?}",
+                w.primary.children_names,
+                name
+            );
+            w.push_submodule(name, full_node);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<StackEle<PyPackageAcc>> {
+        &mut self.stack
+    }
+}
+
+impl<'repo, 'prepro, 'c> PyProjectProcessor<'repo, 'prepro, 'c, PyPackageAcc> {
+    fn python_handle(
+        &self,
+    ) -> crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc> {
+        crate::processing::erased::ParametrizedCommitProcessor2Handle(
+            self.handle.1,
+            std::marker::PhantomData,
+        )
+    }
+
+    fn handle_tree_cached(&mut self, name: ObjectName, oid: Oid) {
+        if let Some(s) = self.dir_path.peek() {
+            if name
+                .as_bytes()
+                .eq(std::ffi::OsStr::as_encoded_bytes(s.as_os_str()))
+            {
+                self.dir_path.next();
+                self.stack.last_mut().expect("never empty").cs.clear();
+                let tree = self.repository.find_tree(oid).unwrap();
+                let prepared = prepare_dir_exploration(tree);
+                self.stack.push(StackEle::new(
+                    oid,
+                    prepared,
+                    PyPackageAcc::new(name.try_into().unwrap()),
+                ));
+            }
+            return;
+        }
+        let python_proc = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<PythonProcessorHolder>()
+            .with_parameters_mut(self.handle.1);
+        if let Some(already) = python_proc.cache.package_map.get(&oid) {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            let name = self.prepro.intern_object_name(name);
+            assert!(!w.primary.children_names.contains(&name));
+            w.push_submodule(name, full_node);
+            return;
+        }
+        log::debug!("python tree {:?}", name.try_str());
+        let tree = self.repository.find_tree(oid).unwrap();
+        let is_package_root = tree
+            .iter()
+            .any(|x| file_sys::PyProject::matches(&x.name_bytes().into()));
+        if is_package_root {
+            let prepared = prepare_dir_exploration(tree);
+            self.stack.push(StackEle::new(
+                oid,
+                prepared,
+                PyPackageAcc::new(name.try_into().unwrap()),
+            ));
+            return;
+        }
+        // TODO look for nested package roots, eg. in the packages/ directory of monorepos
+        let handle = self.python_handle();
+        let (name, (full_node,)) = self.prepro.help_handle_python_folder(
+            self.repository,
+            self.dir_path,
+            oid,
+            &name,
+            handle,
+        );
+        let parent_acc = &mut self.stack.last_mut().unwrap().acc;
+        assert!(!parent_acc.primary.children_names.contains(&name));
+        parent_acc.push_source_directory(name, full_node);
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter {}
+
+#[derive(Default)]
+pub(crate) struct PythonProcessorHolder(Option<PythonProc>);
+pub(crate) struct PythonProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Python,
+    manifest_cache: crate::processing::caches::PyProject,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for PythonProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0;
+                // TODO enable multi configs for python, do the same as the one for Java
+                self.0 = Some(PythonProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    manifest_cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+
+struct PreparedPythonCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+    pub(crate) handle: ParametrizedCommitProcessorHandle,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedPythonCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedPythonCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyperast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = PyProjectProcessor::<PyPackageAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+            self.handle,
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<PythonProcessorHolder>();
+        let handle = self.handle;
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.1)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+
+impl crate::processing::erased::CommitProc for PythonProc {
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+        handle: crate::processing::ParametrizedCommitProcessorHandle,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedPythonCommitProc {
+            repository,
+            commit_builder,
+            handle,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        Box::new(self.commits.iter())
+    }
+
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+
+    fn get_lang_handle(&self, lang: &str) -> Option<ParametrizedCommitProcessorHandle> {
+        if lang.eq_ignore_ascii_case("python") || lang.eq_ignore_ascii_case("py") {
+            Some(ParametrizedCommitProcessorHandle(
+                CommitProcessorHandle(std::any::TypeId::of::<PythonProcessorHolder>()),
+                crate::processing::erased::ConfigParametersHandle(0),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for PythonProc {
+    type Holder = PythonProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for PythonProcessorHolder {
+    type Proc = PythonProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::Python> for PythonProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Python {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Python {
+        &self.cache
+    }
+}
+
+impl CacheHolding<crate::processing::caches::PyProject> for PythonProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::PyProject {
+        &mut self.manifest_cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::PyProject {
+        &self.manifest_cache
+    }
+}
+
+impl CacheHolding<crate::processing::caches::Python> for PythonProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Python {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Python {
+        &self.0.as_ref().unwrap().cache
+    }
+}
+
+impl RepositoryProcessor {
+    pub(crate) fn handle_python_blob(
+        &mut self,
+        oid: Oid,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+    ) -> Result<(py_gen::Local,), crate::ParseErr> {
+        self.processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::Python>()
+            .handle2(oid, repository, &name, parameters, |c, n, t| {
+                let line_break = if t.contains(&b'\r') { "\r\n" } else { "\n" }
+                    .as_bytes()
+                    .to_vec();
+                let holder = c.mut_or_default::<PythonProcessorHolder>();
+                let py_proc = holder.0.as_mut().unwrap();
+                let md_cache = &mut py_proc.cache.md_cache;
+                let stores = self
+                    .main_stores
+                    .mut_with_ts::<hyperast_gen_ts_python::types::TStore>();
+                let mut py_tree_gen = py_gen::PythonTreeGen {
+                    line_break,
+                    stores,
+                    md_cache,
+                    more: Default::default(),
+                };
+                crate::python::handle_python_file(&mut py_tree_gen, n, t)
+                    .map(|x| {
+                        let local = x.node.local.clone();
+                        self.parsing_time += x.parsing_time;
+                        self.processing_time += x.processing_time;
+                        (local,)
+                    })
+                    .map_err(|_| crate::ParseErr::IllFormed)
+            })
+    }
+
+    fn handle_pyproject_toml(
+        &mut self,
+        oid: Oid,
+        parent_acc: &mut PyPackageAcc,
+        name: ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let x = self
+            .processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::PyProject>()
+            .handle(oid, repository, &name, parameters, |c, n, t| {
+                let line_break = if t.contains(&b'\r') { "\r\n" } else { "\n" }
+                    .as_bytes()
+                    .to_vec();
+                let holder = c.mut_or_default::<PythonProcessorHolder>();
+                let py_proc = holder.0.as_mut().unwrap();
+                let md_cache = &mut py_proc.manifest_cache.md_cache;
+                let mut toml_tree_gen = hyperast_gen_ts_toml::legion::TomlTreeGen {
+                    line_break,
+                    stores: self.main_stores.mut_with_ts(),
+                    md_cache,
+                    more: Default::default(),
+                };
+                crate::python::handle_pyproject_file(&mut toml_tree_gen, n, t)
+            })?;
+        let name = self.intern_object_name(&name);
+        assert!(!parent_acc.primary.children_names.contains(&name));
+        parent_acc.push_manifest(name, x);
+        Ok(())
+    }
+
+    pub(crate) fn help_handle_python_file(
+        &mut self,
+        oid: Oid,
+        parent: &mut PythonAcc,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let (full_node,) = self.handle_python_blob(oid, name, repository, parameters)?;
+        let name = self.intern_object_name(name);
+        assert!(!parent.primary.children_names.contains(&name));
+        parent.push(name, full_node);
+        Ok(())
+    }
+
+    pub(crate) fn handle_python_directory<'b, 'd: 'b>(
+        &mut self,
+        repository: &Repository,
+        dir_path: &'b mut Peekable<Components<'d>>,
+        name: &ObjectName,
+        oid: git2::Oid,
+        handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+    ) -> (py_gen::Local,) {
+        PythonProcessor::<PythonAcc>::new(repository, self, dir_path, name, oid, &handle).process()
+    }
+
+    pub(crate) fn help_handle_python_folder<'a, 'b, 'c, 'd: 'c>(
+        &'a mut self,
+        repository: &'b Repository,
+        dir_path: &'c mut Peekable<Components<'d>>,
+        oid: Oid,
+        name: &ObjectName,
+        handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+    ) -> <PythonAcc as hyperast::tree_gen::Accumulator>::Node {
+        let full_node = self.handle_python_directory(repository, dir_path, name, oid, handle);
+        let name = self.intern_object_name(name);
+        (name, full_node)
+    }
+}
+
+pub(crate) fn make(acc: PythonAcc, stores: &mut SimpleStores) -> py_gen::Local {
+    use hyperast::hashed::{IndexingHashBuilder, MetaDataHashsBuilder};
+    let node_store = &mut stores.node_store;
+    let label_store = &mut stores.label_store;
+    let kind = Type::Directory;
+    let interned_kind = hyperast_gen_ts_python::types::TStore::intern(kind);
+    let label_id = label_store.get_or_insert(acc.primary.name.clone());
+
+    let primary = acc
+        .primary
+        .map_metrics(|m| m.finalize(&interned_kind, &label_id, 0));
+    let hashable = primary.metrics.hashs.most_discriminating();
+    let eq = eq_node(&interned_kind, Some(&label_id), &primary.children);
+    let insertion = node_store.prepare_insertion(&hashable, eq);
+
+    if let Some(id) = insertion.occupied_id() {
+        let metrics = primary.metrics.map_hashs(|h| h.build());
+        return py_gen::Local {
+            compressed_node: id,
+            metrics,
+            role: None,
+            precomp_queries: Default::default(),
+        };
+    }
+
+    let mut dyn_builder = hyperast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+
+    let children_is_empty = primary.children.is_empty();
+
+    let metrics = primary.persist(&mut dyn_builder, interned_kind, label_id);
+    let metrics = metrics.map_hashs(|h| h.build());
+    let hashs = metrics.add_md_metrics(&mut dyn_builder, children_is_empty);
+    hashs.persist(&mut dyn_builder);
+
+    let vacant = insertion.vacant();
+    let node_id = hyperast::store::nodes::legion::NodeStore::insert_built_after_prepare(
+        vacant,
+        dyn_builder.build(),
+    );
+
+    py_gen::Local {
+        compressed_node: node_id,
+        metrics,
+        role: None,
+        precomp_queries: Default::default(),
+    }
+}
+
+pub(crate) fn make_package(
+    acc: PyPackageAcc,
+    stores: &mut SimpleStores,
+) -> (NodeIdentifier, DefaultMetrics) {
+    use hyperast::hashed::{IndexingHashBuilder, MetaDataHashsBuilder};
+    let kind = Type::Directory;
+    let interned_kind = hyperast_gen_ts_python::types::TStore::intern(kind);
+    let label_id = stores.label_store.get_or_insert(acc.primary.name.clone());
+
+    let primary = acc
+        .primary
+        .map_metrics(|m| m.finalize(&interned_kind, &label_id, 0));
+
+    let hashable = primary.metrics.hashs.most_discriminating();
+
+    let eq = eq_node(&interned_kind, Some(&label_id), &primary.children);
+
+    assert_eq!(primary.children_names.len(), primary.children.len());
+
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    if let Some(id) = insertion.occupied_id() {
+        let metrics = primary
+            .metrics
+            .map_hashs(|h| MetaDataHashsBuilder::build(h));
+        return (id, metrics);
+    }
+
+    log::info!(
+        "make python package {} {}",
+        &primary.name,
+        primary.children.len()
+    );
+
+    let mut dyn_builder = hyperast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+
+    let children_is_empty = primary.children.is_empty();
+
+    let metrics = primary.persist(&mut dyn_builder, interned_kind, label_id);
+    let metrics = metrics.map_hashs(|h| h.build());
+    let hashs = metrics.add_md_metrics(&mut dyn_builder, children_is_empty);
+    hashs.persist(&mut dyn_builder);
+
+    let vacant = insertion.vacant();
+    let node_id = hyperast::store::nodes::legion::NodeStore::insert_built_after_prepare(
+        vacant,
+        dyn_builder.build(),
+    );
+
+    (node_id, metrics)
+}
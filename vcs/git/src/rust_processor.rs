@@ -0,0 +1,363 @@
+use crate::{
+    Processor, StackEle,
+    git::BasicGitObject,
+    preprocessed::RepositoryProcessor,
+    processing::{CacheHolding, InFiles, ObjectName, file_sys},
+    rust::RustAcc,
+};
+use git2::{Oid, Repository};
+use hyperast::{
+    store::nodes::legion::eq_node,
+    types::{ETypeStore as _, LabelStore},
+};
+use hyperast_gen_ts_rust::{legion as rs_gen, types::Type};
+use std::{iter::Peekable, path::Components};
+
+pub(crate) fn prepare_dir_exploration(tree: git2::Tree) -> Vec<BasicGitObject> {
+    tree.iter()
+        .rev()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect()
+}
+
+pub type SimpleStores = hyperast::store::SimpleStores<hyperast_gen_ts_rust::types::TStore>;
+
+pub struct RustProcessor<'repo, 'prepro, 'd, 'c, Acc> {
+    repository: &'repo Repository,
+    prepro: &'prepro mut RepositoryProcessor,
+    stack: Vec<StackEle<Acc>>,
+    pub dir_path: &'d mut Peekable<Components<'c>>,
+    parameters: &'d crate::processing::erased::ParametrizedCommitProcessor2Handle<RustProc>,
+}
+
+impl<'repo, 'b, 'd, 'c, Acc: From<String>> RustProcessor<'repo, 'b, 'd, 'c, Acc> {
+    pub(crate) fn new(
+        repository: &'repo Repository,
+        prepro: &'b mut RepositoryProcessor,
+        dir_path: &'d mut Peekable<Components<'c>>,
+        name: &ObjectName,
+        oid: git2::Oid,
+        parameters: &'d crate::processing::erased::ParametrizedCommitProcessor2Handle<RustProc>,
+    ) -> Self {
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        let name = name.try_into().unwrap();
+        let stack = vec![StackEle::new(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            parameters,
+        }
+    }
+}
+
+impl<'repo, 'b, 'd, 'c> Processor<RustAcc> for RustProcessor<'repo, 'b, 'd, 'c, RustAcc> {
+    fn pre(&mut self, current_object: BasicGitObject) {
+        match current_object {
+            BasicGitObject::Tree(oid, name) => {
+                self.handle_tree_cached(oid, name);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if file_sys::Rust::matches(&name) {
+                    if let Err(e) = self.prepro.help_handle_rust_file(
+                        oid,
+                        &mut self.stack.last_mut().unwrap().acc,
+                        &name,
+                        self.repository,
+                        *self.parameters,
+                    ) {
+                        log::warn!("skipping {:?}: {:?}", name.try_str(), e);
+                    }
+                } else {
+                    log::debug!("not rust source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: RustAcc) -> Option<(rs_gen::Local,)> {
+        let name = acc.primary.name.clone();
+        let key = (oid, name.as_bytes().into());
+        let full_node = make(acc, self.prepro.main_stores_mut().mut_with_ts());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<RustProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .insert(key, (full_node.clone(),));
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some((full_node,))
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            assert!(
+                !w.primary.children_names.contains(&name),
+                "{:?} {:?}",
+                w.primary.children_names,
+                name
+            );
+            w.push(name, full_node.clone());
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<StackEle<RustAcc>> {
+        &mut self.stack
+    }
+}
+
+impl<'repo, 'prepro, 'd, 'c> RustProcessor<'repo, 'prepro, 'd, 'c, RustAcc> {
+    fn handle_tree_cached(&mut self, oid: Oid, name: ObjectName) {
+        if let Some(already) = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<RustProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .get(&(oid, name.clone()))
+        {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().acc;
+            let name = self.prepro.intern_object_name(&name);
+            assert!(!w.primary.children_names.contains(&name));
+            hyperast::tree_gen::Accumulator::push(w, (name, full_node));
+        } else {
+            log::debug!("tree {:?}", name.try_str());
+            let tree = self.repository.find_tree(oid).unwrap();
+            let prepared: Vec<BasicGitObject> = prepare_dir_exploration(tree);
+            self.stack.push(StackEle::new(
+                oid,
+                prepared,
+                RustAcc::new(name.try_into().unwrap()),
+            ));
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter {}
+
+#[derive(Default)]
+pub(crate) struct RustProcessorHolder(Option<RustProc>);
+pub(crate) struct RustProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Rust,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for RustProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0;
+                // TODO enable multi configs for rust, do the same as the one for Java
+                self.0 = Some(RustProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+
+impl crate::processing::erased::CommitProc for RustProc {
+    fn prepare_processing(
+        &self,
+        _repository: &git2::Repository,
+        _builder: crate::preprocessed::CommitBuilder,
+        _handle: crate::processing::ParametrizedCommitProcessorHandle,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc> {
+        unimplemented!("required for processing rust at the root of a project")
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn commits(&self) -> Box<dyn Iterator<Item = (&git2::Oid, &crate::Commit)> + '_> {
+        Box::new(self.commits.iter())
+    }
+
+    fn restore_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for RustProc {
+    type Holder = RustProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for RustProcessorHolder {
+    type Proc = RustProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::Rust> for RustProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Rust {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Rust {
+        &self.cache
+    }
+}
+
+impl CacheHolding<crate::processing::caches::Rust> for RustProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Rust {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Rust {
+        &self.0.as_ref().unwrap().cache
+    }
+}
+
+impl RepositoryProcessor {
+    pub(crate) fn handle_rust_blob(
+        &mut self,
+        oid: Oid,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<RustProc>,
+    ) -> Result<(rs_gen::Local,), crate::ParseErr> {
+        self.processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::Rust>()
+            .handle2(oid, repository, &name, parameters, |c, n, t| {
+                let line_break = if t.contains(&b'\r') { "\r\n" } else { "\n" }
+                    .as_bytes()
+                    .to_vec();
+                let holder = c.mut_or_default::<RustProcessorHolder>();
+                let rs_proc = holder.0.as_mut().unwrap();
+                let md_cache = &mut rs_proc.cache.md_cache;
+                let stores = self
+                    .main_stores
+                    .mut_with_ts::<hyperast_gen_ts_rust::types::TStore>();
+                let mut rs_tree_gen = rs_gen::RustTreeGen {
+                    line_break,
+                    stores,
+                    md_cache,
+                    more: Default::default(),
+                };
+                crate::rust::handle_rust_file(&mut rs_tree_gen, n, t)
+                    .map(|x| {
+                        let local = x.node.local.clone();
+                        self.parsing_time += x.parsing_time;
+                        self.processing_time += x.processing_time;
+                        (local,)
+                    })
+                    .map_err(|_| crate::ParseErr::IllFormed)
+            })
+    }
+
+    pub(crate) fn help_handle_rust_file(
+        &mut self,
+        oid: Oid,
+        parent: &mut RustAcc,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<RustProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let (full_node,) = self.handle_rust_blob(oid, name, repository, parameters)?;
+        let name = self.intern_object_name(name);
+        assert!(!parent.primary.children_names.contains(&name));
+        parent.push(name, full_node);
+        Ok(())
+    }
+
+    pub(crate) fn handle_rust_directory<'b, 'd: 'b>(
+        &mut self,
+        repository: &Repository,
+        dir_path: &'b mut Peekable<Components<'d>>,
+        name: &ObjectName,
+        oid: git2::Oid,
+        handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<RustProc>,
+    ) -> (rs_gen::Local,) {
+        RustProcessor::<RustAcc>::new(repository, self, dir_path, name, oid, &handle).process()
+    }
+
+    pub(crate) fn help_handle_rust_folder<'a, 'b, 'c, 'd: 'c>(
+        &'a mut self,
+        repository: &'b Repository,
+        dir_path: &'c mut Peekable<Components<'d>>,
+        oid: Oid,
+        name: &ObjectName,
+        handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<RustProc>,
+    ) -> <RustAcc as hyperast::tree_gen::Accumulator>::Node {
+        let full_node = self.handle_rust_directory(repository, dir_path, name, oid, handle);
+        let name = self.intern_object_name(name);
+        (name, full_node)
+    }
+}
+
+pub(crate) fn make(acc: RustAcc, stores: &mut SimpleStores) -> rs_gen::Local {
+    use hyperast::hashed::{IndexingHashBuilder, MetaDataHashsBuilder};
+    let node_store = &mut stores.node_store;
+    let label_store = &mut stores.label_store;
+    let kind = Type::Directory;
+    let interned_kind = hyperast_gen_ts_rust::types::TStore::intern(kind);
+    let label_id = label_store.get_or_insert(acc.primary.name.clone());
+
+    let primary = acc
+        .primary
+        .map_metrics(|m| m.finalize(&interned_kind, &label_id, 0));
+    let hashable = primary.metrics.hashs.most_discriminating();
+    let eq = eq_node(&interned_kind, Some(&label_id), &primary.children);
+    let insertion = node_store.prepare_insertion(&hashable, eq);
+
+    if let Some(id) = insertion.occupied_id() {
+        let metrics = primary.metrics.map_hashs(|h| h.build());
+        return rs_gen::Local {
+            compressed_node: id,
+            metrics,
+            role: None,
+            precomp_queries: Default::default(),
+        };
+    }
+
+    let mut dyn_builder = hyperast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+
+    let children_is_empty = primary.children.is_empty();
+
+    let metrics = primary.persist(&mut dyn_builder, interned_kind, label_id);
+    let metrics = metrics.map_hashs(|h| h.build());
+    let hashs = metrics.add_md_metrics(&mut dyn_builder, children_is_empty);
+    hashs.persist(&mut dyn_builder);
+
+    let vacant = insertion.vacant();
+    let node_id = hyperast::store::nodes::legion::NodeStore::insert_built_after_prepare(
+        vacant,
+        dyn_builder.build(),
+    );
+
+    rs_gen::Local {
+        compressed_node: node_id,
+        metrics,
+        role: None,
+        precomp_queries: Default::default(),
+    }
+}
@@ -4,6 +4,8 @@ pub mod direct_type_ref;
 #[cfg(test)]
 pub mod extends_package_local;
 pub mod obj_creation;
+#[cfg(feature = "maven_java")]
+mod on_disk;
 
 use crate::{git::fetch_github_repository, preprocessed::PreProcessedRepository};
 #[cfg(feature = "impact")]
@@ -0,0 +1,416 @@
+//! Processing a fixture repository in place, ie. without any remote operation.
+use std::path::{Path, PathBuf};
+
+use crate::git::{Forge, Repo};
+use crate::multi_preprocessed::PreProcessedRepositories;
+use crate::processing::RepoConfig;
+
+static POM: &str = r#"<project>
+    <modelVersion>4.0.0</modelVersion>
+    <groupId>org.example</groupId>
+    <artifactId>fixture</artifactId>
+    <version>1.0</version>
+</project>
+"#;
+
+static CLASS: &str = r#"package org.example;
+
+public class A {
+    int f() {
+        return 42;
+    }
+}
+"#;
+
+/// Initializes a repository without remote in the temporary directory
+fn fixture(name: &str) -> (PathBuf, git2::Oid) {
+    let java = "src/main/java/org/example/A.java";
+    fixture_with(name, &[("pom.xml", POM), (java, CLASS)])
+}
+
+/// Initializes a repository made of `files` without remote in the temporary directory
+fn fixture_with(name: &str, files: &[(&str, &str)]) -> (PathBuf, git2::Oid) {
+    let path = std::env::temp_dir().join("hyperast_fixtures").join(format!(
+        "{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&path);
+    git2::Repository::init(&path).unwrap();
+    let oid = commit_files(&path, files);
+    (path, oid)
+}
+
+/// Writes `files` in the fixture repository at `path` and commits them on top of HEAD
+fn commit_files(path: &Path, files: &[(&str, &str)]) -> git2::Oid {
+    let repository = git2::Repository::open(path).unwrap();
+    for (file, text) in files {
+        let file = path.join(file);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, text).unwrap();
+    }
+    let mut index = repository.index().unwrap();
+    index
+        .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repository.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("fixture", "fixture@example.org").unwrap();
+    let parent = repository.head().ok().map(|x| x.peel_to_commit().unwrap());
+    let parents: Vec<_> = parent.iter().collect();
+    repository
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "fixture",
+            &tree,
+            &parents,
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_process_on_disk() {
+    let (path, oid) = fixture("on_disk");
+    let repo = Repo::on_disk(&path, Some("fixture")).unwrap();
+    assert_eq!(repo.forge(), Forge::Disk);
+    assert_eq!(repo.name(), "fixture");
+    assert_eq!(repo.path(), Some(path.canonicalize().unwrap().as_path()));
+
+    let mut repositories = PreProcessedRepositories::default();
+    repositories
+        .register_config(repo.clone(), RepoConfig::JavaMaven)
+        .unwrap();
+    // as given by a route, without the path
+    let spec = Forge::Disk.repo(repo.user(), repo.name());
+    let repository = repositories.get_config(spec).unwrap().fetch();
+    assert!(repository.repo.remotes().unwrap().is_empty());
+
+    let commits = repositories
+        .pre_process_with_limit(&repository, "", &oid.to_string(), 1)
+        .unwrap();
+    assert_eq!(commits, [oid]);
+    assert!(repositories.get_commit(&repository.config, &oid).is_some());
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+static PARENT_POM: &str = r#"<project>
+    <modelVersion>4.0.0</modelVersion>
+    <groupId>org.example</groupId>
+    <artifactId>parent</artifactId>
+    <version>1.0</version>
+    <packaging>pom</packaging>
+    <modules>
+        <module>core</module>
+    </modules>
+    <properties>
+        <guava.version>32.1</guava.version>
+    </properties>
+    <dependencyManagement>
+        <dependencies>
+            <dependency>
+                <groupId>com.google.guava</groupId>
+                <artifactId>guava</artifactId>
+                <version>${guava.version}</version>
+            </dependency>
+        </dependencies>
+    </dependencyManagement>
+</project>
+"#;
+
+static CORE_POM: &str = r#"<project>
+    <modelVersion>4.0.0</modelVersion>
+    <parent>
+        <groupId>org.example</groupId>
+        <artifactId>parent</artifactId>
+        <version>1.0</version>
+    </parent>
+    <artifactId>core</artifactId>
+    <dependencies>
+        <dependency>
+            <groupId>com.google.guava</groupId>
+            <artifactId>guava</artifactId>
+        </dependency>
+    </dependencies>
+</project>
+"#;
+
+#[test]
+fn test_dependency_graph_on_disk() {
+    use crate::maven::Coordinates;
+    use crate::maven_dependencies::DependencyGraph;
+    let java = "core/src/main/java/org/example/A.java";
+    let files = [
+        ("pom.xml", PARENT_POM),
+        ("core/pom.xml", CORE_POM),
+        (java, CLASS),
+    ];
+    let (path, oid) = fixture_with("dependency_graph", &files);
+    let repo = Repo::on_disk(&path, Some("dependency_graph")).unwrap();
+    let mut repositories = PreProcessedRepositories::default();
+    let repository = repositories
+        .register_config(repo, RepoConfig::JavaMaven)
+        .unwrap()
+        .fetch();
+    repositories
+        .pre_process_with_limit(&repository, "", &oid.to_string(), 1)
+        .unwrap();
+    let commit = repositories.get_commit(&repository.config, &oid).unwrap();
+    let stores = &repositories.processor.main_stores;
+    let graph = DependencyGraph::compute(stores, commit.ast_root).unwrap();
+
+    let paths: Vec<_> = graph.modules.iter().map(|x| x.path.as_str()).collect();
+    assert_eq!(paths, ["", "core"]);
+    let [edge] = &graph.edges[..] else {
+        panic!("{:?}", graph.edges)
+    };
+    assert_eq!(edge.from.artifact_id.as_deref(), Some("core"));
+    // the version is managed by the parent, through one of its properties
+    let guava = Coordinates {
+        group_id: Some("com.google.guava".to_owned()),
+        artifact_id: Some("guava".to_owned()),
+        version: Some("32.1".to_owned()),
+    };
+    assert_eq!(edge.to, guava);
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+static OTHER_CLASS: &str = r#"package org.example;
+
+public class B {
+    int g() {
+        if (true) {
+            return 1;
+        }
+        return 0;
+    }
+}
+"#;
+
+#[test]
+fn test_memoized_query_on_disk() {
+    use hyperast_tsquery::match_cache::{MatchCache, MemoizedQuery};
+    let java = "src/main/java/org/example/A.java";
+    let (path, first) = fixture_with("memoized_query", &[("pom.xml", POM), (java, CLASS)]);
+    // the pom and A are shared by both commits
+    let other = "src/main/java/org/example/B.java";
+    let second = commit_files(&path, &[(other, OTHER_CLASS)]);
+    let repo = Repo::on_disk(&path, Some("memoized_query")).unwrap();
+    let mut repositories = PreProcessedRepositories::default();
+    let repository = repositories
+        .register_config(repo, RepoConfig::JavaMaven)
+        .unwrap()
+        .fetch();
+    let commits = repositories
+        .pre_process_with_limit(&repository, "", &second.to_string(), 2)
+        .unwrap();
+    assert_eq!(commits, [second, first]);
+    let stores = &repositories.processor.main_stores;
+
+    let language = crate::resolve_language("Java").unwrap();
+    // a local pattern, counted file by file, and a non local one, counted on the whole commit
+    let query = "(method_declaration) @m\n(_ (block)) @b";
+    let query = hyperast_tsquery::Query::new(query, language).unwrap();
+    let plain = |root| {
+        let pos = hyperast::position::StructuralPosition::new(root);
+        let cursor = hyperast_tsquery::hyperast_cursor::TreeCursor::new(stores, pos);
+        let mut result = vec![0; query.enabled_pattern_count()];
+        for m in query.matches(cursor) {
+            let i = query.enabled_pattern_index(m.pattern_index).unwrap();
+            result[i as usize] += 1;
+        }
+        result
+    };
+    let memoized = MemoizedQuery::new(&query, 0);
+    let cache = MatchCache::default();
+    // from the oldest commit, the second one reusing the counts of the shared subtrees
+    let mut cached = 0;
+    for (oid, methods) in [(first, 1), (second, 2)] {
+        let root = repositories
+            .get_commit(&repository.config, &oid)
+            .unwrap()
+            .ast_root;
+        let result = memoized
+            .count(stores, root, &cache, None, |_| false)
+            .unwrap();
+        assert_eq!(result, plain(root));
+        assert_eq!(result[0], methods);
+        assert!(result[1] > 0);
+        assert!(cache.len() > cached);
+        cached = cache.len();
+    }
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_metric_predicates_on_disk() {
+    use hyperast::position::TreePath;
+    use hyperast::types::{HyperAST, WithMetrics, WithStats};
+    let java = "src/main/java/org/example/A.java";
+    let other = "src/main/java/org/example/B.java";
+    let files = [("pom.xml", POM), (java, CLASS), (other, OTHER_CLASS)];
+    let (path, oid) = fixture_with("metric_predicates", &files);
+    let repo = Repo::on_disk(&path, Some("metric_predicates")).unwrap();
+    let mut repositories = PreProcessedRepositories::default();
+    let repository = repositories
+        .register_config(repo, RepoConfig::JavaMaven)
+        .unwrap()
+        .fetch();
+    repositories
+        .pre_process_with_limit(&repository, "", &oid.to_string(), 1)
+        .unwrap();
+    let stores = &repositories.processor.main_stores;
+    let root = repositories
+        .get_commit(&repository.config, &oid)
+        .unwrap()
+        .ast_root;
+
+    let language = crate::resolve_language("Java").unwrap();
+    // the methods captured by `query`, in the order of the files
+    let methods = |query: &str| {
+        let query = hyperast_tsquery::Query::new(query, language.clone()).unwrap();
+        let capture = query.capture_index_for_name("m").unwrap();
+        let pos = hyperast::position::StructuralPosition::new(root);
+        let cursor = hyperast_tsquery::hyperast_cursor::TreeCursor::new(stores, pos);
+        let mut result = vec![];
+        for m in query.matches(cursor) {
+            result.extend(
+                m.nodes_for_capture_index(capture)
+                    .map(|n| *n.pos.node().unwrap()),
+            );
+        }
+        result
+    };
+    let [f_id, g_id] = methods("(method_declaration) @m")[..] else {
+        panic!("expected the methods of A and B")
+    };
+    let (f, g) = (stores.resolve(&f_id), stores.resolve(&g_id));
+    // g wraps its first return in an if, making it larger, deeper and more complex than f
+    assert!(f.size() < g.size());
+    assert!(f.height() < g.height());
+    assert_eq!(f.get_metric("mcc"), Some(1.0));
+    assert_eq!(g.get_metric("mcc"), Some(2.0));
+
+    let size = format!("((method_declaration) @m (#size>? @m {}))", f.size());
+    assert_eq!(methods(&size), [g_id]);
+    let size = format!("((method_declaration) @m (#size<? @m {}))", f.size());
+    assert!(methods(&size).is_empty());
+    let height = format!("((method_declaration) @m (#height<? @m {}))", g.height());
+    assert_eq!(methods(&height), [f_id]);
+    let mcc = "((method_declaration) @m (#metric>? @m \"mcc\" 1))";
+    assert_eq!(methods(mcc), [g_id]);
+    let mcc = "((method_declaration) @m (#metric<? @m \"mcc\" 2))";
+    assert_eq!(methods(mcc), [f_id]);
+    // a metric that no node carries rejects every match
+    assert!(methods("((method_declaration) @m (#metric>? @m \"absent\" 0))").is_empty());
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+static WORKSPACE_MANIFEST: &str = r#"[workspace]
+members = ["crates/*", "tools/cli"]
+exclude = ["crates/legacy"]
+"#;
+
+static CORE_MANIFEST: &str = r#"[package]
+name = "core"
+version = "0.1.0"
+"#;
+
+static CLI_MANIFEST: &str = r#"[package]
+name = "cli"
+version = "0.1.0"
+
+[dependencies]
+core = { path = "../../crates/core" }
+"#;
+
+static LIB: &str = r#"pub fn answer() -> u32 {
+    42
+}
+"#;
+
+static MAIN: &str = r#"fn main() {
+    println!("{}", core::answer());
+}
+"#;
+
+#[cfg(all(feature = "cargo", feature = "rust"))]
+#[test]
+fn test_cargo_workspace_on_disk() {
+    use crate::cargo::SemFlag::{self, *};
+    use enumset::EnumSet;
+    use hyperast::store::defaults::NodeIdentifier;
+    use hyperast::store::nodes::compo::Flags;
+    use hyperast::types::{Childrn, LabelStore as _, Labeled, WithChildren};
+    use hyperast_gen_ts_toml::types::{Lang as TomlLang, Type};
+    let files = [
+        ("Cargo.toml", WORKSPACE_MANIFEST),
+        ("crates/core/Cargo.toml", CORE_MANIFEST),
+        ("crates/core/src/lib.rs", LIB),
+        ("crates/legacy/Cargo.toml", CORE_MANIFEST),
+        ("crates/legacy/src/lib.rs", LIB),
+        ("tools/cli/Cargo.toml", CLI_MANIFEST),
+        ("tools/cli/src/main.rs", MAIN),
+        ("tools/cli/tests/cli.rs", LIB),
+    ];
+    let (path, oid) = fixture_with("cargo_workspace", &files);
+    let repo = Repo::on_disk(&path, Some("cargo_workspace")).unwrap();
+    let mut repositories = PreProcessedRepositories::default();
+    let repository = repositories
+        .register_config(repo, RepoConfig::RustCargo)
+        .unwrap()
+        .fetch();
+    repositories
+        .pre_process_with_limit(&repository, "", &oid.to_string(), 1)
+        .unwrap();
+    let stores = &repositories.processor.main_stores;
+    let root = repositories
+        .get_commit(&repository.config, &oid)
+        .unwrap()
+        .ast_root;
+
+    let child = |id: NodeIdentifier, name: &str| {
+        let n = stores.node_store.resolve(id);
+        n.children()?.iter_children().find(|x| {
+            let c = stores.node_store.resolve(*x);
+            c.try_get_label()
+                .is_some_and(|l| stores.label_store.resolve(l) == name)
+        })
+    };
+    let at = |path: &str| {
+        path.split('/')
+            .filter(|x| !x.is_empty())
+            .try_fold(root, |id, name| child(id, name))
+    };
+    let flags = |path: &str| {
+        let id = at(path).expect(path);
+        let resolve = |id| stores.node_store.try_resolve_typed2::<TomlLang>(&id);
+        let Some((n, Type::CargoDirectory)) = resolve(id) else {
+            panic!("{} is not a cargo directory", path)
+        };
+        n.get_component::<Flags<EnumSet<SemFlag>>>()
+            .map_or(EnumSet::empty(), |x| x.0)
+    };
+    // the members are followed through the globs and the intermediate directories
+    assert_eq!(flags(""), IsCargoWorkspace | HoldCargoMember);
+    assert_eq!(flags("crates"), EnumSet::only(HoldCargoMember));
+    assert_eq!(flags("crates/core"), IsCargoPackage | HoldMainFolder);
+    assert_eq!(flags("tools"), EnumSet::only(HoldCargoMember));
+    assert_eq!(
+        flags("tools/cli"),
+        IsCargoPackage | HoldMainFolder | HoldTestFolder
+    );
+    // excluded members are left out of the workspace
+    assert_eq!(at("crates/legacy"), None);
+    // and the files are kept verbatim
+    for (file, text) in files
+        .iter()
+        .filter(|(x, _)| !x.starts_with("crates/legacy"))
+    {
+        let id = at(file).expect(file);
+        let out = hyperast::nodes::TextSerializer::new(stores, id).to_string();
+        assert_eq!(&out, text, "{}", file);
+    }
+    let _ = std::fs::remove_dir_all(&path);
+}
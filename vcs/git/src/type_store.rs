@@ -7,10 +7,20 @@ pub struct TStore;
 
 #[cfg(feature = "cpp")]
 impl hyperast::store::TyDown<hyperast_gen_ts_cpp::types::TStore> for TStore {}
+#[cfg(feature = "c")]
+impl hyperast::store::TyDown<hyperast_gen_ts_c::types::TStore> for TStore {}
 #[cfg(feature = "java")]
 impl hyperast::store::TyDown<hyperast_gen_ts_java::types::TStore> for TStore {}
 #[cfg(feature = "maven")]
 impl hyperast::store::TyDown<hyperast_gen_ts_xml::types::TStore> for TStore {}
+#[cfg(feature = "ts")]
+impl hyperast::store::TyDown<hyperast_gen_ts_ts::types::TStore> for TStore {}
+#[cfg(feature = "python")]
+impl hyperast::store::TyDown<hyperast_gen_ts_python::types::TStore> for TStore {}
+#[cfg(feature = "rust")]
+impl hyperast::store::TyDown<hyperast_gen_ts_rust::types::TStore> for TStore {}
+#[cfg(feature = "toml")]
+impl hyperast::store::TyDown<hyperast_gen_ts_toml::types::TStore> for TStore {}
 
 impl Default for TStore {
     fn default() -> Self {
@@ -39,6 +49,33 @@ impl<'a> hyperast::types::RoleStore for TStore {
                 );
                 hyperast_gen_ts_cpp::types::TStore::resolve_field(t.get_lang(), field_id)
             }
+            #[cfg(feature = "c")]
+            "hyperast_gen_ts_c::types::Lang" => {
+                let t =
+                    hyperast_gen_ts_c::types::TType::new(hyperast_gen_ts_c::types::Type::Spaces);
+                hyperast_gen_ts_c::types::TStore::resolve_field(t.get_lang(), field_id)
+            }
+            #[cfg(feature = "python")]
+            "hyperast_gen_ts_python::types::Lang" => {
+                let t = hyperast_gen_ts_python::types::TType::new(
+                    hyperast_gen_ts_python::types::Type::Spaces,
+                );
+                hyperast_gen_ts_python::types::TStore::resolve_field(t.get_lang(), field_id)
+            }
+            #[cfg(feature = "rust")]
+            "hyperast_gen_ts_rust::types::Lang" => {
+                let t = hyperast_gen_ts_rust::types::TType::new(
+                    hyperast_gen_ts_rust::types::Type::Spaces,
+                );
+                hyperast_gen_ts_rust::types::TStore::resolve_field(t.get_lang(), field_id)
+            }
+            #[cfg(feature = "toml")]
+            "hyperast_gen_ts_toml::types::Lang" => {
+                let t = hyperast_gen_ts_toml::types::TType::new(
+                    hyperast_gen_ts_toml::types::Type::Spaces,
+                );
+                hyperast_gen_ts_toml::types::TStore::resolve_field(t.get_lang(), field_id)
+            }
             #[cfg(feature = "maven")]
             "hyperast_gen_ts_xml::types::Lang" => {
                 let t = hyperast_gen_ts_xml::types::TType::new(
@@ -67,6 +104,33 @@ impl<'a> hyperast::types::RoleStore for TStore {
                 );
                 hyperast_gen_ts_cpp::types::TStore::intern_role(t.get_lang(), role)
             }
+            #[cfg(feature = "c")]
+            "hyperast_gen_ts_c::types::Lang" => {
+                let t =
+                    hyperast_gen_ts_c::types::TType::new(hyperast_gen_ts_c::types::Type::Spaces);
+                hyperast_gen_ts_c::types::TStore::intern_role(t.get_lang(), role)
+            }
+            #[cfg(feature = "python")]
+            "hyperast_gen_ts_python::types::Lang" => {
+                let t = hyperast_gen_ts_python::types::TType::new(
+                    hyperast_gen_ts_python::types::Type::Spaces,
+                );
+                hyperast_gen_ts_python::types::TStore::intern_role(t.get_lang(), role)
+            }
+            #[cfg(feature = "rust")]
+            "hyperast_gen_ts_rust::types::Lang" => {
+                let t = hyperast_gen_ts_rust::types::TType::new(
+                    hyperast_gen_ts_rust::types::Type::Spaces,
+                );
+                hyperast_gen_ts_rust::types::TStore::intern_role(t.get_lang(), role)
+            }
+            #[cfg(feature = "toml")]
+            "hyperast_gen_ts_toml::types::Lang" => {
+                let t = hyperast_gen_ts_toml::types::TType::new(
+                    hyperast_gen_ts_toml::types::Type::Spaces,
+                );
+                hyperast_gen_ts_toml::types::TStore::intern_role(t.get_lang(), role)
+            }
             #[cfg(feature = "maven")]
             "hyperast_gen_ts_xml::types::Lang" => {
                 let t = hyperast_gen_ts_xml::types::TType::new(
@@ -108,6 +172,72 @@ impl TypeStore for TStore {
             }
             .map(|t| t.as_static().into())
         })
+        .or_else(|| {
+            #[cfg(feature = "c")]
+            return unsafe {
+                erazed.unerase_ref_unchecked::<hyperast_gen_ts_c::types::TType>(
+                    std::any::TypeId::of::<hyperast_gen_ts_c::types::TType>(),
+                )
+            }
+            .map(|t| t.as_static().into());
+            #[cfg(not(feature = "c"))]
+            None
+        })
+        .or_else(|| {
+            #[cfg(feature = "ts")]
+            return unsafe {
+                erazed.unerase_ref_unchecked::<hyperast_gen_ts_ts::types::TType>(
+                    std::any::TypeId::of::<hyperast_gen_ts_ts::types::TType>(),
+                )
+            }
+            .map(|t| t.as_static().into());
+            #[cfg(not(feature = "ts"))]
+            None
+        })
+        .or_else(|| {
+            #[cfg(feature = "python")]
+            return unsafe {
+                erazed.unerase_ref_unchecked::<hyperast_gen_ts_python::types::TType>(
+                    std::any::TypeId::of::<hyperast_gen_ts_python::types::TType>(),
+                )
+            }
+            .map(|t| t.as_static().into());
+            #[cfg(not(feature = "python"))]
+            None
+        })
+        .or_else(|| {
+            #[cfg(feature = "rust")]
+            return unsafe {
+                erazed.unerase_ref_unchecked::<hyperast_gen_ts_rust::types::TType>(
+                    std::any::TypeId::of::<hyperast_gen_ts_rust::types::TType>(),
+                )
+            }
+            .map(|t| t.as_static().into());
+            #[cfg(not(feature = "rust"))]
+            None
+        })
+        .or_else(|| {
+            #[cfg(feature = "toml")]
+            return unsafe {
+                erazed.unerase_ref_unchecked::<hyperast_gen_ts_toml::types::TType>(
+                    std::any::TypeId::of::<hyperast_gen_ts_toml::types::TType>(),
+                )
+            }
+            .map(|t| t.as_static().into());
+            #[cfg(not(feature = "toml"))]
+            None
+        })
+        .or_else(|| {
+            #[cfg(feature = "any")]
+            return unsafe {
+                erazed.unerase_ref_unchecked::<crate::any::TType>(std::any::TypeId::of::<
+                    crate::any::TType,
+                >())
+            }
+            .map(|t| t.as_static().into());
+            #[cfg(not(feature = "any"))]
+            None
+        })
         .unwrap_or_else(|| {
             dbg!(tid);
             dbg!(std::any::type_name::<Self::Ty>());